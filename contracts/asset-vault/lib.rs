@@ -0,0 +1,324 @@
+// This file is part of Polkadex.
+
+// Copyright (C) 2020-2021 Polkadex oü.
+// SPDX-License-Identifier: GPL-3.0-or-later WITH Classpath-exception-2.0
+
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with this program. If not, see <https://www.gnu.org/licenses/>.
+
+//! Example contract holding `orml_tokens` assets, and trading them in the orderbook, through
+//! the Polkadex chain extension.
+
+#![cfg_attr(not(feature = "std"), no_std)]
+
+use ink_env::Environment;
+use ink_lang as ink;
+
+/// The assets this example deals with.
+///
+/// The variants are encoded like their counterparts in `polkadex_primitives::assets::AssetId`.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, scale::Encode, scale::Decode)]
+#[cfg_attr(feature = "std", derive(scale_info::TypeInfo))]
+pub enum AssetId {
+    #[codec(index = 0)]
+    POLKADEX,
+    #[codec(index = 1)]
+    DOT,
+}
+
+/// Id of an order in the runtime's orderbook.
+pub type OrderId = u64;
+
+/// A market of the orderbook, encoded like `pallet_orderbook::TradingPair`.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, scale::Encode, scale::Decode)]
+#[cfg_attr(feature = "std", derive(scale_info::TypeInfo))]
+pub struct TradingPair {
+    pub base: AssetId,
+    pub quote: AssetId,
+}
+
+/// Encoded like `pallet_orderbook::Side`.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, scale::Encode, scale::Decode)]
+#[cfg_attr(feature = "std", derive(scale_info::TypeInfo))]
+pub enum Side {
+    Bid,
+    Ask,
+}
+
+/// Encoded like `pallet_orderbook::TriggerKind`.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, scale::Encode, scale::Decode)]
+#[cfg_attr(feature = "std", derive(scale_info::TypeInfo))]
+pub enum TriggerKind {
+    Stop,
+    TakeProfit,
+}
+
+/// Encoded like `pallet_orderbook::Trigger`, with the price as the inner value of a
+/// `FixedU128`.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, scale::Encode, scale::Decode)]
+#[cfg_attr(feature = "std", derive(scale_info::TypeInfo))]
+pub struct Trigger {
+    pub kind: TriggerKind,
+    pub price: u128,
+}
+
+/// Encoded like `pallet_orderbook::TimeInForce`.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, scale::Encode, scale::Decode)]
+#[cfg_attr(feature = "std", derive(scale_info::TypeInfo))]
+pub enum TimeInForce {
+    GoodTillCancelled,
+    ImmediateOrCancel,
+    FillOrKill,
+    PostOnly,
+    GoodTillBlock(u32),
+}
+
+/// Functions of the runtime's `PolkadexExtension`.
+#[ink::chain_extension]
+pub trait PolkadexExtension {
+    type ErrorCode = PolkadexExtensionError;
+
+    #[ink(extension = 1001, returns_result = false, handle_status = false)]
+    fn free_balance(asset_id: AssetId, who: ink_env::AccountId) -> u128;
+
+    #[ink(extension = 1002, returns_result = false, handle_status = false)]
+    fn total_balance(asset_id: AssetId, who: ink_env::AccountId) -> u128;
+
+    #[ink(extension = 1003, returns_result = false)]
+    fn transfer(asset_id: AssetId, to: ink_env::AccountId, amount: u128);
+
+    #[ink(extension = 1004, returns_result = false)]
+    fn place_order(
+        pair: TradingPair,
+        side: Side,
+        amount: u128,
+        limit: Option<u128>,
+        trigger: Option<Trigger>,
+        time_in_force: TimeInForce,
+    ) -> OrderId;
+
+    #[ink(extension = 1005, returns_result = false)]
+    fn cancel_order(id: OrderId);
+}
+
+/// Status codes returned by the runtime's `PolkadexExtension`.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, scale::Encode, scale::Decode)]
+#[cfg_attr(feature = "std", derive(scale_info::TypeInfo))]
+pub enum PolkadexExtensionError {
+    TransferFailed,
+    OrderFailed,
+}
+
+impl ink_env::chain_extension::FromStatusCode for PolkadexExtensionError {
+    fn from_status_code(status_code: u32) -> Result<(), Self> {
+        match status_code {
+            0 => Ok(()),
+            1 => Err(Self::TransferFailed),
+            2 => Err(Self::OrderFailed),
+            _ => panic!("encountered unknown status code"),
+        }
+    }
+}
+
+/// The default environment with the Polkadex chain extension plugged in.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "std", derive(scale_info::TypeInfo))]
+pub enum PolkadexEnvironment {}
+
+impl Environment for PolkadexEnvironment {
+    const MAX_EVENT_TOPICS: usize = <ink_env::DefaultEnvironment as Environment>::MAX_EVENT_TOPICS;
+
+    type AccountId = <ink_env::DefaultEnvironment as Environment>::AccountId;
+    type Balance = <ink_env::DefaultEnvironment as Environment>::Balance;
+    type Hash = <ink_env::DefaultEnvironment as Environment>::Hash;
+    type BlockNumber = <ink_env::DefaultEnvironment as Environment>::BlockNumber;
+    type Timestamp = <ink_env::DefaultEnvironment as Environment>::Timestamp;
+    type RentFraction = <ink_env::DefaultEnvironment as Environment>::RentFraction;
+
+    type ChainExtension = PolkadexExtension;
+}
+
+#[ink::contract(env = crate::PolkadexEnvironment)]
+mod asset_vault {
+    use super::{AssetId, OrderId, PolkadexExtensionError, Side, TimeInForce, TradingPair};
+
+    /// Holds assets and lets its owner move them out or trade them.
+    #[ink(storage)]
+    pub struct AssetVault {
+        owner: AccountId,
+    }
+
+    #[derive(Debug, PartialEq, Eq, scale::Encode, scale::Decode)]
+    #[cfg_attr(feature = "std", derive(scale_info::TypeInfo))]
+    pub enum Error {
+        /// Only the owner may withdraw or trade.
+        NotOwner,
+        /// The runtime refused the transfer or the order.
+        Extension(PolkadexExtensionError),
+    }
+
+    impl From<PolkadexExtensionError> for Error {
+        fn from(e: PolkadexExtensionError) -> Self {
+            Error::Extension(e)
+        }
+    }
+
+    impl AssetVault {
+        #[ink(constructor, selector = "0x00000001")]
+        pub fn new() -> Self {
+            Self {
+                owner: Self::env().caller(),
+            }
+        }
+
+        /// Free balance of the vault in `asset_id`.
+        #[ink(message, selector = "0x00000002")]
+        pub fn balance_of(&self, asset_id: AssetId) -> Balance {
+            self.env()
+                .extension()
+                .free_balance(asset_id, self.env().account_id())
+        }
+
+        /// Sends `amount` of `asset_id` from the vault to `to`.
+        #[ink(message, selector = "0x00000003")]
+        pub fn withdraw(&mut self, asset_id: AssetId, to: AccountId, amount: Balance) -> Result<(), Error> {
+            if self.env().caller() != self.owner {
+                return Err(Error::NotOwner);
+            }
+            self.env().extension().transfer(asset_id, to, amount)?;
+            Ok(())
+        }
+
+        /// Places a good till cancelled order of the vault for `amount` of the base asset of
+        /// `pair`, at the `limit` price given as the inner value of a `FixedU128`.
+        #[ink(message, selector = "0x00000004")]
+        pub fn place_order(
+            &mut self,
+            pair: TradingPair,
+            side: Side,
+            amount: Balance,
+            limit: u128,
+        ) -> Result<OrderId, Error> {
+            if self.env().caller() != self.owner {
+                return Err(Error::NotOwner);
+            }
+            let id = self.env().extension().place_order(
+                pair,
+                side,
+                amount,
+                Some(limit),
+                None,
+                TimeInForce::GoodTillCancelled,
+            )?;
+            Ok(id)
+        }
+
+        /// Cancels the vault's order `id`.
+        #[ink(message, selector = "0x00000005")]
+        pub fn cancel_order(&mut self, id: OrderId) -> Result<(), Error> {
+            if self.env().caller() != self.owner {
+                return Err(Error::NotOwner);
+            }
+            self.env().extension().cancel_order(id)?;
+            Ok(())
+        }
+    }
+
+    #[cfg(test)]
+    mod tests {
+        use ink_lang as ink;
+
+        use super::*;
+
+        struct MockedBalance;
+
+        impl ink_env::test::ChainExtension for MockedBalance {
+            fn func_id(&self) -> u32 {
+                1001
+            }
+
+            fn call(&mut self, _input: &[u8], output: &mut Vec<u8>) -> u32 {
+                scale::Encode::encode_to(&100u128, output);
+                0
+            }
+        }
+
+        struct MockedTransfer(u32);
+
+        impl ink_env::test::ChainExtension for MockedTransfer {
+            fn func_id(&self) -> u32 {
+                1003
+            }
+
+            fn call(&mut self, _input: &[u8], _output: &mut Vec<u8>) -> u32 {
+                self.0
+            }
+        }
+
+        struct MockedPlaceOrder(u32);
+
+        impl ink_env::test::ChainExtension for MockedPlaceOrder {
+            fn func_id(&self) -> u32 {
+                1004
+            }
+
+            fn call(&mut self, _input: &[u8], output: &mut Vec<u8>) -> u32 {
+                scale::Encode::encode_to(&7u64, output);
+                self.0
+            }
+        }
+
+        const PAIR: TradingPair = TradingPair { base: AssetId::DOT, quote: AssetId::POLKADEX };
+
+        #[ink::test]
+        fn balance_of_works() {
+            ink_env::test::register_chain_extension(MockedBalance);
+            let vault = AssetVault::new();
+            assert_eq!(vault.balance_of(AssetId::DOT), 100);
+        }
+
+        #[ink::test]
+        fn withdraw_reports_failed_transfer() {
+            ink_env::test::register_chain_extension(MockedTransfer(1));
+            let mut vault = AssetVault::new();
+            assert_eq!(
+                vault.withdraw(AssetId::DOT, AccountId::from([2u8; 32]), 10),
+                Err(Error::Extension(PolkadexExtensionError::TransferFailed))
+            );
+        }
+
+        #[ink::test]
+        fn withdraw_works() {
+            ink_env::test::register_chain_extension(MockedTransfer(0));
+            let mut vault = AssetVault::new();
+            assert_eq!(vault.withdraw(AssetId::DOT, AccountId::from([2u8; 32]), 10), Ok(()));
+        }
+
+        #[ink::test]
+        fn place_order_returns_the_order_id() {
+            ink_env::test::register_chain_extension(MockedPlaceOrder(0));
+            let mut vault = AssetVault::new();
+            assert_eq!(vault.place_order(PAIR, Side::Ask, 10, 1), Ok(7));
+        }
+
+        #[ink::test]
+        fn place_order_reports_refused_orders() {
+            ink_env::test::register_chain_extension(MockedPlaceOrder(2));
+            let mut vault = AssetVault::new();
+            assert_eq!(
+                vault.place_order(PAIR, Side::Ask, 10, 1),
+                Err(Error::Extension(PolkadexExtensionError::OrderFailed))
+            );
+        }
+    }
+}
@@ -0,0 +1,252 @@
+// This file is part of Polkadex.
+
+// Copyright (C) 2020-2021 Polkadex oü.
+// SPDX-License-Identifier: GPL-3.0-or-later WITH Classpath-exception-2.0
+
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with this program. If not, see <https://www.gnu.org/licenses/>.
+
+//! Derivation of genesis authority keys.
+
+use std::path::PathBuf;
+
+use sc_keystore::LocalKeystore;
+use sp_core::{
+    crypto::{key_types, KeyTypeId, SecretString, Ss58Codec},
+    ed25519,
+    hexdisplay::HexDisplay,
+    sr25519, Pair,
+};
+use sp_keystore::SyncCryptoStore;
+use structopt::StructOpt;
+
+/// Keys of a genesis authority derived from a secret.
+///
+/// Every key is derived with its own hard junction: `<secret>//stash`, `<secret>//controller`,
+/// `<secret>//grandpa`, `<secret>//babe`, `<secret>//im_online` and
+/// `<secret>//authority_discovery`.
+///
+/// Authorities with an index follow the layout of the staging testnet instead: the accounts
+/// are derived with soft junctions, as in `<secret>/stash/<index>`, and the session keys with
+/// hard ones, as in `<secret>//babe//<index>`.
+pub struct DerivedAuthority {
+    secret: String,
+    index: Option<u32>,
+    pub stash: sr25519::Public,
+    pub controller: sr25519::Public,
+    pub grandpa: ed25519::Public,
+    pub babe: sr25519::Public,
+    pub im_online: sr25519::Public,
+    pub authority_discovery: sr25519::Public,
+}
+
+fn suri(secret: &str, role: &str, index: Option<u32>) -> String {
+    match index {
+        Some(index) if role == "stash" || role == "controller" => format!("{}/{}/{}", secret, role, index),
+        Some(index) => format!("{}//{}//{}", secret, role, index),
+        None => format!("{}//{}", secret, role),
+    }
+}
+
+fn public<P: Pair>(secret: &str, role: &str, index: Option<u32>) -> Result<P::Public, String> {
+    P::from_string(&suri(secret, role, index), None)
+        .map(|pair| pair.public())
+        .map_err(|e| format!("Invalid secret: {:?}", e))
+}
+
+impl DerivedAuthority {
+    /// Derives the keys of an authority from a secret phrase, seed or URI, and the authority's
+    /// `index` if it has one.
+    pub fn from_secret(secret: &str, index: Option<u32>) -> Result<Self, String> {
+        Ok(DerivedAuthority {
+            secret: secret.to_owned(),
+            index,
+            stash: public::<sr25519::Pair>(secret, "stash", index)?,
+            controller: public::<sr25519::Pair>(secret, "controller", index)?,
+            grandpa: public::<ed25519::Pair>(secret, "grandpa", index)?,
+            babe: public::<sr25519::Pair>(secret, "babe", index)?,
+            im_online: public::<sr25519::Pair>(secret, "im_online", index)?,
+            authority_discovery: public::<sr25519::Pair>(secret, "authority_discovery", index)?,
+        })
+    }
+
+    fn session_keys(&self) -> [(&'static str, KeyTypeId, &[u8]); 4] {
+        [
+            ("grandpa", key_types::GRANDPA, &self.grandpa.0[..]),
+            ("babe", key_types::BABE, &self.babe.0[..]),
+            ("im_online", key_types::IM_ONLINE, &self.im_online.0[..]),
+            (
+                "authority_discovery",
+                key_types::AUTHORITY_DISCOVERY,
+                &self.authority_discovery.0[..],
+            ),
+        ]
+    }
+
+    /// Inserts the session keys into `keystore`.
+    pub fn insert_session_keys(&self, keystore: &dyn SyncCryptoStore) -> Result<(), String> {
+        for (role, key_type, public) in self.session_keys().iter() {
+            SyncCryptoStore::insert_unknown(keystore, *key_type, &suri(&self.secret, role, self.index), public)
+                .map_err(|_| format!("Failed to insert the {} key into the keystore", role))?;
+        }
+        Ok(())
+    }
+
+    /// The authority tuple taken by `testnet_genesis`, in the format of the chain spec sources.
+    pub fn to_chain_spec_tuple(&self) -> String {
+        let accounts = [(self.stash, "stash"), (self.controller, "controller")];
+        let session_keys = [
+            (self.grandpa.to_ss58check(), self.grandpa.0, "grandpa"),
+            (self.babe.to_ss58check(), self.babe.0, "babe"),
+            (self.im_online.to_ss58check(), self.im_online.0, "im_online"),
+            (
+                self.authority_discovery.to_ss58check(),
+                self.authority_discovery.0,
+                "authority_discovery",
+            ),
+        ];
+
+        let mut tuple = String::from("(\n");
+        for (public, role) in accounts.iter() {
+            tuple += &format!(
+                "    // {} ({})\n    hex![\"{}\"].into(),\n",
+                public.to_ss58check(),
+                role,
+                HexDisplay::from(&public.0),
+            );
+        }
+        for (ss58, public, role) in session_keys.iter() {
+            tuple += &format!(
+                "    // {} ({})\n    hex![\"{}\"]\n        .unchecked_into(),\n",
+                ss58,
+                role,
+                HexDisplay::from(public),
+            );
+        }
+        tuple.push(')');
+        tuple
+    }
+}
+
+/// The `generate-authority` command used to derive the keys of a genesis authority.
+#[derive(Debug, StructOpt)]
+pub struct GenerateAuthorityCmd {
+    /// Secret phrase, seed or URI of the authority, or a file containing it. Prompted for if
+    /// not given.
+    #[structopt(long)]
+    pub secret: Option<String>,
+
+    /// Index of the authority, deriving its keys with the layout of the staging testnet:
+    /// `<secret>/stash/<index>`, `<secret>/controller/<index>` and `<secret>//<key>//<index>`.
+    #[structopt(long)]
+    pub index: Option<u32>,
+
+    /// Keystore directory to insert the session keys into.
+    #[structopt(long, parse(from_os_str))]
+    pub keystore_path: Option<PathBuf>,
+
+    /// Password of the keystore.
+    #[structopt(long, requires = "keystore-path")]
+    pub password: Option<String>,
+}
+
+impl GenerateAuthorityCmd {
+    /// Run the generate-authority command
+    pub fn run(&self) -> sc_cli::Result<()> {
+        let secret = sc_cli::utils::read_uri(self.secret.as_ref())?;
+        let authority = DerivedAuthority::from_secret(&secret, self.index)?;
+
+        if let Some(path) = &self.keystore_path {
+            let password = self.password.clone().map(SecretString::new);
+            let keystore = LocalKeystore::open(path, password)
+                .map_err(|e| format!("Failed to open keystore {}: {}", path.display(), e))?;
+            authority.insert_session_keys(&keystore)?;
+        }
+
+        println!("{}", authority.to_chain_spec_tuple());
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::BTreeSet;
+
+    use super::*;
+
+    const SECRET: &str =
+        "bottom drive obey lake curtain smoke basket hold race lonely fit walk//polkadex//1";
+
+    #[test]
+    fn derives_distinct_keys() {
+        let authority = DerivedAuthority::from_secret(SECRET, None).unwrap();
+        let keys = vec![
+            authority.stash.0,
+            authority.controller.0,
+            authority.grandpa.0,
+            authority.babe.0,
+            authority.im_online.0,
+            authority.authority_discovery.0,
+        ];
+        assert_eq!(keys.iter().collect::<BTreeSet<_>>().len(), keys.len());
+
+        let again = DerivedAuthority::from_secret(SECRET, None).unwrap();
+        assert_eq!(again.to_chain_spec_tuple(), authority.to_chain_spec_tuple());
+        assert!(DerivedAuthority::from_secret("not a secret phrase", None).is_err());
+    }
+
+    #[test]
+    fn indexed_authorities_follow_the_staging_layout() {
+        let authority = DerivedAuthority::from_secret(SECRET, Some(3)).unwrap();
+        let derive = |suri: String| sr25519::Pair::from_string(&suri, None).unwrap().public();
+        assert_eq!(authority.stash, derive(format!("{}/stash/3", SECRET)));
+        assert_eq!(authority.controller, derive(format!("{}/controller/3", SECRET)));
+        assert_eq!(authority.babe, derive(format!("{}//babe//3", SECRET)));
+        assert_eq!(authority.im_online, derive(format!("{}//im_online//3", SECRET)));
+
+        let keys = vec![
+            authority.stash.0,
+            authority.controller.0,
+            authority.grandpa.0,
+            authority.babe.0,
+            authority.im_online.0,
+            authority.authority_discovery.0,
+        ];
+        assert_eq!(keys.iter().collect::<BTreeSet<_>>().len(), keys.len());
+    }
+
+    #[test]
+    fn inserts_session_keys() {
+        let dir = tempfile::tempdir().unwrap();
+        let keystore = LocalKeystore::open(dir.path(), None).unwrap();
+        let authority = DerivedAuthority::from_secret(SECRET, None).unwrap();
+        authority.insert_session_keys(&keystore).unwrap();
+
+        let keys = authority
+            .session_keys()
+            .iter()
+            .map(|(_, key_type, public)| (public.to_vec(), *key_type))
+            .collect::<Vec<_>>();
+        assert!(SyncCryptoStore::has_keys(&keystore, &keys));
+    }
+
+    #[test]
+    fn prints_chain_spec_tuple() {
+        let authority = DerivedAuthority::from_secret(SECRET, None).unwrap();
+        let tuple = authority.to_chain_spec_tuple();
+        assert_eq!(tuple.matches(".into()").count(), 2);
+        assert_eq!(tuple.matches(".unchecked_into()").count(), 4);
+        assert!(tuple.contains(&authority.babe.to_ss58check()));
+        assert!(tuple.contains(&format!("{}", HexDisplay::from(&authority.grandpa.0))));
+    }
+}
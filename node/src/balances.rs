@@ -0,0 +1,294 @@
+// This file is part of Polkadex.
+
+// Copyright (C) 2020-2021 Polkadex oü.
+// SPDX-License-Identifier: GPL-3.0-or-later WITH Classpath-exception-2.0
+
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with this program. If not, see <https://www.gnu.org/licenses/>.
+//! RPC for the balances of an account in every asset, served by full and light nodes alike.
+//!
+//! PDEX lives in `pallet_balances` and the other assets in `orml_tokens`. Both are read
+//! straight from storage, so a light client only needs a remote read proof to answer.
+
+use std::sync::Arc;
+
+use codec::Decode;
+use frame_support::storage::{StorageDoubleMap, StorageMap};
+use futures::{FutureExt, TryFutureExt};
+use jsonrpc_core::{BoxFuture, Error as RpcError, ErrorCode, Result};
+use jsonrpc_derive::rpc;
+use node_polkadex_runtime::Runtime;
+use polkadex_primitives::assets::AssetId;
+use polkadex_primitives::{AccountId, Balance, Block, Hash, Index};
+use sc_client_api::light::{future_header, Fetcher, RemoteBlockchain, RemoteReadRequest};
+use sc_client_api::StorageProvider;
+use serde::Serialize;
+use sp_blockchain::HeaderBackend;
+use sp_core::storage::StorageKey;
+use sp_rpc::number::NumberOrHex;
+use sp_runtime::generic::BlockId;
+
+use crate::genesis_file::parse_asset;
+
+/// Balance of an account in one asset.
+#[derive(Clone, Debug, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AssetBalance {
+    /// Symbol of the asset, as given in the request.
+    pub asset: String,
+    /// Balance that can be transferred, minus `frozen`.
+    pub free: NumberOrHex,
+    /// Balance reserved by pallets, e.g. for open orders or deposits.
+    pub reserved: NumberOrHex,
+    /// Part of `free` that cannot be transferred.
+    pub frozen: NumberOrHex,
+}
+
+/// Balances of an account across assets.
+#[rpc]
+pub trait BalancesApi<BlockHash> {
+    /// Balances of `account` in each of `assets`, given by symbol such as `PDEX` or `DOT`.
+    #[rpc(name = "assets_balances")]
+    fn balances(
+        &self,
+        account: AccountId,
+        assets: Vec<String>,
+        at: Option<BlockHash>,
+    ) -> BoxFuture<Vec<AssetBalance>>;
+}
+
+/// Error codes of this RPC.
+enum Error {
+    /// An asset symbol is unknown.
+    UnknownAsset,
+    /// Storage could not be read.
+    StorageError,
+}
+
+impl From<Error> for i64 {
+    fn from(e: Error) -> i64 {
+        match e {
+            Error::UnknownAsset => 1,
+            Error::StorageError => 2,
+        }
+    }
+}
+
+fn rpc_error(code: Error, message: &str, data: impl std::fmt::Debug) -> RpcError {
+    RpcError {
+        code: ErrorCode::ServerError(code.into()),
+        message: message.into(),
+        data: Some(format!("{:?}", data).into()),
+    }
+}
+
+/// Storage key holding the balance of `account` in `asset`.
+///
+/// PDEX is the native currency of `orml_currencies`, kept in `System::Account`; every other
+/// asset is kept in `Tokens::Accounts`.
+fn balance_key(account: &AccountId, asset: AssetId) -> StorageKey {
+    let key = if asset == AssetId::POLKADEX {
+        frame_system::Account::<Runtime>::hashed_key_for(account)
+    } else {
+        orml_tokens::Accounts::<Runtime>::hashed_key_for(account, asset)
+    };
+    StorageKey(key)
+}
+
+/// Decodes the value stored under `balance_key`, a missing value meaning an empty account.
+fn decode_balance(symbol: String, asset: AssetId, value: Option<Vec<u8>>) -> Result<AssetBalance> {
+    let decode_error = |e| rpc_error(Error::StorageError, "Unable to decode balance.", e);
+    let (free, reserved, frozen) = match value {
+        None => (0, 0, 0),
+        Some(value) if asset == AssetId::POLKADEX => {
+            let info = frame_system::AccountInfo::<Index, pallet_balances::AccountData<Balance>>::decode(
+                &mut &value[..],
+            )
+            .map_err(decode_error)?;
+            let data = info.data;
+            (data.free, data.reserved, data.misc_frozen.max(data.fee_frozen))
+        }
+        Some(value) => {
+            let data =
+                orml_tokens::AccountData::<Balance>::decode(&mut &value[..]).map_err(decode_error)?;
+            (data.free, data.reserved, data.frozen)
+        }
+    };
+    Ok(AssetBalance {
+        asset: symbol,
+        free: NumberOrHex::Hex(free.into()),
+        reserved: NumberOrHex::Hex(reserved.into()),
+        frozen: NumberOrHex::Hex(frozen.into()),
+    })
+}
+
+fn parse_assets(assets: Vec<String>) -> Result<Vec<(String, AssetId)>> {
+    assets
+        .into_iter()
+        .map(|symbol| {
+            let asset = parse_asset(&symbol.to_uppercase())
+                .map_err(|e| rpc_error(Error::UnknownAsset, "Unknown asset.", e))?;
+            Ok((symbol, asset))
+        })
+        .collect()
+}
+
+/// Implements [`BalancesApi`] by reading the local database of a full node.
+pub struct FullBalances<C, B> {
+    client: Arc<C>,
+    _backend: std::marker::PhantomData<B>,
+}
+
+impl<C, B> FullBalances<C, B> {
+    /// Create a new `FullBalances` for the given client.
+    pub fn new(client: Arc<C>) -> Self {
+        FullBalances {
+            client,
+            _backend: Default::default(),
+        }
+    }
+}
+
+impl<C, B> BalancesApi<Hash> for FullBalances<C, B>
+where
+    C: StorageProvider<Block, B> + HeaderBackend<Block> + Send + Sync + 'static,
+    B: sc_client_api::Backend<Block> + Send + Sync + 'static,
+{
+    fn balances(
+        &self,
+        account: AccountId,
+        assets: Vec<String>,
+        at: Option<Hash>,
+    ) -> BoxFuture<Vec<AssetBalance>> {
+        // If the block hash is not supplied assume the best block.
+        let at = BlockId::hash(at.unwrap_or_else(|| self.client.info().best_hash));
+        let balances = parse_assets(assets).and_then(|assets| {
+            assets
+                .into_iter()
+                .map(|(symbol, asset)| {
+                    let value = self
+                        .client
+                        .storage(&at, &balance_key(&account, asset))
+                        .map_err(|e| rpc_error(Error::StorageError, "Unable to read balance.", e))?;
+                    decode_balance(symbol, asset, value.map(|data| data.0))
+                })
+                .collect()
+        });
+        Box::new(jsonrpc_core::futures::future::result(balances))
+    }
+}
+
+/// Implements [`BalancesApi`] by fetching read proofs from full nodes.
+pub struct LightBalances<C, F> {
+    client: Arc<C>,
+    remote_blockchain: Arc<dyn RemoteBlockchain<Block>>,
+    fetcher: Arc<F>,
+}
+
+impl<C, F> LightBalances<C, F> {
+    /// Create a new `LightBalances` for the given client.
+    pub fn new(
+        client: Arc<C>,
+        remote_blockchain: Arc<dyn RemoteBlockchain<Block>>,
+        fetcher: Arc<F>,
+    ) -> Self {
+        LightBalances {
+            client,
+            remote_blockchain,
+            fetcher,
+        }
+    }
+}
+
+impl<C, F> BalancesApi<Hash> for LightBalances<C, F>
+where
+    C: HeaderBackend<Block> + Send + Sync + 'static,
+    F: Fetcher<Block> + 'static,
+{
+    fn balances(
+        &self,
+        account: AccountId,
+        assets: Vec<String>,
+        at: Option<Hash>,
+    ) -> BoxFuture<Vec<AssetBalance>> {
+        let at = at.unwrap_or_else(|| self.client.info().best_hash);
+        let header = future_header(&*self.remote_blockchain, &*self.fetcher, BlockId::hash(at));
+        let fetcher = self.fetcher.clone();
+
+        let balances = async move {
+            let assets = parse_assets(assets)?;
+            let header = header
+                .await
+                .map_err(|e| rpc_error(Error::StorageError, "Unable to fetch header.", e))?
+                .ok_or_else(|| rpc_error(Error::StorageError, "Unknown block.", at))?;
+            let keys = assets
+                .iter()
+                .map(|(_, asset)| balance_key(&account, *asset).0)
+                .collect::<Vec<_>>();
+            let mut values = fetcher
+                .remote_read(RemoteReadRequest {
+                    block: at,
+                    header,
+                    keys: keys.clone(),
+                    retry_count: None,
+                })
+                .await
+                .map_err(|e| rpc_error(Error::StorageError, "Unable to read balance.", e))?;
+            assets
+                .into_iter()
+                .zip(keys)
+                .map(|((symbol, asset), key)| {
+                    decode_balance(symbol, asset, values.remove(&key).flatten())
+                })
+                .collect()
+        };
+        Box::new(balances.boxed().compat())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use node_polkadex_runtime::Currencies;
+    use orml_traits::MultiCurrency;
+    use sp_runtime::BuildStorage;
+
+    use super::*;
+    use crate::chain_spec::{development_config, get_account_id_from_seed};
+
+    fn read(account: &AccountId, asset: AssetId) -> AssetBalance {
+        let value = sp_io::storage::get(&balance_key(account, asset).0);
+        decode_balance("".into(), asset, value).unwrap()
+    }
+
+    #[test]
+    fn balances_are_read_from_storage() {
+        let storage = development_config().unwrap().build_storage().unwrap();
+        let alice = get_account_id_from_seed::<sp_core::sr25519::Public>("Alice");
+        sp_io::TestExternalities::new(storage).execute_with(|| {
+            for asset in &[AssetId::POLKADEX, AssetId::DOT, AssetId::USD] {
+                let free = <Currencies as MultiCurrency<AccountId>>::free_balance(*asset, &alice);
+                assert!(free > 0);
+                assert_eq!(read(&alice, *asset).free, NumberOrHex::Hex(free.into()));
+            }
+        });
+    }
+
+    #[test]
+    fn empty_accounts_have_no_balance() {
+        sp_io::TestExternalities::new(Default::default()).execute_with(|| {
+            let balance = read(&AccountId::new([7; 32]), AssetId::BTC);
+            assert_eq!(balance.free, NumberOrHex::Hex(0.into()));
+            assert_eq!(balance.reserved, NumberOrHex::Hex(0.into()));
+        });
+    }
+}
@@ -1,5 +1,6 @@
-use frame_benchmarking::frame_support::PalletId;
 use grandpa_primitives::AuthorityId as GrandpaId;
+use std::collections::{BTreeMap, BTreeSet};
+
 use hex_literal::hex;
 use pallet_im_online::sr25519::AuthorityId as ImOnlineId;
 pub use polkadex_primitives::{AccountId, Balance, Signature};
@@ -19,7 +20,8 @@ use sp_runtime::{
 
 use node_polkadex_runtime::{
     AuthorityDiscoveryConfig, BabeConfig, BalancesConfig, ContractsConfig, CouncilConfig,
-    ElectionsConfig, GrandpaConfig, ImOnlineConfig, IndicesConfig, MAX_NOMINATIONS, OrmlVestingConfig, SessionConfig,
+    ElectionsConfig, ExistentialDeposit, GrandpaConfig, ImOnlineConfig, IndicesConfig, MAX_NOMINATIONS,
+    OcexConfig, OcexPalletId, OrmlVestingConfig, PriceOracleConfig, SessionConfig,
     SessionKeys, StakerStatus, StakingConfig, SudoConfig, SystemConfig, TechnicalCommitteeConfig,
     TokensConfig, wasm_binary_unwrap,
 };
@@ -30,6 +32,13 @@ type AccountPublic = <Signature as Verify>::Signer;
 
 const STAGING_TELEMETRY_URL: &str = "wss://telemetry.polkadot.io/submit/";
 
+/// Balance given to every endowed account of the built-in chain specs.
+pub const ENDOWMENT: Balance = 10_000_000 * DOLLARS;
+/// Amount of every asset given to the token-endowed accounts of the built-in chain specs.
+pub const TOKEN_ENDOWMENT: Balance = 1_000_000_000_000_000_000;
+/// Amount bonded by every genesis staker and staked by every genesis council member.
+pub const STASH: Balance = ENDOWMENT / 1000;
+
 /// Node `ChainSpec` extensions.
 ///
 /// Additional parameters for some Substrate core modules,
@@ -41,6 +50,79 @@ pub struct Extensions {
     pub fork_blocks: sc_client_api::ForkBlocks<Block>,
     /// Known bad block hashes.
     pub bad_blocks: sc_client_api::BadBlocks<Block>,
+    /// GRANDPA finality tuning.
+    #[serde(default)]
+    pub grandpa: GrandpaSettings,
+}
+
+/// GRANDPA finality tuning, customizable from the chain spec and overridden from the CLI.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", default, deny_unknown_fields)]
+pub struct GrandpaSettings {
+    /// Interval between gossip rounds of GRANDPA messages, in milliseconds.
+    pub gossip_duration_ms: u64,
+    /// Number of blocks between the justifications generated for finalized blocks.
+    pub justification_period: u32,
+    /// Rule restricting the block voted on.
+    pub voting_rule: VotingRule,
+    /// Whether non-authority nodes run the GRANDPA observer instead of the full voter.
+    pub observer: bool,
+}
+
+impl Default for GrandpaSettings {
+    fn default() -> Self {
+        GrandpaSettings {
+            gossip_duration_ms: 333,
+            justification_period: 512,
+            voting_rule: VotingRule::Default,
+            observer: false,
+        }
+    }
+}
+
+impl GrandpaSettings {
+    /// The settings of `chain_spec`, or the default ones if it has none.
+    pub fn from_chain_spec(chain_spec: &dyn sc_service::ChainSpec) -> Self {
+        sc_chain_spec::get_extension::<GrandpaSettings>(chain_spec.extensions())
+            .cloned()
+            .unwrap_or_default()
+    }
+}
+
+/// Rule restricting the block voted on by GRANDPA.
+#[derive(Clone, Copy, Debug, PartialEq, Serialize, Deserialize)]
+pub enum VotingRule {
+    /// `BeforeBestBlockBy(2)` and `ThreeQuartersOfTheUnfinalizedChain`.
+    Default,
+    /// Vote on a block at least this many blocks behind the best block.
+    BeforeBestBlockBy(u32),
+    /// Vote on a block at most three quarters of the way into the unfinalized chain.
+    ThreeQuartersOfTheUnfinalizedChain,
+    /// Vote on the best block.
+    None,
+}
+
+impl std::str::FromStr for VotingRule {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "default" => Ok(VotingRule::Default),
+            "three-quarters" => Ok(VotingRule::ThreeQuartersOfTheUnfinalizedChain),
+            "none" => Ok(VotingRule::None),
+            _ => s
+                .strip_prefix("before-best:")
+                .and_then(|blocks| blocks.parse().ok())
+                .map(VotingRule::BeforeBestBlockBy)
+                .ok_or_else(|| {
+                    format!(
+                        "Invalid voting rule {:?}, expected one of `default`, `before-best:<blocks>`, \
+                         `three-quarters` or `none`",
+                        s
+                    )
+                }),
+        }
+    }
 }
 
 /// Specialized `ChainSpec`.
@@ -65,11 +147,14 @@ fn session_keys(
 }
 
 fn staging_testnet_config_genesis() -> GenesisConfig {
-    // stash, controller, session-key
+    // stash, controller, session-keys
     // generated with secret:
     // for i in 1 2 3 4 ; do for j in stash controller; do subkey inspect "$secret"/fir/$j/$i; done; done
-    // and
-    // for i in 1 2 3 4 ; do for j in session; do subkey --ed25519 inspect "$secret"//fir//$j//$i; done; done
+    // and the session keys with:
+    // for i in 1 2 3 4 ; do polkadex-node generate-authority --secret "$session_secret"//fir --index $i; done
+    //
+    // keeping only the session keys of its output, one per role, such as
+    // "$session_secret"//fir//babe//$i.
 
     let initial_authorities: Vec<(
         AccountId,
@@ -84,17 +169,17 @@ fn staging_testnet_config_genesis() -> GenesisConfig {
             hex!["9c7a2ee14e565db0c69f78c7b4cd839fbf52b607d867e9e9c5a79042898a0d12"].into(),
             // 5EnCiV7wSHeNhjW3FSUwiJNkcc2SBkPLn5Nj93FmbLtBjQUq
             hex!["781ead1e2fa9ccb74b44c19d29cb2a7a4b5be3972927ae98cd3877523976a276"].into(),
-            // 5Fb9ayurnxnaXj56CjmyQLBiadfRCqUbL2VWNbbe1nZU6wiC
-            hex!["9becad03e6dcac03cee07edebca5475314861492cdfc96a2144a67bbe9699332"]
+            // 5F9kWZrdka5XtKjpJpgCTs4UdUueWJCQKye9FTExYTRoZBav
+            hex!["888ddc0eb3ccd7537a1f4a5ebab6278439fe60480d5e6ed58555b32fa5781862"]
                 .unchecked_into(),
-            // 5EZaeQ8djPcq9pheJUhgerXQZt9YaHnMJpiHMRhwQeinqUW8
-            hex!["6e7e4eb42cbd2e0ab4cae8708ce5509580b8c04d11f6758dbf686d50fe9f9106"]
+            // 5CDkjxZYhWraPNN1MWwG6TBZ6dsiMiDWTBSdwynowgR6dUTq
+            hex!["06e6eb083a6721c4cd821b856460dd112fe35ab9b543e246a18b9b1402a40c6c"]
                 .unchecked_into(),
-            // 5EZaeQ8djPcq9pheJUhgerXQZt9YaHnMJpiHMRhwQeinqUW8
-            hex!["6e7e4eb42cbd2e0ab4cae8708ce5509580b8c04d11f6758dbf686d50fe9f9106"]
+            // 5HdHnVgUnfXNW6AxPDoaJ3QGA9otx2qujXDErAXVekahgkKA
+            hex!["f6073b704702e9586aeba3f0a11c92e9ee8f75a515b7404f7c1add172ae0a327"]
                 .unchecked_into(),
-            // 5EZaeQ8djPcq9pheJUhgerXQZt9YaHnMJpiHMRhwQeinqUW8
-            hex!["6e7e4eb42cbd2e0ab4cae8708ce5509580b8c04d11f6758dbf686d50fe9f9106"]
+            // 5DFUorUHAHQrQNEG6b71FApWnpZU6J64RyzqXz2cwp7K3R7f
+            hex!["347401f34827c31b3009a2449605d980d34072391bd6a2a91d5a2dded79b4e30"]
                 .unchecked_into(),
         ),
         (
@@ -102,17 +187,17 @@ fn staging_testnet_config_genesis() -> GenesisConfig {
             hex!["68655684472b743e456907b398d3a44c113f189e56d1bbfd55e889e295dfde78"].into(),
             // 5Gc4vr42hH1uDZc93Nayk5G7i687bAQdHHc9unLuyeawHipF
             hex!["c8dc79e36b29395413399edaec3e20fcca7205fb19776ed8ddb25d6f427ec40e"].into(),
-            // 5EockCXN6YkiNCDjpqqnbcqd4ad35nU4RmA1ikM4YeRN4WcE
-            hex!["7932cff431e748892fa48e10c63c17d30f80ca42e4de3921e641249cd7fa3c2f"]
+            // 5G9x7hEp5TVPf19Doi6adZLTiMM9ePtjeXUijHiQ9ttteoFi
+            hex!["b4f1293fb87ec0d80aeb1b5426ea06e8c1f4f369c9c9a45be105425c0ba694d1"]
                 .unchecked_into(),
-            // 5DhLtiaQd1L1LU9jaNeeu9HJkP6eyg3BwXA7iNMzKm7qqruQ
-            hex!["482dbd7297a39fa145c570552249c2ca9dd47e281f0c500c971b59c9dcdcd82e"]
+            // 5EeNJaApxaFwCg1S7qJuEyLECXCyTAvxtdBXiTqEwy3LuPHG
+            hex!["7224fcf4b9a105ea4a38a069355b839c8abd13fce9b66b5ad1fa22227a8cf24e"]
                 .unchecked_into(),
-            // 5DhLtiaQd1L1LU9jaNeeu9HJkP6eyg3BwXA7iNMzKm7qqruQ
-            hex!["482dbd7297a39fa145c570552249c2ca9dd47e281f0c500c971b59c9dcdcd82e"]
+            // 5DJA3VozRDQ9fiCWYT4hmaeUumBCfJFGoQCMVKojERLKKDbu
+            hex!["367e937575198183ebd87ae91fcb82c261dcfd6f9a738e69d6d3f08b99af7b6a"]
                 .unchecked_into(),
-            // 5DhLtiaQd1L1LU9jaNeeu9HJkP6eyg3BwXA7iNMzKm7qqruQ
-            hex!["482dbd7297a39fa145c570552249c2ca9dd47e281f0c500c971b59c9dcdcd82e"]
+            // 5FR9ch83Tqnyk5Zv6TWYRELoFX91czxGxcgwcPha7NTETNzE
+            hex!["944c5231f9d2948ce3b777ea4dfe28c676cd3bf1de72ff25b99d751576831346"]
                 .unchecked_into(),
         ),
         (
@@ -120,17 +205,17 @@ fn staging_testnet_config_genesis() -> GenesisConfig {
             hex!["547ff0ab649283a7ae01dbc2eb73932eba2fb09075e9485ff369082a2ff38d65"].into(),
             // 5FeD54vGVNpFX3PndHPXJ2MDakc462vBCD5mgtWRnWYCpZU9
             hex!["9e42241d7cd91d001773b0b616d523dd80e13c6c2cab860b1234ef1b9ffc1526"].into(),
-            // 5E1jLYfLdUQKrFrtqoKgFrRvxM3oQPMbf6DfcsrugZZ5Bn8d
-            hex!["5633b70b80a6c8bb16270f82cca6d56b27ed7b76c8fd5af2986a25a4788ce440"]
+            // 5DfakSXL3R96C8y6JsPnDsx5At6awi63PMT9ejU3HtN66pho
+            hex!["46d5e5a4775fa8112c5196c9ef172438188d99b7d96546fe4308575dd6c290be"]
                 .unchecked_into(),
-            // 5DhKqkHRkndJu8vq7pi2Q5S3DfftWJHGxbEUNH43b46qNspH
-            hex!["482a3389a6cf42d8ed83888cfd920fec738ea30f97e44699ada7323f08c3380a"]
+            // 5FA915j1MGRoFLW31hmN9qe66JbJibX2r32XqJGV2GJ3bzyT
+            hex!["88d992bd410686e4ec7d1b81130e00e8d6381d46611f9779b698e0c5672b3e08"]
                 .unchecked_into(),
-            // 5DhKqkHRkndJu8vq7pi2Q5S3DfftWJHGxbEUNH43b46qNspH
-            hex!["482a3389a6cf42d8ed83888cfd920fec738ea30f97e44699ada7323f08c3380a"]
+            // 5HVkVGT13AhNEuKzPWC9cMTqNEaJpGpw4T8DoQP6EuYPC5KE
+            hex!["f04728bcf9422a0d082d151c8c3a75fa800517cc1a24932df198acb934f7457e"]
                 .unchecked_into(),
-            // 5DhKqkHRkndJu8vq7pi2Q5S3DfftWJHGxbEUNH43b46qNspH
-            hex!["482a3389a6cf42d8ed83888cfd920fec738ea30f97e44699ada7323f08c3380a"]
+            // 5GRheYbdGma62cEgt4mVbPkzNQTJgJNWh5FtF7xfvdwLwPJL
+            hex!["c0f46238d2004c9d14d40d438724ad4946578085fb19d265c26b78742b482373"]
                 .unchecked_into(),
         ),
         (
@@ -138,17 +223,17 @@ fn staging_testnet_config_genesis() -> GenesisConfig {
             hex!["f26cdb14b5aec7b2789fd5ca80f979cef3761897ae1f37ffb3e154cbcc1c2663"].into(),
             // 5EPQdAQ39WQNLCRjWsCk5jErsCitHiY5ZmjfWzzbXDoAoYbn
             hex!["66bc1e5d275da50b72b15de072a2468a5ad414919ca9054d2695767cf650012f"].into(),
-            // 5DMa31Hd5u1dwoRKgC4uvqyrdK45RHv3CpwvpUC1EzuwDit4
-            hex!["3919132b851ef0fd2dae42a7e734fe547af5a6b809006100f48944d7fae8e8ef"]
+            // 5DYuE7yfSLfvY2RjqL8uRueWP25C8uh29EjVBqYKxHv6C9Pg
+            hex!["41bd6110d9c34e76b0f8e4fbcc435f9ce0ede40dfd0910decf7ba7607357fb06"]
                 .unchecked_into(),
-            // 5C4vDQxA8LTck2xJEy4Yg1hM9qjDt4LvTQaMo4Y8ne43aU6x
-            hex!["00299981a2b92f878baaf5dbeba5c18d4e70f2a1fcd9c61b32ea18daf38f4378"]
+            // 5D25aqRbB1mXem2jv4r6K793erasUasQKCXJNukZwFtyKPUQ
+            hex!["2a3ba30bb50154ab5df2ff9eda5627414e799dc7f0b656b153c3140e017e5d40"]
                 .unchecked_into(),
-            // 5C4vDQxA8LTck2xJEy4Yg1hM9qjDt4LvTQaMo4Y8ne43aU6x
-            hex!["00299981a2b92f878baaf5dbeba5c18d4e70f2a1fcd9c61b32ea18daf38f4378"]
+            // 5DLf7PQiyWLz8o9RjksurjSnVciGXmZF7vRC4uZUYRDcPXJi
+            hex!["3866ea4f76b19e400439695f880ffd684c43668decc010fe1d283b3be58fad27"]
                 .unchecked_into(),
-            // 5C4vDQxA8LTck2xJEy4Yg1hM9qjDt4LvTQaMo4Y8ne43aU6x
-            hex!["00299981a2b92f878baaf5dbeba5c18d4e70f2a1fcd9c61b32ea18daf38f4378"]
+            // 5EZzQwpRUFgYVVQYLaebBHrWZeTZYgqhcEQTm2Xjc2ajE7TD
+            hex!["6ece515ac0e3b105e623e502a3770d3e2b2ff7a6610cb7134c0e05ef4ae06b13"]
                 .unchecked_into(),
         ),
     ];
@@ -165,16 +250,19 @@ fn staging_testnet_config_genesis() -> GenesisConfig {
     testnet_genesis(
         initial_authorities,
         vec![],
-        root_key,
+        root_key.clone(),
         Some(endowed_accounts),
+        token_endowments(&[root_key]),
+        vec![],
         false,
     )
 }
 
 /// Staging testnet config.
-pub fn staging_testnet_config() -> ChainSpec {
+pub fn staging_testnet_config() -> Result<ChainSpec, String> {
+    validate_genesis(&staging_testnet_config_genesis())?;
     let boot_nodes = vec![];
-    ChainSpec::from_genesis(
+    Ok(ChainSpec::from_genesis(
         "Staging Testnet",
         "staging_testnet",
         ChainType::Live,
@@ -187,7 +275,7 @@ pub fn staging_testnet_config() -> ChainSpec {
         None,
         None,
         Default::default(),
-    )
+    ))
 }
 
 /// Helper function to generate a crypto pair from seed
@@ -226,9 +314,107 @@ pub fn authority_keys_from_seed(
     )
 }
 
-pub const OCEXGenesisAccount: PalletId = PalletId(*b"polka/ga");
+/// Genesis balance of the OCEX custody account
+///
+/// The custody account must exist to receive deposits smaller than the existential deposit.
+pub fn ocex_custody_balance() -> (AccountId, Balance) {
+    (OcexPalletId::get().into_account(), ExistentialDeposit::get())
+}
+
+/// Seed of the nominations generator used by the built-in chain specs.
+pub const GENESIS_NOMINATIONS_SEED: u64 = u64::from_le_bytes(*b"polkadex");
+
+/// Helper function to pick nomination targets for `nominators` among `validators`
+///
+/// Every nominator backs between one and `MAX_NOMINATIONS` validators. The targets are drawn
+/// from BLAKE2 hashes of `seed`, so the same `seed` always yields the same nominations,
+/// whatever the versions of the node's dependencies.
+pub fn seeded_nominations(
+    nominators: &[AccountId],
+    validators: &[AccountId],
+    seed: u64,
+) -> Vec<(AccountId, Vec<AccountId>)> {
+    let limit = (MAX_NOMINATIONS as usize).min(validators.len());
+    nominators
+        .iter()
+        .enumerate()
+        .map(|(index, nominator)| {
+            let mut draws = 0;
+            let mut draw = |bound: usize| {
+                draws += 1;
+                (nomination_draw(seed, index, draws) % bound as u64) as usize
+            };
+            let count = if limit == 0 { 0 } else { 1 + draw(limit) };
+            // Partial Fisher-Yates shuffle of the validators.
+            let mut targets = validators.to_vec();
+            for i in 0..count {
+                let j = i + draw(targets.len() - i);
+                targets.swap(i, j);
+            }
+            targets.truncate(count);
+            (nominator.clone(), targets)
+        })
+        .collect()
+}
+
+/// The `draw`-th number drawn for the nominator at `index`.
+fn nomination_draw(seed: u64, index: usize, draw: u64) -> u64 {
+    let mut input = Vec::with_capacity(24);
+    input.extend_from_slice(&seed.to_le_bytes());
+    input.extend_from_slice(&(index as u64).to_le_bytes());
+    input.extend_from_slice(&draw.to_le_bytes());
+    let mut number = [0u8; 8];
+    number.copy_from_slice(&sp_core::hashing::blake2_256(&input)[..8]);
+    u64::from_le_bytes(number)
+}
+
+/// Helper function to endow every account with `TOKEN_ENDOWMENT` of every asset
+pub fn token_endowments(accounts: &[AccountId]) -> Vec<(AccountId, AssetId, Balance)> {
+    accounts
+        .iter()
+        .flat_map(|account| {
+            [AssetId::POLKADEX, AssetId::DOT, AssetId::BTC, AssetId::USD]
+                .iter()
+                .map(move |asset| (account.clone(), *asset, TOKEN_ENDOWMENT))
+        })
+        .collect()
+}
+
+/// Checks the `orml_tokens` endowments of a genesis config and returns the total issuance of
+/// every endowed asset.
+pub fn token_issuance(
+    endowments: &[(AccountId, AssetId, Balance)],
+) -> Result<BTreeMap<AssetId, Balance>, String> {
+    if endowments.is_empty() {
+        return Err("At least one account must be endowed with tokens".into());
+    }
+    let mut endowed = BTreeSet::new();
+    let mut issuance = BTreeMap::new();
+    for (account, asset, amount) in endowments {
+        if !endowed.insert((account, asset)) {
+            return Err(format!("Account {} is endowed twice with {:?}", account, asset));
+        }
+        if *amount == 0 {
+            return Err(format!("Account {} is endowed with no {:?}", account, asset));
+        }
+        let total: &mut Balance = issuance.entry(*asset).or_default();
+        *total = total
+            .checked_add(*amount)
+            .ok_or_else(|| format!("Total issuance of {:?} overflows", asset))?;
+    }
+    Ok(issuance)
+}
+
+/// Checks that the storage of `genesis` can be built and returns the total issuance of every
+/// asset endowed through `orml_tokens`.
+pub fn validate_genesis(genesis: &GenesisConfig) -> Result<BTreeMap<AssetId, Balance>, String> {
+    token_issuance(&genesis.orml_tokens.endowed_accounts)
+}
 
 /// Helper function to create GenesisConfig for testing
+///
+/// `initial_nominators` pairs every nominator with its nomination targets, see
+/// `seeded_nominations` to generate them.
 pub fn testnet_genesis(
     initial_authorities: Vec<(
         AccountId,
@@ -238,12 +424,13 @@ pub fn testnet_genesis(
         ImOnlineId,
         AuthorityDiscoveryId,
     )>,
-    initial_nominators: Vec<AccountId>,
+    initial_nominators: Vec<(AccountId, Vec<AccountId>)>,
     root_key: AccountId,
     endowed_accounts: Option<Vec<AccountId>>,
+    token_endowments: Vec<(AccountId, AssetId, Balance)>,
+    oracle_feeders: Vec<AccountId>,
     enable_println: bool,
 ) -> GenesisConfig {
-    let genesis: AccountId = OCEXGenesisAccount.into_account();
     let mut endowed_accounts: Vec<AccountId> = endowed_accounts.unwrap_or_else(|| {
         vec![
             get_account_id_from_seed::<sr25519::Public>("Alice"),
@@ -264,7 +451,7 @@ pub fn testnet_genesis(
     initial_authorities
         .iter()
         .map(|x| &x.0)
-        .chain(initial_nominators.iter())
+        .chain(initial_nominators.iter().map(|x| &x.0))
         .for_each(|x| {
             if !endowed_accounts.contains(&x) {
                 endowed_accounts.push(x.clone())
@@ -272,34 +459,21 @@ pub fn testnet_genesis(
         });
 
     // stakers: all validators and nominators.
-    let mut rng = rand::thread_rng();
     let stakers = initial_authorities
         .iter()
         .map(|x| (x.0.clone(), x.1.clone(), STASH, StakerStatus::Validator))
-        .chain(initial_nominators.iter().map(|x| {
-            use rand::{seq::SliceRandom, Rng};
-            let limit = (MAX_NOMINATIONS as usize).min(initial_authorities.len());
-            let count = rng.gen::<usize>() % limit;
-            let nominations = initial_authorities
-                .as_slice()
-                .choose_multiple(&mut rng, count)
-                .into_iter()
-                .map(|choice| choice.0.clone())
-                .collect::<Vec<_>>();
+        .chain(initial_nominators.iter().map(|(x, nominations)| {
             (
                 x.clone(),
                 x.clone(),
                 STASH,
-                StakerStatus::Nominator(nominations),
+                StakerStatus::Nominator(nominations.clone()),
             )
         }))
         .collect::<Vec<_>>();
 
     let num_endowed_accounts = endowed_accounts.len();
 
-    const ENDOWMENT: Balance = 10_000_000 * DOLLARS;
-    const STASH: Balance = ENDOWMENT / 1000;
-
     GenesisConfig {
         frame_system: SystemConfig {
             code: wasm_binary_unwrap().to_vec(),
@@ -310,6 +484,7 @@ pub fn testnet_genesis(
                 .iter()
                 .cloned()
                 .map(|x| (x, ENDOWMENT))
+                .chain(std::iter::once(ocex_custody_balance()))
                 .collect(),
         },
         pallet_indices: IndicesConfig { indices: vec![] },
@@ -368,34 +543,48 @@ pub fn testnet_genesis(
         pallet_treasury: Default::default(),
         pallet_vesting: Default::default(),
         orml_vesting: OrmlVestingConfig { vesting: vec![] },
+        pallet_ocex: OcexConfig { operators: vec![] },
+        pallet_price_oracle: PriceOracleConfig {
+            feeders: oracle_feeders,
+        },
         orml_tokens: TokensConfig {
-            endowed_accounts: vec![
-                (endowed_accounts[0].to_owned(), AssetId::POLKADEX, 1000000000000000000u128),
-                (endowed_accounts[0].to_owned(), AssetId::DOT, 1000000000000000000u128),
-                (endowed_accounts[0].to_owned(), AssetId::BTC, 1000000000000000000u128),
-                (endowed_accounts[0].to_owned(), AssetId::USD, 1000000000000000000u128),
-                (endowed_accounts[1].to_owned(), AssetId::POLKADEX, 1000000000000000000u128),
-                (endowed_accounts[1].to_owned(), AssetId::DOT, 1000000000000000000u128),
-                (endowed_accounts[1].to_owned(), AssetId::BTC, 1000000000000000000u128),
-                (endowed_accounts[1].to_owned(), AssetId::USD, 1000000000000000000u128),
-            ],
+            endowed_accounts: token_endowments,
         },
     }
 }
 
+fn dev_token_endowments() -> Vec<(AccountId, AssetId, Balance)> {
+    token_endowments(&[
+        get_account_id_from_seed::<sr25519::Public>("Alice"),
+        get_account_id_from_seed::<sr25519::Public>("Bob"),
+    ])
+}
+
+/// Enough feeders to reach the runtime's price quorum.
+fn dev_oracle_feeders() -> Vec<AccountId> {
+    vec![
+        get_account_id_from_seed::<sr25519::Public>("Alice"),
+        get_account_id_from_seed::<sr25519::Public>("Bob"),
+        get_account_id_from_seed::<sr25519::Public>("Charlie"),
+    ]
+}
+
 fn development_config_genesis() -> GenesisConfig {
     testnet_genesis(
         vec![authority_keys_from_seed("Alice")],
         vec![],
         get_account_id_from_seed::<sr25519::Public>("Alice"),
         None,
+        dev_token_endowments(),
+        dev_oracle_feeders(),
         true,
     )
 }
 
 /// Development config (single validator Alice)
-pub fn development_config() -> ChainSpec {
-    ChainSpec::from_genesis(
+pub fn development_config() -> Result<ChainSpec, String> {
+    validate_genesis(&development_config_genesis())?;
+    Ok(ChainSpec::from_genesis(
         "Development",
         "dev",
         ChainType::Development,
@@ -405,7 +594,7 @@ pub fn development_config() -> ChainSpec {
         None,
         None,
         Default::default(),
-    )
+    ))
 }
 
 fn local_testnet_genesis() -> GenesisConfig {
@@ -417,13 +606,16 @@ fn local_testnet_genesis() -> GenesisConfig {
         vec![],
         get_account_id_from_seed::<sr25519::Public>("Alice"),
         None,
+        dev_token_endowments(),
+        dev_oracle_feeders(),
         false,
     )
 }
 
 /// Local testnet config (multivalidator Alice + Bob)
-pub fn local_testnet_config() -> ChainSpec {
-    ChainSpec::from_genesis(
+pub fn local_testnet_config() -> Result<ChainSpec, String> {
+    validate_genesis(&local_testnet_genesis())?;
+    Ok(ChainSpec::from_genesis(
         "Local Testnet",
         "local_testnet",
         ChainType::Local,
@@ -433,9 +625,52 @@ pub fn local_testnet_config() -> ChainSpec {
         None,
         None,
         Default::default(),
+    ))
+}
+
+/// Seed of the validator at `index` in a local network: `Alice`, `Bob`, `Charlie`, `Dave`,
+/// `Eve`, `Ferdie`, then `Validator7`, `Validator8` and so on.
+pub fn local_net_seed(index: usize) -> String {
+    const SEEDS: [&str; 6] = ["Alice", "Bob", "Charlie", "Dave", "Eve", "Ferdie"];
+    SEEDS
+        .get(index)
+        .map(|seed| seed.to_string())
+        .unwrap_or_else(|| format!("Validator{}", index + 1))
+}
+
+fn local_net_genesis(validators: usize) -> GenesisConfig {
+    testnet_genesis(
+        (0..validators)
+            .map(|index| authority_keys_from_seed(&local_net_seed(index)))
+            .collect(),
+        vec![],
+        get_account_id_from_seed::<sr25519::Public>("Alice"),
+        None,
+        dev_token_endowments(),
+        dev_oracle_feeders(),
+        false,
     )
 }
 
+/// Local network config with `validators` validators, named after `local_net_seed`
+pub fn local_net_config(validators: usize) -> Result<ChainSpec, String> {
+    if validators == 0 {
+        return Err("A local network needs at least one validator".into());
+    }
+    validate_genesis(&local_net_genesis(validators))?;
+    Ok(ChainSpec::from_genesis(
+        "Local Network",
+        "local_net",
+        ChainType::Local,
+        move || local_net_genesis(validators),
+        vec![],
+        None,
+        None,
+        None,
+        Default::default(),
+    ))
+}
+
 #[cfg(test)]
 pub(crate) mod tests {
     use sp_runtime::BuildStorage;
@@ -450,6 +685,8 @@ pub(crate) mod tests {
             vec![],
             get_account_id_from_seed::<sr25519::Public>("Alice"),
             None,
+            dev_token_endowments(),
+            dev_oracle_feeders(),
             false,
         )
     }
@@ -484,18 +721,211 @@ pub(crate) mod tests {
         )
     }
 
+    fn nominated_testnet_genesis() -> GenesisConfig {
+        let initial_authorities = vec![
+            authority_keys_from_seed("Alice"),
+            authority_keys_from_seed("Bob"),
+        ];
+        let validators = initial_authorities.iter().map(|x| x.0.clone()).collect::<Vec<_>>();
+        let nominators = ["Charlie", "Dave", "Eve", "Ferdie"]
+            .iter()
+            .map(|seed| get_account_id_from_seed::<sr25519::Public>(seed))
+            .collect::<Vec<_>>();
+        testnet_genesis(
+            initial_authorities,
+            seeded_nominations(&nominators, &validators, GENESIS_NOMINATIONS_SEED),
+            get_account_id_from_seed::<sr25519::Public>("Alice"),
+            None,
+            dev_token_endowments(),
+            dev_oracle_feeders(),
+            false,
+        )
+    }
+
+    fn nominated_testnet_config() -> Result<ChainSpec, String> {
+        validate_genesis(&nominated_testnet_genesis())?;
+        Ok(ChainSpec::from_genesis(
+            "Nominated Testnet",
+            "nominated_testnet",
+            ChainType::Local,
+            nominated_testnet_genesis,
+            vec![],
+            None,
+            None,
+            None,
+            Default::default(),
+        ))
+    }
+
+    #[test]
+    fn seeded_nominations_are_deterministic() {
+        let validators = ["Alice", "Bob", "Charlie"]
+            .iter()
+            .map(|seed| get_account_id_from_seed::<sr25519::Public>(seed))
+            .collect::<Vec<_>>();
+        let nominators = ["Dave", "Eve", "Ferdie"]
+            .iter()
+            .map(|seed| get_account_id_from_seed::<sr25519::Public>(seed))
+            .collect::<Vec<_>>();
+
+        let nominations = seeded_nominations(&nominators, &validators, 7);
+        assert_eq!(nominations, seeded_nominations(&nominators, &validators, 7));
+        for (nominator, targets) in nominations {
+            assert!(nominators.contains(&nominator));
+            assert!(!targets.is_empty());
+            assert!(targets.iter().all(|target| validators.contains(target)));
+        }
+    }
+
+    #[test]
+    fn seeded_nominations_match_the_fixture() {
+        let fixture: serde_json::Value =
+            serde_json::from_str(include_str!("../res/genesis-nominations.json")).unwrap();
+        let validators = (0..fixture["validators"].as_u64().unwrap())
+            .map(|index| AccountId::from([index as u8; 32]))
+            .collect::<Vec<_>>();
+        let expected = fixture["nominations"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .map(|targets| {
+                targets
+                    .as_array()
+                    .unwrap()
+                    .iter()
+                    .map(|index| validators[index.as_u64().unwrap() as usize].clone())
+                    .collect::<Vec<_>>()
+            })
+            .collect::<Vec<_>>();
+        let nominators = (0..expected.len())
+            .map(|index| AccountId::from([100 + index as u8; 32]))
+            .collect::<Vec<_>>();
+
+        let nominations = seeded_nominations(&nominators, &validators, GENESIS_NOMINATIONS_SEED);
+        assert_eq!(
+            nominations.into_iter().map(|(_, targets)| targets).collect::<Vec<_>>(),
+            expected
+        );
+    }
+
+    #[test]
+    fn raw_chain_spec_is_byte_identical_across_builds() {
+        let specs: Vec<fn() -> Result<ChainSpec, String>> = vec![
+            development_config,
+            local_testnet_config,
+            staging_testnet_config,
+            nominated_testnet_config,
+        ];
+        for spec in specs {
+            assert_eq!(
+                spec().unwrap().as_json(true).unwrap(),
+                spec().unwrap().as_json(true).unwrap()
+            );
+        }
+    }
+
+    #[test]
+    fn grandpa_settings_are_read_from_the_chain_spec() {
+        let spec = development_config().unwrap();
+        assert_eq!(GrandpaSettings::from_chain_spec(&spec), GrandpaSettings::default());
+
+        let mut json: serde_json::Value =
+            serde_json::from_str(&spec.as_json(false).unwrap()).unwrap();
+        json["grandpa"] = serde_json::json!({
+            "justificationPeriod": 16,
+            "votingRule": { "BeforeBestBlockBy": 1 },
+            "observer": true,
+        });
+        let spec = ChainSpec::from_json_bytes(json.to_string().into_bytes()).unwrap();
+        assert_eq!(
+            GrandpaSettings::from_chain_spec(&spec),
+            GrandpaSettings {
+                justification_period: 16,
+                voting_rule: VotingRule::BeforeBestBlockBy(1),
+                observer: true,
+                ..Default::default()
+            }
+        );
+    }
+
+    #[test]
+    fn voting_rules_are_parsed() {
+        assert_eq!("default".parse(), Ok(VotingRule::Default));
+        assert_eq!("before-best:4".parse(), Ok(VotingRule::BeforeBestBlockBy(4)));
+        assert_eq!(
+            "three-quarters".parse(),
+            Ok(VotingRule::ThreeQuartersOfTheUnfinalizedChain)
+        );
+        assert_eq!("none".parse(), Ok(VotingRule::None));
+        assert!("before-best:".parse::<VotingRule>().is_err());
+        assert!("fastest".parse::<VotingRule>().is_err());
+    }
+
+    #[test]
+    fn token_issuance_is_checked() {
+        let alice = get_account_id_from_seed::<sr25519::Public>("Alice");
+        let bob = get_account_id_from_seed::<sr25519::Public>("Bob");
+
+        let issuance = token_issuance(&token_endowments(&[alice.clone(), bob.clone()])).unwrap();
+        assert_eq!(issuance.len(), 4);
+        assert!(issuance.values().all(|total| *total == 2 * TOKEN_ENDOWMENT));
+
+        assert!(token_issuance(&[]).is_err());
+        assert!(token_issuance(&[(alice.clone(), AssetId::DOT, 0)]).is_err());
+        assert!(token_issuance(&[
+            (alice.clone(), AssetId::DOT, 1),
+            (alice.clone(), AssetId::DOT, 1),
+        ])
+        .is_err());
+        assert!(token_issuance(&[
+            (alice, AssetId::DOT, Balance::max_value()),
+            (bob, AssetId::DOT, 1),
+        ])
+        .is_err());
+    }
+
+    #[test]
+    fn genesis_token_issuance_matches_endowments() {
+        let storage = development_config().unwrap().build_storage().unwrap();
+        let expected = validate_genesis(&development_config_genesis()).unwrap();
+        sp_io::TestExternalities::new(storage).execute_with(|| {
+            for (asset, total) in expected {
+                assert_eq!(node_polkadex_runtime::Tokens::total_issuance(asset), total);
+            }
+        });
+    }
+
+    #[test]
+    fn empty_token_endowments_are_rejected() {
+        let mut genesis = development_config_genesis();
+        genesis.orml_tokens.endowed_accounts.clear();
+        assert!(validate_genesis(&genesis).is_err());
+    }
+
+    #[test]
+    fn local_net_has_one_authority_per_validator() {
+        assert!(local_net_config(0).is_err());
+        let genesis = local_net_genesis(8);
+        assert_eq!(genesis.pallet_session.keys.len(), 8);
+        assert_eq!(
+            genesis.pallet_session.keys[7].0,
+            get_account_id_from_seed::<sr25519::Public>("Validator8//stash")
+        );
+        local_net_config(8).unwrap().build_storage().unwrap();
+    }
+
     #[test]
     fn test_create_development_chain_spec() {
-        development_config().build_storage().unwrap();
+        development_config().unwrap().build_storage().unwrap();
     }
 
     #[test]
     fn test_create_local_testnet_chain_spec() {
-        local_testnet_config().build_storage().unwrap();
+        local_testnet_config().unwrap().build_storage().unwrap();
     }
 
     #[test]
     fn test_staging_test_net_chain_spec() {
-        staging_testnet_config().build_storage().unwrap();
+        staging_testnet_config().unwrap().build_storage().unwrap();
     }
 }
@@ -16,6 +16,8 @@
 // You should have received a copy of the GNU General Public License
 // along with this program. If not, see <https://www.gnu.org/licenses/>.
 
+use crate::chain_spec::{GrandpaSettings, VotingRule};
+use crate::service::Sealing;
 use sc_cli::{KeySubcommand, RunCmd, SignCmd, VanityCmd, VerifyCmd};
 use structopt::StructOpt;
 
@@ -28,6 +30,58 @@ pub struct Cli {
     #[allow(missing_docs)]
     #[structopt(flatten)]
     pub run: RunCmd,
+    #[allow(missing_docs)]
+    #[structopt(flatten)]
+    pub grandpa: GrandpaParams,
+
+    /// Seal blocks on demand instead of running BABE and GRANDPA: `instant`, `manual` or
+    /// `interval:<milliseconds>`. Meant for development and integration tests, and only
+    /// accepted on development and local chains.
+    #[structopt(long = "sealing", value_name = "MODE")]
+    pub sealing: Option<Sealing>,
+
+    /// Index trades and transfers of finalized blocks and serve candles, trade history and
+    /// account transfers over RPC. Best run together with `--pruning archive`.
+    #[structopt(long = "indexer")]
+    pub indexer: bool,
+}
+
+/// Overrides of the GRANDPA settings of the chain spec.
+#[derive(Debug, StructOpt)]
+pub struct GrandpaParams {
+    /// Interval between gossip rounds of GRANDPA messages, in milliseconds.
+    #[structopt(long = "grandpa-gossip-duration", value_name = "MILLISECONDS")]
+    pub gossip_duration_ms: Option<u64>,
+
+    /// Number of blocks between the justifications generated for finalized blocks.
+    #[structopt(long = "grandpa-justification-period", value_name = "BLOCKS")]
+    pub justification_period: Option<u32>,
+
+    /// Rule restricting the block voted on: `default`, `before-best:<blocks>`,
+    /// `three-quarters` or `none`.
+    #[structopt(long = "grandpa-voting-rule", value_name = "RULE")]
+    pub voting_rule: Option<VotingRule>,
+
+    /// Run the GRANDPA observer instead of the full voter on a non-authority node.
+    #[structopt(long = "grandpa-observer")]
+    pub observer: bool,
+}
+
+impl GrandpaParams {
+    /// Applies the overrides to the settings of a chain spec.
+    pub fn apply(&self, mut settings: GrandpaSettings) -> GrandpaSettings {
+        if let Some(gossip_duration_ms) = self.gossip_duration_ms {
+            settings.gossip_duration_ms = gossip_duration_ms;
+        }
+        if let Some(justification_period) = self.justification_period {
+            settings.justification_period = justification_period;
+        }
+        if let Some(voting_rule) = self.voting_rule {
+            settings.voting_rule = voting_rule;
+        }
+        settings.observer |= self.observer;
+        settings
+    }
 }
 
 /// Possible subcommands of the main binary.
@@ -36,12 +90,13 @@ pub enum Subcommand {
     /// Key management cli utilities
     Key(KeySubcommand),
 
-    // /// The custom inspect subcommmand for decoding blocks and extrinsics.
-    // #[structopt(
-    // name = "inspect",
-    // about = "Decode given block or extrinsic using current native runtime."
-    // )]
-    // Inspect(node_inspect::cli::InspectKeyCmd),
+    /// The custom inspect subcommmand for decoding blocks and extrinsics.
+    #[structopt(
+        name = "inspect",
+        about = "Decode given block or extrinsic using current native runtime."
+    )]
+    Inspect(crate::inspect::InspectCmd),
+
     /// The custom benchmark subcommmand benchmarking runtime pallets.
     #[structopt(name = "benchmark", about = "Benchmark runtime pallets.")]
     Benchmark(frame_benchmarking_cli::BenchmarkCmd),
@@ -60,9 +115,25 @@ pub enum Subcommand {
     /// Sign a message, with a given (secret) key.
     Sign(SignCmd),
 
+    /// Build and sign a transaction offline, printing it as hex.
+    #[structopt(name = "tx")]
+    Tx(crate::transaction::TxCmd),
+
     /// Build a chain specification.
     BuildSpec(sc_cli::BuildSpecCmd),
 
+    /// Build a chain specification from a genesis file.
+    #[structopt(name = "generate-spec")]
+    GenerateSpec(crate::genesis_file::GenerateSpecCmd),
+
+    /// Derive the stash, controller and session keys of a genesis authority.
+    #[structopt(name = "generate-authority")]
+    GenerateAuthority(crate::authority_keys::GenerateAuthorityCmd),
+
+    /// Start a network of validators in this process.
+    #[structopt(name = "local-net")]
+    LocalNet(crate::local_net::LocalNetCmd),
+
     /// Validate blocks.
     CheckBlock(sc_cli::CheckBlockCmd),
 
@@ -17,10 +17,11 @@
 // along with this program. If not, see <https://www.gnu.org/licenses/>.
 use crate::service::new_partial;
 use crate::service::Executor;
+use crate::chain_spec::GrandpaSettings;
 use crate::{chain_spec, cli::Cli, cli::Subcommand, service};
 use node_polkadex_runtime::Block;
 use sc_cli::{ChainSpec, Result, Role, RuntimeVersion, SubstrateCli};
-use sc_service::PartialComponents;
+use sc_service::{ChainType, PartialComponents};
 impl SubstrateCli for Cli {
     fn impl_name() -> String {
         "Polkadex Node".into()
@@ -54,10 +55,10 @@ impl SubstrateCli for Cli {
                         .into(),
                 )
             }
-            "dev" => Box::new(chain_spec::development_config()),
-            "local" => Box::new(chain_spec::local_testnet_config()),
+            "dev" => Box::new(chain_spec::development_config()?),
+            "local" => Box::new(chain_spec::local_testnet_config()?),
             // "fir" | "flaming-fir" => Box::new(chain_spec::flaming_fir_config()?),
-            "staging" => Box::new(chain_spec::staging_testnet_config()),
+            "staging" => Box::new(chain_spec::staging_testnet_config()?),
             path => Box::new(chain_spec::ChainSpec::from_json_file(
                 std::path::PathBuf::from(path),
             )?),
@@ -78,18 +79,36 @@ pub fn run() -> Result<()> {
         None => {
             let runner = cli.create_runner(&cli.run)?;
             runner.run_node_until_exit(|config| async move {
-                match config.role {
-                    Role::Light => service::new_light(config),
-                    _ => service::new_full(config),
+                let grandpa = cli
+                    .grandpa
+                    .apply(GrandpaSettings::from_chain_spec(&*config.chain_spec));
+                match (config.role.clone(), cli.sealing) {
+                    (Role::Light, None) => service::new_light(config),
+                    (Role::Light, Some(_)) => Err(sc_service::Error::Other(
+                        "Light clients cannot seal blocks".into(),
+                    )),
+                    (_, Some(_))
+                        if !matches!(
+                            config.chain_spec.chain_type(),
+                            ChainType::Development | ChainType::Local
+                        ) =>
+                    {
+                        Err(sc_service::Error::Other(
+                            "Blocks can only be sealed on demand on development and local chains"
+                                .into(),
+                        ))
+                    }
+                    (_, Some(sealing)) => service::new_manual_seal(config, sealing, cli.indexer),
+                    (_, None) => service::new_full(config, grandpa, cli.indexer),
                 }
                 .map_err(sc_cli::Error::Service)
             })
         }
-        // Some(Subcommand::Inspect(cmd)) => {
-        //     let runner = cli.create_runner(cmd)?;
-        //
-        //     runner.sync_run(|config| cmd.run::<Block, RuntimeApi, Executor>(config))
-        // }
+        Some(Subcommand::Inspect(cmd)) => {
+            let runner = cli.create_runner(cmd)?;
+
+            runner.sync_run(|config| cmd.run(config))
+        }
         Some(Subcommand::Benchmark(cmd)) => {
             if cfg!(feature = "runtime-benchmarks") {
                 let runner = cli.create_runner(cmd)?;
@@ -103,12 +122,20 @@ pub fn run() -> Result<()> {
         }
         Some(Subcommand::Key(cmd)) => cmd.run(&cli),
         Some(Subcommand::Sign(cmd)) => cmd.run(),
+        Some(Subcommand::Tx(cmd)) => cmd.run(),
         Some(Subcommand::Verify(cmd)) => cmd.run(),
         Some(Subcommand::Vanity(cmd)) => cmd.run(),
         Some(Subcommand::BuildSpec(cmd)) => {
             let runner = cli.create_runner(cmd)?;
             runner.sync_run(|config| cmd.run(config.chain_spec, config.network))
         }
+        Some(Subcommand::GenerateSpec(cmd)) => cmd.run(),
+        Some(Subcommand::GenerateAuthority(cmd)) => cmd.run(),
+        Some(Subcommand::LocalNet(cmd)) => {
+            let nodes = cmd.nodes()?;
+            let runner = cli.create_runner(&nodes[0].run)?;
+            runner.run_node_until_exit(|config| async { cmd.run(&cli, &nodes, config) })
+        }
         Some(Subcommand::CheckBlock(cmd)) => {
             let runner = cli.create_runner(cmd)?;
             runner.async_run(|config| {
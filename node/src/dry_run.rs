@@ -0,0 +1,322 @@
+// This file is part of Polkadex.
+
+// Copyright (C) 2020-2021 Polkadex oü.
+// SPDX-License-Identifier: GPL-3.0-or-later WITH Classpath-exception-2.0
+
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with this program. If not, see <https://www.gnu.org/licenses/>.
+//! RPC for estimating fees and dry running call data that has not been signed yet.
+//!
+//! `payment_queryInfo` needs a fully built extrinsic, which a wallet only has after asking
+//! its user to sign. These methods take the SCALE encoded `Call` and the account meant to
+//! sign it instead.
+
+use std::convert::TryFrom;
+use std::sync::Arc;
+
+use codec::{Decode, Encode};
+use jsonrpc_core::{Error as RpcError, ErrorCode, Result};
+use jsonrpc_derive::rpc;
+use node_polkadex_runtime::{Call, Event};
+use polkadex_dry_run_runtime_api::{
+    DryRunApi as DryRunRuntimeApi, FeeDetails, FeeEstimate, InclusionFee,
+};
+use polkadex_primitives::{AccountId, Balance, Block, Hash};
+use sc_rpc_api::DenyUnsafe;
+use serde::Serialize;
+use sp_api::ProvideRuntimeApi;
+use sp_blockchain::HeaderBackend;
+use sp_core::Bytes;
+use sp_rpc::number::NumberOrHex;
+use sp_runtime::generic::BlockId;
+use sp_runtime::ApplyExtrinsicResult;
+
+/// Weight, length and fees of a call.
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CallFeeEstimate {
+    /// Weight declared by the call.
+    pub weight: u64,
+    /// Length of the signed extrinsic in bytes.
+    pub len: u32,
+    /// Fees charged before any refund of unused weight.
+    pub fee: FeeDetails<NumberOrHex>,
+}
+
+impl From<FeeEstimate<Balance>> for CallFeeEstimate {
+    fn from(estimate: FeeEstimate<Balance>) -> Self {
+        CallFeeEstimate {
+            weight: estimate.weight,
+            len: estimate.len,
+            fee: fee_details(estimate.fee),
+        }
+    }
+}
+
+/// How applying the extrinsic went.
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase", tag = "status")]
+pub enum DryRunStatus {
+    /// The call was dispatched and succeeded.
+    Success,
+    /// The extrinsic would be included, but its call failed.
+    DispatchFailed {
+        /// The dispatch error.
+        error: String,
+    },
+    /// The extrinsic would not be included in a block, e.g. for a bad nonce or unpaid fees.
+    Invalid {
+        /// The validity error.
+        error: String,
+    },
+}
+
+/// An event deposited during the dry run.
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DryRunEvent {
+    /// Human readable form of the event.
+    pub event: String,
+    /// SCALE encoded event.
+    pub encoded: Bytes,
+}
+
+/// Outcome of a dry run.
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DryRunResult {
+    /// Block the call was applied on top of.
+    pub at: Hash,
+    /// How applying the extrinsic went.
+    pub result: DryRunStatus,
+    /// Events deposited while applying the extrinsic.
+    pub events: Vec<DryRunEvent>,
+    /// Weight actually consumed.
+    pub actual_weight: u64,
+    /// Fees paid once unused weight is refunded.
+    pub actual_fee: NumberOrHex,
+    /// Estimate computed from the declared weight.
+    pub estimate: CallFeeEstimate,
+}
+
+/// Fee estimation and dry runs of call data.
+#[rpc]
+pub trait DryRunApi<BlockHash> {
+    /// Weight, length and fees of the SCALE encoded `call` if `signer` submitted it with `tip`.
+    #[rpc(name = "payment_estimateCallFee")]
+    fn estimate_call_fee(
+        &self,
+        call: Bytes,
+        signer: AccountId,
+        tip: Option<NumberOrHex>,
+        at: Option<BlockHash>,
+    ) -> Result<CallFeeEstimate>;
+
+    /// Apply the SCALE encoded `call` signed by `signer` on top of block `at` and report what
+    /// happened. Nothing is written to the chain and the signature is never checked.
+    #[rpc(name = "payment_dryRunCall")]
+    fn dry_run_call(
+        &self,
+        call: Bytes,
+        signer: AccountId,
+        tip: Option<NumberOrHex>,
+        at: Option<BlockHash>,
+    ) -> Result<DryRunResult>;
+}
+
+/// Implements [`DryRunApi`] on top of the runtime's dry run API.
+pub struct DryRun<C> {
+    client: Arc<C>,
+    deny_unsafe: DenyUnsafe,
+}
+
+impl<C> DryRun<C> {
+    /// Create a new `DryRun` for the given client.
+    pub fn new(client: Arc<C>, deny_unsafe: DenyUnsafe) -> Self {
+        DryRun {
+            client,
+            deny_unsafe,
+        }
+    }
+}
+
+/// Error codes of this RPC.
+enum Error {
+    /// The call data could not be decoded.
+    DecodeError,
+    /// The tip does not fit in a balance.
+    InvalidTip,
+    /// The runtime API call failed.
+    RuntimeError,
+}
+
+impl From<Error> for i64 {
+    fn from(e: Error) -> i64 {
+        match e {
+            Error::DecodeError => 1,
+            Error::InvalidTip => 2,
+            Error::RuntimeError => 3,
+        }
+    }
+}
+
+fn rpc_error(code: Error, message: &str, data: impl std::fmt::Debug) -> RpcError {
+    RpcError {
+        code: ErrorCode::ServerError(code.into()),
+        message: message.into(),
+        data: Some(format!("{:?}", data).into()),
+    }
+}
+
+pub(crate) fn decode_call(call: &Bytes) -> Result<Call> {
+    let mut input = &call[..];
+    let decoded = Call::decode(&mut input)
+        .map_err(|e| rpc_error(Error::DecodeError, "Unable to decode call.", e))?;
+    if !input.is_empty() {
+        return Err(rpc_error(
+            Error::DecodeError,
+            "Unable to decode call.",
+            format!("{} trailing bytes", input.len()),
+        ));
+    }
+    Ok(decoded)
+}
+
+pub(crate) fn decode_tip(tip: Option<NumberOrHex>) -> Result<Balance> {
+    tip.map_or(Ok(0), |tip| {
+        Balance::try_from(tip).map_err(|e| rpc_error(Error::InvalidTip, "Invalid tip.", e))
+    })
+}
+
+fn number_or_hex(balance: Balance) -> NumberOrHex {
+    NumberOrHex::Hex(balance.into())
+}
+
+pub(crate) fn fee_details(fee: FeeDetails<Balance>) -> FeeDetails<NumberOrHex> {
+    FeeDetails {
+        inclusion_fee: fee.inclusion_fee.map(|fee| InclusionFee {
+            base_fee: number_or_hex(fee.base_fee),
+            len_fee: number_or_hex(fee.len_fee),
+            adjusted_weight_fee: number_or_hex(fee.adjusted_weight_fee),
+        }),
+        tip: number_or_hex(fee.tip),
+    }
+}
+
+fn status(result: ApplyExtrinsicResult) -> DryRunStatus {
+    match result {
+        Ok(Ok(())) => DryRunStatus::Success,
+        Ok(Err(e)) => DryRunStatus::DispatchFailed {
+            error: format!("{:?}", e),
+        },
+        Err(e) => DryRunStatus::Invalid {
+            error: format!("{:?}", e),
+        },
+    }
+}
+
+impl<C: HeaderBackend<Block>> DryRun<C> {
+    fn at(&self, at: Option<Hash>) -> Hash {
+        // If the block hash is not supplied assume the best block.
+        at.unwrap_or_else(|| self.client.info().best_hash)
+    }
+}
+
+impl<C> DryRunApi<Hash> for DryRun<C>
+where
+    C: ProvideRuntimeApi<Block> + HeaderBackend<Block> + Send + Sync + 'static,
+    C::Api: DryRunRuntimeApi<Block, AccountId, Call, Balance, Event>,
+{
+    fn estimate_call_fee(
+        &self,
+        call: Bytes,
+        signer: AccountId,
+        tip: Option<NumberOrHex>,
+        at: Option<Hash>,
+    ) -> Result<CallFeeEstimate> {
+        let call = decode_call(&call)?;
+        let tip = decode_tip(tip)?;
+        let at = BlockId::hash(self.at(at));
+
+        let estimate = self
+            .client
+            .runtime_api()
+            .estimate_fee(&at, signer, call, tip)
+            .map_err(|e| rpc_error(Error::RuntimeError, "Unable to estimate fee.", e))?;
+        Ok(estimate.into())
+    }
+
+    fn dry_run_call(
+        &self,
+        call: Bytes,
+        signer: AccountId,
+        tip: Option<NumberOrHex>,
+        at: Option<Hash>,
+    ) -> Result<DryRunResult> {
+        // Executes arbitrary calls on the node, unlike a plain fee query.
+        self.deny_unsafe.check_if_safe()?;
+
+        let call = decode_call(&call)?;
+        let tip = decode_tip(tip)?;
+        let hash = self.at(at);
+
+        // Changes made by runtime API calls stay in an overlay that is thrown away.
+        let outcome = self
+            .client
+            .runtime_api()
+            .dry_run(&BlockId::hash(hash), signer, call, tip, hash)
+            .map_err(|e| rpc_error(Error::RuntimeError, "Unable to dry run call.", e))?;
+        Ok(DryRunResult {
+            at: hash,
+            result: status(outcome.result),
+            events: outcome
+                .events
+                .into_iter()
+                .map(|event| DryRunEvent {
+                    event: format!("{:?}", event),
+                    encoded: event.encode().into(),
+                })
+                .collect(),
+            actual_weight: outcome.actual_weight,
+            actual_fee: number_or_hex(outcome.actual_fee),
+            estimate: outcome.estimate.into(),
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use node_polkadex_runtime::Runtime;
+
+    use super::*;
+
+    #[test]
+    fn call_data_must_be_fully_consumed() {
+        let call: Call = frame_system::Call::<Runtime>::remark(vec![1, 2, 3]).into();
+        let mut encoded = call.encode();
+        assert_eq!(decode_call(&encoded.clone().into()).unwrap(), call);
+
+        encoded.push(0);
+        assert!(decode_call(&encoded.into()).is_err());
+    }
+
+    #[test]
+    fn dry_run_status_is_tagged() {
+        let json = serde_json::to_value(status(Err(
+            sp_runtime::transaction_validity::InvalidTransaction::Payment.into(),
+        )))
+        .unwrap();
+        assert_eq!(json["status"], "invalid");
+        assert_eq!(serde_json::to_value(status(Ok(Ok(())))).unwrap()["status"], "success");
+    }
+}
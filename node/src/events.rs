@@ -0,0 +1,545 @@
+// This file is part of Polkadex.
+
+// Copyright (C) 2020-2021 Polkadex oü.
+// SPDX-License-Identifier: GPL-3.0-or-later WITH Classpath-exception-2.0
+
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with this program. If not, see <https://www.gnu.org/licenses/>.
+//! Subscriptions to the events of finalized blocks, filtered by pallet, event and account.
+//!
+//! Pallets and events are named as in the runtime metadata, e.g. `Tokens` and `Transferred`.
+//! An event involves an account if one of its fields the metadata types as an `AccountId` holds
+//! the account. The fields are decoded in order, so fields following one of a type the node does
+//! not know the encoding of are not looked at.
+
+use std::collections::BTreeMap;
+use std::sync::Arc;
+
+use codec::{Compact, Decode, Encode};
+use frame_metadata::{DecodeDifferent, RuntimeMetadata, RuntimeMetadataPrefixed};
+use frame_system::Phase;
+use futures::{future, StreamExt, TryStreamExt};
+use jsonrpc_core::futures::{Future as _, Sink as _};
+use jsonrpc_core::{Error as RpcError, ErrorCode, Result};
+use jsonrpc_derive::rpc;
+use jsonrpc_pubsub::{manager::SubscriptionManager, typed::Subscriber, SubscriptionId};
+use node_polkadex_runtime::{Event, ProxyType, Runtime};
+use polkadex_primitives::assets::AssetId;
+use polkadex_primitives::{AccountId, Balance, Block, BlockNumber, Hash};
+use sc_client_api::{BlockchainEvents, StorageProvider};
+use sc_rpc::SubscriptionTaskExecutor;
+use serde::{Deserialize, Serialize};
+use sp_core::{Bytes, H160, U256};
+use sp_runtime::traits::Header as HeaderT;
+use sp_runtime::{DispatchError, FixedU128};
+
+use crate::indexer::read_events;
+
+/// Events to subscribe to. Empty filters match everything.
+#[derive(Clone, Debug, Default, PartialEq, Eq, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct EventFilter {
+    /// Pallets to match, e.g. `["Tokens", "Currencies"]`.
+    pub pallets: Vec<String>,
+    /// Events to match within those pallets, e.g. `["Transfer"]`.
+    pub events: Vec<String>,
+    /// Account to be involved in the events.
+    pub account: Option<AccountId>,
+}
+
+/// An event matching a filter.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct FilteredEvent {
+    /// Index of the event among the events of the block.
+    pub index: u32,
+    /// Index of the extrinsic depositing the event, if it was deposited by one.
+    pub extrinsic_index: Option<u32>,
+    pub pallet: String,
+    pub event: String,
+    /// SCALE encoded fields of the event.
+    pub data: Bytes,
+}
+
+/// The events of a finalized block matching a filter.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BlockEvents {
+    pub hash: Hash,
+    pub number: BlockNumber,
+    pub events: Vec<FilteredEvent>,
+}
+
+/// An event field, by the type name the metadata gives for it.
+enum Field {
+    /// An account, compared with the account of a filter.
+    Account,
+    Option(Box<Field>),
+    Vec(Box<Field>),
+    /// A type the node knows the encoding of, skipped by decoding it.
+    Other(fn(&mut &[u8]) -> bool),
+    /// A type the node does not know the encoding of.
+    Unknown,
+}
+
+fn skip<T: Decode>(input: &mut &[u8]) -> bool {
+    T::decode(input).is_ok()
+}
+
+impl Field {
+    /// Reads a type name such as `T::AccountId`, `BalanceOf<T>` or `Vec<T::AccountId>`.
+    fn of(type_name: &str) -> Self {
+        let name: String = type_name.split_whitespace().collect();
+        Self::parse(&name)
+    }
+
+    fn parse(name: &str) -> Self {
+        let inner = |container: &str| {
+            name.strip_prefix(container)
+                .and_then(|name| name.strip_prefix('<'))
+                .and_then(|name| name.strip_suffix('>'))
+        };
+        if let Some(inner) = inner("Vec") {
+            return Field::Vec(Box::new(Self::parse(inner)));
+        }
+        if let Some(inner) = inner("Option") {
+            return Field::Option(Box::new(Self::parse(inner)));
+        }
+        let name = name.rsplit("::").next().unwrap_or(name);
+        match name.split('<').next().unwrap_or(name) {
+            "AccountId" => Field::Account,
+            "bool" => Field::Other(skip::<bool>),
+            "u8" => Field::Other(skip::<u8>),
+            "u16" => Field::Other(skip::<u16>),
+            "u32" | "AccountIndex" | "SessionIndex" | "EraIndex" | "ProposalIndex"
+            | "MemberCount" | "BountyIndex" => Field::Other(skip::<u32>),
+            "u64" | "OrderId" | "Weight" => Field::Other(skip::<u64>),
+            "u128" => Field::Other(skip::<u128>),
+            "Balance" | "BalanceOf" => Field::Other(skip::<Balance>),
+            "Amount" | "AmountOf" => Field::Other(skip::<i128>),
+            "BlockNumber" => Field::Other(skip::<BlockNumber>),
+            "Hash" | "H256" => Field::Other(skip::<Hash>),
+            "H160" => Field::Other(skip::<H160>),
+            "U256" => Field::Other(skip::<U256>),
+            "AssetId" | "CurrencyId" | "CurrencyIdOf" => Field::Other(skip::<AssetId>),
+            "FixedU128" => Field::Other(skip::<FixedU128>),
+            "Side" => Field::Other(skip::<pallet_orderbook::Side>),
+            "MarketMode" => Field::Other(skip::<pallet_orderbook::MarketMode>),
+            "ProxyType" => Field::Other(skip::<ProxyType>),
+            "DispatchError" => Field::Other(skip::<DispatchError>),
+            "DispatchInfo" => Field::Other(skip::<frame_support::weights::DispatchInfo>),
+            _ => Field::Unknown,
+        }
+    }
+
+    /// Decodes a value of the field from `input`, telling whether it holds `account`. `None` if
+    /// the value cannot be decoded.
+    fn holds(&self, input: &mut &[u8], account: &AccountId) -> Option<bool> {
+        match self {
+            Field::Account => AccountId::decode(input).ok().map(|value| &value == account),
+            Field::Option(inner) => match u8::decode(input).ok()? {
+                0 => Some(false),
+                1 => inner.holds(input, account),
+                _ => None,
+            },
+            Field::Vec(inner) => {
+                let Compact(len) = Compact::<u32>::decode(input).ok()?;
+                let mut holds = false;
+                for _ in 0..len {
+                    holds |= inner.holds(input, account)?;
+                }
+                Some(holds)
+            }
+            Field::Other(skip) => skip(input).then(|| false),
+            Field::Unknown => None,
+        }
+    }
+}
+
+/// Whether one of the fields of an event, encoded as `data`, holds `account`.
+fn involves(fields: &[Field], mut data: &[u8], account: &AccountId) -> bool {
+    for field in fields {
+        match field.holds(&mut data, account) {
+            Some(true) => return true,
+            Some(false) => {}
+            None => return false,
+        }
+    }
+    false
+}
+
+/// Names of the pallets with events and of their events, with the fields of the events, by index.
+struct EventNames(BTreeMap<u8, (String, Vec<(String, Vec<Field>)>)>);
+
+impl EventNames {
+    /// Reads the names from the metadata of the native runtime.
+    fn native() -> std::result::Result<Self, String> {
+        let metadata = RuntimeMetadataPrefixed::decode(&mut &Runtime::metadata().encode()[..])
+            .map_err(|e| e.to_string())?;
+        let modules = match metadata.1 {
+            RuntimeMetadata::V12(metadata) => decoded(metadata.modules)?,
+            _ => return Err("Unsupported metadata version".into()),
+        };
+        let mut names = BTreeMap::new();
+        for module in modules {
+            let events = match module.event {
+                Some(events) => decoded(events)?,
+                None => continue,
+            };
+            let events = events
+                .into_iter()
+                .map(|event| {
+                    let fields = decoded(event.arguments)?
+                        .into_iter()
+                        .map(|type_name| Field::of(&type_name))
+                        .collect();
+                    Ok((decoded(event.name)?, fields))
+                })
+                .collect::<std::result::Result<_, String>>()?;
+            names.insert(module.index, (decoded(module.name)?, events));
+        }
+        Ok(EventNames(names))
+    }
+
+    /// Pallet and event names and event fields of an encoded event.
+    fn of(&self, encoded: &[u8]) -> Option<(&str, &str, &[Field])> {
+        let (pallet, events) = self.0.get(encoded.get(0)?)?;
+        let (event, fields) = events.get(*encoded.get(1)? as usize)?;
+        Some((pallet, event, fields))
+    }
+
+    fn has_pallet(&self, pallet: &str) -> bool {
+        self.0.values().any(|(name, _)| name == pallet)
+    }
+}
+
+fn decoded<B, O>(value: DecodeDifferent<B, O>) -> std::result::Result<O, String>
+where
+    B: Encode + 'static,
+    O: Decode + 'static,
+{
+    match value {
+        DecodeDifferent::Decoded(value) => Ok(value),
+        DecodeDifferent::Encode(_) => Err("Metadata is not decoded".into()),
+    }
+}
+
+/// The events of a block matching `filter`.
+fn filter_events(
+    names: &EventNames,
+    filter: &EventFilter,
+    events: Vec<frame_system::EventRecord<Event, Hash>>,
+) -> Vec<FilteredEvent> {
+    events
+        .into_iter()
+        .enumerate()
+        .filter_map(|(index, record)| {
+            let encoded = record.event.encode();
+            let (pallet, event, fields) = names.of(&encoded)?;
+            let data = &encoded[2..];
+            let matches = (filter.pallets.is_empty() || filter.pallets.iter().any(|p| p == pallet))
+                && (filter.events.is_empty() || filter.events.iter().any(|e| e == event))
+                && filter
+                    .account
+                    .as_ref()
+                    .map_or(true, |account| involves(fields, data, account));
+            if !matches {
+                return None;
+            }
+            Some(FilteredEvent {
+                index: index as u32,
+                extrinsic_index: match record.phase {
+                    Phase::ApplyExtrinsic(index) => Some(index),
+                    _ => None,
+                },
+                pallet: pallet.into(),
+                event: event.into(),
+                data: data.to_vec().into(),
+            })
+        })
+        .collect()
+}
+
+/// Filtered event subscriptions.
+#[rpc]
+pub trait EventsApi {
+    /// RPC metadata.
+    type Metadata;
+
+    /// Events of finalized blocks matching `filter`. There is a notification for every
+    /// finalized block with matching events.
+    #[pubsub(
+        subscription = "events_filtered",
+        subscribe,
+        name = "events_subscribeFiltered"
+    )]
+    fn subscribe_filtered(
+        &self,
+        metadata: Self::Metadata,
+        subscriber: Subscriber<BlockEvents>,
+        filter: EventFilter,
+    );
+
+    /// Cancels a filtered events subscription.
+    #[pubsub(
+        subscription = "events_filtered",
+        unsubscribe,
+        name = "events_unsubscribeFiltered"
+    )]
+    fn unsubscribe_filtered(
+        &self,
+        metadata: Option<Self::Metadata>,
+        id: SubscriptionId,
+    ) -> Result<bool>;
+}
+
+/// Implements [`EventsApi`] with the events of a full client.
+pub struct EventsRpc<C, B> {
+    client: Arc<C>,
+    names: Arc<EventNames>,
+    manager: SubscriptionManager,
+    _backend: std::marker::PhantomData<B>,
+}
+
+impl<C, B> EventsRpc<C, B> {
+    /// Create a new `EventsRpc` driving its subscriptions with `executor`.
+    pub fn new(client: Arc<C>, executor: SubscriptionTaskExecutor) -> Self {
+        let names = EventNames::native().unwrap_or_else(|e| {
+            log::error!(target: "events", "Unable to read the event names: {}", e);
+            EventNames(BTreeMap::new())
+        });
+        EventsRpc {
+            client,
+            names: Arc::new(names),
+            manager: SubscriptionManager::new(Arc::new(executor)),
+            _backend: Default::default(),
+        }
+    }
+}
+
+impl<C, B> EventsApi for EventsRpc<C, B>
+where
+    C: BlockchainEvents<Block> + StorageProvider<Block, B> + Send + Sync + 'static,
+    B: sc_client_api::Backend<Block> + Send + Sync + 'static,
+{
+    type Metadata = sc_rpc::Metadata;
+
+    fn subscribe_filtered(
+        &self,
+        _metadata: Self::Metadata,
+        subscriber: Subscriber<BlockEvents>,
+        filter: EventFilter,
+    ) {
+        if let Some(pallet) = filter.pallets.iter().find(|p| !self.names.has_pallet(p)) {
+            let _ = subscriber.reject(RpcError {
+                code: ErrorCode::InvalidParams,
+                message: format!("Unknown pallet or pallet without events: {}", pallet),
+                data: None,
+            });
+            return;
+        }
+
+        let client = self.client.clone();
+        let names = self.names.clone();
+        let notifications = self
+            .client
+            .finality_notification_stream()
+            .filter_map(move |notification| {
+                let events = match read_events::<_, B>(&*client, notification.hash) {
+                    Ok((_, events)) => filter_events(&names, &filter, events),
+                    Err(e) => {
+                        log::warn!(
+                            target: "events",
+                            "Unable to read the events of block {}: {}",
+                            notification.hash,
+                            e,
+                        );
+                        Vec::new()
+                    }
+                };
+                future::ready(if events.is_empty() {
+                    None
+                } else {
+                    Some(Ok::<_, ()>(Ok(BlockEvents {
+                        hash: notification.hash,
+                        number: *notification.header.number(),
+                        events,
+                    })))
+                })
+            })
+            .boxed()
+            .compat();
+
+        self.manager.add(subscriber, |sink| {
+            sink.sink_map_err(|e| log::warn!(target: "events", "Error sending events: {:?}", e))
+                .send_all(notifications)
+                .map(|_| ())
+        });
+    }
+
+    fn unsubscribe_filtered(
+        &self,
+        _metadata: Option<Self::Metadata>,
+        id: SubscriptionId,
+    ) -> Result<bool> {
+        Ok(self.manager.cancel(id))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use frame_system::EventRecord;
+
+    fn account(seed: u8) -> AccountId {
+        AccountId::new([seed; 32])
+    }
+
+    fn events() -> Vec<EventRecord<Event, Hash>> {
+        vec![
+            EventRecord {
+                phase: Phase::Initialization,
+                event: Event::pallet_balances(pallet_balances::Event::Transfer(
+                    account(1),
+                    account(2),
+                    10,
+                )),
+                topics: vec![],
+            },
+            EventRecord {
+                phase: Phase::ApplyExtrinsic(3),
+                event: Event::orml_currencies(orml_currencies::Event::Transferred(
+                    AssetId::DOT,
+                    account(2),
+                    account(3),
+                    20,
+                )),
+                topics: vec![],
+            },
+        ]
+    }
+
+    fn matching(filter: EventFilter) -> Vec<(u32, Option<u32>, String, String)> {
+        let names = EventNames::native().unwrap();
+        filter_events(&names, &filter, events())
+            .into_iter()
+            .map(|event| {
+                (
+                    event.index,
+                    event.extrinsic_index,
+                    event.pallet,
+                    event.event,
+                )
+            })
+            .collect()
+    }
+
+    #[test]
+    fn events_are_filtered_by_pallet_event_and_account() {
+        let balances = (0, None, "Balances".to_string(), "Transfer".to_string());
+        let currencies = (
+            1,
+            Some(3),
+            "Currencies".to_string(),
+            "Transferred".to_string(),
+        );
+
+        assert_eq!(
+            matching(EventFilter::default()),
+            vec![balances.clone(), currencies.clone()]
+        );
+        assert_eq!(
+            matching(EventFilter {
+                pallets: vec!["Tokens".into(), "Currencies".into()],
+                ..Default::default()
+            }),
+            vec![currencies.clone()]
+        );
+        assert_eq!(
+            matching(EventFilter {
+                events: vec!["Transfer".into()],
+                ..Default::default()
+            }),
+            vec![balances.clone()]
+        );
+        assert_eq!(
+            matching(EventFilter {
+                account: Some(account(2)),
+                ..Default::default()
+            }),
+            vec![balances, currencies.clone()]
+        );
+        assert_eq!(
+            matching(EventFilter {
+                account: Some(account(3)),
+                ..Default::default()
+            }),
+            vec![currencies]
+        );
+        assert!(matching(EventFilter {
+            pallets: vec!["Balances".into()],
+            account: Some(account(3)),
+            ..Default::default()
+        })
+        .is_empty());
+    }
+
+    #[test]
+    fn data_holds_the_encoded_fields() {
+        let names = EventNames::native().unwrap();
+        let filtered = filter_events(&names, &EventFilter::default(), events());
+        assert_eq!(
+            filtered[0].data.0,
+            (account(1), account(2), 10 as Balance).encode()
+        );
+        assert!(names.has_pallet("Tokens"));
+        assert!(names.has_pallet("Orderbook"));
+        assert!(!names.has_pallet("Timestamp"));
+    }
+
+    #[test]
+    fn only_account_fields_are_compared() {
+        let names = EventNames::native().unwrap();
+        // The two amounts of the trade encode as 32 bytes of 4.
+        let amount = Balance::from_le_bytes([4; 16]);
+        let trade = EventRecord {
+            phase: Phase::ApplyExtrinsic(1),
+            event: Event::pallet_orderbook(pallet_orderbook::RawEvent::Trade(
+                1,
+                2,
+                FixedU128::from(1),
+                amount,
+                amount,
+            )),
+            topics: vec![],
+        };
+        let filter = EventFilter {
+            account: Some(account(4)),
+            ..Default::default()
+        };
+        assert!(filter_events(&names, &filter, vec![trade]).is_empty());
+
+        let placed = EventRecord {
+            phase: Phase::ApplyExtrinsic(1),
+            event: Event::pallet_orderbook(pallet_orderbook::RawEvent::OrderPlaced(
+                1,
+                account(4),
+                AssetId::POLKADEX,
+                AssetId::DOT,
+                pallet_orderbook::Side::Bid,
+            )),
+            topics: vec![],
+        };
+        assert_eq!(filter_events(&names, &filter, vec![placed]).len(), 1);
+    }
+}
@@ -0,0 +1,582 @@
+// This file is part of Polkadex.
+
+// Copyright (C) 2020-2021 Polkadex oü.
+// SPDX-License-Identifier: GPL-3.0-or-later WITH Classpath-exception-2.0
+
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with this program. If not, see <https://www.gnu.org/licenses/>.
+
+//! Chain specs described by a declarative genesis file.
+//!
+//! A genesis file is a TOML (or JSON) document such as:
+//!
+//! ```toml
+//! name = "Polkadex Testnet"
+//! id = "polkadex_testnet"
+//! chain_type = "Live"
+//! boot_nodes = ["/dns/boot.polkadex.trade/tcp/30333/p2p/12D3KooW..."]
+//! telemetry = ["wss://telemetry.polkadot.io/submit/"]
+//! sudo = "5Ff3iXP75ruzroPWRP2FYBHWnmGGBSb63857BgnzCoXNxfPo"
+//! council = ["5Ff3iXP75ruzroPWRP2FYBHWnmGGBSb63857BgnzCoXNxfPo"]
+//! technical_committee = ["5Ff3iXP75ruzroPWRP2FYBHWnmGGBSb63857BgnzCoXNxfPo"]
+//! oracle_feeders = ["5Ff3iXP75ruzroPWRP2FYBHWnmGGBSb63857BgnzCoXNxfPo"]
+//!
+//! [[authorities]]
+//! stash = "5Fbsd6WXDGiLTxunqeK5BATNiocfCqu9bS1yArVjCgeBLkVy"
+//! controller = "5EnCiV7wSHeNhjW3FSUwiJNkcc2SBkPLn5Nj93FmbLtBjQUq"
+//! grandpa = "5F9kWZrdka5XtKjpJpgCTs4UdUueWJCQKye9FTExYTRoZBav"
+//! babe = "5CDkjxZYhWraPNN1MWwG6TBZ6dsiMiDWTBSdwynowgR6dUTq"
+//! im_online = "5HdHnVgUnfXNW6AxPDoaJ3QGA9otx2qujXDErAXVekahgkKA"
+//! authority_discovery = "5DFUorUHAHQrQNEG6b71FApWnpZU6J64RyzqXz2cwp7K3R7f"
+//!
+//! [[balances]]
+//! account = "5Fbsd6WXDGiLTxunqeK5BATNiocfCqu9bS1yArVjCgeBLkVy"
+//! amount = "10000000000000000000000000"
+//!
+//! [[tokens]]
+//! account = "5Ff3iXP75ruzroPWRP2FYBHWnmGGBSb63857BgnzCoXNxfPo"
+//! asset = "DOT"
+//! amount = "1000000000000000000"
+//!
+//! [[vesting]]
+//! account = "5Fbsd6WXDGiLTxunqeK5BATNiocfCqu9bS1yArVjCgeBLkVy"
+//! start = 0
+//! period = 14400
+//! period_count = 100
+//! per_period = "1000000000000000000"
+//! ```
+//!
+//! Accounts and session keys are SS58 addresses. Each session key of an authority should be its
+//! own key, as printed by the `generate-authority` command. Amounts are in the smallest unit and
+//! may be given as strings, which is required for values that do not fit in 64 bits.
+
+use std::{
+    collections::{BTreeMap, BTreeSet},
+    fs,
+    io::Write,
+    path::{Path, PathBuf},
+};
+
+use grandpa_primitives::AuthorityId as GrandpaId;
+use pallet_im_online::sr25519::AuthorityId as ImOnlineId;
+use polkadex_primitives::assets::AssetId;
+use polkadex_primitives::{AccountId, Balance, BlockNumber};
+use sc_chain_spec::Properties;
+use sc_network::config::MultiaddrWithPeerId;
+use sc_service::ChainType;
+use sc_telemetry::TelemetryEndpoints;
+use serde::{Deserialize, Deserializer};
+use sp_authority_discovery::AuthorityId as AuthorityDiscoveryId;
+use sp_consensus_babe::AuthorityId as BabeId;
+use structopt::StructOpt;
+
+use node_polkadex_runtime::{DesiredMembers, ExistentialDeposit, GenesisConfig, MAX_NOMINATIONS};
+
+use crate::chain_spec::{ocex_custody_balance, testnet_genesis, validate_genesis, ChainSpec, STASH};
+
+/// A validator of the genesis file, with its session keys.
+#[derive(Clone, Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct GenesisAuthority {
+    pub stash: AccountId,
+    pub controller: AccountId,
+    pub grandpa: GrandpaId,
+    pub babe: BabeId,
+    pub im_online: ImOnlineId,
+    pub authority_discovery: AuthorityDiscoveryId,
+}
+
+/// A nominator of the genesis file and the authorities' stashes it nominates.
+#[derive(Clone, Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct GenesisNominator {
+    pub account: AccountId,
+    pub targets: Vec<AccountId>,
+}
+
+/// Free native balance of an account.
+#[derive(Clone, Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct GenesisBalance {
+    pub account: AccountId,
+    #[serde(deserialize_with = "deserialize_balance")]
+    pub amount: Balance,
+}
+
+/// `orml_tokens` balance of an account.
+#[derive(Clone, Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct GenesisTokenBalance {
+    pub account: AccountId,
+    #[serde(deserialize_with = "deserialize_asset")]
+    pub asset: AssetId,
+    #[serde(deserialize_with = "deserialize_balance")]
+    pub amount: Balance,
+}
+
+/// `orml_vesting` schedule locking part of an account's native balance.
+#[derive(Clone, Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct GenesisVesting {
+    pub account: AccountId,
+    pub start: BlockNumber,
+    pub period: BlockNumber,
+    pub period_count: u32,
+    #[serde(deserialize_with = "deserialize_balance")]
+    pub per_period: Balance,
+}
+
+/// Declarative description of a chain and its genesis state.
+#[derive(Clone, Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct GenesisFile {
+    pub name: String,
+    pub id: String,
+    #[serde(default = "default_chain_type")]
+    pub chain_type: ChainType,
+    #[serde(default)]
+    pub boot_nodes: Vec<String>,
+    #[serde(default)]
+    pub telemetry: Vec<String>,
+    #[serde(default)]
+    pub protocol_id: Option<String>,
+    #[serde(default)]
+    pub properties: Option<Properties>,
+    pub sudo: AccountId,
+    pub authorities: Vec<GenesisAuthority>,
+    #[serde(default)]
+    pub nominators: Vec<GenesisNominator>,
+    #[serde(default)]
+    pub balances: Vec<GenesisBalance>,
+    #[serde(default)]
+    pub tokens: Vec<GenesisTokenBalance>,
+    #[serde(default)]
+    pub vesting: Vec<GenesisVesting>,
+    #[serde(default)]
+    pub council: Vec<AccountId>,
+    #[serde(default)]
+    pub technical_committee: Vec<AccountId>,
+    #[serde(default)]
+    pub oracle_feeders: Vec<AccountId>,
+}
+
+fn default_chain_type() -> ChainType {
+    ChainType::Live
+}
+
+fn deserialize_balance<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Balance, D::Error> {
+    #[derive(Deserialize)]
+    #[serde(untagged)]
+    enum Amount {
+        Number(u64),
+        Text(String),
+    }
+
+    match Amount::deserialize(deserializer)? {
+        Amount::Number(amount) => Ok(amount.into()),
+        Amount::Text(amount) => amount
+            .replace('_', "")
+            .parse()
+            .map_err(|e| serde::de::Error::custom(format!("invalid amount {:?}: {}", amount, e))),
+    }
+}
+
+/// Parses an asset symbol, such as `PDEX` or `DOT`.
+pub fn parse_asset(symbol: &str) -> Result<AssetId, String> {
+    match symbol {
+        "PDEX" | "POLKADEX" => Ok(AssetId::POLKADEX),
+        "DOT" => Ok(AssetId::DOT),
+        "BTC" => Ok(AssetId::BTC),
+        "USD" => Ok(AssetId::USD),
+        _ => Err(format!("unknown asset {:?}", symbol)),
+    }
+}
+
+fn deserialize_asset<'de, D: Deserializer<'de>>(deserializer: D) -> Result<AssetId, D::Error> {
+    parse_asset(&String::deserialize(deserializer)?).map_err(serde::de::Error::custom)
+}
+
+impl GenesisFile {
+    /// Reads a genesis file, in JSON if its extension is `.json` and in TOML otherwise.
+    pub fn load(path: &Path) -> Result<Self, String> {
+        let content = fs::read_to_string(path)
+            .map_err(|e| format!("Error reading genesis file {}: {}", path.display(), e))?;
+        let parsed = match path.extension().and_then(|ext| ext.to_str()) {
+            Some("json") => serde_json::from_str(&content).map_err(|e| e.to_string()),
+            _ => toml::from_str(&content).map_err(|e| e.to_string()),
+        };
+        parsed.map_err(|e| format!("Error parsing genesis file {}: {}", path.display(), e))
+    }
+
+    fn balance_of(&self, account: &AccountId) -> Balance {
+        self.balances
+            .iter()
+            .find(|balance| &balance.account == account)
+            .map_or(0, |balance| balance.amount)
+    }
+
+    fn token_endowments(&self) -> Vec<(AccountId, AssetId, Balance)> {
+        self.tokens
+            .iter()
+            .map(|t| (t.account.clone(), t.asset, t.amount))
+            .collect()
+    }
+
+    /// Checks that the described genesis state can be built, with the checks of
+    /// `validate_genesis`, and returns the total issuance of every endowed asset.
+    pub fn validate(&self) -> Result<BTreeMap<AssetId, Balance>, String> {
+        if self.authorities.is_empty() {
+            return Err("At least one authority is required".into());
+        }
+        let stashes = self.authorities.iter().map(|a| &a.stash).collect::<BTreeSet<_>>();
+        if stashes.len() != self.authorities.len() {
+            return Err("Authority stashes must be unique".into());
+        }
+        let mut controllers = BTreeSet::new();
+        for authority in &self.authorities {
+            if !controllers.insert(&authority.controller) {
+                return Err(format!("Controller {} is used twice", authority.controller));
+            }
+        }
+
+        let mut endowed = BTreeSet::new();
+        for balance in &self.balances {
+            if !endowed.insert(&balance.account) {
+                return Err(format!("Account {} is endowed twice", balance.account));
+            }
+            if balance.amount < ExistentialDeposit::get() {
+                return Err(format!(
+                    "Balance of {} is below the existential deposit",
+                    balance.account
+                ));
+            }
+        }
+
+        let stakers = self
+            .authorities
+            .iter()
+            .map(|a| &a.stash)
+            .chain(self.nominators.iter().map(|n| &n.account));
+        for staker in stakers {
+            if self.balance_of(staker) < STASH {
+                return Err(format!("Staker {} must be endowed with at least {}", staker, STASH));
+            }
+        }
+        for nominator in &self.nominators {
+            if stashes.contains(&nominator.account) {
+                return Err(format!("Nominator {} is an authority", nominator.account));
+            }
+            if nominator.targets.is_empty() || nominator.targets.len() > MAX_NOMINATIONS as usize {
+                return Err(format!(
+                    "Nominator {} must nominate between 1 and {} authorities",
+                    nominator.account, MAX_NOMINATIONS
+                ));
+            }
+            if let Some(target) = nominator.targets.iter().find(|t| !stashes.contains(t)) {
+                return Err(format!(
+                    "Nominator {} nominates {}, which is not an authority stash",
+                    nominator.account, target
+                ));
+            }
+        }
+
+        let mut vested = BTreeSet::new();
+        for schedule in &self.vesting {
+            if !vested.insert(&schedule.account) {
+                return Err(format!("Account {} has two vesting schedules", schedule.account));
+            }
+            if schedule.period == 0 || schedule.period_count == 0 {
+                return Err(format!(
+                    "Vesting schedule of {} must have a non-zero period and period count",
+                    schedule.account
+                ));
+            }
+            let locked = schedule
+                .per_period
+                .checked_mul(schedule.period_count.into())
+                .ok_or_else(|| format!("Vesting schedule of {} overflows", schedule.account))?;
+            if self.balance_of(&schedule.account) < locked {
+                return Err(format!(
+                    "Account {} must be endowed with the {} it vests",
+                    schedule.account, locked
+                ));
+            }
+        }
+
+        if self.council.len() > DesiredMembers::get() as usize {
+            return Err(format!("The council has more than {} members", DesiredMembers::get()));
+        }
+        if self.council.iter().collect::<BTreeSet<_>>().len() != self.council.len() {
+            return Err("Council members must be unique".into());
+        }
+        if let Some(member) = self.council.iter().find(|m| self.balance_of(m) < STASH) {
+            return Err(format!(
+                "Council member {} must be endowed with at least {}",
+                member, STASH
+            ));
+        }
+        let technical_committee = self.technical_committee.iter().collect::<BTreeSet<_>>();
+        if technical_committee.len() != self.technical_committee.len() {
+            return Err("Technical committee members must be unique".into());
+        }
+
+        self.boot_nodes()?;
+        self.telemetry_endpoints()?;
+        validate_genesis(&self.genesis())
+    }
+
+    fn boot_nodes(&self) -> Result<Vec<MultiaddrWithPeerId>, String> {
+        self.boot_nodes
+            .iter()
+            .map(|node| node.parse().map_err(|e| format!("Invalid boot node {}: {}", node, e)))
+            .collect()
+    }
+
+    fn telemetry_endpoints(&self) -> Result<Option<TelemetryEndpoints>, String> {
+        if self.telemetry.is_empty() {
+            return Ok(None);
+        }
+        TelemetryEndpoints::new(self.telemetry.iter().map(|url| (url.clone(), 0)).collect())
+            .map(Some)
+            .map_err(|e| format!("Invalid telemetry endpoint: {}", e))
+    }
+
+    /// Builds the genesis config. The file must have been validated.
+    pub fn genesis(&self) -> GenesisConfig {
+        let mut genesis = testnet_genesis(
+            self.authorities
+                .iter()
+                .map(|a| {
+                    (
+                        a.stash.clone(),
+                        a.controller.clone(),
+                        a.grandpa.clone(),
+                        a.babe.clone(),
+                        a.im_online.clone(),
+                        a.authority_discovery.clone(),
+                    )
+                })
+                .collect(),
+            self.nominators
+                .iter()
+                .map(|n| (n.account.clone(), n.targets.clone()))
+                .collect(),
+            self.sudo.clone(),
+            Some(self.balances.iter().map(|b| b.account.clone()).collect()),
+            self.token_endowments(),
+            self.oracle_feeders.clone(),
+            false,
+        );
+
+        genesis.pallet_balances.balances = self
+            .balances
+            .iter()
+            .map(|b| (b.account.clone(), b.amount))
+            .chain(std::iter::once(ocex_custody_balance()))
+            .collect();
+        genesis.orml_vesting.vesting = self
+            .vesting
+            .iter()
+            .map(|v| (v.account.clone(), v.start, v.period, v.period_count, v.per_period))
+            .collect();
+        genesis.pallet_elections_phragmen.members =
+            self.council.iter().map(|m| (m.clone(), STASH)).collect();
+        genesis.pallet_collective_Instance2.members = self.technical_committee.clone();
+        genesis
+    }
+
+    /// Validates the file and builds the chain spec it describes.
+    pub fn into_chain_spec(self) -> Result<ChainSpec, String> {
+        self.validate()?;
+        let boot_nodes = self.boot_nodes()?;
+        let telemetry_endpoints = self.telemetry_endpoints()?;
+        let (name, id, chain_type) = (self.name.clone(), self.id.clone(), self.chain_type.clone());
+        let protocol_id = self.protocol_id.clone();
+        let properties = self.properties.clone();
+        Ok(ChainSpec::from_genesis(
+            &name,
+            &id,
+            chain_type,
+            move || self.genesis(),
+            boot_nodes,
+            telemetry_endpoints,
+            protocol_id.as_deref(),
+            properties,
+            Default::default(),
+        ))
+    }
+}
+
+/// The `generate-spec` command used to build a chain spec from a genesis file.
+#[derive(Debug, StructOpt)]
+pub struct GenerateSpecCmd {
+    /// Genesis file describing the chain, in TOML or JSON (`.json` extension) format.
+    #[structopt(parse(from_os_str))]
+    pub genesis_file: PathBuf,
+
+    /// Force raw genesis storage output.
+    #[structopt(long = "raw")]
+    pub raw: bool,
+
+    /// Print the genesis issuance of every asset to stderr.
+    #[structopt(long = "verbose")]
+    pub verbose: bool,
+}
+
+impl GenerateSpecCmd {
+    /// Run the generate-spec command
+    pub fn run(&self) -> sc_cli::Result<()> {
+        let file = GenesisFile::load(&self.genesis_file)?;
+        let issuance = file.validate()?;
+        if self.verbose {
+            for (asset, issuance) in issuance {
+                eprintln!("Genesis issuance of {:?}: {}", asset, issuance);
+            }
+        }
+        let spec = file.into_chain_spec()?;
+        let json = sc_service::chain_ops::build_spec(&spec, self.raw)?;
+        std::io::stdout().write_all(json.as_bytes())?;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use serde::Serialize;
+    use sp_core::sr25519;
+    use sp_runtime::BuildStorage;
+
+    use crate::chain_spec::{authority_keys_from_seed, get_account_id_from_seed};
+
+    use super::*;
+
+    fn ss58<T: Serialize>(key: &T) -> String {
+        serde_json::to_value(key).unwrap().as_str().unwrap().to_owned()
+    }
+
+    fn account(seed: &str) -> String {
+        ss58(&get_account_id_from_seed::<sr25519::Public>(seed))
+    }
+
+    fn authority(seed: &str) -> String {
+        let (stash, controller, grandpa, babe, im_online, authority_discovery) =
+            authority_keys_from_seed(seed);
+        format!(
+            "[[authorities]]\nstash = {:?}\ncontroller = {:?}\ngrandpa = {:?}\nbabe = {:?}\n\
+             im_online = {:?}\nauthority_discovery = {:?}\n",
+            ss58(&stash),
+            ss58(&controller),
+            ss58(&grandpa),
+            ss58(&babe),
+            ss58(&im_online),
+            ss58(&authority_discovery),
+        )
+    }
+
+    fn balance(account: &str, amount: &str) -> String {
+        format!("[[balances]]\naccount = {:?}\namount = {:?}\n", account, amount)
+    }
+
+    fn genesis_file(extra: &str) -> String {
+        format!(
+            "name = \"Test\"\nid = \"test\"\nchain_type = \"Local\"\nsudo = {alice:?}\n\
+             council = [{alice:?}]\ntechnical_committee = [{alice:?}]\n{}{}{}{}\
+             [[tokens]]\naccount = {alice:?}\nasset = \"DOT\"\namount = 1000000\n\
+             [[vesting]]\naccount = {alice:?}\nstart = 0\nperiod = 10\nperiod_count = 5\n\
+             per_period = \"1_000_000_000_000\"\n{}",
+            authority("Alice"),
+            balance(&account("Alice"), "10_000_000_000_000_000_000"),
+            balance(&account("Alice//stash"), "10_000_000_000_000_000_000"),
+            balance(&account("Bob"), "10_000_000_000_000_000_000"),
+            extra,
+            alice = account("Alice"),
+        )
+    }
+
+    fn parse(content: &str) -> GenesisFile {
+        toml::from_str(content).unwrap()
+    }
+
+    #[test]
+    fn builds_chain_spec_from_toml() {
+        let file = parse(&genesis_file(""));
+        let issuance = file.validate().unwrap();
+        assert_eq!(issuance.into_iter().collect::<Vec<_>>(), vec![(AssetId::DOT, 1_000_000)]);
+        assert_eq!(file.tokens[0].asset, AssetId::DOT);
+        assert_eq!(file.tokens[0].amount, 1_000_000);
+        assert_eq!(file.vesting[0].per_period, 1_000_000_000_000);
+
+        let genesis = file.genesis();
+        assert_eq!(
+            genesis.pallet_balances.balances.len(),
+            file.balances.len() + 1
+        );
+        assert_eq!(genesis.orml_vesting.vesting.len(), 1);
+
+        let spec = file.into_chain_spec().unwrap();
+        spec.build_storage().unwrap();
+        assert_eq!(
+            spec.as_json(true).unwrap(),
+            parse(&genesis_file("")).into_chain_spec().unwrap().as_json(true).unwrap()
+        );
+    }
+
+    #[test]
+    fn parses_json() {
+        let toml_value: toml::Value = toml::from_str(&genesis_file("")).unwrap();
+        let json = serde_json::to_string(&toml_value).unwrap();
+        let file: GenesisFile = serde_json::from_str(&json).unwrap();
+        assert!(file.validate().is_ok());
+    }
+
+    #[test]
+    fn rejects_unknown_assets_and_fields() {
+        let unknown_asset = genesis_file("").replace("\"DOT\"", "\"ETH\"");
+        assert!(toml::from_str::<GenesisFile>(&unknown_asset).is_err());
+        let unknown_field = format!("{}\nfoo = 1\n", genesis_file(""));
+        assert!(toml::from_str::<GenesisFile>(&unknown_field).is_err());
+    }
+
+    #[test]
+    fn rejects_inconsistent_genesis() {
+        let mut file = parse(&genesis_file(""));
+        file.authorities.clear();
+        assert!(file.validate().is_err());
+
+        let file = parse(&genesis_file(&balance(&account("Bob"), "1_000_000_000_000")));
+        assert!(file.validate().unwrap_err().contains("endowed twice"));
+
+        let mut file = parse(&genesis_file(""));
+        file.balances.retain(|b| b.account != file.authorities[0].stash);
+        assert!(file.validate().unwrap_err().contains("Staker"));
+
+        let mut file = parse(&genesis_file(""));
+        file.nominators.push(GenesisNominator {
+            account: get_account_id_from_seed::<sr25519::Public>("Bob"),
+            targets: vec![get_account_id_from_seed::<sr25519::Public>("Charlie")],
+        });
+        assert!(file.validate().unwrap_err().contains("not an authority stash"));
+
+        let mut file = parse(&genesis_file(""));
+        file.vesting[0].period_count = u32::MAX;
+        assert!(file.validate().unwrap_err().contains("vests"));
+
+        let mut file = parse(&genesis_file(""));
+        file.council.push(get_account_id_from_seed::<sr25519::Public>("Charlie"));
+        assert!(file.validate().unwrap_err().contains("Council member"));
+
+        let mut file = parse(&genesis_file(""));
+        file.boot_nodes.push("not a multiaddr".into());
+        assert!(file.validate().unwrap_err().contains("boot node"));
+    }
+}
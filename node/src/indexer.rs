@@ -0,0 +1,1191 @@
+// This file is part of Polkadex.
+
+// Copyright (C) 2020-2021 Polkadex oü.
+// SPDX-License-Identifier: GPL-3.0-or-later WITH Classpath-exception-2.0
+
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with this program. If not, see <https://www.gnu.org/licenses/>.
+//! Index of the trades and transfers of finalized blocks, serving candles and trade history.
+//!
+//! The runtime only keeps the events of the current block, so charts and account histories
+//! need a node that records them. When started with `--indexer`, a full node follows
+//! finalized blocks, decodes their `System::Events` and stores in a local sled database:
+//!
+//! - trades of the orderbook, continuous or in batch auctions, and trades settled by
+//!   `pallet_signed_orders`, per trading pair, along with OHLCV candles of one minute, five
+//!   minutes, one hour and one day;
+//! - PDEX transfers of `pallet_balances` and token transfers of `orml_currencies`, per
+//!   account.
+//!
+//! The orderbook reports its trades by order id, so the indexer also keeps the owner, pair
+//! and side of the open orders it saw placed.
+//!
+//! The runtime has no migration pallet yet, so there are no migration events to index.
+//!
+//! Events are read from the state of each block, and indexing stops at the first block whose
+//! state cannot be read. Run an archive node (`--pruning archive`) to index a chain from
+//! genesis.
+
+use std::collections::HashMap;
+use std::path::Path;
+use std::sync::Arc;
+
+use codec::{Decode, Encode};
+use frame_system::{EventRecord, Phase};
+use futures::StreamExt;
+use jsonrpc_core::{Error as RpcError, ErrorCode, Result};
+use jsonrpc_derive::rpc;
+use frame_support::traits::PalletInfo;
+use frame_support::StorageValue;
+use node_polkadex_runtime::{Event, Runtime};
+use pallet_orderbook::{OrderId, Side};
+use polkadex_primitives::assets::AssetId;
+use polkadex_primitives::{AccountId, Balance, Block, BlockNumber, Hash, Moment};
+use sc_client_api::{BlockchainEvents, StorageProvider};
+use serde::{Deserialize, Serialize};
+use sp_blockchain::HeaderBackend;
+use sp_core::hashing::twox_128;
+use sp_core::storage::StorageKey;
+use sp_rpc::number::NumberOrHex;
+use sp_runtime::generic::BlockId;
+use sp_runtime::traits::Header as HeaderT;
+use sp_runtime::{FixedPointNumber, FixedU128};
+
+use crate::genesis_file::parse_asset;
+
+/// Number of items returned by a page when the request does not say.
+const DEFAULT_PAGE_SIZE: u32 = 100;
+/// Maximum number of items returned by a page or candle request.
+const MAX_PAGE_SIZE: u32 = 1_000;
+
+const TRADES: u8 = b't';
+const TRANSFERS: u8 = b'x';
+const CANDLES: u8 = b'c';
+const ORDERS: u8 = b'o';
+const LAST_INDEXED: &[u8] = b"m/last-indexed";
+
+/// Length of a candle.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Deserialize, Serialize)]
+pub enum CandleInterval {
+    #[serde(rename = "1m")]
+    OneMinute,
+    #[serde(rename = "5m")]
+    FiveMinutes,
+    #[serde(rename = "1h")]
+    OneHour,
+    #[serde(rename = "1d")]
+    OneDay,
+}
+
+impl CandleInterval {
+    const ALL: [CandleInterval; 4] = [
+        CandleInterval::OneMinute,
+        CandleInterval::FiveMinutes,
+        CandleInterval::OneHour,
+        CandleInterval::OneDay,
+    ];
+
+    fn millis(self) -> Moment {
+        match self {
+            CandleInterval::OneMinute => 60_000,
+            CandleInterval::FiveMinutes => 5 * 60_000,
+            CandleInterval::OneHour => 60 * 60_000,
+            CandleInterval::OneDay => 24 * 60 * 60_000,
+        }
+    }
+
+    /// Start of the candle containing `timestamp`.
+    fn start(self, timestamp: Moment) -> Moment {
+        timestamp - timestamp % self.millis()
+    }
+}
+
+/// Position of an event in the chain, used to page through histories.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Cursor {
+    pub block: BlockNumber,
+    /// Index of the event among the events of the block.
+    pub event_index: u32,
+}
+
+impl Cursor {
+    fn key(self) -> [u8; 8] {
+        let mut key = [0u8; 8];
+        key[..4].copy_from_slice(&self.block.to_be_bytes());
+        key[4..].copy_from_slice(&self.event_index.to_be_bytes());
+        key
+    }
+
+    fn from_key(key: &[u8]) -> Cursor {
+        let mut block = [0u8; 4];
+        let mut event_index = [0u8; 4];
+        block.copy_from_slice(&key[key.len() - 8..key.len() - 4]);
+        event_index.copy_from_slice(&key[key.len() - 4..]);
+        Cursor {
+            block: BlockNumber::from_be_bytes(block),
+            event_index: u32::from_be_bytes(event_index),
+        }
+    }
+}
+
+/// A page of a history, newest first.
+#[derive(Clone, Debug, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Page<T> {
+    pub items: Vec<T>,
+    /// Cursor to pass as `before` to get the next page, if there are older items.
+    pub next: Option<Cursor>,
+}
+
+/// A trade between two orders.
+#[derive(Clone, Debug, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Trade {
+    pub block: BlockNumber,
+    pub event_index: u32,
+    /// Index of the settling extrinsic in the block.
+    pub extrinsic_index: Option<u32>,
+    /// Timestamp of the block, in milliseconds since the Unix epoch.
+    pub timestamp: Moment,
+    pub buyer: AccountId,
+    pub seller: AccountId,
+    /// Quote asset paid per unit of the base asset, as a decimal.
+    pub price: String,
+    pub base_amount: NumberOrHex,
+    pub quote_amount: NumberOrHex,
+}
+
+/// A transfer of an asset between two accounts.
+#[derive(Clone, Debug, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Transfer {
+    pub block: BlockNumber,
+    pub event_index: u32,
+    pub extrinsic_index: Option<u32>,
+    pub timestamp: Moment,
+    /// Symbol of the transferred asset.
+    pub asset: String,
+    pub from: AccountId,
+    pub to: AccountId,
+    pub amount: NumberOrHex,
+}
+
+/// Open, high, low and close prices and volumes of the trades of a pair within an interval.
+///
+/// Intervals without trades have no candle.
+#[derive(Clone, Debug, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Candle {
+    /// Start of the interval, in milliseconds since the Unix epoch.
+    pub start: Moment,
+    pub open: String,
+    pub high: String,
+    pub low: String,
+    pub close: String,
+    /// Traded amount of the base asset.
+    pub base_volume: NumberOrHex,
+    /// Traded amount of the quote asset.
+    pub quote_volume: NumberOrHex,
+    /// Number of trades.
+    pub trades: u32,
+}
+
+#[derive(Clone, Debug, PartialEq, Encode, Decode)]
+struct TradeRecord {
+    extrinsic_index: Option<u32>,
+    timestamp: Moment,
+    buyer: AccountId,
+    seller: AccountId,
+    price: FixedU128,
+    base_amount: Balance,
+    quote_amount: Balance,
+}
+
+/// An open order of the orderbook.
+#[derive(Clone, Debug, PartialEq, Encode, Decode)]
+pub(crate) struct OrderRecord {
+    owner: AccountId,
+    base: AssetId,
+    quote: AssetId,
+    side: Side,
+}
+
+impl From<pallet_orderbook::OrderOf<Runtime>> for OrderRecord {
+    fn from(order: pallet_orderbook::OrderOf<Runtime>) -> Self {
+        OrderRecord {
+            owner: order.owner,
+            base: order.pair.base,
+            quote: order.pair.quote,
+            side: order.side,
+        }
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Encode, Decode)]
+struct TransferRecord {
+    extrinsic_index: Option<u32>,
+    timestamp: Moment,
+    asset: AssetId,
+    from: AccountId,
+    to: AccountId,
+    amount: Balance,
+}
+
+#[derive(Clone, Debug, PartialEq, Encode, Decode)]
+struct CandleRecord {
+    open: FixedU128,
+    high: FixedU128,
+    low: FixedU128,
+    close: FixedU128,
+    base_volume: Balance,
+    quote_volume: Balance,
+    trades: u32,
+}
+
+impl CandleRecord {
+    fn new(price: FixedU128) -> Self {
+        CandleRecord {
+            open: price,
+            high: price,
+            low: price,
+            close: price,
+            base_volume: 0,
+            quote_volume: 0,
+            trades: 0,
+        }
+    }
+
+    fn add(&mut self, price: FixedU128, trade: &TradeRecord) {
+        self.high = self.high.max(price);
+        self.low = self.low.min(price);
+        self.close = price;
+        self.base_volume = self.base_volume.saturating_add(trade.base_amount);
+        self.quote_volume = self.quote_volume.saturating_add(trade.quote_amount);
+        self.trades += 1;
+    }
+}
+
+/// Formats `price` as a decimal with 18 fractional digits.
+pub(crate) fn format_price(price: FixedU128) -> String {
+    let inner = price.into_inner();
+    let accuracy = FixedU128::accuracy();
+    format!("{}.{:018}", inner / accuracy, inner % accuracy)
+}
+
+fn trade_view(cursor: Cursor, trade: TradeRecord) -> Trade {
+    Trade {
+        block: cursor.block,
+        event_index: cursor.event_index,
+        extrinsic_index: trade.extrinsic_index,
+        timestamp: trade.timestamp,
+        price: format_price(trade.price),
+        buyer: trade.buyer,
+        seller: trade.seller,
+        base_amount: NumberOrHex::Hex(trade.base_amount.into()),
+        quote_amount: NumberOrHex::Hex(trade.quote_amount.into()),
+    }
+}
+
+fn pair_key(prefix: u8, base: AssetId, quote: AssetId) -> Vec<u8> {
+    let mut key = vec![prefix];
+    (base, quote).encode_to(&mut key);
+    key
+}
+
+fn candle_key(base: AssetId, quote: AssetId, interval: CandleInterval, start: Moment) -> Vec<u8> {
+    let mut key = pair_key(CANDLES, base, quote);
+    key.push(interval as u8);
+    key.extend_from_slice(&start.to_be_bytes());
+    key
+}
+
+fn order_key(id: OrderId) -> Vec<u8> {
+    let mut key = vec![ORDERS];
+    key.extend_from_slice(&id.to_be_bytes());
+    key
+}
+
+fn account_key(account: &AccountId) -> Vec<u8> {
+    let mut key = vec![TRANSFERS];
+    key.extend_from_slice(account.as_ref());
+    key
+}
+
+/// A trade of the orderbook between two orders, whose owners and pair are looked up.
+struct BookTrade {
+    orders: (OrderId, OrderId),
+    extrinsic_index: Option<u32>,
+    price: FixedU128,
+    base_amount: Balance,
+    quote_amount: Balance,
+}
+
+impl BookTrade {
+    /// The pair and record of the trade between the orders `first` and `second`.
+    fn record(&self, timestamp: Moment, first: OrderRecord, second: OrderRecord) -> (AssetId, AssetId, TradeRecord) {
+        let (bid, ask) = if first.side == Side::Bid {
+            (first, second)
+        } else {
+            (second, first)
+        };
+        let trade = TradeRecord {
+            extrinsic_index: self.extrinsic_index,
+            timestamp,
+            buyer: bid.owner,
+            seller: ask.owner,
+            price: self.price,
+            base_amount: self.base_amount,
+            quote_amount: self.quote_amount,
+        };
+        (bid.base, bid.quote, trade)
+    }
+}
+
+/// What an event is indexed as.
+enum Indexed {
+    Trade {
+        base: AssetId,
+        quote: AssetId,
+        trade: TradeRecord,
+    },
+    BookTrade(BookTrade),
+    OrderPlaced(OrderId, OrderRecord),
+    OrderClosed(OrderId),
+    Transfer(TransferRecord),
+}
+
+/// Decodes the indexed events of a block with timestamp `timestamp`.
+fn indexed_events(
+    timestamp: Moment,
+    events: Vec<EventRecord<Event, Hash>>,
+) -> Vec<(u32, Indexed)> {
+    events
+        .into_iter()
+        .enumerate()
+        .filter_map(|(index, record)| {
+            let extrinsic_index = match record.phase {
+                Phase::ApplyExtrinsic(index) => Some(index),
+                _ => None,
+            };
+            let transfer = |asset, from, to, amount| {
+                Indexed::Transfer(TransferRecord {
+                    extrinsic_index,
+                    timestamp,
+                    asset,
+                    from,
+                    to,
+                    amount,
+                })
+            };
+            let indexed = match record.event {
+                Event::pallet_signed_orders(pallet_signed_orders::RawEvent::OrdersSettled(
+                    buyer,
+                    _,
+                    seller,
+                    _,
+                    base,
+                    quote,
+                    base_amount,
+                    quote_amount,
+                )) => Indexed::Trade {
+                    base,
+                    quote,
+                    trade: TradeRecord {
+                        extrinsic_index,
+                        timestamp,
+                        buyer,
+                        seller,
+                        price: FixedU128::checked_from_rational(quote_amount, base_amount).unwrap_or_default(),
+                        base_amount,
+                        quote_amount,
+                    },
+                },
+                Event::pallet_orderbook(pallet_orderbook::RawEvent::Trade(
+                    maker,
+                    taker,
+                    price,
+                    base_amount,
+                    quote_amount,
+                )) => Indexed::BookTrade(BookTrade {
+                    orders: (maker, taker),
+                    extrinsic_index,
+                    price,
+                    base_amount,
+                    quote_amount,
+                }),
+                Event::pallet_orderbook(pallet_orderbook::RawEvent::AuctionTrade(
+                    bid,
+                    ask,
+                    price,
+                    base_amount,
+                    quote_amount,
+                )) => Indexed::BookTrade(BookTrade {
+                    orders: (bid, ask),
+                    extrinsic_index,
+                    price,
+                    base_amount,
+                    quote_amount,
+                }),
+                Event::pallet_orderbook(pallet_orderbook::RawEvent::OrderPlaced(id, owner, base, quote, side)) => {
+                    Indexed::OrderPlaced(id, OrderRecord { owner, base, quote, side })
+                }
+                Event::pallet_orderbook(pallet_orderbook::RawEvent::OrderFilled(id))
+                | Event::pallet_orderbook(pallet_orderbook::RawEvent::OrderCancelled(id))
+                | Event::pallet_orderbook(pallet_orderbook::RawEvent::OrderExpired(id)) => Indexed::OrderClosed(id),
+                Event::pallet_balances(pallet_balances::Event::Transfer(from, to, amount)) => {
+                    transfer(AssetId::POLKADEX, from, to, amount)
+                }
+                // Native transfers are already indexed from their `pallet_balances` event.
+                Event::orml_currencies(orml_currencies::Event::Transferred(asset, from, to, amount))
+                    if asset != AssetId::POLKADEX =>
+                {
+                    transfer(asset, from, to, amount)
+                }
+                _ => return None,
+            };
+            Some((index as u32, indexed))
+        })
+        .collect()
+}
+
+/// Stores `trade` of the `base`/`quote` pair at `cursor` and adds it to its candles.
+fn insert_trade(
+    db: &sled::transaction::TransactionalTree,
+    base: AssetId,
+    quote: AssetId,
+    cursor: &[u8],
+    trade: &TradeRecord,
+) -> std::result::Result<(), sled::transaction::UnabortableTransactionError> {
+    let mut key = pair_key(TRADES, base, quote);
+    key.extend_from_slice(cursor);
+    db.insert(key, trade.encode())?;
+
+    for interval in CandleInterval::ALL.iter() {
+        let key = candle_key(base, quote, *interval, interval.start(trade.timestamp));
+        let mut candle = match db.get(&key)? {
+            Some(value) => CandleRecord::decode(&mut &value[..])
+                .unwrap_or_else(|_| CandleRecord::new(trade.price)),
+            None => CandleRecord::new(trade.price),
+        };
+        candle.add(trade.price, trade);
+        db.insert(key, candle.encode())?;
+    }
+    Ok(())
+}
+
+/// The open order `id`, if the indexer saw it placed.
+fn open_order(
+    db: &sled::transaction::TransactionalTree,
+    id: OrderId,
+) -> std::result::Result<Option<OrderRecord>, sled::transaction::UnabortableTransactionError> {
+    Ok(db
+        .get(order_key(id))?
+        .and_then(|value| OrderRecord::decode(&mut &value[..]).ok()))
+}
+
+/// Trades of the `base`/`quote` pair among the `events` of block `number`.
+///
+/// The orders of the orderbook placed before the block are looked up with `open_order`.
+pub(crate) fn block_trades(
+    number: BlockNumber,
+    timestamp: Moment,
+    events: Vec<EventRecord<Event, Hash>>,
+    base: AssetId,
+    quote: AssetId,
+    open_order: impl Fn(OrderId) -> Option<OrderRecord>,
+) -> Vec<Trade> {
+    let mut placed = HashMap::new();
+    indexed_events(timestamp, events)
+        .into_iter()
+        .filter_map(|(event_index, indexed)| {
+            let (trade_base, trade_quote, trade) = match indexed {
+                Indexed::Trade { base, quote, trade } => (base, quote, trade),
+                Indexed::BookTrade(book_trade) => {
+                    let order = |id| placed.get(&id).cloned().or_else(|| open_order(id));
+                    let (first, second) = (order(book_trade.orders.0)?, order(book_trade.orders.1)?);
+                    book_trade.record(timestamp, first, second)
+                }
+                Indexed::OrderPlaced(id, order) => {
+                    placed.insert(id, order);
+                    return None;
+                }
+                _ => return None,
+            };
+            if trade_base != base || trade_quote != quote {
+                return None;
+            }
+            Some(trade_view(
+                Cursor {
+                    block: number,
+                    event_index,
+                },
+                trade,
+            ))
+        })
+        .collect()
+}
+
+/// The indexer database.
+pub struct Indexer {
+    db: sled::Db,
+}
+
+impl Indexer {
+    /// Opens the database in `path`, creating it if needed.
+    pub fn open(path: &Path) -> std::result::Result<Self, String> {
+        let db = sled::open(path)
+            .map_err(|e| format!("Error opening indexer database {}: {}", path.display(), e))?;
+        Ok(Indexer { db })
+    }
+
+    /// Opens a database that is removed when dropped.
+    pub fn temporary() -> std::result::Result<Self, String> {
+        let db = sled::Config::new()
+            .temporary(true)
+            .open()
+            .map_err(|e| format!("Error opening indexer database: {}", e))?;
+        Ok(Indexer { db })
+    }
+
+    /// Number of the last indexed block.
+    pub fn last_indexed(&self) -> std::result::Result<Option<BlockNumber>, String> {
+        let value = self.db.get(LAST_INDEXED).map_err(|e| e.to_string())?;
+        value
+            .map(|value| BlockNumber::decode(&mut &value[..]).map_err(|e| e.to_string()))
+            .transpose()
+    }
+
+    /// Records the trades and transfers among the `events` of block `number`.
+    fn index_block(
+        &self,
+        number: BlockNumber,
+        timestamp: Moment,
+        events: Vec<EventRecord<Event, Hash>>,
+    ) -> std::result::Result<(), String> {
+        let indexed = indexed_events(timestamp, events);
+        let result: sled::transaction::TransactionResult<()> = self.db.transaction(|db| {
+            for (event_index, indexed) in &indexed {
+                let cursor = Cursor {
+                    block: number,
+                    event_index: *event_index,
+                }
+                .key();
+                match indexed {
+                    Indexed::Trade { base, quote, trade } => insert_trade(db, *base, *quote, &cursor, trade)?,
+                    Indexed::BookTrade(book_trade) => {
+                        let orders = book_trade.orders;
+                        match (open_order(db, orders.0)?, open_order(db, orders.1)?) {
+                            (Some(first), Some(second)) => {
+                                let (base, quote, trade) = book_trade.record(timestamp, first, second);
+                                insert_trade(db, base, quote, &cursor, &trade)?;
+                            }
+                            _ => log::warn!(
+                                target: "indexer",
+                                "Trade between unknown orders {} and {} in block #{}",
+                                orders.0,
+                                orders.1,
+                                number,
+                            ),
+                        }
+                    }
+                    Indexed::OrderPlaced(id, order) => {
+                        db.insert(order_key(*id), order.encode())?;
+                    }
+                    Indexed::OrderClosed(id) => {
+                        db.remove(order_key(*id))?;
+                    }
+                    Indexed::Transfer(transfer) => {
+                        let mut accounts = vec![&transfer.from];
+                        if transfer.to != transfer.from {
+                            accounts.push(&transfer.to);
+                        }
+                        for account in accounts {
+                            let mut key = account_key(account);
+                            key.extend_from_slice(&cursor);
+                            db.insert(key, transfer.encode())?;
+                        }
+                    }
+                }
+            }
+            db.insert(LAST_INDEXED, number.encode())?;
+            Ok(())
+        });
+        result.map_err(|e| format!("{:?}", e))
+    }
+
+    /// Items stored under `prefix` before `before`, newest first.
+    fn page<R: Decode, T>(
+        &self,
+        prefix: Vec<u8>,
+        before: Option<Cursor>,
+        limit: u32,
+        view: impl Fn(Cursor, R) -> T,
+    ) -> std::result::Result<Page<T>, String> {
+        let end = [
+            &prefix[..],
+            &before.map_or([0xff; 8], Cursor::key)[..],
+        ]
+        .concat();
+        let mut items = Vec::new();
+        let mut next = None;
+        for entry in self.db.range(prefix..end).rev() {
+            let (key, value) = entry.map_err(|e| e.to_string())?;
+            if items.len() == limit as usize {
+                next = items.last().map(|(cursor, _)| *cursor);
+                break;
+            }
+            let record = R::decode(&mut &value[..]).map_err(|e| e.to_string())?;
+            let cursor = Cursor::from_key(&key);
+            items.push((cursor, view(cursor, record)));
+        }
+        Ok(Page {
+            items: items.into_iter().map(|(_, item)| item).collect(),
+            next,
+        })
+    }
+
+    /// Trades of a pair, newest first.
+    pub fn trades(
+        &self,
+        base: AssetId,
+        quote: AssetId,
+        before: Option<Cursor>,
+        limit: u32,
+    ) -> std::result::Result<Page<Trade>, String> {
+        self.page(pair_key(TRADES, base, quote), before, limit, trade_view)
+    }
+
+    /// Transfers from or to `account`, newest first.
+    pub fn transfers(
+        &self,
+        account: &AccountId,
+        before: Option<Cursor>,
+        limit: u32,
+    ) -> std::result::Result<Page<Transfer>, String> {
+        self.page(account_key(account), before, limit, |cursor, transfer: TransferRecord| Transfer {
+            block: cursor.block,
+            event_index: cursor.event_index,
+            extrinsic_index: transfer.extrinsic_index,
+            timestamp: transfer.timestamp,
+            asset: format!("{:?}", transfer.asset),
+            from: transfer.from,
+            to: transfer.to,
+            amount: NumberOrHex::Hex(transfer.amount.into()),
+        })
+    }
+
+    /// Candles of a pair starting from `from` up to `to`, oldest first.
+    pub fn candles(
+        &self,
+        base: AssetId,
+        quote: AssetId,
+        interval: CandleInterval,
+        from: Moment,
+        to: Option<Moment>,
+        limit: u32,
+    ) -> std::result::Result<Vec<Candle>, String> {
+        let start = candle_key(base, quote, interval, interval.start(from));
+        let end = candle_key(base, quote, interval, to.unwrap_or(Moment::max_value()));
+        self.db
+            .range(start..=end)
+            .take(limit as usize)
+            .map(|entry| {
+                let (key, value) = entry.map_err(|e| e.to_string())?;
+                let candle = CandleRecord::decode(&mut &value[..]).map_err(|e| e.to_string())?;
+                let mut start = [0u8; 8];
+                start.copy_from_slice(&key[key.len() - 8..]);
+                Ok(Candle {
+                    start: Moment::from_be_bytes(start),
+                    open: format_price(candle.open),
+                    high: format_price(candle.high),
+                    low: format_price(candle.low),
+                    close: format_price(candle.close),
+                    base_volume: NumberOrHex::Hex(candle.base_volume.into()),
+                    quote_volume: NumberOrHex::Hex(candle.quote_volume.into()),
+                    trades: candle.trades,
+                })
+            })
+            .collect()
+    }
+
+    /// Indexes the finalized blocks following the last indexed one, up to `finalized`.
+    fn catch_up<C, B>(&self, client: &C, finalized: BlockNumber)
+    where
+        C: HeaderBackend<Block> + StorageProvider<Block, B>,
+        B: sc_client_api::Backend<Block>,
+    {
+        let next = match self.last_indexed() {
+            Ok(last) => last.map_or(1, |last| last + 1),
+            Err(e) => {
+                log::error!(target: "indexer", "Unable to read the last indexed block: {}", e);
+                return;
+            }
+        };
+        for number in next..=finalized {
+            let hash = match client.hash(number) {
+                Ok(Some(hash)) => hash,
+                _ => {
+                    log::warn!(target: "indexer", "Finalized block #{} is unknown", number);
+                    return;
+                }
+            };
+            // Stop rather than skip, so the block is indexed once its state can be read.
+            let (timestamp, events) = match read_events::<_, B>(client, hash) {
+                Ok(read) => read,
+                Err(e) => {
+                    log::error!(
+                        target: "indexer",
+                        "Unable to read the events of block #{} ({}), is its state pruned? {}",
+                        number,
+                        hash,
+                        e,
+                    );
+                    return;
+                }
+            };
+            if let Err(e) = self.index_block(number, timestamp, events) {
+                log::error!(target: "indexer", "Unable to index block #{}: {}", number, e);
+                return;
+            }
+        }
+    }
+}
+
+/// The value stored under `key` in the state of block `hash`.
+pub(crate) fn storage_value<C, B, T: Decode>(
+    client: &C,
+    hash: Hash,
+    key: StorageKey,
+) -> std::result::Result<Option<T>, String>
+where
+    C: StorageProvider<Block, B>,
+    B: sc_client_api::Backend<Block>,
+{
+    client
+        .storage(&BlockId::Hash(hash), &key)
+        .map_err(|e| e.to_string())?
+        .map(|data| T::decode(&mut &data.0[..]).map_err(|e| e.to_string()))
+        .transpose()
+}
+
+/// Key of the events of a block.
+///
+/// `frame_system` keeps its `Events` storage item private, so only the pallet prefix can be
+/// taken from the runtime.
+fn events_key() -> StorageKey {
+    let system = <Runtime as frame_system::Config>::PalletInfo::name::<frame_system::Pallet<Runtime>>()
+        .expect("System is a pallet of the runtime; qed");
+    StorageKey([&twox_128(system.as_bytes())[..], &twox_128(b"Events")[..]].concat())
+}
+
+/// Timestamp and events of block `hash`.
+pub(crate) fn read_events<C, B>(
+    client: &C,
+    hash: Hash,
+) -> std::result::Result<(Moment, Vec<EventRecord<Event, Hash>>), String>
+where
+    C: StorageProvider<Block, B>,
+    B: sc_client_api::Backend<Block>,
+{
+    let now = StorageKey(pallet_timestamp::Now::<Runtime>::hashed_key().to_vec());
+    let timestamp = storage_value::<_, B, Moment>(client, hash, now)?.unwrap_or_default();
+    let events = storage_value::<_, B, Vec<EventRecord<Event, Hash>>>(client, hash, events_key())?
+        .unwrap_or_default();
+    Ok((timestamp, events))
+}
+
+/// Indexes finalized blocks as they come.
+pub async fn run<C, B>(client: Arc<C>, indexer: Arc<Indexer>)
+where
+    C: BlockchainEvents<Block> + HeaderBackend<Block> + StorageProvider<Block, B>,
+    B: sc_client_api::Backend<Block>,
+{
+    let mut finality_notifications = client.finality_notification_stream();
+    indexer.catch_up::<_, B>(&*client, client.info().finalized_number);
+    while let Some(notification) = finality_notifications.next().await {
+        indexer.catch_up::<_, B>(&*client, *notification.header.number());
+    }
+}
+
+/// Candles and histories recorded by the indexer.
+#[rpc]
+pub trait IndexerApi {
+    /// Candles of the `base`/`quote` pair of length `interval` (`1m`, `5m`, `1h` or `1d`),
+    /// starting with the candle containing `from` and ending with the one containing `to`.
+    /// Timestamps are in milliseconds.
+    #[rpc(name = "indexer_candles")]
+    fn candles(
+        &self,
+        base: String,
+        quote: String,
+        interval: CandleInterval,
+        from: Moment,
+        to: Option<Moment>,
+        limit: Option<u32>,
+    ) -> Result<Vec<Candle>>;
+
+    /// Trades of the `base`/`quote` pair before `before`, newest first.
+    #[rpc(name = "indexer_trades")]
+    fn trades(
+        &self,
+        base: String,
+        quote: String,
+        before: Option<Cursor>,
+        limit: Option<u32>,
+    ) -> Result<Page<Trade>>;
+
+    /// Transfers from or to `account` before `before`, newest first.
+    #[rpc(name = "indexer_transfers")]
+    fn transfers(
+        &self,
+        account: AccountId,
+        before: Option<Cursor>,
+        limit: Option<u32>,
+    ) -> Result<Page<Transfer>>;
+}
+
+/// Error codes of this RPC.
+enum Error {
+    /// An asset symbol is unknown.
+    UnknownAsset,
+    /// The database could not be read.
+    DatabaseError,
+}
+
+impl From<Error> for i64 {
+    fn from(e: Error) -> i64 {
+        match e {
+            Error::UnknownAsset => 1,
+            Error::DatabaseError => 2,
+        }
+    }
+}
+
+fn rpc_error(code: Error, message: &str, data: impl std::fmt::Debug) -> RpcError {
+    RpcError {
+        code: ErrorCode::ServerError(code.into()),
+        message: message.into(),
+        data: Some(format!("{:?}", data).into()),
+    }
+}
+
+/// Parses the asset symbols of a trading pair.
+pub(crate) fn parse_pair(base: &str, quote: &str) -> Result<(AssetId, AssetId)> {
+    let parse = |symbol: &str| {
+        parse_asset(&symbol.to_uppercase())
+            .map_err(|e| rpc_error(Error::UnknownAsset, "Unknown asset.", e))
+    };
+    Ok((parse(base)?, parse(quote)?))
+}
+
+fn page_size(limit: Option<u32>) -> u32 {
+    limit.unwrap_or(DEFAULT_PAGE_SIZE).clamp(1, MAX_PAGE_SIZE)
+}
+
+fn database_error(e: String) -> RpcError {
+    rpc_error(Error::DatabaseError, "Unable to read the indexer database.", e)
+}
+
+/// Implements [`IndexerApi`] with an [`Indexer`].
+pub struct IndexerRpc {
+    indexer: Arc<Indexer>,
+}
+
+impl IndexerRpc {
+    /// Create a new `IndexerRpc` reading from `indexer`.
+    pub fn new(indexer: Arc<Indexer>) -> Self {
+        IndexerRpc { indexer }
+    }
+}
+
+impl IndexerApi for IndexerRpc {
+    fn candles(
+        &self,
+        base: String,
+        quote: String,
+        interval: CandleInterval,
+        from: Moment,
+        to: Option<Moment>,
+        limit: Option<u32>,
+    ) -> Result<Vec<Candle>> {
+        let (base, quote) = parse_pair(&base, &quote)?;
+        self.indexer
+            .candles(base, quote, interval, from, to, page_size(limit))
+            .map_err(database_error)
+    }
+
+    fn trades(
+        &self,
+        base: String,
+        quote: String,
+        before: Option<Cursor>,
+        limit: Option<u32>,
+    ) -> Result<Page<Trade>> {
+        let (base, quote) = parse_pair(&base, &quote)?;
+        self.indexer
+            .trades(base, quote, before, page_size(limit))
+            .map_err(database_error)
+    }
+
+    fn transfers(
+        &self,
+        account: AccountId,
+        before: Option<Cursor>,
+        limit: Option<u32>,
+    ) -> Result<Page<Transfer>> {
+        self.indexer
+            .transfers(&account, before, page_size(limit))
+            .map_err(database_error)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn account(seed: u8) -> AccountId {
+        AccountId::new([seed; 32])
+    }
+
+    fn record(extrinsic_index: u32, event: Event) -> EventRecord<Event, Hash> {
+        EventRecord {
+            phase: Phase::ApplyExtrinsic(extrinsic_index),
+            event,
+            topics: vec![],
+        }
+    }
+
+    fn trade(base_amount: Balance, quote_amount: Balance) -> EventRecord<Event, Hash> {
+        record(
+            1,
+            Event::pallet_signed_orders(pallet_signed_orders::RawEvent::OrdersSettled(
+                account(1),
+                0,
+                account(2),
+                0,
+                AssetId::DOT,
+                AssetId::USD,
+                base_amount,
+                quote_amount,
+            )),
+        )
+    }
+
+    #[test]
+    fn trades_are_aggregated_into_candles() {
+        let indexer = Indexer::temporary().unwrap();
+        indexer
+            .index_block(1, 60_000, vec![trade(10, 20), trade(10, 40)])
+            .unwrap();
+        indexer.index_block(2, 90_000, vec![trade(5, 5)]).unwrap();
+        indexer.index_block(3, 120_000, vec![trade(1, 3)]).unwrap();
+
+        let candles = indexer
+            .candles(AssetId::DOT, AssetId::USD, CandleInterval::OneMinute, 0, None, 10)
+            .unwrap();
+        assert_eq!(candles.len(), 2);
+        assert_eq!(candles[0].start, 60_000);
+        assert_eq!(candles[0].open, "2.000000000000000000");
+        assert_eq!(candles[0].high, "4.000000000000000000");
+        assert_eq!(candles[0].low, "1.000000000000000000");
+        assert_eq!(candles[0].close, "1.000000000000000000");
+        assert_eq!(candles[0].base_volume, NumberOrHex::Hex(25.into()));
+        assert_eq!(candles[0].quote_volume, NumberOrHex::Hex(65.into()));
+        assert_eq!(candles[0].trades, 3);
+        assert_eq!(candles[1].start, 120_000);
+        assert_eq!(candles[1].trades, 1);
+
+        let hourly = indexer
+            .candles(AssetId::DOT, AssetId::USD, CandleInterval::OneHour, 0, None, 10)
+            .unwrap();
+        assert_eq!(hourly.len(), 1);
+        assert_eq!(hourly[0].trades, 4);
+        assert_eq!(hourly[0].close, "3.000000000000000000");
+
+        let range = |from, to| {
+            indexer
+                .candles(AssetId::DOT, AssetId::USD, CandleInterval::OneMinute, from, to, 10)
+                .unwrap()
+        };
+        assert_eq!(range(120_000, None), candles[1..].to_vec());
+        assert_eq!(range(0, Some(90_000)), candles[..1].to_vec());
+        assert!(indexer
+            .candles(AssetId::BTC, AssetId::USD, CandleInterval::OneDay, 0, None, 10)
+            .unwrap()
+            .is_empty());
+        assert_eq!(indexer.last_indexed().unwrap(), Some(3));
+    }
+
+    #[test]
+    fn trade_history_is_paged_newest_first() {
+        let indexer = Indexer::temporary().unwrap();
+        for block in 1..=5 {
+            indexer.index_block(block, 0, vec![trade(1, block as Balance)]).unwrap();
+        }
+
+        let first = indexer.trades(AssetId::DOT, AssetId::USD, None, 2).unwrap();
+        assert_eq!(
+            first.items.iter().map(|trade| trade.block).collect::<Vec<_>>(),
+            vec![5, 4]
+        );
+        assert_eq!(first.items[0].buyer, account(1));
+        assert_eq!(first.items[0].extrinsic_index, Some(1));
+        let next = first.next.unwrap();
+        assert_eq!(next, Cursor { block: 4, event_index: 0 });
+
+        let second = indexer.trades(AssetId::DOT, AssetId::USD, Some(next), 10).unwrap();
+        assert_eq!(
+            second.items.iter().map(|trade| trade.block).collect::<Vec<_>>(),
+            vec![3, 2, 1]
+        );
+        assert_eq!(second.next, None);
+    }
+
+    fn orderbook(event: pallet_orderbook::RawEvent<AccountId, Balance>) -> EventRecord<Event, Hash> {
+        record(2, Event::pallet_orderbook(event))
+    }
+
+    fn placed(id: OrderId, owner: u8, side: Side) -> EventRecord<Event, Hash> {
+        orderbook(pallet_orderbook::RawEvent::OrderPlaced(id, account(owner), AssetId::DOT, AssetId::USD, side))
+    }
+
+    #[test]
+    fn orderbook_trades_are_indexed_with_their_owners() {
+        let indexer = Indexer::temporary().unwrap();
+        let price = |value| FixedU128::saturating_from_integer(value);
+        indexer
+            .index_block(1, 60_000, vec![placed(0, 1, Side::Ask), placed(1, 2, Side::Ask)])
+            .unwrap();
+        indexer
+            .index_block(
+                2,
+                60_000,
+                vec![
+                    placed(2, 3, Side::Bid),
+                    orderbook(pallet_orderbook::RawEvent::Trade(0, 2, price(2), 10, 20)),
+                    orderbook(pallet_orderbook::RawEvent::OrderFilled(0)),
+                    placed(3, 4, Side::Bid),
+                    orderbook(pallet_orderbook::RawEvent::AuctionTrade(3, 1, price(3), 5, 15)),
+                    // Order 0 is filled, so this trade cannot be attributed.
+                    orderbook(pallet_orderbook::RawEvent::Trade(0, 3, price(2), 1, 2)),
+                ],
+            )
+            .unwrap();
+
+        let trades = indexer.trades(AssetId::DOT, AssetId::USD, None, 10).unwrap();
+        assert_eq!(trades.items.len(), 2);
+        assert_eq!(trades.items[0].buyer, account(4));
+        assert_eq!(trades.items[0].seller, account(2));
+        assert_eq!(trades.items[0].price, "3.000000000000000000");
+        assert_eq!(trades.items[1].buyer, account(3));
+        assert_eq!(trades.items[1].seller, account(1));
+        assert_eq!(trades.items[1].base_amount, NumberOrHex::Hex(10.into()));
+        assert_eq!(trades.items[1].extrinsic_index, Some(2));
+
+        let candles = indexer
+            .candles(AssetId::DOT, AssetId::USD, CandleInterval::OneMinute, 0, None, 10)
+            .unwrap();
+        assert_eq!(candles.len(), 1);
+        assert_eq!(candles[0].trades, 2);
+        assert_eq!(candles[0].open, "2.000000000000000000");
+        assert_eq!(candles[0].close, "3.000000000000000000");
+    }
+
+    #[test]
+    fn transfers_are_indexed_for_both_accounts() {
+        let indexer = Indexer::temporary().unwrap();
+        indexer
+            .index_block(
+                1,
+                0,
+                vec![
+                    record(
+                        0,
+                        Event::pallet_balances(pallet_balances::Event::Transfer(
+                            account(1),
+                            account(2),
+                            7,
+                        )),
+                    ),
+                    record(
+                        1,
+                        Event::orml_currencies(orml_currencies::Event::Transferred(
+                            AssetId::DOT,
+                            account(2),
+                            account(3),
+                            9,
+                        )),
+                    ),
+                    // Already indexed from the `pallet_balances` event of the same transfer.
+                    record(
+                        1,
+                        Event::orml_currencies(orml_currencies::Event::Transferred(
+                            AssetId::POLKADEX,
+                            account(1),
+                            account(2),
+                            7,
+                        )),
+                    ),
+                ],
+            )
+            .unwrap();
+
+        let transfers = indexer.transfers(&account(2), None, 10).unwrap();
+        assert_eq!(transfers.items.len(), 2);
+        assert_eq!(transfers.items[0].asset, "DOT");
+        assert_eq!(transfers.items[0].to, account(3));
+        assert_eq!(transfers.items[1].asset, "POLKADEX");
+        assert_eq!(transfers.items[1].amount, NumberOrHex::Hex(7.into()));
+        assert_eq!(indexer.transfers(&account(1), None, 10).unwrap().items.len(), 1);
+        assert!(indexer.transfers(&account(4), None, 10).unwrap().items.is_empty());
+    }
+
+    #[test]
+    fn block_trades_are_those_of_the_pair() {
+        let transfer = record(
+            2,
+            Event::pallet_balances(pallet_balances::Event::Transfer(account(1), account(2), 7)),
+        );
+        let events = vec![
+            trade(2, 6),
+            transfer,
+            trade(1, 2),
+            placed(2, 3, Side::Bid),
+            orderbook(pallet_orderbook::RawEvent::Trade(0, 2, FixedU128::saturating_from_integer(5), 1, 5)),
+        ];
+        // Order 0 was placed before the block.
+        let open_order = |id| {
+            (id == 0).then(|| OrderRecord {
+                owner: account(1),
+                base: AssetId::DOT,
+                quote: AssetId::USD,
+                side: Side::Ask,
+            })
+        };
+        let trades = block_trades(4, 1_000, events, AssetId::DOT, AssetId::USD, open_order);
+        assert_eq!(
+            trades.iter().map(|trade| (trade.event_index, trade.price.as_str())).collect::<Vec<_>>(),
+            vec![
+                (0, "3.000000000000000000"),
+                (2, "2.000000000000000000"),
+                (4, "5.000000000000000000")
+            ]
+        );
+        assert_eq!(trades[0].block, 4);
+        assert_eq!(trades[0].timestamp, 1_000);
+        assert_eq!(trades[2].buyer, account(3));
+        assert_eq!(trades[2].seller, account(1));
+        assert!(block_trades(4, 1_000, vec![trade(2, 6)], AssetId::BTC, AssetId::USD, |_| None).is_empty());
+    }
+}
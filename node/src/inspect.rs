@@ -0,0 +1,389 @@
+// This file is part of Polkadex.
+
+// Copyright (C) 2020-2021 Polkadex oü.
+// SPDX-License-Identifier: GPL-3.0-or-later WITH Classpath-exception-2.0
+
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with this program. If not, see <https://www.gnu.org/licenses/>.
+
+//! Decoding of blocks and extrinsics with the native runtime.
+
+use std::{fmt, fs, path::Path, str::FromStr};
+
+use codec::{Decode, Encode};
+use frame_support::dispatch::{CallMetadata, GetCallMetadata};
+use node_polkadex_runtime::{Address, Block, SignedExtra, UncheckedExtrinsic};
+use polkadex_primitives::{Block as OpaqueBlock, BlockNumber};
+use sc_cli::{CliConfiguration, ImportParams, SharedParams};
+use sc_client_api::BlockBackend;
+use sc_service::Configuration;
+use serde::Serialize;
+use sp_core::{bytes::from_hex, H256};
+use sp_runtime::{
+    generic::BlockId,
+    traits::{BlakeTwo256, Block as BlockT, Hash, Header as HeaderT, SignedExtension},
+    MultiAddress,
+};
+use structopt::StructOpt;
+
+use crate::service::new_partial;
+
+/// Output format of the inspect command.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum OutputFormat {
+    /// Human-readable text.
+    Text,
+    /// JSON.
+    Json,
+}
+
+impl FromStr for OutputFormat {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "text" => Ok(OutputFormat::Text),
+            "json" => Ok(OutputFormat::Json),
+            _ => Err(format!("Invalid output format {:?}, expected `text` or `json`", s)),
+        }
+    }
+}
+
+/// What to inspect.
+#[derive(Debug, StructOpt)]
+pub enum InspectSubCmd {
+    /// Decode a block.
+    Block {
+        /// Number or hash of a block of the local database, or path to a file containing a
+        /// SCALE encoded block, as binary or hex.
+        input: String,
+    },
+    /// Decode a hex encoded extrinsic.
+    Extrinsic {
+        /// SCALE encoded extrinsic, as hex.
+        input: String,
+    },
+}
+
+/// The `inspect` command used to decode blocks and extrinsics with the native runtime.
+#[derive(Debug, StructOpt)]
+pub struct InspectCmd {
+    #[allow(missing_docs)]
+    #[structopt(subcommand)]
+    pub command: InspectSubCmd,
+
+    /// Output format: `text` or `json`.
+    #[structopt(long, default_value = "text", value_name = "FORMAT")]
+    pub output: OutputFormat,
+
+    #[allow(missing_docs)]
+    #[structopt(flatten)]
+    pub shared_params: SharedParams,
+
+    #[allow(missing_docs)]
+    #[structopt(flatten)]
+    pub import_params: ImportParams,
+}
+
+impl CliConfiguration for InspectCmd {
+    fn shared_params(&self) -> &SharedParams {
+        &self.shared_params
+    }
+
+    fn import_params(&self) -> Option<&ImportParams> {
+        Some(&self.import_params)
+    }
+}
+
+impl InspectCmd {
+    /// Run the inspect command
+    pub fn run(&self, config: Configuration) -> sc_cli::Result<()> {
+        let output = match &self.command {
+            InspectSubCmd::Block { input } => {
+                let block = if Path::new(input).is_file() {
+                    block_from_file(Path::new(input))?
+                } else {
+                    let client = new_partial(&config)?.client;
+                    let id = parse_block_id(input)?;
+                    let block = client
+                        .block(&id)?
+                        .ok_or_else(|| format!("Block {} not found", input))?
+                        .block;
+                    Block::decode(&mut &block.encode()[..])
+                        .map_err(|e| format!("Failed to decode block {}: {}", input, e))?
+                };
+                render(&BlockInfo::from(&block), self.output)
+            }
+            InspectSubCmd::Extrinsic { input } => {
+                let bytes = from_hex(input).map_err(|e| format!("Invalid hex: {}", e))?;
+                let extrinsic = UncheckedExtrinsic::decode(&mut &bytes[..])
+                    .map_err(|e| format!("Failed to decode extrinsic: {}", e))?;
+                render(&ExtrinsicInfo::from(&extrinsic), self.output)
+            }
+        };
+        println!("{}", output);
+        Ok(())
+    }
+}
+
+fn parse_block_id(input: &str) -> Result<BlockId<OpaqueBlock>, String> {
+    if input.starts_with("0x") {
+        let hash = from_hex(input).map_err(|e| format!("Invalid block hash: {}", e))?;
+        if hash.len() != 32 {
+            return Err(format!("Invalid block hash {}", input));
+        }
+        Ok(BlockId::hash(H256::from_slice(&hash)))
+    } else {
+        input
+            .parse::<BlockNumber>()
+            .map(BlockId::number)
+            .map_err(|_| format!("Invalid block number or hash {}", input))
+    }
+}
+
+fn block_from_file(path: &Path) -> Result<Block, String> {
+    let content =
+        fs::read(path).map_err(|e| format!("Failed to read {}: {}", path.display(), e))?;
+    let bytes = match std::str::from_utf8(&content) {
+        Ok(text) if text.trim().starts_with("0x") => {
+            from_hex(text.trim()).map_err(|e| format!("Invalid hex: {}", e))?
+        }
+        _ => content,
+    };
+    Block::decode(&mut &bytes[..]).map_err(|e| format!("Failed to decode block: {}", e))
+}
+
+fn render<T: Serialize + fmt::Display>(info: &T, output: OutputFormat) -> String {
+    match output {
+        OutputFormat::Text => info.to_string(),
+        OutputFormat::Json => {
+            serde_json::to_string_pretty(info).expect("Inspected data is serializable; qed")
+        }
+    }
+}
+
+/// A field of the `SignedExtra` of an extrinsic.
+#[derive(Debug, Serialize)]
+pub struct ExtraField {
+    pub name: &'static str,
+    pub value: String,
+}
+
+fn extra_field<E: SignedExtension>(extension: &E) -> ExtraField {
+    ExtraField {
+        name: E::IDENTIFIER,
+        value: format!("{:?}", extension),
+    }
+}
+
+fn extra_fields(extra: &SignedExtra) -> Vec<ExtraField> {
+    let (spec_version, tx_version, genesis, era, nonce, weight, payment) = extra;
+    vec![
+        extra_field(spec_version),
+        extra_field(tx_version),
+        extra_field(genesis),
+        extra_field(era),
+        extra_field(nonce),
+        extra_field(weight),
+        extra_field(payment),
+    ]
+}
+
+fn address(address: &Address) -> String {
+    match address {
+        MultiAddress::Id(account) => account.to_string(),
+        other => format!("{:?}", other),
+    }
+}
+
+/// Signature part of a signed extrinsic.
+#[derive(Debug, Serialize)]
+pub struct SignedInfo {
+    pub signer: String,
+    pub signature: String,
+    pub extra: Vec<ExtraField>,
+}
+
+/// A decoded extrinsic.
+#[derive(Debug, Serialize)]
+pub struct ExtrinsicInfo {
+    pub hash: H256,
+    pub pallet: &'static str,
+    pub call: &'static str,
+    pub arguments: String,
+    pub signed: Option<SignedInfo>,
+}
+
+impl From<&UncheckedExtrinsic> for ExtrinsicInfo {
+    fn from(extrinsic: &UncheckedExtrinsic) -> Self {
+        let CallMetadata {
+            pallet_name,
+            function_name,
+        } = extrinsic.function.get_call_metadata();
+        ExtrinsicInfo {
+            hash: BlakeTwo256::hash_of(extrinsic),
+            pallet: pallet_name,
+            call: function_name,
+            arguments: format!("{:?}", extrinsic.function),
+            signed: extrinsic
+                .signature
+                .as_ref()
+                .map(|(signer, signature, extra)| SignedInfo {
+                    signer: address(signer),
+                    signature: format!("{:?}", signature),
+                    extra: extra_fields(extra),
+                }),
+        }
+    }
+}
+
+impl fmt::Display for ExtrinsicInfo {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        writeln!(f, "Extrinsic {:?}", self.hash)?;
+        writeln!(f, "  call: {}.{}", self.pallet, self.call)?;
+        write!(f, "  arguments: {}", self.arguments)?;
+        match &self.signed {
+            Some(signed) => {
+                writeln!(f)?;
+                writeln!(f, "  signer: {}", signed.signer)?;
+                writeln!(f, "  signature: {}", signed.signature)?;
+                write!(f, "  extra:")?;
+                for field in &signed.extra {
+                    write!(f, "\n    {}: {}", field.name, field.value)?;
+                }
+                Ok(())
+            }
+            None => write!(f, "\n  unsigned"),
+        }
+    }
+}
+
+/// A decoded block.
+#[derive(Debug, Serialize)]
+pub struct BlockInfo {
+    pub number: BlockNumber,
+    pub hash: H256,
+    pub parent_hash: H256,
+    pub state_root: H256,
+    pub extrinsics_root: H256,
+    pub digest_logs: usize,
+    pub extrinsics: Vec<ExtrinsicInfo>,
+}
+
+impl From<&Block> for BlockInfo {
+    fn from(block: &Block) -> Self {
+        let header = block.header();
+        BlockInfo {
+            number: *header.number(),
+            hash: header.hash(),
+            parent_hash: *header.parent_hash(),
+            state_root: *header.state_root(),
+            extrinsics_root: *header.extrinsics_root(),
+            digest_logs: header.digest().logs().len(),
+            extrinsics: block.extrinsics().iter().map(ExtrinsicInfo::from).collect(),
+        }
+    }
+}
+
+impl fmt::Display for BlockInfo {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        writeln!(f, "Block #{} {:?}", self.number, self.hash)?;
+        writeln!(f, "  parent hash: {:?}", self.parent_hash)?;
+        writeln!(f, "  state root: {:?}", self.state_root)?;
+        writeln!(f, "  extrinsics root: {:?}", self.extrinsics_root)?;
+        write!(f, "  digest logs: {}", self.digest_logs)?;
+        for (index, extrinsic) in self.extrinsics.iter().enumerate() {
+            write!(f, "\n\n#{} {}", index, extrinsic)?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use node_polkadex_runtime::{signed_extra, BalancesCall, Call, Header, Runtime};
+    use sp_core::sr25519;
+    use sp_keyring::AccountKeyring;
+    use sp_runtime::{generic::Era, MultiSignature};
+
+    use super::*;
+
+    fn transfer() -> UncheckedExtrinsic {
+        UncheckedExtrinsic::new_signed(
+            Call::Balances(BalancesCall::transfer(AccountKeyring::Bob.to_account_id().into(), 42)),
+            AccountKeyring::Alice.to_account_id().into(),
+            MultiSignature::Sr25519(sr25519::Signature::default()),
+            signed_extra(7, Era::Immortal, 0),
+        )
+    }
+
+    #[test]
+    fn decodes_signed_extrinsic() {
+        let info = ExtrinsicInfo::from(&transfer());
+        assert_eq!((info.pallet, info.call), ("Balances", "transfer"));
+        let signed = info.signed.as_ref().unwrap();
+        assert_eq!(signed.signer, AccountKeyring::Alice.to_account_id().to_string());
+        assert_eq!(signed.extra.len(), 7);
+        assert_eq!(signed.extra[4].name, "CheckNonce");
+        assert!(signed.extra[4].value.contains('7'));
+
+        let text = render(&info, OutputFormat::Text);
+        assert!(text.contains("call: Balances.transfer"));
+        assert!(text.contains("ChargeSponsoredTransactionPayment"));
+
+        let json: serde_json::Value =
+            serde_json::from_str(&render(&info, OutputFormat::Json)).unwrap();
+        assert_eq!(json["pallet"], "Balances");
+        assert_eq!(json["signed"]["extra"][4]["name"], "CheckNonce");
+    }
+
+    #[test]
+    fn decodes_block() {
+        let block = Block::new(
+            Header::new(
+                5,
+                Default::default(),
+                Default::default(),
+                Default::default(),
+                Default::default(),
+            ),
+            vec![
+                UncheckedExtrinsic::new_unsigned(Call::System(frame_system::Call::<Runtime>::remark(
+                    vec![1, 2, 3],
+                ))),
+                transfer(),
+            ],
+        );
+        let info = BlockInfo::from(&block);
+        assert_eq!(info.number, 5);
+        assert_eq!(info.extrinsics.len(), 2);
+        assert_eq!((info.extrinsics[0].pallet, info.extrinsics[0].call), ("System", "remark"));
+        assert!(info.extrinsics[0].signed.is_none());
+        assert!(render(&info, OutputFormat::Text).contains("#1 Extrinsic"));
+
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("block.hex");
+        fs::write(&path, format!("0x{}", sp_core::hexdisplay::HexDisplay::from(&block.encode())))
+            .unwrap();
+        assert_eq!(block_from_file(&path).unwrap(), block);
+    }
+
+    #[test]
+    fn parses_block_ids() {
+        assert_eq!(parse_block_id("12").unwrap(), BlockId::number(12));
+        let hash = H256::repeat_byte(1);
+        assert_eq!(parse_block_id(&format!("{:?}", hash)).unwrap(), BlockId::hash(hash));
+        assert!(parse_block_id("0x12").is_err());
+        assert!(parse_block_id("latest").is_err());
+    }
+}
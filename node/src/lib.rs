@@ -1,3 +1,14 @@
+pub mod authority_keys;
+pub mod balances;
 pub mod chain_spec;
+pub mod dry_run;
+pub mod events;
+pub mod genesis_file;
+pub mod indexer;
+pub mod inspect;
+pub mod light_payment;
+pub mod local_net;
+pub mod market;
 pub mod rpc;
 pub mod service;
+pub mod transaction;
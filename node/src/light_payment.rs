@@ -0,0 +1,209 @@
+// This file is part of Polkadex.
+
+// Copyright (C) 2020-2021 Polkadex oü.
+// SPDX-License-Identifier: GPL-3.0-or-later WITH Classpath-exception-2.0
+
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with this program. If not, see <https://www.gnu.org/licenses/>.
+//! Fee queries for light clients.
+//!
+//! The methods mirror `payment_queryInfo` and `payment_queryFeeDetails` of full nodes, as well
+//! as `payment_estimateCallFee` from [`crate::dry_run`]. Runtime calls are executed by a full
+//! node through the `Fetcher`, and the light client checks the returned execution proof.
+
+use std::sync::Arc;
+
+use codec::{Decode, Encode};
+use futures::{FutureExt, TryFutureExt};
+use jsonrpc_core::{BoxFuture, Error as RpcError, ErrorCode};
+use jsonrpc_derive::rpc;
+use pallet_transaction_payment_rpc_runtime_api::{FeeDetails, RuntimeDispatchInfo};
+use polkadex_dry_run_runtime_api::FeeEstimate;
+use polkadex_primitives::{AccountId, Balance, Block, Hash};
+use sc_client_api::light::{future_header, Fetcher, RemoteBlockchain, RemoteCallRequest};
+use sp_blockchain::HeaderBackend;
+use sp_core::Bytes;
+use sp_rpc::number::NumberOrHex;
+use sp_runtime::generic::BlockId;
+use sp_runtime::traits::Block as BlockT;
+
+use crate::dry_run::{decode_call, decode_tip, fee_details, CallFeeEstimate};
+
+/// Fee queries answered with remote calls.
+#[rpc]
+pub trait LightTransactionPaymentApi<BlockHash> {
+    /// Fee information of the SCALE encoded extrinsic.
+    #[rpc(name = "payment_queryInfo")]
+    fn query_info(
+        &self,
+        encoded_xt: Bytes,
+        at: Option<BlockHash>,
+    ) -> BoxFuture<RuntimeDispatchInfo<Balance>>;
+
+    /// Fee breakdown of the SCALE encoded extrinsic.
+    #[rpc(name = "payment_queryFeeDetails")]
+    fn query_fee_details(
+        &self,
+        encoded_xt: Bytes,
+        at: Option<BlockHash>,
+    ) -> BoxFuture<FeeDetails<NumberOrHex>>;
+
+    /// Weight, length and fees of the SCALE encoded `call` if `signer` submitted it with `tip`.
+    #[rpc(name = "payment_estimateCallFee")]
+    fn estimate_call_fee(
+        &self,
+        call: Bytes,
+        signer: AccountId,
+        tip: Option<NumberOrHex>,
+        at: Option<BlockHash>,
+    ) -> BoxFuture<CallFeeEstimate>;
+}
+
+/// Implements [`LightTransactionPaymentApi`] on top of the `Fetcher`.
+pub struct LightTransactionPayment<C, F> {
+    client: Arc<C>,
+    remote_blockchain: Arc<dyn RemoteBlockchain<Block>>,
+    fetcher: Arc<F>,
+}
+
+impl<C, F> LightTransactionPayment<C, F> {
+    /// Create a new `LightTransactionPayment` for the given client.
+    pub fn new(
+        client: Arc<C>,
+        remote_blockchain: Arc<dyn RemoteBlockchain<Block>>,
+        fetcher: Arc<F>,
+    ) -> Self {
+        LightTransactionPayment {
+            client,
+            remote_blockchain,
+            fetcher,
+        }
+    }
+}
+
+/// Error codes of this RPC, matching the ones of the full node RPCs.
+enum Error {
+    /// The extrinsic or call could not be decoded.
+    DecodeError,
+    /// The remote runtime call failed.
+    RuntimeError,
+}
+
+impl From<Error> for i64 {
+    fn from(e: Error) -> i64 {
+        match e {
+            Error::DecodeError => 1,
+            Error::RuntimeError => 3,
+        }
+    }
+}
+
+fn rpc_error(code: Error, message: &str, data: impl std::fmt::Debug) -> RpcError {
+    RpcError {
+        code: ErrorCode::ServerError(code.into()),
+        message: message.into(),
+        data: Some(format!("{:?}", data).into()),
+    }
+}
+
+fn decode_extrinsic(
+    encoded_xt: &Bytes,
+) -> jsonrpc_core::Result<(<Block as BlockT>::Extrinsic, u32)> {
+    let uxt = Decode::decode(&mut &**encoded_xt)
+        .map_err(|e| rpc_error(Error::DecodeError, "Unable to decode extrinsic.", e))?;
+    Ok((uxt, encoded_xt.len() as u32))
+}
+
+impl<C, F> LightTransactionPayment<C, F>
+where
+    C: HeaderBackend<Block> + Send + Sync + 'static,
+    F: Fetcher<Block> + 'static,
+{
+    /// Calls the runtime API `method` at block `at` on a remote node and decodes its result.
+    fn remote_call<R: Decode + Send + 'static>(
+        &self,
+        method: &'static str,
+        call_data: jsonrpc_core::Result<Vec<u8>>,
+        at: Option<Hash>,
+    ) -> BoxFuture<R> {
+        // If the block hash is not supplied assume the best block.
+        let at = at.unwrap_or_else(|| self.client.info().best_hash);
+        let header = future_header(&*self.remote_blockchain, &*self.fetcher, BlockId::hash(at));
+        let fetcher = self.fetcher.clone();
+
+        let result = async move {
+            let call_data = call_data?;
+            let runtime_error = |e| rpc_error(Error::RuntimeError, "Remote call failed.", e);
+            let header = header
+                .await
+                .map_err(runtime_error)?
+                .ok_or_else(|| rpc_error(Error::RuntimeError, "Unknown block.", at))?;
+            let result = fetcher
+                .remote_call(RemoteCallRequest {
+                    block: at,
+                    header,
+                    method: method.into(),
+                    call_data,
+                    retry_count: None,
+                })
+                .await
+                .map_err(runtime_error)?;
+            R::decode(&mut &result[..])
+                .map_err(|e| rpc_error(Error::RuntimeError, "Unable to decode result.", e))
+        };
+        Box::new(result.boxed().compat())
+    }
+}
+
+impl<C, F> LightTransactionPaymentApi<Hash> for LightTransactionPayment<C, F>
+where
+    C: HeaderBackend<Block> + Send + Sync + 'static,
+    F: Fetcher<Block> + 'static,
+{
+    fn query_info(
+        &self,
+        encoded_xt: Bytes,
+        at: Option<Hash>,
+    ) -> BoxFuture<RuntimeDispatchInfo<Balance>> {
+        let call_data = decode_extrinsic(&encoded_xt).map(|args| args.encode());
+        self.remote_call("TransactionPaymentApi_query_info", call_data, at)
+    }
+
+    fn query_fee_details(
+        &self,
+        encoded_xt: Bytes,
+        at: Option<Hash>,
+    ) -> BoxFuture<FeeDetails<NumberOrHex>> {
+        let call_data = decode_extrinsic(&encoded_xt).map(|args| args.encode());
+        let details = self.remote_call::<FeeDetails<Balance>>(
+            "TransactionPaymentApi_query_fee_details",
+            call_data,
+            at,
+        );
+        Box::new(jsonrpc_core::futures::Future::map(details, fee_details))
+    }
+
+    fn estimate_call_fee(
+        &self,
+        call: Bytes,
+        signer: AccountId,
+        tip: Option<NumberOrHex>,
+        at: Option<Hash>,
+    ) -> BoxFuture<CallFeeEstimate> {
+        let call_data = decode_call(&call)
+            .and_then(|call| Ok((signer, call, decode_tip(tip)?).encode()));
+        let estimate =
+            self.remote_call::<FeeEstimate<Balance>>("DryRunApi_estimate_fee", call_data, at);
+        Box::new(jsonrpc_core::futures::Future::map(estimate, CallFeeEstimate::from))
+    }
+}
@@ -0,0 +1,264 @@
+// This file is part of Polkadex.
+
+// Copyright (C) 2020-2021 Polkadex oü.
+// SPDX-License-Identifier: GPL-3.0-or-later WITH Classpath-exception-2.0
+
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with this program. If not, see <https://www.gnu.org/licenses/>.
+//! In-process local network of validators.
+//!
+//! `local-net --validators N` starts N full nodes in one process on the chain returned by
+//! `local_net_config`. Node `i` uses the dev keys of `local_net_seed(i)`, a node key derived
+//! from its index and consecutive ports, and boots from every other node.
+
+use std::path::PathBuf;
+
+use sc_cli::{CliConfiguration, RunCmd, SubstrateCli};
+use sc_keystore::LocalKeystore;
+use sc_network::config::identity::{ed25519, Keypair};
+use sc_service::config::KeystoreConfig;
+use sc_service::{Configuration, TaskManager};
+use sp_core::crypto::key_types;
+use sp_core::{hexdisplay::HexDisplay, Pair};
+use sp_keystore::SyncCryptoStore;
+use structopt::StructOpt;
+
+use crate::chain_spec::{self, local_net_seed, GrandpaSettings};
+use crate::service::{new_full_base, NewFullBase};
+
+/// The `local-net` command, starting several validators in one process.
+#[derive(Debug, StructOpt)]
+pub struct LocalNetCmd {
+    /// Number of validators to start.
+    #[structopt(long, default_value = "2")]
+    pub validators: u8,
+
+    /// Directory holding one sub-directory per node. Temporary directories are used if not
+    /// given.
+    #[structopt(long, parse(from_os_str))]
+    pub base_path: Option<PathBuf>,
+
+    /// p2p port of the first node, the others use the following ports.
+    #[structopt(long, default_value = "30333")]
+    pub port: u16,
+
+    /// HTTP RPC port of the first node, the others use the following ports.
+    #[structopt(long, default_value = "9933")]
+    pub rpc_port: u16,
+
+    /// WebSocket RPC port of the first node, the others use the following ports.
+    #[structopt(long, default_value = "9944")]
+    pub ws_port: u16,
+}
+
+/// A node of the local network.
+#[derive(Debug)]
+pub struct LocalNode {
+    /// Seed of the validator keys, also used as node name.
+    pub seed: String,
+    /// Command line of the node.
+    pub run: RunCmd,
+    /// Address other nodes boot from.
+    pub multiaddr: String,
+    /// HTTP RPC port.
+    pub rpc_port: u16,
+    /// WebSocket RPC port.
+    pub ws_port: u16,
+}
+
+/// Node key of the node at `index`, so that its peer id is known before it starts.
+fn node_key(index: u8) -> [u8; 32] {
+    [index + 1; 32]
+}
+
+fn peer_id(index: u8) -> Result<String, String> {
+    let secret = ed25519::SecretKey::from_bytes(node_key(index))
+        .map_err(|e| format!("Invalid node key: {}", e))?;
+    Ok(Keypair::Ed25519(secret.into())
+        .public()
+        .into_peer_id()
+        .to_base58())
+}
+
+fn port(base: u16, index: u8) -> Result<u16, String> {
+    base.checked_add(index.into())
+        .ok_or_else(|| format!("Port {} + {} is out of range", base, index))
+}
+
+impl LocalNetCmd {
+    /// The nodes of the network, ready to be configured.
+    pub fn nodes(&self) -> Result<Vec<LocalNode>, String> {
+        if self.validators == 0 {
+            return Err("A local network needs at least one validator".into());
+        }
+        let multiaddrs = (0..self.validators)
+            .map(|index| {
+                Ok(format!(
+                    "/ip4/127.0.0.1/tcp/{}/p2p/{}",
+                    port(self.port, index)?,
+                    peer_id(index)?
+                ))
+            })
+            .collect::<Result<Vec<_>, String>>()?;
+
+        (0..self.validators)
+            .map(|index| {
+                let seed = local_net_seed(index.into());
+                let rpc_port = port(self.rpc_port, index)?;
+                let ws_port = port(self.ws_port, index)?;
+                let mut args = vec![
+                    "polkadex-node".to_string(),
+                    "--validator".into(),
+                    "--chain".into(),
+                    "local".into(),
+                    "--name".into(),
+                    seed.clone(),
+                    "--node-key".into(),
+                    HexDisplay::from(&node_key(index)).to_string(),
+                    "--port".into(),
+                    port(self.port, index)?.to_string(),
+                    "--rpc-port".into(),
+                    rpc_port.to_string(),
+                    "--ws-port".into(),
+                    ws_port.to_string(),
+                    "--no-prometheus".into(),
+                    "--no-telemetry".into(),
+                    "--no-mdns".into(),
+                ];
+                match &self.base_path {
+                    Some(base_path) => {
+                        args.push("--base-path".into());
+                        args.push(base_path.join(&seed).display().to_string());
+                    }
+                    None => args.push("--tmp".into()),
+                }
+                for (other, multiaddr) in multiaddrs.iter().enumerate() {
+                    if other != usize::from(index) {
+                        args.push("--bootnodes".into());
+                        args.push(multiaddr.clone());
+                    }
+                }
+                Ok(LocalNode {
+                    seed,
+                    run: RunCmd::from_iter_safe(args).map_err(|e| e.to_string())?,
+                    multiaddr: multiaddrs[usize::from(index)].clone(),
+                    rpc_port,
+                    ws_port,
+                })
+            })
+            .collect()
+    }
+
+    /// Starts every node, `first` being the configuration of the first one.
+    ///
+    /// The other nodes are children of the task manager of the first one, so the whole network
+    /// stops together.
+    pub fn run(
+        &self,
+        cli: &impl SubstrateCli,
+        nodes: &[LocalNode],
+        first: Configuration,
+    ) -> sc_cli::Result<TaskManager> {
+        let chain_spec = chain_spec::local_net_config(self.validators.into())?;
+        let task_executor = first.task_executor.clone();
+
+        let mut task_manager = None;
+        let mut first = Some(first);
+        for node in nodes {
+            let mut config = match first.take() {
+                Some(config) => config,
+                None => node.run.create_configuration(cli, task_executor.clone())?,
+            };
+            config.chain_spec = Box::new(chain_spec.clone());
+            insert_session_keys(&node.seed, &config.keystore)?;
+
+            log::info!(
+                "🏠 {}: {}, RPC http://127.0.0.1:{}, WebSocket ws://127.0.0.1:{}",
+                node.seed,
+                node.multiaddr,
+                node.rpc_port,
+                node.ws_port,
+            );
+            let grandpa = GrandpaSettings::from_chain_spec(&*config.chain_spec);
+            let NewFullBase {
+                task_manager: node_task_manager,
+                ..
+            } = new_full_base(config, grandpa, false, |_, _| ())?;
+            match &mut task_manager {
+                None => task_manager = Some(node_task_manager),
+                Some(parent) => parent.add_child(node_task_manager),
+            }
+        }
+        task_manager.ok_or_else(|| "A local network needs at least one validator".into())
+    }
+}
+
+/// Inserts the session keys of `//<seed>` into the keystore of a node.
+fn insert_session_keys(seed: &str, keystore: &KeystoreConfig) -> Result<(), String> {
+    let (path, password) = match keystore {
+        KeystoreConfig::Path { path, password } => (path, password.clone()),
+        KeystoreConfig::InMemory => return Err("Local network nodes need a keystore path".into()),
+    };
+    let keystore = LocalKeystore::open(path, password)
+        .map_err(|e| format!("Failed to open keystore {}: {}", path.display(), e))?;
+
+    let suri = format!("//{}", seed);
+    let sr25519 = sp_core::sr25519::Pair::from_string(&suri, None)
+        .map_err(|e| format!("Invalid seed {}: {:?}", seed, e))?
+        .public();
+    let ed25519 = sp_core::ed25519::Pair::from_string(&suri, None)
+        .map_err(|e| format!("Invalid seed {}: {:?}", seed, e))?
+        .public();
+    let keys = [
+        (key_types::BABE, &sr25519.0[..]),
+        (key_types::GRANDPA, &ed25519.0[..]),
+        (key_types::IM_ONLINE, &sr25519.0[..]),
+        (key_types::AUTHORITY_DISCOVERY, &sr25519.0[..]),
+    ];
+    for (key_type, public) in keys.iter() {
+        SyncCryptoStore::insert_unknown(&keystore, *key_type, &suri, public)
+            .map_err(|_| format!("Failed to insert the {:?} key of {}", key_type, seed))?;
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::BTreeSet;
+
+    use super::*;
+
+    #[test]
+    fn nodes_get_distinct_ports_and_boot_from_each_other() {
+        let cmd = LocalNetCmd::from_iter_safe(&["local-net", "--validators", "3"]).unwrap();
+        let nodes = cmd.nodes().unwrap();
+
+        assert_eq!(
+            nodes.iter().map(|node| node.seed.as_str()).collect::<Vec<_>>(),
+            ["Alice", "Bob", "Charlie"]
+        );
+        assert_eq!(
+            nodes.iter().map(|node| node.rpc_port).collect::<Vec<_>>(),
+            [9933, 9934, 9935]
+        );
+        let multiaddrs = nodes.iter().map(|node| &node.multiaddr).collect::<BTreeSet<_>>();
+        assert_eq!(multiaddrs.len(), 3);
+        for node in &nodes {
+            assert_eq!(node.run.network_params.bootnodes.len(), 2);
+        }
+        assert!(LocalNetCmd::from_iter_safe(&["local-net", "--validators", "0"])
+            .unwrap()
+            .nodes()
+            .is_err());
+    }
+}
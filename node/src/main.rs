@@ -1,12 +1,23 @@
 //! Substrate Node Template CLI library.
 #![warn(missing_docs)]
 
+mod authority_keys;
+mod balances;
 mod chain_spec;
 #[macro_use]
 mod service;
 mod cli;
 mod command;
+mod dry_run;
+mod events;
+mod genesis_file;
+mod indexer;
+mod inspect;
+mod light_payment;
+mod local_net;
+mod market;
 mod rpc;
+mod transaction;
 
 fn main() -> sc_cli::Result<()> {
     command::run()
@@ -0,0 +1,627 @@
+// This file is part of Polkadex.
+
+// Copyright (C) 2020-2021 Polkadex oü.
+// SPDX-License-Identifier: GPL-3.0-or-later WITH Classpath-exception-2.0
+
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with this program. If not, see <https://www.gnu.org/licenses/>.
+//! Subscriptions to the trades and the order book of a trading pair.
+//!
+//! Trades are read from the events of `pallet_orderbook` and `pallet_signed_orders` in each
+//! block, the depth and top of book from the `pallet_orderbook` storage. Every subscription
+//! starts with a snapshot of the best block and follows new best and finalized blocks. After
+//! the snapshot, depth updates only carry the price levels that changed.
+
+use std::sync::Arc;
+
+use frame_support::{StorageDoubleMap, StorageMap};
+use futures::{future, stream, StreamExt, TryStreamExt};
+use jsonrpc_core::futures::{Future as _, Sink as _};
+use jsonrpc_core::{Error as RpcError, Result};
+use jsonrpc_derive::rpc;
+use jsonrpc_pubsub::{manager::SubscriptionManager, typed::Subscriber, SubscriptionId};
+use node_polkadex_runtime::{Event, Runtime};
+use pallet_orderbook::{OrderBook, OrderId, OrderOf, Orders, Side, TradingPair};
+use polkadex_primitives::assets::AssetId;
+use polkadex_primitives::{Balance, Block, BlockNumber, Hash};
+use sc_client_api::{BlockchainEvents, StorageProvider};
+use sc_rpc::SubscriptionTaskExecutor;
+use serde::Serialize;
+use sp_blockchain::HeaderBackend;
+use sp_core::storage::StorageKey;
+use sp_rpc::number::NumberOrHex;
+use sp_runtime::generic::BlockId;
+use sp_runtime::traits::{Block as BlockT, Header as HeaderT};
+use sp_runtime::FixedU128;
+
+use crate::indexer::{block_trades, format_price, parse_pair, read_events, storage_value, OrderRecord, Trade};
+
+type Header = <Block as BlockT>::Header;
+
+/// Number of price levels on each side of a depth update when the request does not say.
+const DEFAULT_DEPTH: u32 = 20;
+/// Maximum number of price levels on each side of a depth update.
+const MAX_DEPTH: u32 = 100;
+
+/// Block an update is about.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub enum UpdateKind {
+    /// The best block when subscribing.
+    Snapshot,
+    /// A new best block. Its changes are reverted if the block is retracted.
+    Best,
+    /// A finalized block, whose changes are final.
+    Finalized,
+}
+
+/// An update of a subscription.
+#[derive(Clone, Debug, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Update<T> {
+    /// Number of the update in the subscription, starting from 0 for the snapshot. A gap
+    /// means updates were lost and the client should subscribe again.
+    pub sequence: u64,
+    pub kind: UpdateKind,
+    pub hash: Hash,
+    pub number: BlockNumber,
+    #[serde(flatten)]
+    pub data: T,
+}
+
+/// Trades of a pair in a block.
+#[derive(Clone, Debug, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BlockTrades {
+    pub trades: Vec<Trade>,
+}
+
+/// Orders resting in a book at the same price.
+#[derive(Clone, Debug, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PriceLevel {
+    /// Limit price of the orders, as a decimal.
+    pub price: String,
+    /// Amount of the base asset left to buy or sell at the price.
+    pub amount: NumberOrHex,
+    /// Number of orders at the price.
+    pub orders: u32,
+}
+
+/// Best price levels of both sides of a book, or the levels that changed since the previous
+/// update.
+///
+/// A changed level has its new amount and number of orders. A level that was removed, or fell
+/// out of the best levels, has an amount of zero.
+#[derive(Clone, Debug, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Depth {
+    /// Bids, highest price first.
+    pub bids: Vec<PriceLevel>,
+    /// Asks, lowest price first.
+    pub asks: Vec<PriceLevel>,
+}
+
+/// Best bid and ask of a book.
+#[derive(Clone, Debug, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TopOfBook {
+    pub bid: Option<PriceLevel>,
+    pub ask: Option<PriceLevel>,
+}
+
+impl Depth {
+    /// The levels of `self` that differ from those of `last`.
+    fn changes_since(&self, last: &Depth) -> Depth {
+        fn side_changes(last: &[PriceLevel], levels: &[PriceLevel]) -> Vec<PriceLevel> {
+            let mut changes: Vec<PriceLevel> = levels
+                .iter()
+                .filter(|level| !last.contains(level))
+                .cloned()
+                .collect();
+            changes.extend(
+                last.iter()
+                    .filter(|old| levels.iter().all(|level| level.price != old.price))
+                    .map(|old| PriceLevel {
+                        price: old.price.clone(),
+                        amount: NumberOrHex::Hex(0.into()),
+                        orders: 0,
+                    }),
+            );
+            changes
+        }
+        Depth {
+            bids: side_changes(&last.bids, &self.bids),
+            asks: side_changes(&last.asks, &self.asks),
+        }
+    }
+}
+
+impl From<Depth> for TopOfBook {
+    fn from(depth: Depth) -> Self {
+        TopOfBook {
+            bid: depth.bids.into_iter().next(),
+            ask: depth.asks.into_iter().next(),
+        }
+    }
+}
+
+/// Market data subscriptions.
+#[rpc]
+pub trait MarketApi {
+    /// RPC metadata.
+    type Metadata;
+
+    /// Trades of the `base`/`quote` pair. The first update is a snapshot of the best block,
+    /// followed by an update for every new best block and every finalized block with trades
+    /// of the pair.
+    #[pubsub(
+        subscription = "market_trades",
+        subscribe,
+        name = "market_subscribeTrades"
+    )]
+    fn subscribe_trades(
+        &self,
+        metadata: Self::Metadata,
+        subscriber: Subscriber<Update<BlockTrades>>,
+        base: String,
+        quote: String,
+    );
+
+    /// Cancels a trades subscription.
+    #[pubsub(
+        subscription = "market_trades",
+        unsubscribe,
+        name = "market_unsubscribeTrades"
+    )]
+    fn unsubscribe_trades(
+        &self,
+        metadata: Option<Self::Metadata>,
+        id: SubscriptionId,
+    ) -> Result<bool>;
+
+    /// The best `levels` price levels of each side of the `base`/`quote` book. The first
+    /// update is a snapshot of the book in the best block, followed by an update for every
+    /// new best block and every finalized block changing these levels. These updates only
+    /// carry the changed levels: best updates change the book of the previous best update,
+    /// finalized ones the book of the previous finalized update, and both start from the
+    /// snapshot.
+    #[pubsub(
+        subscription = "market_depth",
+        subscribe,
+        name = "market_subscribeDepth"
+    )]
+    fn subscribe_depth(
+        &self,
+        metadata: Self::Metadata,
+        subscriber: Subscriber<Update<Depth>>,
+        base: String,
+        quote: String,
+        levels: Option<u32>,
+    );
+
+    /// Cancels a depth subscription.
+    #[pubsub(
+        subscription = "market_depth",
+        unsubscribe,
+        name = "market_unsubscribeDepth"
+    )]
+    fn unsubscribe_depth(
+        &self,
+        metadata: Option<Self::Metadata>,
+        id: SubscriptionId,
+    ) -> Result<bool>;
+
+    /// The best bid and ask of the `base`/`quote` book. The first update is a snapshot of
+    /// the best block, followed by an update for every new best block and every finalized
+    /// block changing them.
+    #[pubsub(
+        subscription = "market_topOfBook",
+        subscribe,
+        name = "market_subscribeTopOfBook"
+    )]
+    fn subscribe_top_of_book(
+        &self,
+        metadata: Self::Metadata,
+        subscriber: Subscriber<Update<TopOfBook>>,
+        base: String,
+        quote: String,
+    );
+
+    /// Cancels a top of book subscription.
+    #[pubsub(
+        subscription = "market_topOfBook",
+        unsubscribe,
+        name = "market_unsubscribeTopOfBook"
+    )]
+    fn unsubscribe_top_of_book(
+        &self,
+        metadata: Option<Self::Metadata>,
+        id: SubscriptionId,
+    ) -> Result<bool>;
+}
+
+/// The open order `id` of the orderbook in the state of block `hash`.
+fn open_order<C, B>(client: &C, hash: Hash, id: OrderId) -> Option<OrderRecord>
+where
+    C: StorageProvider<Block, B>,
+    B: sc_client_api::Backend<Block>,
+{
+    let key = StorageKey(Orders::<Runtime>::hashed_key_for(id));
+    storage_value::<_, B, OrderOf<Runtime>>(client, hash, key)
+        .ok()
+        .flatten()
+        .map(Into::into)
+}
+
+/// The best `levels` price levels of each side of the book of `pair` in block `hash`.
+fn read_depth<C, B>(
+    client: &C,
+    hash: Hash,
+    pair: TradingPair,
+    levels: u32,
+) -> std::result::Result<Depth, String>
+where
+    C: StorageProvider<Block, B>,
+    B: sc_client_api::Backend<Block>,
+{
+    let read_side = |side| -> std::result::Result<Vec<PriceLevel>, String> {
+        let key = StorageKey(OrderBook::<Runtime>::hashed_key_for(pair, side));
+        let book = storage_value::<_, B, Vec<(FixedU128, OrderId)>>(client, hash, key)?.unwrap_or_default();
+        let mut side_levels: Vec<(FixedU128, Balance, u32)> = Vec::new();
+        for (price, id) in book {
+            match side_levels.last() {
+                Some((level_price, _, _)) if *level_price == price => {}
+                _ if side_levels.len() == levels as usize => break,
+                _ => side_levels.push((price, 0, 0)),
+            }
+            let key = StorageKey(Orders::<Runtime>::hashed_key_for(id));
+            let amount = storage_value::<_, B, OrderOf<Runtime>>(client, hash, key)?
+                .map_or(0, |order| order.amount);
+            let level = side_levels.last_mut().expect("the level of the order was pushed; qed");
+            level.1 = level.1.saturating_add(amount);
+            level.2 += 1;
+        }
+        Ok(side_levels
+            .into_iter()
+            .map(|(price, amount, orders)| PriceLevel {
+                price: format_price(price),
+                amount: NumberOrHex::Hex(amount.into()),
+                orders,
+            })
+            .collect())
+    };
+    Ok(Depth {
+        bids: read_side(Side::Bid)?,
+        asks: read_side(Side::Ask)?,
+    })
+}
+
+/// The last book view sent for best and for finalized blocks, with the block it was read at.
+struct BookState<T> {
+    best: Option<(Hash, T)>,
+    finalized: Option<(Hash, T)>,
+}
+
+impl<T> BookState<T> {
+    fn new() -> Self {
+        BookState {
+            best: None,
+            finalized: None,
+        }
+    }
+
+    fn last(&mut self, kind: UpdateKind) -> &mut Option<(Hash, T)> {
+        match kind {
+            UpdateKind::Snapshot | UpdateKind::Best => &mut self.best,
+            UpdateKind::Finalized => &mut self.finalized,
+        }
+    }
+}
+
+/// Implements [`MarketApi`] with the events and storage of a full client.
+pub struct MarketRpc<C, B> {
+    client: Arc<C>,
+    manager: SubscriptionManager,
+    _backend: std::marker::PhantomData<B>,
+}
+
+impl<C, B> MarketRpc<C, B> {
+    /// Create a new `MarketRpc` driving its subscriptions with `executor`.
+    pub fn new(client: Arc<C>, executor: SubscriptionTaskExecutor) -> Self {
+        MarketRpc {
+            client,
+            manager: SubscriptionManager::new(Arc::new(executor)),
+            _backend: Default::default(),
+        }
+    }
+}
+
+impl<C, B> MarketRpc<C, B>
+where
+    C: BlockchainEvents<Block> + HeaderBackend<Block> + StorageProvider<Block, B>,
+    C: Send + Sync + 'static,
+    B: sc_client_api::Backend<Block> + Send + Sync + 'static,
+{
+    /// Sends `subscriber` the updates `update` makes of the best block, then of every new
+    /// best block and every finalized block. Blocks `update` makes no update of are skipped.
+    fn subscribe<T, F>(&self, subscriber: Subscriber<Update<T>>, mut update: F)
+    where
+        T: Serialize + Send + 'static,
+        F: FnMut(&C, UpdateKind, &Header) -> Option<T> + Send + 'static,
+    {
+        // The streams are opened before the snapshot is read so that no block is missed.
+        let best_blocks = self
+            .client
+            .import_notification_stream()
+            .filter(|notification| future::ready(notification.is_new_best))
+            .map(|notification| (UpdateKind::Best, notification.header));
+        let finalized_blocks = self
+            .client
+            .finality_notification_stream()
+            .map(|notification| (UpdateKind::Finalized, notification.header));
+        let best = match self
+            .client
+            .header(BlockId::Hash(self.client.info().best_hash))
+        {
+            Ok(Some(header)) => header,
+            Ok(None) | Err(_) => {
+                let _ = subscriber.reject(RpcError::internal_error());
+                return;
+            }
+        };
+        // The snapshot block may have been imported after the streams were opened.
+        let snapshot_hash = best.hash();
+        let best_blocks =
+            best_blocks.filter(move |(_, header)| future::ready(header.hash() != snapshot_hash));
+        let client = self.client.clone();
+        let updates = stream::once(future::ready((UpdateKind::Snapshot, best)))
+            .chain(stream::select(best_blocks, finalized_blocks))
+            .filter_map(move |(kind, header)| {
+                future::ready(update(&*client, kind, &header).map(|data| (kind, header, data)))
+            })
+            .enumerate()
+            .map(|(sequence, (kind, header, data))| {
+                Ok::<_, ()>(Ok(Update {
+                    sequence: sequence as u64,
+                    kind,
+                    hash: header.hash(),
+                    number: *header.number(),
+                    data,
+                }))
+            })
+            .boxed()
+            .compat();
+
+        self.manager.add(subscriber, |sink| {
+            sink.sink_map_err(|e| log::warn!(target: "market", "Error sending update: {:?}", e))
+                .send_all(updates)
+                .map(|_| ())
+        });
+    }
+
+    /// The trades of the `base`/`quote` pair in block `header`, if there is an update to send.
+    fn trades(
+        client: &C,
+        kind: UpdateKind,
+        header: &Header,
+        base: AssetId,
+        quote: AssetId,
+    ) -> Option<BlockTrades> {
+        let hash = header.hash();
+        let (timestamp, events) = match read_events::<_, B>(client, hash) {
+            Ok(read) => read,
+            Err(e) => {
+                log::warn!(target: "market", "Unable to read the events of block {}: {}", hash, e);
+                return None;
+            }
+        };
+        // Orders that traded in the block were placed in it or are open in its parent.
+        let parent = *header.parent_hash();
+        let trades = block_trades(*header.number(), timestamp, events, base, quote, |id| {
+            open_order::<_, B>(client, parent, id)
+        });
+        if trades.is_empty() && kind != UpdateKind::Snapshot {
+            return None;
+        }
+        Some(BlockTrades { trades })
+    }
+
+    /// The view `view` of the book of `pair` in block `header`, with the last one sent for
+    /// blocks of `kind`, if it changed since. The snapshot is the last view sent for both kinds
+    /// until there is another one.
+    fn book<T: Clone + PartialEq>(
+        client: &C,
+        state: &mut BookState<T>,
+        kind: UpdateKind,
+        header: &Header,
+        pair: TradingPair,
+        levels: u32,
+        view: fn(Depth) -> T,
+    ) -> Option<(T, Option<T>)> {
+        let hash = header.hash();
+        let last = state.last(kind);
+        // A block without orderbook events leaves the book of its parent as it was.
+        if let Some((last_hash, _)) = last {
+            if *last_hash == *header.parent_hash() {
+                if let Ok((_, events)) = read_events::<_, B>(client, hash) {
+                    if !events
+                        .iter()
+                        .any(|record| matches!(record.event, Event::pallet_orderbook(_)))
+                    {
+                        *last_hash = hash;
+                        return None;
+                    }
+                }
+            }
+        }
+        let book = match read_depth::<_, B>(client, hash, pair, levels) {
+            Ok(depth) => view(depth),
+            Err(e) => {
+                log::warn!(target: "market", "Unable to read the book of block {}: {}", hash, e);
+                return None;
+            }
+        };
+        if kind == UpdateKind::Snapshot {
+            state.best = Some((hash, book.clone()));
+            state.finalized = Some((hash, book.clone()));
+            return Some((book, None));
+        }
+        match last.replace((hash, book.clone())) {
+            Some((_, sent)) if sent == book => None,
+            last => Some((book, last.map(|(_, sent)| sent))),
+        }
+    }
+}
+
+impl<C, B> MarketApi for MarketRpc<C, B>
+where
+    C: BlockchainEvents<Block> + HeaderBackend<Block> + StorageProvider<Block, B>,
+    C: Send + Sync + 'static,
+    B: sc_client_api::Backend<Block> + Send + Sync + 'static,
+{
+    type Metadata = sc_rpc::Metadata;
+
+    fn subscribe_trades(
+        &self,
+        _metadata: Self::Metadata,
+        subscriber: Subscriber<Update<BlockTrades>>,
+        base: String,
+        quote: String,
+    ) {
+        let (base, quote) = match parse_pair(&base, &quote) {
+            Ok(pair) => pair,
+            Err(e) => {
+                let _ = subscriber.reject(e);
+                return;
+            }
+        };
+        self.subscribe(subscriber, move |client, kind, header| {
+            Self::trades(client, kind, header, base, quote)
+        });
+    }
+
+    fn unsubscribe_trades(
+        &self,
+        _metadata: Option<Self::Metadata>,
+        id: SubscriptionId,
+    ) -> Result<bool> {
+        Ok(self.manager.cancel(id))
+    }
+
+    fn subscribe_depth(
+        &self,
+        _metadata: Self::Metadata,
+        subscriber: Subscriber<Update<Depth>>,
+        base: String,
+        quote: String,
+        levels: Option<u32>,
+    ) {
+        let (base, quote) = match parse_pair(&base, &quote) {
+            Ok(pair) => pair,
+            Err(e) => {
+                let _ = subscriber.reject(e);
+                return;
+            }
+        };
+        let pair = TradingPair { base, quote };
+        let levels = levels.unwrap_or(DEFAULT_DEPTH).clamp(1, MAX_DEPTH);
+        let mut state = BookState::new();
+        self.subscribe(subscriber, move |client, kind, header| {
+            Self::book(client, &mut state, kind, header, pair, levels, |depth| {
+                depth
+            })
+            .map(|(depth, last)| match last {
+                Some(last) => depth.changes_since(&last),
+                None => depth,
+            })
+        });
+    }
+
+    fn unsubscribe_depth(
+        &self,
+        _metadata: Option<Self::Metadata>,
+        id: SubscriptionId,
+    ) -> Result<bool> {
+        Ok(self.manager.cancel(id))
+    }
+
+    fn subscribe_top_of_book(
+        &self,
+        _metadata: Self::Metadata,
+        subscriber: Subscriber<Update<TopOfBook>>,
+        base: String,
+        quote: String,
+    ) {
+        let (base, quote) = match parse_pair(&base, &quote) {
+            Ok(pair) => pair,
+            Err(e) => {
+                let _ = subscriber.reject(e);
+                return;
+            }
+        };
+        let pair = TradingPair { base, quote };
+        let mut state = BookState::new();
+        self.subscribe(subscriber, move |client, kind, header| {
+            Self::book(client, &mut state, kind, header, pair, 1, TopOfBook::from)
+                .map(|(top, _)| top)
+        });
+    }
+
+    fn unsubscribe_top_of_book(
+        &self,
+        _metadata: Option<Self::Metadata>,
+        id: SubscriptionId,
+    ) -> Result<bool> {
+        Ok(self.manager.cancel(id))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn level(price: &str, amount: u64, orders: u32) -> PriceLevel {
+        PriceLevel {
+            price: price.into(),
+            amount: NumberOrHex::Hex(amount.into()),
+            orders,
+        }
+    }
+
+    #[test]
+    fn depth_changes_hold_changed_and_removed_levels() {
+        let last = Depth {
+            bids: vec![level("2", 10, 1), level("1.5", 5, 2)],
+            asks: vec![level("3", 7, 1)],
+        };
+        let depth = Depth {
+            bids: vec![level("2", 4, 1), level("1", 8, 1)],
+            asks: vec![level("3", 7, 1)],
+        };
+        assert_eq!(
+            depth.changes_since(&last),
+            Depth {
+                bids: vec![level("2", 4, 1), level("1", 8, 1), level("1.5", 0, 0)],
+                asks: vec![],
+            }
+        );
+        assert_eq!(
+            depth.changes_since(&depth),
+            Depth {
+                bids: vec![],
+                asks: vec![],
+            }
+        );
+    }
+}
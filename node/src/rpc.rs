@@ -115,6 +115,8 @@ pub fn create_full<C, P, SC, B>(
 where
     C: ProvideRuntimeApi<Block>
         + HeaderBackend<Block>
+        + sc_client_api::StorageProvider<Block, B>
+        + sc_client_api::BlockchainEvents<Block>
         + AuxStore
         + HeaderMetadata<Block, Error = BlockChainError>
         + Sync
@@ -126,11 +128,22 @@ where
     C::Api: pallet_transaction_payment_rpc::TransactionPaymentRuntimeApi<Block, Balance>,
     C::Api: BabeApi<Block>,
     C::Api: BlockBuilder<Block>,
+    C::Api: polkadex_dry_run_runtime_api::DryRunApi<
+        Block,
+        AccountId,
+        node_polkadex_runtime::Call,
+        Balance,
+        node_polkadex_runtime::Event,
+    >,
     P: TransactionPool + 'static,
     SC: SelectChain<Block> + 'static,
     B: sc_client_api::Backend<Block> + Send + Sync + 'static,
     B::State: sc_client_api::backend::StateBackend<sp_runtime::traits::HashFor<Block>>,
 {
+    use crate::balances::{BalancesApi, FullBalances};
+    use crate::dry_run::{DryRun, DryRunApi};
+    use crate::events::{EventsApi, EventsRpc};
+    use crate::market::{MarketApi, MarketRpc};
     use pallet_contracts_rpc::{Contracts, ContractsApi};
     use substrate_frame_rpc_system::{FullSystem, SystemApi};
     // use pallet_mmr_rpc::{MmrApi, Mmr};
@@ -175,6 +188,16 @@ where
     io.extend_with(TransactionPaymentApi::to_delegate(TransactionPayment::new(
         client.clone(),
     )));
+    io.extend_with(DryRunApi::to_delegate(DryRun::new(client.clone(), deny_unsafe)));
+    io.extend_with(BalancesApi::to_delegate(FullBalances::<_, B>::new(client.clone())));
+    io.extend_with(MarketApi::to_delegate(MarketRpc::<_, B>::new(
+        client.clone(),
+        subscription_executor.clone(),
+    )));
+    io.extend_with(EventsApi::to_delegate(EventsRpc::<_, B>::new(
+        client.clone(),
+        subscription_executor.clone(),
+    )));
     io.extend_with(sc_consensus_babe_rpc::BabeApi::to_delegate(
         BabeRpcHandler::new(
             client.clone(),
@@ -217,6 +240,8 @@ where
     P: TransactionPool + 'static,
     M: jsonrpc_core::Metadata + Default,
 {
+    use crate::balances::{BalancesApi, LightBalances};
+    use crate::light_payment::{LightTransactionPayment, LightTransactionPaymentApi};
     use substrate_frame_rpc_system::{LightSystem, SystemApi};
 
     let LightDeps {
@@ -227,8 +252,18 @@ where
     } = deps;
     let mut io = jsonrpc_core::IoHandler::default();
     io.extend_with(SystemApi::<Hash, AccountId, Index>::to_delegate(
-        LightSystem::new(client, remote_blockchain, fetcher, pool),
+        LightSystem::new(client.clone(), remote_blockchain.clone(), fetcher.clone(), pool),
+    ));
+    // Fees and balances are answered with remote calls and read proofs from full nodes,
+    // so wallets get the same methods from a light client.
+    io.extend_with(LightTransactionPaymentApi::to_delegate(
+        LightTransactionPayment::new(client.clone(), remote_blockchain.clone(), fetcher.clone()),
     ));
+    io.extend_with(BalancesApi::to_delegate(LightBalances::new(
+        client,
+        remote_blockchain,
+        fetcher,
+    )));
 
     io
 }
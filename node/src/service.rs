@@ -20,6 +20,8 @@
 
 //! Service implementation. Specialized wrapper over substrate service.
 
+use crate::chain_spec::{GrandpaSettings, VotingRule};
+use crate::indexer::Indexer;
 use crate::rpc as node_rpc;
 use futures::prelude::*;
 use node_polkadex_runtime::RuntimeApi;
@@ -44,6 +46,7 @@ native_executor_instance!(
 
 use sc_consensus_babe::SlotProportion;
 use sc_telemetry::{Telemetry, TelemetryWorker};
+use sp_keystore::SyncCryptoStorePtr;
 
 type FullClient = sc_service::TFullClient<Block, RuntimeApi, Executor>;
 type FullBackend = sc_service::TFullBackend<Block>;
@@ -152,51 +155,15 @@ pub fn new_partial(
 
     let (rpc_extensions_builder, rpc_setup) = {
         let (_, grandpa_link, babe_link) = &import_setup;
-
-        let justification_stream = grandpa_link.justification_stream();
-        let shared_authority_set = grandpa_link.shared_authority_set().clone();
-        let shared_voter_state = grandpa::SharedVoterState::empty();
-        let rpc_setup = shared_voter_state.clone();
-
-        let finality_proof_provider = grandpa::FinalityProofProvider::new_for_service(
+        full_rpc_extensions(
+            config,
+            client.clone(),
             backend.clone(),
-            Some(shared_authority_set.clone()),
-        );
-
-        let babe_config = babe_link.config().clone();
-        let shared_epoch_changes = babe_link.epoch_changes().clone();
-
-        let client = client.clone();
-        let pool = transaction_pool.clone();
-        let select_chain = select_chain.clone();
-        let keystore = keystore_container.sync_keystore();
-        let chain_spec = config.chain_spec.cloned_box();
-
-        let rpc_extensions_builder = move |deny_unsafe, subscription_executor| {
-            let deps = node_rpc::FullDeps {
-                client: client.clone(),
-                pool: pool.clone(),
-                select_chain: select_chain.clone(),
-                chain_spec: chain_spec.cloned_box(),
-                deny_unsafe,
-                babe: node_rpc::BabeDeps {
-                    babe_config: babe_config.clone(),
-                    shared_epoch_changes: shared_epoch_changes.clone(),
-                    keystore: keystore.clone(),
-                },
-                grandpa: node_rpc::GrandpaDeps {
-                    shared_voter_state: shared_voter_state.clone(),
-                    shared_authority_set: shared_authority_set.clone(),
-                    justification_stream: justification_stream.clone(),
-                    subscription_executor,
-                    finality_provider: finality_proof_provider.clone(),
-                },
-            };
-
-            node_rpc::create_full(deps)
-        };
-
-        (rpc_extensions_builder, rpc_setup)
+            transaction_pool.clone(),
+            keystore_container.sync_keystore(),
+            grandpa_link,
+            babe_link,
+        )
     };
 
     Ok(sc_service::PartialComponents {
@@ -211,6 +178,94 @@ pub fn new_partial(
     })
 }
 
+/// Builds the RPC extensions of a full node, along with the GRANDPA voter state they report.
+fn full_rpc_extensions(
+    config: &Configuration,
+    client: Arc<FullClient>,
+    backend: Arc<FullBackend>,
+    pool: Arc<sc_transaction_pool::FullPool<Block, FullClient>>,
+    keystore: SyncCryptoStorePtr,
+    grandpa_link: &grandpa::LinkHalf<Block, FullClient, FullSelectChain>,
+    babe_link: &sc_consensus_babe::BabeLink<Block>,
+) -> (
+    impl Fn(node_rpc::DenyUnsafe, sc_rpc::SubscriptionTaskExecutor) -> node_rpc::IoHandler,
+    grandpa::SharedVoterState,
+) {
+    let select_chain = sc_consensus::LongestChain::new(backend.clone());
+    let justification_stream = grandpa_link.justification_stream();
+    let shared_authority_set = grandpa_link.shared_authority_set().clone();
+    let shared_voter_state = grandpa::SharedVoterState::empty();
+    let rpc_setup = shared_voter_state.clone();
+
+    let finality_proof_provider = grandpa::FinalityProofProvider::new_for_service(
+        backend,
+        Some(shared_authority_set.clone()),
+    );
+
+    let babe_config = babe_link.config().clone();
+    let shared_epoch_changes = babe_link.epoch_changes().clone();
+    let chain_spec = config.chain_spec.cloned_box();
+
+    let rpc_extensions_builder = move |deny_unsafe, subscription_executor| {
+        let deps = node_rpc::FullDeps {
+            client: client.clone(),
+            pool: pool.clone(),
+            select_chain: select_chain.clone(),
+            chain_spec: chain_spec.cloned_box(),
+            deny_unsafe,
+            babe: node_rpc::BabeDeps {
+                babe_config: babe_config.clone(),
+                shared_epoch_changes: shared_epoch_changes.clone(),
+                keystore: keystore.clone(),
+            },
+            grandpa: node_rpc::GrandpaDeps {
+                shared_voter_state: shared_voter_state.clone(),
+                shared_authority_set: shared_authority_set.clone(),
+                justification_stream: justification_stream.clone(),
+                subscription_executor,
+                finality_provider: finality_proof_provider.clone(),
+            },
+        };
+
+        node_rpc::create_full(deps)
+    };
+
+    (rpc_extensions_builder, rpc_setup)
+}
+
+/// Opens the indexer database next to the chain database if `enabled`.
+fn open_indexer(
+    config: &Configuration,
+    enabled: bool,
+) -> Result<Option<Arc<Indexer>>, ServiceError> {
+    if !enabled {
+        return Ok(None);
+    }
+    let indexer = match config.database.path() {
+        Some(path) => Indexer::open(&path.with_file_name("indexer")),
+        None => Indexer::temporary(),
+    }
+    .map_err(ServiceError::Other)?;
+    Ok(Some(Arc::new(indexer)))
+}
+
+/// Adds the indexer RPC to the RPC extensions built by `rpc_extensions_builder`, if there is
+/// an indexer.
+fn with_indexer_rpc(
+    rpc_extensions_builder: impl Fn(node_rpc::DenyUnsafe, sc_rpc::SubscriptionTaskExecutor) -> node_rpc::IoHandler,
+    indexer: Option<Arc<Indexer>>,
+) -> impl Fn(node_rpc::DenyUnsafe, sc_rpc::SubscriptionTaskExecutor) -> node_rpc::IoHandler {
+    use crate::indexer::{IndexerApi, IndexerRpc};
+
+    move |deny_unsafe, subscription_executor| {
+        let mut io = rpc_extensions_builder(deny_unsafe, subscription_executor);
+        if let Some(indexer) = &indexer {
+            io.extend_with(IndexerApi::to_delegate(IndexerRpc::new(indexer.clone())));
+        }
+        io
+    }
+}
+
 pub struct NewFullBase {
     pub task_manager: TaskManager,
     pub client: Arc<FullClient>,
@@ -220,8 +275,12 @@ pub struct NewFullBase {
 }
 
 /// Creates a full service from the configuration.
+///
+/// With `indexer`, trades and transfers of finalized blocks are indexed and served over RPC.
 pub fn new_full_base(
     mut config: Configuration,
+    grandpa_settings: GrandpaSettings,
+    indexer: bool,
     with_startup_data: impl FnOnce(
         &sc_consensus_babe::BabeBlockImport<Block, FullClient, FullGrandpaBlockImport>,
         &sc_consensus_babe::BabeLink<Block>,
@@ -238,6 +297,7 @@ pub fn new_full_base(
         other: (rpc_extensions_builder, import_setup, rpc_setup, mut telemetry),
     } = new_partial(&config)?;
 
+    let indexer = open_indexer(&config, indexer)?;
     let shared_voter_state = rpc_setup;
     let auth_disc_publish_non_global_ips = config.network.allow_non_globals_in_dht;
 
@@ -290,7 +350,7 @@ pub fn new_full_base(
         client: client.clone(),
         keystore: keystore_container.sync_keystore(),
         network: network.clone(),
-        rpc_extensions_builder: Box::new(rpc_extensions_builder),
+        rpc_extensions_builder: Box::new(with_indexer_rpc(rpc_extensions_builder, indexer.clone())),
         transaction_pool: transaction_pool.clone(),
         task_manager: &mut task_manager,
         on_demand: None,
@@ -300,6 +360,13 @@ pub fn new_full_base(
         telemetry: telemetry.as_mut(),
     })?;
 
+    if let Some(indexer) = indexer {
+        task_manager.spawn_handle().spawn_blocking(
+            "indexer",
+            crate::indexer::run::<_, FullBackend>(client.clone(), indexer),
+        );
+    }
+
     let (block_import, grandpa_link, babe_link) = import_setup;
 
     (with_startup_data)(&block_import, &babe_link);
@@ -398,30 +465,45 @@ pub fn new_full_base(
         None
     };
 
+    // NOTE: non-authorities run the full voter unless the observer is enabled. The full voter
+    // should provide better guarantees of block and vote data availability than the observer.
+    // The observer has not been tested extensively yet and having most nodes in a network run
+    // it could lead to finality stalls.
+    let observer_enabled = grandpa_settings.observer && !role.is_authority();
     let config = grandpa::Config {
-        // FIXME #1578 make this available through chainspec
-        gossip_duration: std::time::Duration::from_millis(333),
-        justification_period: 512,
+        gossip_duration: std::time::Duration::from_millis(grandpa_settings.gossip_duration_ms),
+        justification_period: grandpa_settings.justification_period,
         name: Some(name),
-        observer_enabled: false,
+        observer_enabled,
         keystore,
         is_authority: role.is_authority(),
         telemetry: telemetry.as_ref().map(|x| x.handle()),
     };
 
-    if enable_grandpa {
+    if enable_grandpa && observer_enabled {
+        // the GRANDPA observer task is considered infallible, i.e.
+        // if it fails we take down the service with it.
+        task_manager.spawn_essential_handle().spawn_blocking(
+            "grandpa-observer",
+            grandpa::run_grandpa_observer(config, grandpa_link, network.clone())?,
+        );
+    } else if enable_grandpa {
         // start the full GRANDPA voter
-        // NOTE: non-authorities could run the GRANDPA observer protocol, but at
-        // this point the full voter should provide better guarantees of block
-        // and vote data availability than the observer. The observer has not
-        // been tested extensively yet and having most nodes in a network run it
-        // could lead to finality stalls.
+        let voting_rule = match grandpa_settings.voting_rule {
+            VotingRule::Default => grandpa::VotingRulesBuilder::default(),
+            VotingRule::BeforeBestBlockBy(blocks) => {
+                grandpa::VotingRulesBuilder::new().add(grandpa::BeforeBestBlockBy(blocks))
+            }
+            VotingRule::ThreeQuartersOfTheUnfinalizedChain => grandpa::VotingRulesBuilder::new()
+                .add(grandpa::ThreeQuartersOfTheUnfinalizedChain),
+            VotingRule::None => grandpa::VotingRulesBuilder::new(),
+        };
         let grandpa_config = grandpa::GrandpaParams {
             config,
             link: grandpa_link,
             network: network.clone(),
             telemetry: telemetry.as_ref().map(|x| x.handle()),
-            voting_rule: grandpa::VotingRulesBuilder::default().build(),
+            voting_rule: voting_rule.build(),
             prometheus_registry,
             shared_voter_state,
         };
@@ -444,8 +526,13 @@ pub fn new_full_base(
 }
 
 /// Builds a new service for a full client.
-pub fn new_full(config: Configuration) -> Result<TaskManager, ServiceError> {
-    new_full_base(config, |_, _| ()).map(|NewFullBase { task_manager, .. }| task_manager)
+pub fn new_full(
+    config: Configuration,
+    grandpa_settings: GrandpaSettings,
+    indexer: bool,
+) -> Result<TaskManager, ServiceError> {
+    new_full_base(config, grandpa_settings, indexer, |_, _| ())
+        .map(|NewFullBase { task_manager, .. }| task_manager)
 }
 
 pub fn new_light_base(
@@ -607,12 +694,227 @@ pub fn new_light(config: Configuration) -> Result<TaskManager, ServiceError> {
     new_light_base(config).map(|(task_manager, _, _, _, _)| task_manager)
 }
 
+/// How a development node seals blocks when it runs without BABE and GRANDPA.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Sealing {
+    /// Seal and finalize a block as soon as a transaction enters the pool.
+    Instant,
+    /// Seal blocks only when asked to with `engine_createBlock`.
+    Manual,
+    /// Seal and finalize a block every given number of milliseconds.
+    Interval(u64),
+}
+
+impl std::str::FromStr for Sealing {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "instant" => Ok(Sealing::Instant),
+            "manual" => Ok(Sealing::Manual),
+            _ => s
+                .strip_prefix("interval:")
+                .and_then(|millis| millis.parse().ok())
+                .filter(|millis| *millis > 0)
+                .map(Sealing::Interval)
+                .ok_or_else(|| {
+                    format!(
+                        "Invalid sealing mode {:?}, expected one of `instant`, `manual` or \
+                         `interval:<milliseconds>`",
+                        s
+                    )
+                }),
+        }
+    }
+}
+
+/// Builds a full node that seals blocks on demand instead of running BABE and GRANDPA.
+///
+/// Whatever the mode, blocks can also be sealed and finalized through the `engine_createBlock`
+/// and `engine_finalizeBlock` RPCs. Blocks still carry a BABE pre-digest and the timestamp of
+/// their slot, so the runtime sees the same inherents as on a live chain.
+pub fn new_manual_seal(
+    config: Configuration,
+    sealing: Sealing,
+    indexer: bool,
+) -> Result<TaskManager, ServiceError> {
+    new_manual_seal_base(config, sealing, indexer).map(|(task_manager, _, _)| task_manager)
+}
+
+/// Builds a manual seal node, returning its client and RPC handlers as well.
+pub fn new_manual_seal_base(
+    config: Configuration,
+    sealing: Sealing,
+    indexer: bool,
+) -> Result<(TaskManager, Arc<FullClient>, RpcHandlers), ServiceError> {
+    use sc_consensus_manual_seal::{
+        consensus::babe::{BabeConsensusDataProvider, SlotTimestampProvider},
+        rpc::{ManualSeal, ManualSealApi},
+        EngineCommand, ManualSealParams,
+    };
+    use sp_transaction_pool::TransactionPool;
+
+    let (client, backend, keystore_container, mut task_manager) =
+        sc_service::new_full_parts::<Block, RuntimeApi, Executor>(&config, None)?;
+    let client = Arc::new(client);
+    let select_chain = sc_consensus::LongestChain::new(backend.clone());
+
+    let transaction_pool = sc_transaction_pool::BasicPool::new_full(
+        config.transaction_pool.clone(),
+        config.role.is_authority().into(),
+        config.prometheus_registry(),
+        task_manager.spawn_handle(),
+        client.clone(),
+    );
+
+    // GRANDPA never votes here, but its block import keeps track of authority set changes for
+    // the RPCs that report them.
+    let (grandpa_block_import, grandpa_link) = grandpa::block_import(
+        client.clone(),
+        &(client.clone() as Arc<_>),
+        select_chain.clone(),
+        None,
+    )?;
+    let babe_genesis_config = sc_consensus_babe::Config::get_or_compute(&*client)?;
+    let authorities = babe_genesis_config.genesis_authorities.clone();
+    let (block_import, babe_link) = sc_consensus_babe::block_import(
+        babe_genesis_config,
+        grandpa_block_import,
+        client.clone(),
+    )?;
+
+    let import_queue = sc_consensus_manual_seal::import_queue(
+        Box::new(block_import.clone()),
+        &task_manager.spawn_essential_handle(),
+        config.prometheus_registry(),
+    );
+
+    let (network, network_status_sinks, system_rpc_tx, network_starter) =
+        sc_service::build_network(sc_service::BuildNetworkParams {
+            config: &config,
+            client: client.clone(),
+            transaction_pool: transaction_pool.clone(),
+            spawn_handle: task_manager.spawn_handle(),
+            import_queue,
+            on_demand: None,
+            block_announce_validator_builder: None,
+        })?;
+
+    let (command_sink, commands_stream) = futures::channel::mpsc::channel(1024);
+    let (full_rpc_extensions, _) = full_rpc_extensions(
+        &config,
+        client.clone(),
+        backend.clone(),
+        transaction_pool.clone(),
+        keystore_container.sync_keystore(),
+        &grandpa_link,
+        &babe_link,
+    );
+    let indexer = open_indexer(&config, indexer)?;
+    let rpc_extensions_builder = move |deny_unsafe, subscription_executor| {
+        let mut io = full_rpc_extensions(deny_unsafe, subscription_executor);
+        io.extend_with(ManualSealApi::to_delegate(ManualSeal::new(command_sink.clone())));
+        io
+    };
+
+    let prometheus_registry = config.prometheus_registry().cloned();
+    let keystore = keystore_container.sync_keystore();
+
+    let rpc_handlers = sc_service::spawn_tasks(sc_service::SpawnTasksParams {
+        config,
+        backend,
+        client: client.clone(),
+        keystore: keystore.clone(),
+        network,
+        rpc_extensions_builder: Box::new(with_indexer_rpc(rpc_extensions_builder, indexer.clone())),
+        transaction_pool: transaction_pool.clone(),
+        task_manager: &mut task_manager,
+        on_demand: None,
+        remote_blockchain: None,
+        network_status_sinks,
+        system_rpc_tx,
+        telemetry: None,
+    })?;
+
+    if let Some(indexer) = indexer {
+        task_manager.spawn_handle().spawn_blocking(
+            "indexer",
+            crate::indexer::run::<_, FullBackend>(client.clone(), indexer),
+        );
+    }
+
+    let seal_and_finalize = || EngineCommand::SealNewBlock {
+        create_empty: true,
+        finalize: true,
+        parent_hash: None,
+        sender: None,
+    };
+    let triggers = match sealing {
+        Sealing::Instant => transaction_pool
+            .import_notification_stream()
+            .map(move |_| seal_and_finalize())
+            .boxed(),
+        Sealing::Manual => futures::stream::pending().boxed(),
+        Sealing::Interval(millis) => futures::stream::unfold((), move |()| async move {
+            futures_timer::Delay::new(std::time::Duration::from_millis(millis)).await;
+            Some((seal_and_finalize(), ()))
+        })
+        .boxed(),
+    };
+
+    let proposer = sc_basic_authorship::ProposerFactory::new(
+        task_manager.spawn_handle(),
+        client.clone(),
+        transaction_pool.clone(),
+        prometheus_registry.as_ref(),
+        None,
+    );
+    let consensus_data_provider = BabeConsensusDataProvider::new(
+        client.clone(),
+        keystore,
+        babe_link.epoch_changes().clone(),
+        authorities,
+    )
+    .map_err(|e| ServiceError::Other(format!("{:?}", e)))?;
+
+    let client_clone = client.clone();
+    let authorship = sc_consensus_manual_seal::run_manual_seal(ManualSealParams {
+        block_import,
+        env: proposer,
+        client: client.clone(),
+        pool: transaction_pool.pool().clone(),
+        commands_stream: futures::stream::select(commands_stream, triggers),
+        select_chain,
+        consensus_data_provider: Some(Box::new(consensus_data_provider)),
+        create_inherent_data_providers: move |_, ()| {
+            let client = client_clone.clone();
+            async move {
+                // Timestamps advance by one slot per block, whatever the wall clock says.
+                let timestamp = SlotTimestampProvider::new(client)
+                    .map_err(|e| format!("{:?}", e))?;
+                let slot =
+                    sp_consensus_babe::inherents::InherentDataProvider::new(timestamp.slot().into());
+                Ok((timestamp, slot))
+            }
+        },
+    });
+    task_manager
+        .spawn_essential_handle()
+        .spawn_blocking("manual-seal", authorship);
+
+    network_starter.start_network();
+    Ok((task_manager, client, rpc_handlers))
+}
+
 #[cfg(test)]
 mod tests {
-    use crate::service::{new_full_base, new_light_base, NewFullBase};
+    use crate::chain_spec::GrandpaSettings;
+    use crate::service::{
+        new_full_base, new_light_base, new_manual_seal_base, NewFullBase, Sealing,
+    };
     use codec::Encode;
     use node_polkadex_runtime::constants::{currency::CENTS, time::SLOT_DURATION};
-    use node_polkadex_runtime::{Address, BalancesCall, Call, UncheckedExtrinsic};
+    use node_polkadex_runtime::{signed_extra, Address, BalancesCall, Call, UncheckedExtrinsic};
     use polkadex_primitives::{Block, DigestItem, Signature};
     use sc_client_api::BlockBackend;
     use sc_consensus_babe::{BabeIntermediate, CompatibleDigestItem, INTERMEDIATE_KEY};
@@ -673,6 +975,8 @@ mod tests {
                     ..
                 } = new_full_base(
                     config,
+                    GrandpaSettings::default(),
+                    false,
                     |block_import: &sc_consensus_babe::BabeBlockImport<Block, _, _>,
                      babe_link: &sc_consensus_babe::BabeLink<Block>| {
                         setup_handles = Some((block_import.clone(), babe_link.clone()));
@@ -824,22 +1128,7 @@ mod tests {
 
                 let function = Call::Balances(BalancesCall::transfer(to.into(), amount));
 
-                let check_spec_version = frame_system::CheckSpecVersion::new();
-                let check_tx_version = frame_system::CheckTxVersion::new();
-                let check_genesis = frame_system::CheckGenesis::new();
-                let check_era = frame_system::CheckEra::from(Era::Immortal);
-                let check_nonce = frame_system::CheckNonce::from(index);
-                let check_weight = frame_system::CheckWeight::new();
-                let payment = pallet_transaction_payment::ChargeTransactionPayment::from(0);
-                let extra = (
-                    check_spec_version,
-                    check_tx_version,
-                    check_genesis,
-                    check_era,
-                    check_nonce,
-                    check_weight,
-                    payment,
-                );
+                let extra = signed_extra(index, Era::Immortal, 0);
                 let raw_payload = SignedPayload::from_raw(
                     function,
                     extra,
@@ -874,7 +1163,7 @@ mod tests {
                     network,
                     transaction_pool,
                     ..
-                } = new_full_base(config, |_, _| ())?;
+                } = new_full_base(config, GrandpaSettings::default(), false, |_, _| ())?;
                 Ok(sc_service_test::TestNetComponents::new(
                     task_manager,
                     client,
@@ -894,4 +1183,144 @@ mod tests {
             vec!["//Alice".into(), "//Bob".into()],
         )
     }
+
+    /// Loads the development chain, whatever the `--chain` option says.
+    struct DevCli;
+
+    impl sc_cli::SubstrateCli for DevCli {
+        fn impl_name() -> String {
+            "Polkadex Node".into()
+        }
+
+        fn impl_version() -> String {
+            env!("CARGO_PKG_VERSION").into()
+        }
+
+        fn description() -> String {
+            env!("CARGO_PKG_DESCRIPTION").into()
+        }
+
+        fn author() -> String {
+            env!("CARGO_PKG_AUTHORS").into()
+        }
+
+        fn support_url() -> String {
+            "support.anonymous.an".into()
+        }
+
+        fn copyright_start_year() -> i32 {
+            2017
+        }
+
+        fn load_spec(&self, _: &str) -> Result<Box<dyn sc_service::ChainSpec>, String> {
+            Ok(Box::new(crate::chain_spec::development_config()?))
+        }
+
+        fn native_runtime_version(
+            _: &Box<dyn sc_service::ChainSpec>,
+        ) -> &'static sc_cli::RuntimeVersion {
+            &node_polkadex_runtime::VERSION
+        }
+    }
+
+    /// Sends a JSON-RPC request and returns the `result` of the response.
+    async fn rpc_call(
+        rpc_handlers: &sc_service::RpcHandlers,
+        method: &str,
+        params: serde_json::Value,
+    ) -> serde_json::Value {
+        let (tx, _rx) = futures::channel::mpsc::unbounded();
+        let request = serde_json::json!({
+            "jsonrpc": "2.0",
+            "id": 1,
+            "method": method,
+            "params": params,
+        });
+        let response = rpc_handlers
+            .rpc_query(&sc_rpc::Metadata::new(tx), &request.to_string())
+            .await
+            .expect("A call gets a response");
+        let mut response: serde_json::Value = serde_json::from_str(&response).unwrap();
+        assert_eq!(response["error"], serde_json::Value::Null, "{} failed", method);
+        response["result"].take()
+    }
+
+    #[test]
+    fn manual_seal_creates_and_finalizes_blocks() {
+        use sc_cli::SubstrateCli;
+        use structopt::StructOpt;
+
+        let run = sc_cli::RunCmd::from_iter_safe(&[
+            "polkadex-node",
+            "--dev",
+            "--tmp",
+            "--port",
+            "0",
+            "--rpc-port",
+            "0",
+            "--ws-port",
+            "0",
+            "--no-prometheus",
+            "--no-telemetry",
+            "--no-mdns",
+        ])
+        .unwrap();
+        let runner = DevCli.create_runner(&run).unwrap();
+        runner
+            .async_run(|config| {
+                let (task_manager, client, rpc_handlers) =
+                    new_manual_seal_base(config, Sealing::Manual, false)?;
+                let checks = async move {
+                    // Sealed and finalized at once: the BABE and timestamp inherents of the
+                    // block were accepted on import.
+                    let created = rpc_call(
+                        &rpc_handlers,
+                        "engine_createBlock",
+                        serde_json::json!([true, true, null]),
+                    )
+                    .await;
+                    let first: H256 = serde_json::from_value(created["hash"].clone()).unwrap();
+                    assert_eq!(client.chain_info().best_hash, first);
+                    assert_eq!(client.chain_info().finalized_hash, first);
+                    let header = client.header(&BlockId::hash(first)).unwrap().unwrap();
+                    assert!(header
+                        .digest()
+                        .logs()
+                        .iter()
+                        .any(|log| log.as_babe_pre_digest().is_some()));
+
+                    // Sealed only, then finalized on request.
+                    let created = rpc_call(
+                        &rpc_handlers,
+                        "engine_createBlock",
+                        serde_json::json!([true, false, null]),
+                    )
+                    .await;
+                    let second: H256 = serde_json::from_value(created["hash"].clone()).unwrap();
+                    assert_eq!(client.chain_info().best_number, 2);
+                    assert_eq!(client.chain_info().finalized_hash, first);
+                    let finalized = rpc_call(
+                        &rpc_handlers,
+                        "engine_finalizeBlock",
+                        serde_json::json!([second, null]),
+                    )
+                    .await;
+                    assert_eq!(finalized, serde_json::Value::Bool(true));
+                    assert_eq!(client.chain_info().finalized_hash, second);
+                    Ok(())
+                };
+                Ok::<_, sc_cli::Error>((checks, task_manager))
+            })
+            .unwrap();
+    }
+
+    #[test]
+    fn sealing_modes_are_parsed() {
+        assert_eq!("instant".parse(), Ok(Sealing::Instant));
+        assert_eq!("manual".parse(), Ok(Sealing::Manual));
+        assert_eq!("interval:500".parse(), Ok(Sealing::Interval(500)));
+        assert!("interval:0".parse::<Sealing>().is_err());
+        assert!("interval".parse::<Sealing>().is_err());
+        assert!("babe".parse::<Sealing>().is_err());
+    }
 }
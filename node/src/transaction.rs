@@ -0,0 +1,473 @@
+// This file is part of Polkadex.
+
+// Copyright (C) 2020-2021 Polkadex oü.
+// SPDX-License-Identifier: GPL-3.0-or-later WITH Classpath-exception-2.0
+
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with this program. If not, see <https://www.gnu.org/licenses/>.
+
+//! Offline construction and signing of transactions.
+
+use std::path::PathBuf;
+
+use codec::{Decode, Encode};
+use node_polkadex_runtime::{
+    signed_extra, Call, Runtime, SignedExtra, SignedPayload, UncheckedExtrinsic, VERSION,
+};
+use pallet_staking::RewardDestination;
+use polkadex_primitives::assets::AssetId;
+use polkadex_primitives::{AccountId, Balance, BlockNumber, Index, Signature};
+use sc_keystore::LocalKeystore;
+use sp_core::{
+    bytes::from_hex,
+    crypto::{key_types, CryptoTypePublicPair, SecretString},
+    hexdisplay::HexDisplay,
+    sr25519, Pair, H256,
+};
+use sp_keystore::SyncCryptoStore;
+use sp_runtime::generic::Era;
+use structopt::StructOpt;
+
+use crate::genesis_file::parse_asset;
+
+fn parse_hash(input: &str) -> Result<H256, String> {
+    let bytes = from_hex(input).map_err(|e| format!("Invalid hash {}: {}", input, e))?;
+    if bytes.len() != 32 {
+        return Err(format!("Invalid hash {}: expected 32 bytes", input));
+    }
+    Ok(H256::from_slice(&bytes))
+}
+
+fn parse_payee(input: &str) -> Result<RewardDestination<AccountId>, String> {
+    match input {
+        "staked" => Ok(RewardDestination::Staked),
+        "stash" => Ok(RewardDestination::Stash),
+        "controller" => Ok(RewardDestination::Controller),
+        "none" => Ok(RewardDestination::None),
+        account => account
+            .parse()
+            .map(RewardDestination::Account)
+            .map_err(|_| format!("Invalid payee {}", input)),
+    }
+}
+
+/// Call of the transaction.
+#[derive(Debug, StructOpt)]
+pub enum TxCall {
+    /// Any call, given SCALE encoded as hex.
+    Raw {
+        /// SCALE encoded call, as hex.
+        call: String,
+    },
+    /// `Currencies::transfer`.
+    Transfer {
+        /// Recipient account.
+        #[structopt(long)]
+        dest: AccountId,
+        /// Asset symbol: `PDEX`, `DOT`, `BTC` or `USD`.
+        #[structopt(long, parse(try_from_str = parse_asset))]
+        asset: AssetId,
+        /// Amount, in the smallest unit.
+        #[structopt(long)]
+        amount: Balance,
+    },
+    /// `Staking::bond`.
+    Bond {
+        /// Controller account.
+        #[structopt(long)]
+        controller: AccountId,
+        /// Amount to bond, in the smallest unit.
+        #[structopt(long)]
+        value: Balance,
+        /// Reward destination: `staked`, `stash`, `controller`, `none` or an account.
+        #[structopt(long, default_value = "staked", parse(try_from_str = parse_payee))]
+        payee: RewardDestination<AccountId>,
+    },
+}
+
+impl TxCall {
+    /// The runtime call.
+    pub fn call(&self) -> Result<Call, String> {
+        match self {
+            TxCall::Raw { call } => {
+                let bytes = from_hex(call).map_err(|e| format!("Invalid hex: {}", e))?;
+                Call::decode(&mut &bytes[..]).map_err(|e| format!("Failed to decode call: {}", e))
+            }
+            TxCall::Transfer {
+                dest,
+                asset,
+                amount,
+            } => Ok(Call::Currencies(orml_currencies::Call::<Runtime>::transfer(
+                dest.clone().into(),
+                *asset,
+                *amount,
+            ))),
+            TxCall::Bond {
+                controller,
+                value,
+                payee,
+            } => Ok(Call::Staking(pallet_staking::Call::<Runtime>::bond(
+                controller.clone().into(),
+                *value,
+                payee.clone(),
+            ))),
+        }
+    }
+}
+
+/// Fields of the `SignedExtra` of a transaction and the data they sign.
+#[derive(Debug, Clone, PartialEq)]
+pub struct TxParams {
+    pub nonce: Index,
+    pub tip: Balance,
+    pub era: Era,
+    pub era_hash: H256,
+    pub genesis_hash: H256,
+    pub spec_version: u32,
+    pub transaction_version: u32,
+}
+
+impl TxParams {
+    fn extra(&self) -> SignedExtra {
+        signed_extra(self.nonce, self.era, self.tip)
+    }
+
+    /// The payload signed for `call`.
+    pub fn payload(&self, call: Call) -> SignedPayload {
+        SignedPayload::from_raw(
+            call,
+            self.extra(),
+            (
+                self.spec_version,
+                self.transaction_version,
+                self.genesis_hash,
+                self.era_hash,
+                (),
+                (),
+                (),
+            ),
+        )
+    }
+}
+
+/// Signer of a transaction.
+pub enum TxSigner {
+    /// A key pair derived from a secret.
+    Pair(sr25519::Pair),
+    /// An account key of a keystore.
+    Keystore(LocalKeystore, sr25519::Public),
+}
+
+impl TxSigner {
+    fn public(&self) -> sr25519::Public {
+        match self {
+            TxSigner::Pair(pair) => pair.public(),
+            TxSigner::Keystore(_, public) => *public,
+        }
+    }
+
+    fn sign(&self, message: &[u8]) -> Result<sr25519::Signature, String> {
+        match self {
+            TxSigner::Pair(pair) => Ok(pair.sign(message)),
+            TxSigner::Keystore(keystore, public) => {
+                let key = CryptoTypePublicPair(sr25519::CRYPTO_ID, public.0.to_vec());
+                let signature =
+                    SyncCryptoStore::sign_with(keystore, key_types::ACCOUNT, &key, message)
+                        .map_err(|e| format!("Failed to sign with the keystore: {}", e))?;
+                sr25519::Signature::decode(&mut &signature[..])
+                    .map_err(|e| format!("Invalid signature from the keystore: {}", e))
+            }
+        }
+    }
+
+    /// Builds `call` into a transaction signed over its `SignedPayload`.
+    pub fn sign_transaction(
+        &self,
+        call: Call,
+        params: &TxParams,
+    ) -> Result<UncheckedExtrinsic, String> {
+        let payload = params.payload(call);
+        let signature = payload.using_encoded(|payload| self.sign(payload))?;
+        let (call, extra, _) = payload.deconstruct();
+        let signer: AccountId = self.public().into();
+        Ok(UncheckedExtrinsic::new_signed(
+            call,
+            signer.into(),
+            Signature::Sr25519(signature),
+            extra,
+        ))
+    }
+}
+
+/// The `tx` command used to build and sign transactions offline.
+#[derive(Debug, StructOpt)]
+pub struct TxCmd {
+    #[allow(missing_docs)]
+    #[structopt(subcommand)]
+    pub call: TxCall,
+
+    /// Secret URI of the signer, or a file containing it. Prompted for if neither it nor a
+    /// keystore is given.
+    #[structopt(long, conflicts_with = "keystore-path")]
+    pub suri: Option<String>,
+
+    /// Keystore holding the sr25519 account key (`acco` key type) of `--signer`.
+    #[structopt(long, parse(from_os_str), requires = "signer")]
+    pub keystore_path: Option<PathBuf>,
+
+    /// Password of the keystore.
+    #[structopt(long, requires = "keystore-path")]
+    pub password: Option<String>,
+
+    /// Account signing with the keystore.
+    #[structopt(long, requires = "keystore-path")]
+    pub signer: Option<AccountId>,
+
+    /// Nonce of the signer.
+    #[structopt(long)]
+    pub nonce: Index,
+
+    /// Tip for the block author, in the smallest unit.
+    #[structopt(long, default_value = "0")]
+    pub tip: Balance,
+
+    /// Genesis hash of the chain.
+    #[structopt(long, parse(try_from_str = parse_hash))]
+    pub genesis_hash: H256,
+
+    /// Number of blocks the transaction is valid for. The transaction is immortal if not
+    /// given.
+    #[structopt(long, requires_all = &["era-block-number", "era-block-hash"])]
+    pub era_period: Option<u64>,
+
+    /// Number of the block the mortal transaction is valid from. Eras longer than 4096 blocks
+    /// start on multiples of `period / 4096` only: the command tells the block to use instead
+    /// of any other.
+    #[structopt(long, requires = "era-period")]
+    pub era_block_number: Option<BlockNumber>,
+
+    /// Hash of the block at `--era-block-number`, which the mortal transaction is signed with.
+    #[structopt(long, requires = "era-period", parse(try_from_str = parse_hash))]
+    pub era_block_hash: Option<H256>,
+
+    /// Spec version of the runtime. Defaults to the native runtime's.
+    #[structopt(long)]
+    pub spec_version: Option<u32>,
+
+    /// Transaction version of the runtime. Defaults to the native runtime's.
+    #[structopt(long)]
+    pub transaction_version: Option<u32>,
+}
+
+impl TxCmd {
+    fn params(&self) -> sc_cli::Result<TxParams> {
+        let (era, era_hash) = match (self.era_period, self.era_block_number, self.era_block_hash) {
+            (Some(period), Some(number), Some(hash)) => {
+                // The runtime checks the signature against the hash of the block the era is born
+                // at, which is only `number` if the era starts there.
+                let era = Era::mortal(period, number.into());
+                let birth = era.birth(number.into());
+                if birth != u64::from(number) {
+                    return Err(format!(
+                        "A {} blocks era cannot start at block {}: pass the number and hash of \
+                         block {} instead",
+                        period, number, birth
+                    )
+                    .into());
+                }
+                (era, hash)
+            }
+            _ => (Era::Immortal, self.genesis_hash),
+        };
+        Ok(TxParams {
+            nonce: self.nonce,
+            tip: self.tip,
+            era,
+            era_hash,
+            genesis_hash: self.genesis_hash,
+            spec_version: self.spec_version.unwrap_or(VERSION.spec_version),
+            transaction_version: self
+                .transaction_version
+                .unwrap_or(VERSION.transaction_version),
+        })
+    }
+
+    fn signer(&self) -> sc_cli::Result<TxSigner> {
+        match (&self.keystore_path, &self.signer) {
+            (Some(path), Some(signer)) => {
+                let password = self.password.clone().map(SecretString::new);
+                let keystore = LocalKeystore::open(path, password)
+                    .map_err(|e| format!("Failed to open keystore {}: {}", path.display(), e))?;
+                let public = sr25519::Public::from_raw(*AsRef::<[u8; 32]>::as_ref(signer));
+                Ok(TxSigner::Keystore(keystore, public))
+            }
+            _ => {
+                let suri = sc_cli::utils::read_uri(self.suri.as_ref())?;
+                let pair = sr25519::Pair::from_string(&suri, None)
+                    .map_err(|e| format!("Invalid secret URI: {:?}", e))?;
+                Ok(TxSigner::Pair(pair))
+            }
+        }
+    }
+
+    /// Run the tx command
+    pub fn run(&self) -> sc_cli::Result<()> {
+        let extrinsic = self
+            .signer()?
+            .sign_transaction(self.call.call()?, &self.params()?)?;
+        println!("0x{}", HexDisplay::from(&extrinsic.encode()));
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use sp_keyring::AccountKeyring;
+    use sp_runtime::{traits::Verify, MultiAddress};
+
+    use super::*;
+
+    fn params(era: Era, era_hash: H256) -> TxParams {
+        TxParams {
+            nonce: 3,
+            tip: 10,
+            era,
+            era_hash,
+            genesis_hash: H256::repeat_byte(1),
+            spec_version: VERSION.spec_version,
+            transaction_version: VERSION.transaction_version,
+        }
+    }
+
+    fn verify(extrinsic: &UncheckedExtrinsic, params: &TxParams) {
+        let (address, signature, extra) = extrinsic.signature.clone().unwrap();
+        assert_eq!(extra.encode(), params.extra().encode());
+        let signer = match address {
+            MultiAddress::Id(signer) => signer,
+            _ => panic!("signed by account id"),
+        };
+        let payload = params.payload(extrinsic.function.clone());
+        assert!(payload.using_encoded(|payload| signature.verify(payload, &signer)));
+    }
+
+    #[test]
+    fn signs_transfer() {
+        let call = TxCall::Transfer {
+            dest: AccountKeyring::Bob.to_account_id(),
+            asset: AssetId::DOT,
+            amount: 1_000,
+        }
+        .call()
+        .unwrap();
+        let params = params(Era::Immortal, H256::repeat_byte(1));
+        let extrinsic = TxSigner::Pair(AccountKeyring::Alice.pair())
+            .sign_transaction(call.clone(), &params)
+            .unwrap();
+
+        let decoded = UncheckedExtrinsic::decode(&mut &extrinsic.encode()[..]).unwrap();
+        assert_eq!(decoded.function, call);
+        verify(&decoded, &params);
+    }
+
+    #[test]
+    fn signs_mortal_bond() {
+        let call = TxCall::Bond {
+            controller: AccountKeyring::Bob.to_account_id(),
+            value: 1_000,
+            payee: parse_payee("stash").unwrap(),
+        }
+        .call()
+        .unwrap();
+        let params = params(Era::mortal(64, 100), H256::repeat_byte(2));
+        let extrinsic = TxSigner::Pair(AccountKeyring::Alice.pair())
+            .sign_transaction(call, &params)
+            .unwrap();
+        verify(&extrinsic, &params);
+
+        // The mortal era is signed: the same signature is invalid for another birth block.
+        let other = TxParams {
+            era_hash: H256::repeat_byte(3),
+            ..params
+        };
+        let (address, signature, _) = extrinsic.signature.clone().unwrap();
+        let signer = match address {
+            MultiAddress::Id(signer) => signer,
+            _ => panic!("signed by account id"),
+        };
+        assert!(!other
+            .payload(extrinsic.function.clone())
+            .using_encoded(|payload| signature.verify(payload, &signer)));
+    }
+
+    #[test]
+    fn mortal_eras_start_at_the_given_block() {
+        let cmd = |number: &str| {
+            TxCmd::from_iter(&[
+                "tx",
+                "--nonce",
+                "0",
+                "--genesis-hash",
+                &format!("{:?}", H256::repeat_byte(1)),
+                "--era-period",
+                "16384",
+                "--era-block-number",
+                number,
+                "--era-block-hash",
+                &format!("{:?}", H256::repeat_byte(2)),
+                "raw",
+                "0x00",
+            ])
+        };
+        // Eras of 16384 blocks start on multiples of 4.
+        assert!(cmd("1001").params().is_err());
+        let params = cmd("1000").params().unwrap();
+        assert_eq!(params.era.birth(1000), 1000);
+        assert_eq!(params.era_hash, H256::repeat_byte(2));
+    }
+
+    #[test]
+    fn signs_with_keystore() {
+        let dir = tempfile::tempdir().unwrap();
+        let keystore = LocalKeystore::open(dir.path(), None).unwrap();
+        let public =
+            SyncCryptoStore::sr25519_generate_new(&keystore, key_types::ACCOUNT, Some("//Alice"))
+                .unwrap();
+        let call = TxCall::Raw {
+            call: format!(
+                "0x{}",
+                HexDisplay::from(&Call::System(frame_system::Call::remark(vec![1])).encode())
+            ),
+        }
+        .call()
+        .unwrap();
+        let params = params(Era::Immortal, H256::repeat_byte(1));
+        let extrinsic = TxSigner::Keystore(keystore, public)
+            .sign_transaction(call, &params)
+            .unwrap();
+        verify(&extrinsic, &params);
+    }
+
+    #[test]
+    fn parses_arguments() {
+        assert!(parse_hash("0x1234").is_err());
+        assert_eq!(
+            parse_hash(&format!("{:?}", H256::repeat_byte(7))).unwrap(),
+            H256::repeat_byte(7)
+        );
+        assert_eq!(
+            parse_payee(&AccountKeyring::Bob.to_account_id().to_string()).unwrap(),
+            RewardDestination::Account(AccountKeyring::Bob.to_account_id())
+        );
+        assert!(parse_payee("treasury").is_err());
+    }
+}
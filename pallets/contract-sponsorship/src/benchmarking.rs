@@ -0,0 +1,81 @@
+// This file is part of Polkadex.
+
+// Copyright (C) 2020-2021 Polkadex oü.
+// SPDX-License-Identifier: GPL-3.0-or-later WITH Classpath-exception-2.0
+
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with this program. If not, see <https://www.gnu.org/licenses/>.
+//! Benchmarks for `pallet_contract_sponsorship`.
+
+#![cfg(feature = "runtime-benchmarks")]
+
+use super::*;
+use frame_benchmarking::{account, benchmarks, impl_benchmark_test_suite, whitelisted_caller};
+use frame_system::RawOrigin;
+use sp_std::prelude::*;
+
+const SEED: u32 = 0;
+
+fn sponsored<T: Config>(allowances: u32) -> (T::AccountId, T::AccountId) {
+    let sponsor: T::AccountId = whitelisted_caller();
+    let contract: T::AccountId = account("contract", 0, SEED);
+    Instantiators::<T>::insert(&contract, &sponsor);
+    Module::<T>::register_sponsor(RawOrigin::Signed(sponsor.clone()).into(), contract.clone(), 1_000u32.into())
+        .expect("contract is not sponsored yet");
+    for i in 0..allowances {
+        let user: T::AccountId = account("user", i, SEED);
+        Module::<T>::set_allowance(RawOrigin::Signed(sponsor.clone()).into(), contract.clone(), user, 100u32.into())
+            .expect("caller is the sponsor");
+    }
+    (sponsor, contract)
+}
+
+benchmarks! {
+    register_sponsor {
+        let sponsor: T::AccountId = whitelisted_caller();
+        let contract: T::AccountId = account("contract", 0, SEED);
+        Instantiators::<T>::insert(&contract, &sponsor);
+    }: _(RawOrigin::Signed(sponsor), contract.clone(), 1_000u32.into())
+    verify {
+        assert!(Sponsorships::<T>::contains_key(&contract));
+    }
+
+    set_daily_cap {
+        let (sponsor, contract) = sponsored::<T>(0);
+    }: _(RawOrigin::Signed(sponsor), contract.clone(), 2_000u32.into())
+    verify {
+        assert_eq!(Module::<T>::sponsorship(&contract).unwrap().daily_cap, 2_000u32.into());
+    }
+
+    set_allowance {
+        let (sponsor, contract) = sponsored::<T>(0);
+        let user: T::AccountId = account("user", 0, SEED);
+    }: _(RawOrigin::Signed(sponsor), contract.clone(), user.clone(), 100u32.into())
+    verify {
+        assert_eq!(Module::<T>::allowance(&contract, &user), 100u32.into());
+    }
+
+    remove_sponsor {
+        let a in 0 .. 1_000;
+        let (sponsor, contract) = sponsored::<T>(a);
+    }: _(RawOrigin::Signed(sponsor), contract.clone(), a)
+    verify {
+        assert!(!Sponsorships::<T>::contains_key(&contract));
+    }
+}
+
+impl_benchmark_test_suite!(
+    Module,
+    crate::mock::new_test_ext(),
+    crate::mock::Test,
+);
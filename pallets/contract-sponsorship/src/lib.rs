@@ -0,0 +1,441 @@
+// This file is part of Polkadex.
+
+// Copyright (C) 2020-2021 Polkadex oü.
+// SPDX-License-Identifier: GPL-3.0-or-later WITH Classpath-exception-2.0
+
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with this program. If not, see <https://www.gnu.org/licenses/>.
+//! # Contract sponsorship pallet
+//!
+//! Lets an account pay the transaction fees of other users' calls to a contract.
+//!
+//! The account that instantiated a contract can register as its sponsor, together with a daily
+//! cap, and grant allowances to individual users. [`ChargeSponsoredTransactionPayment`] replaces
+//! `pallet_transaction_payment::ChargeTransactionPayment` in the runtime's `SignedExtra`: when
+//! a signed call targets a sponsored contract and the fee fits both the caller's remaining
+//! allowance and what is left of the daily cap, the fee is withdrawn from the sponsor. Every
+//! other transaction, including sponsored calls that do not fit, is charged to the caller
+//! exactly like `ChargeTransactionPayment` would.
+//!
+//! Which calls can be sponsored, and the contract they target, is decided by the runtime
+//! through [`SponsoredCallTarget`]. The extension also records who instantiates contracts with
+//! a signed extrinsic; contracts instantiated by other contracts cannot be sponsored.
+
+#![cfg_attr(not(feature = "std"), no_std)]
+
+use codec::{Decode, Encode};
+use frame_support::{
+    decl_error, decl_event, decl_module, decl_storage,
+    dispatch::DispatchResult,
+    ensure,
+    traits::Get,
+    weights::{DispatchInfo, PostDispatchInfo},
+    StorageDoubleMap, StorageMap,
+};
+use frame_system::ensure_signed;
+use pallet_transaction_payment::{ChargeTransactionPayment, OnChargeTransaction};
+use sp_runtime::{
+    traits::{
+        DispatchInfoOf, Dispatchable, PostDispatchInfoOf, SaturatedConversion, Saturating,
+        SignedExtension, Zero,
+    },
+    transaction_validity::{
+        TransactionPriority, TransactionValidity, TransactionValidityError, ValidTransaction,
+    },
+    DispatchResult as RuntimeDispatchResult, FixedPointOperand, RuntimeDebug,
+};
+
+pub use weights::WeightInfo;
+
+#[cfg(feature = "runtime-benchmarks")]
+mod benchmarking;
+#[cfg(test)]
+mod mock;
+#[cfg(test)]
+mod tests;
+pub mod weights;
+
+/// Balance type used by the transaction payment pallet.
+pub type BalanceOf<T> = <<T as pallet_transaction_payment::Config>::OnChargeTransaction as OnChargeTransaction<T>>::Balance;
+
+type LiquidityInfoOf<T> = <<T as pallet_transaction_payment::Config>::OnChargeTransaction as OnChargeTransaction<T>>::LiquidityInfo;
+
+/// Picks out the calls that may be sponsored.
+pub trait SponsoredCallTarget<Call, AccountId> {
+    /// Returns the contract targeted by `call`, or `None` if `call` can never be sponsored.
+    fn target(call: &Call) -> Option<AccountId>;
+    /// Returns the contract `call` instantiates when dispatched by `deployer`, or `None` if
+    /// `call` does not instantiate a contract.
+    fn instantiated(call: &Call, deployer: &AccountId) -> Option<AccountId>;
+}
+
+/// Configure the pallet by specifying the parameters and types on which it depends.
+pub trait Config: frame_system::Config + pallet_transaction_payment::Config {
+    /// Because this pallet emits events, it depends on the runtime's definition of an event.
+    type Event: From<Event<Self>> + Into<<Self as frame_system::Config>::Event>;
+    /// Extracts the sponsored contract from a call.
+    type CallTarget: SponsoredCallTarget<<Self as frame_system::Config>::Call, Self::AccountId>;
+    /// Number of blocks the daily cap of a sponsorship applies to.
+    type CapPeriod: Get<Self::BlockNumber>;
+    /// Weight information for extrinsics in this pallet.
+    type WeightInfo: WeightInfo;
+}
+
+/// A sponsor's commitment to pay fees for calls to one contract.
+#[derive(Encode, Decode, Clone, PartialEq, Eq, Default, RuntimeDebug)]
+pub struct Sponsorship<AccountId, Balance, BlockNumber> {
+    /// Account the fees are withdrawn from.
+    pub sponsor: AccountId,
+    /// Maximum amount of fees paid within one cap period.
+    pub daily_cap: Balance,
+    /// Block at which the current cap period started.
+    pub period_start: BlockNumber,
+    /// Fees paid since `period_start`.
+    pub spent: Balance,
+    /// Number of users holding an allowance.
+    pub allowances: u32,
+}
+
+type SponsorshipOf<T> = Sponsorship<<T as frame_system::Config>::AccountId, BalanceOf<T>, <T as frame_system::Config>::BlockNumber>;
+
+decl_storage! {
+    trait Store for Module<T: Config> as ContractSponsorship {
+        /// Account that instantiated each contract, the only one allowed to register its sponsor.
+        pub Instantiators get(fn instantiator):
+            map hasher(blake2_128_concat) T::AccountId => Option<T::AccountId>;
+        /// Sponsorship of each sponsored contract.
+        pub Sponsorships get(fn sponsorship):
+            map hasher(blake2_128_concat) T::AccountId => Option<SponsorshipOf<T>>;
+        /// Fees a user may still have paid by the sponsor of a contract, keyed by contract and user.
+        pub Allowances get(fn allowance):
+            double_map hasher(blake2_128_concat) T::AccountId, hasher(blake2_128_concat) T::AccountId => BalanceOf<T>;
+    }
+}
+
+decl_event!(
+    pub enum Event<T>
+    where
+        AccountId = <T as frame_system::Config>::AccountId,
+        Balance = BalanceOf<T>,
+    {
+        /// A contract is now sponsored. \[contract, sponsor, daily_cap\]
+        SponsorRegistered(AccountId, AccountId, Balance),
+        /// A contract is no longer sponsored. \[contract\]
+        SponsorRemoved(AccountId),
+        /// The daily cap of a sponsorship changed. \[contract, daily_cap\]
+        DailyCapSet(AccountId, Balance),
+        /// The allowance of a user changed. \[contract, user, allowance\]
+        AllowanceSet(AccountId, AccountId, Balance),
+        /// A sponsor paid the fee of a call. \[contract, user, sponsor, fee\]
+        FeeSponsored(AccountId, AccountId, AccountId, Balance),
+    }
+);
+
+decl_error! {
+    pub enum Error for Module<T: Config> {
+        /// The contract already has a sponsor.
+        AlreadySponsored,
+        /// Only the account that instantiated a contract can sponsor it.
+        NotInstantiator,
+        /// The contract is not sponsored.
+        NotSponsored,
+        /// Only the sponsor of a contract can change its sponsorship.
+        NotSponsor,
+        /// The number of allowances given as witness is too low.
+        BadWitness,
+    }
+}
+
+decl_module! {
+    pub struct Module<T: Config> for enum Call where origin: T::Origin {
+        type Error = Error<T>;
+
+        fn deposit_event() = default;
+
+        /// Start paying fees for calls to `contract`, up to `daily_cap` per cap period.
+        ///
+        /// Only the account that instantiated `contract` can sponsor it. Users still need an
+        /// allowance, see [`Call::set_allowance`].
+        #[weight = <T as Config>::WeightInfo::register_sponsor()]
+        pub fn register_sponsor(origin, contract: T::AccountId, daily_cap: BalanceOf<T>) -> DispatchResult {
+            let sponsor = ensure_signed(origin)?;
+            ensure!(Instantiators::<T>::get(&contract) == Some(sponsor.clone()), Error::<T>::NotInstantiator);
+            ensure!(!Sponsorships::<T>::contains_key(&contract), Error::<T>::AlreadySponsored);
+
+            Sponsorships::<T>::insert(&contract, Sponsorship {
+                sponsor: sponsor.clone(),
+                daily_cap,
+                period_start: frame_system::Pallet::<T>::block_number(),
+                spent: Zero::zero(),
+                allowances: 0,
+            });
+            Self::deposit_event(RawEvent::SponsorRegistered(contract, sponsor, daily_cap));
+            Ok(())
+        }
+
+        /// Change the daily cap of a sponsored contract.
+        #[weight = <T as Config>::WeightInfo::set_daily_cap()]
+        pub fn set_daily_cap(origin, contract: T::AccountId, daily_cap: BalanceOf<T>) -> DispatchResult {
+            let who = ensure_signed(origin)?;
+            Sponsorships::<T>::try_mutate(&contract, |sponsorship| -> DispatchResult {
+                let sponsorship = sponsorship.as_mut().ok_or(Error::<T>::NotSponsored)?;
+                ensure!(sponsorship.sponsor == who, Error::<T>::NotSponsor);
+                sponsorship.daily_cap = daily_cap;
+                Ok(())
+            })?;
+            Self::deposit_event(RawEvent::DailyCapSet(contract, daily_cap));
+            Ok(())
+        }
+
+        /// Set the amount of fees `user` may have paid for calls to `contract`.
+        ///
+        /// The allowance is consumed as fees are paid; setting it to zero revokes it.
+        #[weight = <T as Config>::WeightInfo::set_allowance()]
+        pub fn set_allowance(origin, contract: T::AccountId, user: T::AccountId, allowance: BalanceOf<T>) -> DispatchResult {
+            let who = ensure_signed(origin)?;
+            Sponsorships::<T>::try_mutate(&contract, |sponsorship| -> DispatchResult {
+                let sponsorship = sponsorship.as_mut().ok_or(Error::<T>::NotSponsored)?;
+                ensure!(sponsorship.sponsor == who, Error::<T>::NotSponsor);
+
+                let existed = Allowances::<T>::contains_key(&contract, &user);
+                if allowance.is_zero() {
+                    Allowances::<T>::remove(&contract, &user);
+                    if existed {
+                        sponsorship.allowances = sponsorship.allowances.saturating_sub(1);
+                    }
+                } else {
+                    Allowances::<T>::insert(&contract, &user, allowance);
+                    if !existed {
+                        sponsorship.allowances = sponsorship.allowances.saturating_add(1);
+                    }
+                }
+                Ok(())
+            })?;
+            Self::deposit_event(RawEvent::AllowanceSet(contract, user, allowance));
+            Ok(())
+        }
+
+        /// Stop sponsoring `contract`, dropping all allowances granted for it.
+        ///
+        /// `allowances` must be at least the number of users holding an allowance.
+        #[weight = <T as Config>::WeightInfo::remove_sponsor(*allowances)]
+        pub fn remove_sponsor(origin, contract: T::AccountId, allowances: u32) -> DispatchResult {
+            let who = ensure_signed(origin)?;
+            let sponsorship = Sponsorships::<T>::get(&contract).ok_or(Error::<T>::NotSponsored)?;
+            ensure!(sponsorship.sponsor == who, Error::<T>::NotSponsor);
+            ensure!(sponsorship.allowances <= allowances, Error::<T>::BadWitness);
+
+            Sponsorships::<T>::remove(&contract);
+            Allowances::<T>::remove_prefix(&contract);
+            Self::deposit_event(RawEvent::SponsorRemoved(contract));
+            Ok(())
+        }
+    }
+}
+
+impl<T: Config> Module<T> {
+    /// Sponsorship of `contract` as seen at the current block, i.e. with `spent` reset if the
+    /// cap period it was recorded in is over.
+    fn current_sponsorship(contract: &T::AccountId) -> Option<SponsorshipOf<T>> {
+        let mut sponsorship = Sponsorships::<T>::get(contract)?;
+        let now = frame_system::Pallet::<T>::block_number();
+        if now >= sponsorship.period_start.saturating_add(T::CapPeriod::get()) {
+            sponsorship.period_start = now;
+            sponsorship.spent = Zero::zero();
+        }
+        Some(sponsorship)
+    }
+
+    /// Returns the account paying `fee` for `who`'s call to `contract`, if the sponsor covers it.
+    pub fn sponsor_for(contract: &T::AccountId, who: &T::AccountId, fee: BalanceOf<T>) -> Option<T::AccountId> {
+        let sponsorship = Self::current_sponsorship(contract)?;
+        if fee > Allowances::<T>::get(contract, who)
+            || sponsorship.spent.saturating_add(fee) > sponsorship.daily_cap
+        {
+            return None;
+        }
+        Some(sponsorship.sponsor)
+    }
+
+    /// Books `fee` against the daily cap of `contract` and the allowance of `who`.
+    fn note_sponsored_fee(contract: &T::AccountId, who: &T::AccountId, fee: BalanceOf<T>) {
+        if let Some(mut sponsorship) = Self::current_sponsorship(contract) {
+            sponsorship.spent = sponsorship.spent.saturating_add(fee);
+            Sponsorships::<T>::insert(contract, sponsorship);
+        }
+        Allowances::<T>::mutate(contract, who, |allowance| *allowance = allowance.saturating_sub(fee));
+    }
+
+    /// Gives back the part of a booked fee that ended up not being charged.
+    fn refund_sponsored_fee(contract: &T::AccountId, who: &T::AccountId, refund: BalanceOf<T>) {
+        if refund.is_zero() {
+            return;
+        }
+        Sponsorships::<T>::mutate(contract, |sponsorship| {
+            if let Some(sponsorship) = sponsorship {
+                sponsorship.spent = sponsorship.spent.saturating_sub(refund);
+            }
+        });
+        if Sponsorships::<T>::contains_key(contract) {
+            Allowances::<T>::mutate(contract, who, |allowance| *allowance = allowance.saturating_add(refund));
+        }
+    }
+}
+
+/// Drop-in replacement for `ChargeTransactionPayment` that lets sponsors pay for calls to
+/// their contracts. Encodes exactly like `ChargeTransactionPayment`: a compact tip.
+///
+/// Sponsors only pay the tip-free fee. The tip of a sponsored transaction is ignored, so it
+/// neither costs the sponsor nor raises the transaction's priority.
+#[derive(Encode, Decode, Clone, Eq, PartialEq)]
+pub struct ChargeSponsoredTransactionPayment<T: Config>(#[codec(compact)] BalanceOf<T>);
+
+impl<T: Config> ChargeSponsoredTransactionPayment<T>
+where
+    <T as frame_system::Config>::Call: Dispatchable<Info = DispatchInfo, PostInfo = PostDispatchInfo>,
+    BalanceOf<T>: Send + Sync + FixedPointOperand,
+{
+    /// Utility constructor. Used only in client/factory code.
+    pub fn from(tip: BalanceOf<T>) -> Self {
+        Self(tip)
+    }
+
+    fn unsponsored(&self) -> ChargeTransactionPayment<T> {
+        ChargeTransactionPayment::from(self.0)
+    }
+
+    /// Returns the sponsored contract, the sponsor and the tip-free fee if the fee of `who`'s
+    /// `call` is paid for by a sponsor.
+    fn sponsorship(
+        &self,
+        who: &T::AccountId,
+        call: &<T as frame_system::Config>::Call,
+        info: &DispatchInfoOf<<T as frame_system::Config>::Call>,
+        len: usize,
+    ) -> Option<(T::AccountId, T::AccountId, BalanceOf<T>)> {
+        let contract = T::CallTarget::target(call)?;
+        let fee = pallet_transaction_payment::Module::<T>::compute_fee(len as u32, info, Zero::zero());
+        let sponsor = Module::<T>::sponsor_for(&contract, who, fee)?;
+        Some((contract, sponsor, fee))
+    }
+
+    fn withdraw_from_sponsor(
+        &self,
+        sponsor: &T::AccountId,
+        call: &<T as frame_system::Config>::Call,
+        info: &DispatchInfoOf<<T as frame_system::Config>::Call>,
+        fee: BalanceOf<T>,
+    ) -> Result<LiquidityInfoOf<T>, TransactionValidityError> {
+        <<T as pallet_transaction_payment::Config>::OnChargeTransaction as OnChargeTransaction<T>>::withdraw_fee(
+            sponsor, call, info, fee, Zero::zero(),
+        )
+    }
+}
+
+impl<T: Config> sp_std::fmt::Debug for ChargeSponsoredTransactionPayment<T> {
+    #[cfg(feature = "std")]
+    fn fmt(&self, f: &mut sp_std::fmt::Formatter) -> sp_std::fmt::Result {
+        write!(f, "ChargeSponsoredTransactionPayment<{:?}>", self.0)
+    }
+    #[cfg(not(feature = "std"))]
+    fn fmt(&self, _: &mut sp_std::fmt::Formatter) -> sp_std::fmt::Result {
+        Ok(())
+    }
+}
+
+impl<T: Config> SignedExtension for ChargeSponsoredTransactionPayment<T>
+where
+    <T as frame_system::Config>::Call: Dispatchable<Info = DispatchInfo, PostInfo = PostDispatchInfo>,
+    BalanceOf<T>: Send + Sync + From<u64> + FixedPointOperand,
+{
+    const IDENTIFIER: &'static str = "ChargeSponsoredTransactionPayment";
+    type AccountId = T::AccountId;
+    type Call = <T as frame_system::Config>::Call;
+    type AdditionalSigned = ();
+    type Pre = (
+        // tip
+        BalanceOf<T>,
+        // account the fee was withdrawn from
+        Self::AccountId,
+        // imbalance resulting from withdrawing the fee
+        LiquidityInfoOf<T>,
+        // sponsored contract and caller, if a sponsor paid
+        Option<(Self::AccountId, Self::AccountId)>,
+        // instantiated contract and its deployer, if the call instantiates one
+        Option<(Self::AccountId, Self::AccountId)>,
+    );
+
+    fn additional_signed(&self) -> Result<(), TransactionValidityError> {
+        Ok(())
+    }
+
+    fn validate(
+        &self,
+        who: &Self::AccountId,
+        call: &Self::Call,
+        info: &DispatchInfoOf<Self::Call>,
+        len: usize,
+    ) -> TransactionValidity {
+        match self.sponsorship(who, call, info, len) {
+            Some((_, sponsor, fee)) => {
+                self.withdraw_from_sponsor(&sponsor, call, info, fee)?;
+                Ok(ValidTransaction {
+                    priority: fee.saturated_into::<TransactionPriority>(),
+                    ..Default::default()
+                })
+            }
+            None => self.unsponsored().validate(who, call, info, len),
+        }
+    }
+
+    fn pre_dispatch(
+        self,
+        who: &Self::AccountId,
+        call: &Self::Call,
+        info: &DispatchInfoOf<Self::Call>,
+        len: usize,
+    ) -> Result<Self::Pre, TransactionValidityError> {
+        let instantiated = T::CallTarget::instantiated(call, who).map(|contract| (contract, who.clone()));
+        match self.sponsorship(who, call, info, len) {
+            Some((contract, sponsor, fee)) => {
+                let imbalance = self.withdraw_from_sponsor(&sponsor, call, info, fee)?;
+                Module::<T>::note_sponsored_fee(&contract, who, fee);
+                Ok((Zero::zero(), sponsor, imbalance, Some((contract, who.clone())), instantiated))
+            }
+            None => {
+                let (tip, who, imbalance) = self.unsponsored().pre_dispatch(who, call, info, len)?;
+                Ok((tip, who, imbalance, None, instantiated))
+            }
+        }
+    }
+
+    fn post_dispatch(
+        pre: Self::Pre,
+        info: &DispatchInfoOf<Self::Call>,
+        post_info: &PostDispatchInfoOf<Self::Call>,
+        len: usize,
+        result: &RuntimeDispatchResult,
+    ) -> Result<(), TransactionValidityError> {
+        let (tip, payer, imbalance, sponsored, instantiated) = pre;
+        if let (Some((contract, deployer)), Ok(())) = (instantiated, result) {
+            Instantiators::<T>::insert(contract, deployer);
+        }
+        if let Some((contract, user)) = sponsored {
+            let charged = pallet_transaction_payment::Module::<T>::compute_fee(len as u32, info, tip);
+            let actual = pallet_transaction_payment::Module::<T>::compute_actual_fee(len as u32, info, post_info, tip);
+            Module::<T>::refund_sponsored_fee(&contract, &user, charged.saturating_sub(actual));
+            Module::<T>::deposit_event(RawEvent::FeeSponsored(contract, user, payer.clone(), actual));
+        }
+        ChargeTransactionPayment::<T>::post_dispatch((tip, payer, imbalance), info, post_info, len, result)
+    }
+}
@@ -0,0 +1,239 @@
+// This file is part of Polkadex.
+
+// Copyright (C) 2020-2021 Polkadex oü.
+// SPDX-License-Identifier: GPL-3.0-or-later WITH Classpath-exception-2.0
+
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with this program. If not, see <https://www.gnu.org/licenses/>.
+use crate::{mock::*, ChargeSponsoredTransactionPayment, Error, Instantiators};
+use codec::Encode;
+use frame_support::{
+    assert_noop, assert_ok,
+    storage::{with_transaction, TransactionOutcome},
+    weights::{DispatchInfo, PostDispatchInfo},
+};
+use sp_runtime::{traits::SignedExtension, DispatchResult};
+
+const LEN: usize = 10;
+
+fn contract_call() -> Call {
+    Call::System(frame_system::Call::remark(CONTRACT.encode()))
+}
+
+fn info(weight: u64) -> DispatchInfo {
+    DispatchInfo { weight, ..Default::default() }
+}
+
+fn fee(weight: u64) -> u64 {
+    TransactionPayment::compute_fee(LEN as u32, &info(weight), 0)
+}
+
+/// Runs a transaction of `weight` through the extension, `actual_weight` being what it
+/// reports after dispatch.
+fn charge(who: u64, call: &Call, weight: u64, actual_weight: u64) {
+    charge_with_tip(who, call, weight, actual_weight, 0)
+}
+
+fn charge_with_tip(who: u64, call: &Call, weight: u64, actual_weight: u64, tip: u64) {
+    dispatch(who, call, weight, actual_weight, tip, Ok(()))
+}
+
+fn dispatch(who: u64, call: &Call, weight: u64, actual_weight: u64, tip: u64, result: DispatchResult) {
+    let pre = ChargeSponsoredTransactionPayment::<Test>::from(tip)
+        .pre_dispatch(&who, call, &info(weight), LEN)
+        .unwrap();
+    let post_info = PostDispatchInfo { actual_weight: Some(actual_weight), pays_fee: Default::default() };
+    assert_ok!(ChargeSponsoredTransactionPayment::<Test>::post_dispatch(
+        pre,
+        &info(weight),
+        &post_info,
+        LEN,
+        &result
+    ));
+}
+
+fn sponsor_with_allowance(daily_cap: u64, allowance: u64) {
+    Instantiators::<Test>::insert(CONTRACT, SPONSOR);
+    assert_ok!(Sponsorship::register_sponsor(Origin::signed(SPONSOR), CONTRACT, daily_cap));
+    assert_ok!(Sponsorship::set_allowance(Origin::signed(SPONSOR), CONTRACT, USER, allowance));
+}
+
+#[test]
+fn only_the_instantiator_registers_a_sponsor() {
+    new_test_ext().execute_with(|| {
+        let instantiate = Call::Balances(pallet_balances::Call::transfer(CONTRACT, 1));
+        assert_noop!(
+            Sponsorship::register_sponsor(Origin::signed(SPONSOR), CONTRACT, 100),
+            Error::<Test>::NotInstantiator
+        );
+
+        // A failed instantiation does not make the caller the instantiator.
+        dispatch(USER, &instantiate, 5, 5, 0, Err(sp_runtime::DispatchError::Other("failed")));
+        assert_eq!(Sponsorship::instantiator(CONTRACT), None);
+
+        dispatch(SPONSOR, &instantiate, 5, 5, 0, Ok(()));
+        assert_eq!(Sponsorship::instantiator(CONTRACT), Some(SPONSOR));
+        assert_noop!(
+            Sponsorship::register_sponsor(Origin::signed(USER), CONTRACT, 100),
+            Error::<Test>::NotInstantiator
+        );
+        assert_ok!(Sponsorship::register_sponsor(Origin::signed(SPONSOR), CONTRACT, 0));
+        assert_noop!(
+            Sponsorship::register_sponsor(Origin::signed(SPONSOR), CONTRACT, 100),
+            Error::<Test>::AlreadySponsored
+        );
+    });
+}
+
+#[test]
+fn only_the_sponsor_manages_a_sponsorship() {
+    new_test_ext().execute_with(|| {
+        assert_noop!(
+            Sponsorship::set_allowance(Origin::signed(SPONSOR), CONTRACT, USER, 10),
+            Error::<Test>::NotSponsored
+        );
+        sponsor_with_allowance(100, 10);
+        assert_noop!(
+            Sponsorship::set_daily_cap(Origin::signed(USER), CONTRACT, 1_000),
+            Error::<Test>::NotSponsor
+        );
+        assert_noop!(
+            Sponsorship::set_allowance(Origin::signed(USER), CONTRACT, USER, 10),
+            Error::<Test>::NotSponsor
+        );
+        assert_noop!(
+            Sponsorship::remove_sponsor(Origin::signed(USER), CONTRACT, 0),
+            Error::<Test>::NotSponsor
+        );
+
+        assert_ok!(Sponsorship::set_daily_cap(Origin::signed(SPONSOR), CONTRACT, 1_000));
+        assert_eq!(Sponsorship::sponsorship(CONTRACT).unwrap().daily_cap, 1_000);
+    });
+}
+
+#[test]
+fn remove_sponsor_clears_allowances() {
+    new_test_ext().execute_with(|| {
+        sponsor_with_allowance(100, 10);
+        assert_ok!(Sponsorship::set_allowance(Origin::signed(SPONSOR), CONTRACT, 4, 10));
+        assert_ok!(Sponsorship::set_allowance(Origin::signed(SPONSOR), CONTRACT, 4, 0));
+        assert_eq!(Sponsorship::sponsorship(CONTRACT).unwrap().allowances, 1);
+
+        assert_noop!(
+            Sponsorship::remove_sponsor(Origin::signed(SPONSOR), CONTRACT, 0),
+            Error::<Test>::BadWitness
+        );
+        assert_ok!(Sponsorship::remove_sponsor(Origin::signed(SPONSOR), CONTRACT, 1));
+        assert!(Sponsorship::sponsorship(CONTRACT).is_none());
+        assert_eq!(Sponsorship::allowance(CONTRACT, USER), 0);
+    });
+}
+
+#[test]
+fn sponsor_pays_for_calls_to_sponsored_contracts() {
+    new_test_ext().execute_with(|| {
+        sponsor_with_allowance(100, 50);
+        let fee = fee(5);
+
+        charge(USER, &contract_call(), 5, 5);
+
+        assert_eq!(Balances::free_balance(USER), 1_000);
+        assert_eq!(Balances::free_balance(SPONSOR), 1_000 - fee);
+        assert_eq!(Sponsorship::allowance(CONTRACT, USER), 50 - fee);
+        assert_eq!(Sponsorship::sponsorship(CONTRACT).unwrap().spent, fee);
+    });
+}
+
+#[test]
+fn unused_weight_is_refunded_to_allowance_and_cap() {
+    new_test_ext().execute_with(|| {
+        sponsor_with_allowance(100, 50);
+        let actual_fee = fee(2);
+
+        charge(USER, &contract_call(), 5, 2);
+
+        assert_eq!(Balances::free_balance(SPONSOR), 1_000 - actual_fee);
+        assert_eq!(Sponsorship::allowance(CONTRACT, USER), 50 - actual_fee);
+        assert_eq!(Sponsorship::sponsorship(CONTRACT).unwrap().spent, actual_fee);
+    });
+}
+
+#[test]
+fn sponsor_does_not_pay_the_tip() {
+    new_test_ext().execute_with(|| {
+        sponsor_with_allowance(100, 50);
+        let fee = fee(5);
+
+        // Validation withdraws the fee as well, so roll it back like the pool does.
+        let validity = with_transaction(|| {
+            TransactionOutcome::Rollback(
+                ChargeSponsoredTransactionPayment::<Test>::from(20).validate(&USER, &contract_call(), &info(5), LEN),
+            )
+        });
+        assert_eq!(validity.unwrap().priority, fee);
+        charge_with_tip(USER, &contract_call(), 5, 5, 20);
+
+        assert_eq!(Balances::free_balance(USER), 1_000);
+        assert_eq!(Balances::free_balance(SPONSOR), 1_000 - fee);
+        assert_eq!(Sponsorship::allowance(CONTRACT, USER), 50 - fee);
+        assert_eq!(Sponsorship::sponsorship(CONTRACT).unwrap().spent, fee);
+    });
+}
+
+#[test]
+fn caller_pays_without_allowance() {
+    new_test_ext().execute_with(|| {
+        sponsor_with_allowance(100, 1);
+        let fee = fee(5);
+
+        charge(USER, &contract_call(), 5, 5);
+
+        assert_eq!(Balances::free_balance(USER), 1_000 - fee);
+        assert_eq!(Balances::free_balance(SPONSOR), 1_000);
+        assert_eq!(Sponsorship::allowance(CONTRACT, USER), 1);
+    });
+}
+
+#[test]
+fn caller_pays_for_calls_that_cannot_be_sponsored() {
+    new_test_ext().execute_with(|| {
+        sponsor_with_allowance(100, 50);
+        let call = Call::System(frame_system::Call::remark(vec![]));
+        let fee = fee(5);
+
+        charge(USER, &call, 5, 5);
+
+        assert_eq!(Balances::free_balance(USER), 1_000 - fee);
+        assert_eq!(Balances::free_balance(SPONSOR), 1_000);
+    });
+}
+
+#[test]
+fn daily_cap_resets_every_period() {
+    new_test_ext().execute_with(|| {
+        let fee = fee(5);
+        sponsor_with_allowance(fee, 10 * fee);
+
+        charge(USER, &contract_call(), 5, 5);
+        assert_eq!(Balances::free_balance(SPONSOR), 1_000 - fee);
+
+        // Cap used up, the caller pays.
+        charge(USER, &contract_call(), 5, 5);
+        assert_eq!(Balances::free_balance(USER), 1_000 - fee);
+
+        System::set_block_number(1 + CapPeriod::get());
+        charge(USER, &contract_call(), 5, 5);
+        assert_eq!(Balances::free_balance(SPONSOR), 1_000 - 2 * fee);
+        assert_eq!(Sponsorship::sponsorship(CONTRACT).unwrap().spent, fee);
+    });
+}
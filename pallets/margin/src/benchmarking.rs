@@ -0,0 +1,150 @@
+// This file is part of Polkadex.
+
+// Copyright (C) 2020-2021 Polkadex oü.
+// SPDX-License-Identifier: GPL-3.0-or-later WITH Classpath-exception-2.0
+
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with this program. If not, see <https://www.gnu.org/licenses/>.
+//! Benchmarks for `pallet_margin`.
+
+#![cfg(feature = "runtime-benchmarks")]
+
+use super::*;
+use frame_benchmarking::{account, benchmarks, impl_benchmark_test_suite, whitelisted_caller};
+use frame_system::RawOrigin;
+
+const BASE: AssetId = AssetId::DOT;
+const QUOTE: AssetId = AssetId::USD;
+
+/// Amount of `asset` held by the position of `who`.
+fn held<T: Config>(who: &T::AccountId, asset: AssetId) -> T::Balance {
+    T::Currency::total_balance(asset, &Module::<T>::position_account(who, BASE, QUOTE))
+}
+
+/// Places `count` asks of the position of `who` that rest in the book.
+fn place_asks<T: Config>(who: &T::AccountId, count: u32) -> Result<(), &'static str> {
+    T::Orderbook::open_pair(TradingPair { base: BASE, quote: QUOTE });
+    for _ in 0..count {
+        Module::<T>::place_order(
+            RawOrigin::Signed(who.clone()).into(),
+            BASE,
+            QUOTE,
+            Side::Ask,
+            1u32.into(),
+            Some(FixedU128::saturating_from_integer(1_000)),
+            TimeInForce::GoodTillCancelled,
+        )?;
+    }
+    Ok(())
+}
+
+fn funded_account<T: Config>(name: &'static str) -> Result<T::AccountId, &'static str> {
+    let who = account(name, 0, 0);
+    T::Currency::deposit(BASE, &who, 1_000_000u32.into())?;
+    T::Currency::deposit(QUOTE, &who, 1_000_000u32.into())?;
+    Ok(who)
+}
+
+/// A position with 1_000 of the quote asset and 300 of the base asset borrowed at a price of 10.
+fn leveraged_position<T: Config>() -> Result<T::AccountId, &'static str> {
+    T::PriceProvider::set_price(BASE, FixedU128::saturating_from_integer(10));
+    T::PriceProvider::set_price(QUOTE, FixedU128::one());
+    let lender = funded_account::<T>("lender")?;
+    Module::<T>::supply(RawOrigin::Signed(lender).into(), BASE, 100_000u32.into())?;
+    let trader = funded_account::<T>("trader")?;
+    Module::<T>::deposit(RawOrigin::Signed(trader.clone()).into(), BASE, QUOTE, QUOTE, 1_000u32.into())?;
+    Module::<T>::borrow(RawOrigin::Signed(trader.clone()).into(), BASE, QUOTE, BASE, 300u32.into())?;
+    Ok(trader)
+}
+
+benchmarks! {
+    supply {
+        let caller = funded_account::<T>("caller")?;
+    }: _(RawOrigin::Signed(caller.clone()), BASE, 1_000u32.into())
+    verify {
+        assert!(!Module::<T>::pool_shares(BASE, &caller).is_zero());
+    }
+
+    redeem {
+        let caller = funded_account::<T>("caller")?;
+        Module::<T>::supply(RawOrigin::Signed(caller.clone()).into(), BASE, 1_000u32.into())?;
+        let shares = Module::<T>::pool_shares(BASE, &caller);
+    }: _(RawOrigin::Signed(caller.clone()), BASE, shares)
+    verify {
+        assert!(Module::<T>::pool_shares(BASE, &caller).is_zero());
+    }
+
+    deposit {
+        let caller = funded_account::<T>("caller")?;
+    }: _(RawOrigin::Signed(caller.clone()), BASE, QUOTE, QUOTE, 1_000u32.into())
+    verify {
+        assert_eq!(held::<T>(&caller, QUOTE), 1_000u32.into());
+    }
+
+    withdraw {
+        let caller = leveraged_position::<T>()?;
+    }: _(RawOrigin::Signed(caller.clone()), BASE, QUOTE, QUOTE, 100u32.into())
+    verify {
+        assert_eq!(held::<T>(&caller, QUOTE), 900u32.into());
+    }
+
+    borrow {
+        let caller = leveraged_position::<T>()?;
+    }: _(RawOrigin::Signed(caller.clone()), BASE, QUOTE, BASE, 100u32.into())
+    verify {
+        assert_eq!(held::<T>(&caller, BASE), 400u32.into());
+    }
+
+    repay {
+        let caller = leveraged_position::<T>()?;
+    }: _(RawOrigin::Signed(caller.clone()), BASE, QUOTE, BASE, 300u32.into())
+    verify {
+        assert!(Module::<T>::position(&caller, (BASE, QUOTE)).base_debt.is_zero());
+    }
+
+    place_order {
+        let caller = leveraged_position::<T>()?;
+        place_asks::<T>(&caller, T::MaxPositionOrders::get() - 1)?;
+        let limit = Some(FixedU128::saturating_from_integer(1_000));
+    }: _(RawOrigin::Signed(caller.clone()), BASE, QUOTE, Side::Ask, 1u32.into(), limit, TimeInForce::GoodTillCancelled)
+    verify {
+        assert_eq!(Module::<T>::position(&caller, (BASE, QUOTE)).orders.len() as u32, T::MaxPositionOrders::get());
+    }
+
+    cancel_order {
+        let caller = leveraged_position::<T>()?;
+        place_asks::<T>(&caller, T::MaxPositionOrders::get())?;
+        let id = *Module::<T>::position(&caller, (BASE, QUOTE)).orders.last().unwrap();
+    }: _(RawOrigin::Signed(caller.clone()), BASE, QUOTE, id)
+    verify {
+        assert!(!T::Orderbook::is_open(id));
+    }
+
+    liquidate {
+        let o in 0 .. T::MaxPositionOrders::get();
+        let trader = leveraged_position::<T>()?;
+        place_asks::<T>(&trader, o)?;
+        T::PriceProvider::set_price(BASE, FixedU128::saturating_from_integer(100));
+        let caller: T::AccountId = whitelisted_caller();
+        T::Currency::deposit(BASE, &caller, 1_000u32.into())?;
+    }: _(RawOrigin::Signed(caller), trader.clone(), BASE, QUOTE)
+    verify {
+        assert!(!Positions::<T>::contains_key(&trader, (BASE, QUOTE)));
+    }
+}
+
+impl_benchmark_test_suite!(
+    Module,
+    crate::mock::new_test_ext(),
+    crate::mock::Test,
+);
@@ -0,0 +1,617 @@
+// This file is part of Polkadex.
+
+// Copyright (C) 2020-2021 Polkadex oü.
+// SPDX-License-Identifier: GPL-3.0-or-later WITH Classpath-exception-2.0
+
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with this program. If not, see <https://www.gnu.org/licenses/>.
+//! # Margin pallet
+//!
+//! Isolated margin positions on spot pairs, borrowing from lending pools.
+//!
+//! - Lenders supply an asset to its lending pool with [`Call::supply`] and get shares of the
+//!   pool, which they redeem with [`Call::redeem`]. Borrowers pay [`Config::BorrowRate`] of
+//!   their debt per block, which adds to the value of the shares.
+//! - An account has an isolated position per trading pair, holding the base and the quote asset
+//!   in an account of its own and owing debts in both. Traders deposit collateral with
+//!   [`Call::deposit`], borrow from the pools with [`Call::borrow`], pay debts back with
+//!   [`Call::repay`] and withdraw with [`Call::withdraw`]. A position only ever risks its own
+//!   collateral.
+//! - Positions trade their assets in the order books of [`Config::Orderbook`] with
+//!   [`Call::place_order`] and [`Call::cancel_order`]. Fills are settled into the account of
+//!   the position, and what its open orders reserve still counts as its assets.
+//! - Assets and debts are valued at the prices of [`Config::PriceProvider`]. The health of a
+//!   position is the value of its assets over the value of its debts. Borrowing, withdrawing
+//!   and placing orders must leave a health of at least [`Config::InitialMarginRatio`].
+//! - Anyone can liquidate a position whose health fell below
+//!   [`Config::MaintenanceMarginRatio`] with [`Call::liquidate`]. Its open orders are
+//!   cancelled, the liquidator repays its debts and receives its assets worth the debts plus
+//!   [`Config::LiquidationReward`], and the rest of the assets go back to the owner. When the
+//!   assets are worth less than that, the insurance fund pays the difference in the quote
+//!   asset, as far as it can.
+//!
+//! Pools are held by the account derived from [`Config::PalletId`], each position by a sub
+//! account of it, and the insurance fund by the account derived from
+//! [`Config::InsuranceFundId`].
+
+#![cfg_attr(not(feature = "std"), no_std)]
+
+use codec::{Decode, Encode};
+use frame_support::{
+    decl_error, decl_event, decl_module, decl_storage,
+    dispatch::DispatchResult,
+    ensure,
+    traits::Get,
+    transactional,
+    weights::Weight,
+    Parameter, PalletId, StorageDoubleMap, StorageMap,
+};
+use frame_system::ensure_signed;
+use orml_traits::{MultiCurrency, MultiReservableCurrency};
+use pallet_orderbook::{OrderId, OrderManager, Side, TimeInForce, TradingPair};
+use pallet_price_oracle::PriceProvider;
+use polkadex_primitives::assets::AssetId;
+use sp_runtime::{
+    traits::{
+        AccountIdConversion, AtLeast32BitUnsigned, Bounded, CheckedAdd, CheckedSub, Hash,
+        MaybeSerializeDeserialize, Member, One, Saturating, UniqueSaturatedInto, Zero,
+    },
+    DispatchError, FixedPointNumber, FixedPointOperand, FixedU128, Permill, RuntimeDebug,
+};
+use sp_std::prelude::*;
+
+pub use weights::WeightInfo;
+
+#[cfg(feature = "runtime-benchmarks")]
+mod benchmarking;
+#[cfg(test)]
+mod mock;
+#[cfg(test)]
+mod tests;
+pub mod weights;
+
+/// Shares a pool starts with that belong to no lender.
+///
+/// Together with [`VIRTUAL_VALUE`] they price the first shares, so that a first lender cannot
+/// make a share worth so much that the shares of the next lenders round down to nothing.
+const VIRTUAL_SHARES: u32 = 1_000_000;
+/// Value a pool starts with that belongs to no lender.
+const VIRTUAL_VALUE: u32 = 1;
+
+/// The lending pool of an asset.
+#[derive(Encode, Decode, Clone, PartialEq, Eq, RuntimeDebug)]
+pub struct Pool<Balance, BlockNumber> {
+    /// Amount in the pool, available to borrow or redeem.
+    pub cash: Balance,
+    /// Amount lent, including the interest accrued on it.
+    pub borrowed: Balance,
+    /// Total shares of the lenders in `cash + borrowed`.
+    pub shares: Balance,
+    /// Debt owed per unit of scaled debt; grows with the interest.
+    pub borrow_index: FixedU128,
+    /// Block the interest was last accrued at.
+    pub accrued_at: BlockNumber,
+}
+
+impl<Balance: Zero, BlockNumber> Pool<Balance, BlockNumber> {
+    fn new(now: BlockNumber) -> Self {
+        Pool {
+            cash: Zero::zero(),
+            borrowed: Zero::zero(),
+            shares: Zero::zero(),
+            borrow_index: FixedU128::one(),
+            accrued_at: now,
+        }
+    }
+}
+
+/// The debts and orders of an isolated margin position in a trading pair.
+///
+/// The assets of the position are held by its own account.
+#[derive(Encode, Decode, Clone, Default, PartialEq, Eq, RuntimeDebug)]
+pub struct Position<Balance> {
+    /// Debt in the base asset, scaled down by the borrow index of its pool.
+    pub base_debt: Balance,
+    /// Debt in the quote asset, scaled down by the borrow index of its pool.
+    pub quote_debt: Balance,
+    /// Orders of the position in the order book, open when they were last looked at.
+    pub orders: Vec<OrderId>,
+}
+
+impl<Balance> Position<Balance> {
+    /// The scaled debt in the base asset if `is_base`, or else in the quote asset.
+    fn debt_mut(&mut self, is_base: bool) -> &mut Balance {
+        if is_base {
+            &mut self.base_debt
+        } else {
+            &mut self.quote_debt
+        }
+    }
+}
+
+pub type PoolOf<T> = Pool<<T as Config>::Balance, <T as frame_system::Config>::BlockNumber>;
+pub type PositionOf<T> = Position<<T as Config>::Balance>;
+
+/// Configure the pallet by specifying the parameters and types on which it depends.
+pub trait Config: frame_system::Config {
+    /// Because this pallet emits events, it depends on the runtime's definition of an event.
+    type Event: From<Event<Self>> + Into<<Self as frame_system::Config>::Event>;
+    /// Balance of the lent and traded assets.
+    type Balance: Parameter
+        + Member
+        + AtLeast32BitUnsigned
+        + FixedPointOperand
+        + Default
+        + Copy
+        + MaybeSerializeDeserialize;
+    /// Assets lent and held by positions.
+    type Currency: MultiReservableCurrency<Self::AccountId, CurrencyId = AssetId, Balance = Self::Balance>;
+    /// Order books positions trade in.
+    type Orderbook: OrderManager<Self::AccountId, Self::Balance, Self::BlockNumber>;
+    /// Prices assets and debts are valued at, in a common unit.
+    type PriceProvider: PriceProvider;
+    /// Identifies the account holding the pools and the positions.
+    type PalletId: Get<PalletId>;
+    /// Identifies the account of the insurance fund.
+    type InsuranceFundId: Get<PalletId>;
+    /// Interest on borrowed assets per block.
+    type BorrowRate: Get<FixedU128>;
+    /// Health a position must keep when borrowing or withdrawing.
+    type InitialMarginRatio: Get<FixedU128>;
+    /// Health below which a position can be liquidated.
+    type MaintenanceMarginRatio: Get<FixedU128>;
+    /// Reward of liquidators, as a part of the debts they repay.
+    type LiquidationReward: Get<Permill>;
+    /// Maximum number of open orders of a position.
+    type MaxPositionOrders: Get<u32>;
+    /// Weight information for extrinsics in this pallet.
+    type WeightInfo: WeightInfo;
+}
+
+decl_storage! {
+    trait Store for Module<T: Config> as Margin {
+        /// Lending pool of each asset, as of the block its interest was last accrued at.
+        pub Pools get(fn pool): map hasher(twox_64_concat) AssetId => Option<PoolOf<T>>;
+        /// Shares of each lender in each pool.
+        pub PoolShares get(fn pool_shares):
+            double_map hasher(twox_64_concat) AssetId, hasher(blake2_128_concat) T::AccountId => T::Balance;
+        /// Position of each account in each `(base, quote)` pair.
+        pub Positions get(fn position):
+            double_map hasher(blake2_128_concat) T::AccountId, hasher(twox_64_concat) (AssetId, AssetId)
+            => PositionOf<T>;
+    }
+}
+
+decl_event!(
+    pub enum Event<T>
+    where
+        AccountId = <T as frame_system::Config>::AccountId,
+        Balance = <T as Config>::Balance,
+    {
+        /// Assets were supplied to a pool. \[who, asset, amount, shares\]
+        Supplied(AccountId, AssetId, Balance, Balance),
+        /// Pool shares were redeemed. \[who, asset, amount, shares\]
+        Redeemed(AccountId, AssetId, Balance, Balance),
+        /// Collateral was deposited into a position. \[who, base, quote, asset, amount\]
+        Deposited(AccountId, AssetId, AssetId, AssetId, Balance),
+        /// Assets were withdrawn from a position. \[who, base, quote, asset, amount\]
+        Withdrawn(AccountId, AssetId, AssetId, AssetId, Balance),
+        /// A position borrowed from a pool. \[who, base, quote, asset, amount\]
+        Borrowed(AccountId, AssetId, AssetId, AssetId, Balance),
+        /// A position repaid a debt. \[who, base, quote, asset, amount\]
+        Repaid(AccountId, AssetId, AssetId, AssetId, Balance),
+        /// A position placed an order. \[who, base, quote, id\]
+        OrderPlaced(AccountId, AssetId, AssetId, OrderId),
+        /// A position cancelled an order. \[who, base, quote, id\]
+        OrderCancelled(AccountId, AssetId, AssetId, OrderId),
+        /// A position was liquidated. \[who, base, quote, liquidator\]
+        Liquidated(AccountId, AssetId, AssetId, AccountId),
+    }
+);
+
+decl_error! {
+    pub enum Error for Module<T: Config> {
+        /// The base and quote asset are the same.
+        InvalidPair,
+        /// The asset is neither the base nor the quote asset of the pair.
+        AssetNotInPair,
+        /// The amount is zero.
+        ZeroAmount,
+        /// The pool does not have enough cash.
+        InsufficientLiquidity,
+        /// The lender does not have enough shares.
+        InsufficientShares,
+        /// The position does not hold enough of the asset.
+        InsufficientBalance,
+        /// The position would be left below the initial margin ratio.
+        InsufficientMargin,
+        /// There is no price for an asset of the position.
+        PriceUnavailable,
+        /// The position is above the maintenance margin ratio.
+        PositionHealthy,
+        /// The position has the maximum number of open orders.
+        TooManyOrders,
+        /// The order is not an open order of the position.
+        UnknownOrder,
+        /// An amount does not fit in a balance.
+        Overflow,
+    }
+}
+
+decl_module! {
+    pub struct Module<T: Config> for enum Call where origin: T::Origin {
+        type Error = Error<T>;
+
+        fn deposit_event() = default;
+
+        /// Supply `amount` of `asset` to its lending pool for pool shares.
+        #[weight = <T as Config>::WeightInfo::supply()]
+        pub fn supply(origin, asset: AssetId, amount: T::Balance) -> DispatchResult {
+            let who = ensure_signed(origin)?;
+            ensure!(!amount.is_zero(), Error::<T>::ZeroAmount);
+            let mut pool = Self::accrued_pool(asset);
+            let (value, shares) = Self::pool_totals(&pool);
+            let shares = mul_div(amount, shares, value).ok_or(Error::<T>::Overflow)?;
+            ensure!(!shares.is_zero(), Error::<T>::ZeroAmount);
+
+            T::Currency::transfer(asset, &who, &Self::account_id(), amount)?;
+            pool.cash = pool.cash.checked_add(&amount).ok_or(Error::<T>::Overflow)?;
+            pool.shares = pool.shares.checked_add(&shares).ok_or(Error::<T>::Overflow)?;
+            Pools::<T>::insert(asset, pool);
+            PoolShares::<T>::mutate(asset, &who, |held| *held = held.saturating_add(shares));
+            Self::deposit_event(RawEvent::Supplied(who, asset, amount, shares));
+            Ok(())
+        }
+
+        /// Redeem `shares` of the lending pool of `asset` for their part of the pool.
+        #[weight = <T as Config>::WeightInfo::redeem()]
+        pub fn redeem(origin, asset: AssetId, shares: T::Balance) -> DispatchResult {
+            let who = ensure_signed(origin)?;
+            ensure!(!shares.is_zero(), Error::<T>::ZeroAmount);
+            let held = Self::pool_shares(asset, &who)
+                .checked_sub(&shares)
+                .ok_or(Error::<T>::InsufficientShares)?;
+            let mut pool = Self::accrued_pool(asset);
+            let (value, total_shares) = Self::pool_totals(&pool);
+            let amount = mul_div(shares, value, total_shares).ok_or(Error::<T>::Overflow)?;
+            pool.cash = pool.cash.checked_sub(&amount).ok_or(Error::<T>::InsufficientLiquidity)?;
+            pool.shares = pool.shares.saturating_sub(shares);
+
+            T::Currency::transfer(asset, &Self::account_id(), &who, amount)?;
+            Pools::<T>::insert(asset, pool);
+            if held.is_zero() {
+                PoolShares::<T>::remove(asset, &who);
+            } else {
+                PoolShares::<T>::insert(asset, &who, held);
+            }
+            Self::deposit_event(RawEvent::Redeemed(who, asset, amount, shares));
+            Ok(())
+        }
+
+        /// Deposit `amount` of `asset` into the caller's position in the `base`/`quote` pair.
+        #[weight = <T as Config>::WeightInfo::deposit()]
+        pub fn deposit(origin, base: AssetId, quote: AssetId, asset: AssetId, amount: T::Balance) -> DispatchResult {
+            let who = ensure_signed(origin)?;
+            ensure!(!amount.is_zero(), Error::<T>::ZeroAmount);
+            Self::is_base(base, quote, asset)?;
+
+            T::Currency::transfer(asset, &who, &Self::position_account(&who, base, quote), amount)?;
+            Self::deposit_event(RawEvent::Deposited(who, base, quote, asset, amount));
+            Ok(())
+        }
+
+        /// Withdraw `amount` of `asset` from the caller's position in the `base`/`quote` pair,
+        /// keeping the position above the initial margin ratio.
+        #[weight = <T as Config>::WeightInfo::withdraw()]
+        #[transactional]
+        pub fn withdraw(origin, base: AssetId, quote: AssetId, asset: AssetId, amount: T::Balance) -> DispatchResult {
+            let who = ensure_signed(origin)?;
+            ensure!(!amount.is_zero(), Error::<T>::ZeroAmount);
+            Self::is_base(base, quote, asset)?;
+            let account = Self::position_account(&who, base, quote);
+            ensure!(T::Currency::free_balance(asset, &account) >= amount, Error::<T>::InsufficientBalance);
+
+            T::Currency::transfer(asset, &account, &who, amount)?;
+            Self::ensure_margin(&who, base, quote, &Self::position(&who, (base, quote)))?;
+            Self::deposit_event(RawEvent::Withdrawn(who, base, quote, asset, amount));
+            Ok(())
+        }
+
+        /// Borrow `amount` of `asset` from its lending pool into the caller's position in the
+        /// `base`/`quote` pair, keeping the position above the initial margin ratio.
+        #[weight = <T as Config>::WeightInfo::borrow()]
+        #[transactional]
+        pub fn borrow(origin, base: AssetId, quote: AssetId, asset: AssetId, amount: T::Balance) -> DispatchResult {
+            let who = ensure_signed(origin)?;
+            ensure!(!amount.is_zero(), Error::<T>::ZeroAmount);
+            let is_base = Self::is_base(base, quote, asset)?;
+            let mut pool = Self::accrued_pool(asset);
+            pool.cash = pool.cash.checked_sub(&amount).ok_or(Error::<T>::InsufficientLiquidity)?;
+            pool.borrowed = pool.borrowed.checked_add(&amount).ok_or(Error::<T>::Overflow)?;
+            let mut position = Self::position(&who, (base, quote));
+            let debt = position.debt_mut(is_base);
+            *debt = debt
+                .checked_add(&scale_up(amount, pool.borrow_index))
+                .ok_or(Error::<T>::Overflow)?;
+            Pools::<T>::insert(asset, pool);
+
+            T::Currency::transfer(asset, &Self::account_id(), &Self::position_account(&who, base, quote), amount)?;
+            Self::ensure_margin(&who, base, quote, &position)?;
+            Positions::<T>::insert(&who, (base, quote), position);
+            Self::deposit_event(RawEvent::Borrowed(who, base, quote, asset, amount));
+            Ok(())
+        }
+
+        /// Repay up to `amount` of the debt in `asset` of the caller's position in the
+        /// `base`/`quote` pair with the assets of the position.
+        #[weight = <T as Config>::WeightInfo::repay()]
+        pub fn repay(origin, base: AssetId, quote: AssetId, asset: AssetId, amount: T::Balance) -> DispatchResult {
+            let who = ensure_signed(origin)?;
+            let is_base = Self::is_base(base, quote, asset)?;
+            let account = Self::position_account(&who, base, quote);
+            let mut pool = Self::accrued_pool(asset);
+            let mut position = Self::position(&who, (base, quote));
+            let debt = position.debt_mut(is_base);
+            let owed = pool.borrow_index.saturating_mul_int(*debt);
+            let repaid = amount.min(owed).min(T::Currency::free_balance(asset, &account));
+            ensure!(!repaid.is_zero(), Error::<T>::ZeroAmount);
+            *debt = if repaid == owed {
+                Zero::zero()
+            } else {
+                debt.saturating_sub(scale_down(repaid, pool.borrow_index))
+            };
+            pool.cash = pool.cash.checked_add(&repaid).ok_or(Error::<T>::Overflow)?;
+            pool.borrowed = pool.borrowed.saturating_sub(repaid);
+
+            T::Currency::transfer(asset, &account, &Self::account_id(), repaid)?;
+            Pools::<T>::insert(asset, pool);
+            Self::store_position(&who, base, quote, position);
+            Self::deposit_event(RawEvent::Repaid(who, base, quote, asset, repaid));
+            Ok(())
+        }
+
+        /// Place an order for `amount` of `base` with the assets of the caller's position in the
+        /// `base`/`quote` pair, keeping the position above the initial margin ratio.
+        ///
+        /// Without a `limit` price the order is a market order.
+        #[weight = <T as Config>::WeightInfo::place_order()
+            .saturating_add(T::Orderbook::place_order_weight())]
+        #[transactional]
+        pub fn place_order(
+            origin,
+            base: AssetId,
+            quote: AssetId,
+            side: Side,
+            amount: T::Balance,
+            limit: Option<FixedU128>,
+            time_in_force: TimeInForce<T::BlockNumber>,
+        ) -> DispatchResult {
+            let who = ensure_signed(origin)?;
+            ensure!(base != quote, Error::<T>::InvalidPair);
+            let mut position = Self::position(&who, (base, quote));
+            position.orders.retain(|id| T::Orderbook::is_open(*id));
+            ensure!(
+                (position.orders.len() as u32) < T::MaxPositionOrders::get(),
+                Error::<T>::TooManyOrders
+            );
+
+            let account = Self::position_account(&who, base, quote);
+            let id = T::Orderbook::place_order(&account, TradingPair { base, quote }, side, amount, limit, time_in_force)?;
+            if T::Orderbook::is_open(id) {
+                position.orders.push(id);
+            }
+            Self::ensure_margin(&who, base, quote, &position)?;
+            Self::store_position(&who, base, quote, position);
+            Self::deposit_event(RawEvent::OrderPlaced(who, base, quote, id));
+            Ok(())
+        }
+
+        /// Cancel the open order `id` of the caller's position in the `base`/`quote` pair.
+        #[weight = <T as Config>::WeightInfo::cancel_order()
+            .saturating_add(T::Orderbook::cancel_order_weight())]
+        pub fn cancel_order(origin, base: AssetId, quote: AssetId, id: OrderId) -> DispatchResult {
+            let who = ensure_signed(origin)?;
+            let mut position = Self::position(&who, (base, quote));
+            let index = position.orders.iter().position(|order| *order == id).ok_or(Error::<T>::UnknownOrder)?;
+            ensure!(T::Orderbook::is_open(id), Error::<T>::UnknownOrder);
+
+            T::Orderbook::cancel_order(&Self::position_account(&who, base, quote), id)?;
+            position.orders.remove(index);
+            Self::store_position(&who, base, quote, position);
+            Self::deposit_event(RawEvent::OrderCancelled(who, base, quote, id));
+            Ok(())
+        }
+
+        /// Liquidate the position of `who` in the `base`/`quote` pair, which must be below the
+        /// maintenance margin ratio.
+        ///
+        /// The open orders of the position are cancelled. The caller repays the debts of the
+        /// position and receives its assets worth the debts plus the liquidation reward; the
+        /// rest of the assets go back to `who`.
+        #[weight = <T as Config>::WeightInfo::liquidate(T::MaxPositionOrders::get())
+            .saturating_add(T::Orderbook::cancel_order_weight().saturating_mul(T::MaxPositionOrders::get() as Weight))]
+        #[transactional]
+        pub fn liquidate(origin, who: T::AccountId, base: AssetId, quote: AssetId) -> DispatchResult {
+            let liquidator = ensure_signed(origin)?;
+            let position = Self::position(&who, (base, quote));
+            let (assets, debts) = Self::values(&who, base, quote, &position)?;
+            ensure!(
+                !debts.is_zero() && T::MaintenanceMarginRatio::get().saturating_mul_int(debts) > assets,
+                Error::<T>::PositionHealthy
+            );
+            let account = Self::position_account(&who, base, quote);
+            for id in position.orders.iter().filter(|id| T::Orderbook::is_open(**id)) {
+                T::Orderbook::cancel_order(&account, *id)?;
+            }
+
+            for (asset, debt) in [(base, position.base_debt), (quote, position.quote_debt)].iter() {
+                let mut pool = Self::accrued_pool(*asset);
+                let owed = pool.borrow_index.saturating_mul_int(*debt);
+                T::Currency::transfer(*asset, &liquidator, &Self::account_id(), owed)?;
+                pool.cash = pool.cash.checked_add(&owed).ok_or(Error::<T>::Overflow)?;
+                pool.borrowed = pool.borrowed.saturating_sub(owed);
+                Pools::<T>::insert(asset, pool);
+            }
+
+            let claim = debts.saturating_add(T::LiquidationReward::get() * debts);
+            let seized = FixedU128::checked_from_rational(claim, assets)
+                .map_or(FixedU128::one(), |seized| seized.min(FixedU128::one()));
+            for asset in [base, quote].iter() {
+                let held = T::Currency::free_balance(*asset, &account);
+                let to_liquidator = seized.saturating_mul_int(held);
+                T::Currency::transfer(*asset, &account, &liquidator, to_liquidator)?;
+                T::Currency::transfer(*asset, &account, &who, held.saturating_sub(to_liquidator))?;
+            }
+            if claim > assets {
+                let insurance_fund = Self::insurance_fund_id();
+                let price = T::PriceProvider::price(quote).ok_or(Error::<T>::PriceUnavailable)?;
+                let shortfall = price
+                    .reciprocal()
+                    .map_or(Zero::zero(), |reciprocal| reciprocal.saturating_mul_int(claim - assets))
+                    .min(T::Currency::free_balance(quote, &insurance_fund));
+                T::Currency::transfer(quote, &insurance_fund, &liquidator, shortfall)?;
+            }
+
+            Positions::<T>::remove(&who, (base, quote));
+            Self::deposit_event(RawEvent::Liquidated(who, base, quote, liquidator));
+            Ok(())
+        }
+    }
+}
+
+impl<T: Config> Module<T> {
+    /// Account holding the pools.
+    pub fn account_id() -> T::AccountId {
+        T::PalletId::get().into_account()
+    }
+
+    /// Account holding the assets of the position of `who` in the `base`/`quote` pair.
+    pub fn position_account(who: &T::AccountId, base: AssetId, quote: AssetId) -> T::AccountId {
+        T::PalletId::get().into_sub_account(T::Hashing::hash_of(&(who, base, quote)))
+    }
+
+    /// Account of the insurance fund.
+    pub fn insurance_fund_id() -> T::AccountId {
+        T::InsuranceFundId::get().into_account()
+    }
+
+    /// Health of the position of `who` in the `base`/`quote` pair: the value of its assets over
+    /// the value of its debts, or `None` without debts.
+    pub fn health(who: &T::AccountId, base: AssetId, quote: AssetId) -> Result<Option<FixedU128>, DispatchError> {
+        let (assets, debts) = Self::values(who, base, quote, &Self::position(who, (base, quote)))?;
+        if debts.is_zero() {
+            return Ok(None);
+        }
+        Ok(Some(FixedU128::checked_from_rational(assets, debts).unwrap_or_else(Bounded::max_value)))
+    }
+
+    /// The pool of `asset` with its interest accrued up to the current block.
+    fn accrued_pool(asset: AssetId) -> PoolOf<T> {
+        let now = frame_system::Pallet::<T>::block_number();
+        let mut pool = Self::pool(asset).unwrap_or_else(|| Pool::new(now));
+        let blocks: u32 = now.saturating_sub(pool.accrued_at).unique_saturated_into();
+        if blocks > 0 {
+            let factor = FixedU128::one()
+                .saturating_add(T::BorrowRate::get().saturating_mul(FixedU128::saturating_from_integer(blocks)));
+            pool.borrowed = factor.saturating_mul_int(pool.borrowed);
+            pool.borrow_index = pool.borrow_index.saturating_mul(factor);
+            pool.accrued_at = now;
+        }
+        pool
+    }
+
+    /// Value and shares of `pool`, including the virtual ones.
+    fn pool_totals(pool: &PoolOf<T>) -> (T::Balance, T::Balance) {
+        let value = pool
+            .cash
+            .saturating_add(pool.borrowed)
+            .saturating_add(VIRTUAL_VALUE.into());
+        (value, pool.shares.saturating_add(VIRTUAL_SHARES.into()))
+    }
+
+    /// Whether `asset` is the base asset of the `base`/`quote` pair rather than the quote asset.
+    fn is_base(base: AssetId, quote: AssetId, asset: AssetId) -> Result<bool, DispatchError> {
+        ensure!(base != quote, Error::<T>::InvalidPair);
+        ensure!(asset == base || asset == quote, Error::<T>::AssetNotInPair);
+        Ok(asset == base)
+    }
+
+    /// Value of `amount` of `asset`.
+    fn value(asset: AssetId, amount: T::Balance) -> Result<T::Balance, DispatchError> {
+        if amount.is_zero() {
+            return Ok(Zero::zero());
+        }
+        let price = T::PriceProvider::price(asset).ok_or(Error::<T>::PriceUnavailable)?;
+        Ok(price.saturating_mul_int(amount))
+    }
+
+    /// Values of the assets and of the debts of the `position` of `who` in the `base`/`quote`
+    /// pair. The assets include what its open orders reserve.
+    fn values(
+        who: &T::AccountId,
+        base: AssetId,
+        quote: AssetId,
+        position: &PositionOf<T>,
+    ) -> Result<(T::Balance, T::Balance), DispatchError> {
+        let account = Self::position_account(who, base, quote);
+        let held = |asset| T::Currency::total_balance(asset, &account);
+        let debt = |asset, scaled| Self::accrued_pool(asset).borrow_index.saturating_mul_int(scaled);
+        let assets = Self::value(base, held(base))?.saturating_add(Self::value(quote, held(quote))?);
+        let debts = Self::value(base, debt(base, position.base_debt))?
+            .saturating_add(Self::value(quote, debt(quote, position.quote_debt))?);
+        Ok((assets, debts))
+    }
+
+    /// Ensures the `position` of `who` in the `base`/`quote` pair is at least at the initial
+    /// margin ratio.
+    fn ensure_margin(who: &T::AccountId, base: AssetId, quote: AssetId, position: &PositionOf<T>) -> DispatchResult {
+        if position.base_debt.is_zero() && position.quote_debt.is_zero() {
+            return Ok(());
+        }
+        let (assets, debts) = Self::values(who, base, quote, position)?;
+        ensure!(
+            T::InitialMarginRatio::get().saturating_mul_int(debts) <= assets,
+            Error::<T>::InsufficientMargin
+        );
+        Ok(())
+    }
+
+    /// Stores `position`, removing it once it has no debts and no orders.
+    fn store_position(who: &T::AccountId, base: AssetId, quote: AssetId, position: PositionOf<T>) {
+        if position == Default::default() {
+            Positions::<T>::remove(who, (base, quote));
+        } else {
+            Positions::<T>::insert(who, (base, quote), position);
+        }
+    }
+}
+
+/// `amount * numerator / denominator`, rounded down.
+fn mul_div<B: FixedPointOperand>(amount: B, numerator: B, denominator: B) -> Option<B> {
+    FixedU128::checked_from_rational(numerator, denominator)?.checked_mul_int(amount)
+}
+
+/// Scaled debt of `amount` at `index`, rounded up so that debts are never understated.
+fn scale_up<B: FixedPointOperand + Saturating + One>(amount: B, index: FixedU128) -> B {
+    let scaled = scale_down(amount, index);
+    if index.saturating_mul_int(scaled) < amount {
+        scaled.saturating_add(One::one())
+    } else {
+        scaled
+    }
+}
+
+/// Scaled debt of `amount` at `index`, rounded down.
+fn scale_down<B: FixedPointOperand>(amount: B, index: FixedU128) -> B {
+    index.reciprocal().map_or(amount, |reciprocal| reciprocal.saturating_mul_int(amount))
+}
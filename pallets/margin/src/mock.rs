@@ -0,0 +1,250 @@
+// This file is part of Polkadex.
+
+// Copyright (C) 2020-2021 Polkadex oü.
+// SPDX-License-Identifier: GPL-3.0-or-later WITH Classpath-exception-2.0
+
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with this program. If not, see <https://www.gnu.org/licenses/>.
+//! Test environment for the margin pallet.
+
+use crate::{self as pallet_margin};
+use frame_support::{dispatch::DispatchResult, parameter_types, traits::GenesisBuild, weights::Weight, PalletId};
+use orml_traits::{parameter_type_with_key, MultiReservableCurrency};
+use pallet_orderbook::{OrderId, OrderManager, Side, TimeInForce, TradingPair};
+use pallet_price_oracle::PriceProvider;
+use polkadex_primitives::assets::AssetId;
+use sp_core::H256;
+use sp_runtime::{
+    testing::Header,
+    traits::{BlakeTwo256, IdentityLookup},
+    DispatchError, FixedPointNumber, FixedU128, Permill,
+};
+use std::cell::RefCell;
+
+type UncheckedExtrinsic = frame_system::mocking::MockUncheckedExtrinsic<Test>;
+type Block = frame_system::mocking::MockBlock<Test>;
+
+frame_support::construct_runtime!(
+    pub enum Test where
+        Block = Block,
+        NodeBlock = Block,
+        UncheckedExtrinsic = UncheckedExtrinsic,
+    {
+        System: frame_system::{Pallet, Call, Config, Storage, Event<T>},
+        Tokens: orml_tokens::{Pallet, Storage, Event<T>, Config<T>},
+        Margin: pallet_margin::{Pallet, Call, Storage, Event<T>},
+    }
+);
+
+parameter_types! {
+    pub const BlockHashCount: u64 = 250;
+}
+
+impl frame_system::Config for Test {
+    type BaseCallFilter = ();
+    type BlockWeights = ();
+    type BlockLength = ();
+    type DbWeight = ();
+    type Origin = Origin;
+    type Call = Call;
+    type Index = u64;
+    type BlockNumber = u64;
+    type Hash = H256;
+    type Hashing = BlakeTwo256;
+    type AccountId = u64;
+    type Lookup = IdentityLookup<Self::AccountId>;
+    type Header = Header;
+    type Event = Event;
+    type BlockHashCount = BlockHashCount;
+    type Version = ();
+    type PalletInfo = PalletInfo;
+    type AccountData = ();
+    type OnNewAccount = ();
+    type OnKilledAccount = ();
+    type SystemWeightInfo = ();
+    type SS58Prefix = ();
+    type OnSetCode = ();
+}
+
+parameter_type_with_key! {
+    pub ExistentialDeposits: |_currency_id: AssetId| -> u64 {
+        0
+    };
+}
+
+impl orml_tokens::Config for Test {
+    type Event = Event;
+    type Balance = u64;
+    type Amount = i64;
+    type CurrencyId = AssetId;
+    type WeightInfo = ();
+    type ExistentialDeposits = ExistentialDeposits;
+    type OnDust = ();
+}
+
+thread_local! {
+    static PRICES: RefCell<Vec<(AssetId, FixedU128)>> = RefCell::new(Vec::new());
+}
+
+/// Prices set by the tests.
+pub struct MockPrices;
+
+impl PriceProvider for MockPrices {
+    fn price(asset: AssetId) -> Option<FixedU128> {
+        PRICES.with(|prices| {
+            prices
+                .borrow()
+                .iter()
+                .find(|(priced, _)| *priced == asset)
+                .map(|(_, price)| *price)
+        })
+    }
+
+    #[cfg(feature = "runtime-benchmarks")]
+    fn set_price(asset: AssetId, price: FixedU128) {
+        store_price(asset, price);
+    }
+}
+
+fn store_price(asset: AssetId, price: FixedU128) {
+    PRICES.with(|prices| {
+        let mut prices = prices.borrow_mut();
+        prices.retain(|(priced, _)| *priced != asset);
+        prices.push((asset, price));
+    });
+}
+
+pub fn set_price(asset: AssetId, price: u64) {
+    store_price(asset, FixedU128::saturating_from_integer(price));
+}
+
+thread_local! {
+    static ORDERS: RefCell<Vec<MockOrder>> = RefCell::new(Vec::new());
+}
+
+/// An open order of [`MockOrderbook`].
+#[derive(Clone)]
+struct MockOrder {
+    id: OrderId,
+    owner: u64,
+    asset: AssetId,
+    reserved: u64,
+}
+
+/// An order book where limit orders rest until cancelled, reserving what they would pay.
+pub struct MockOrderbook;
+
+impl OrderManager<u64, u64, u64> for MockOrderbook {
+    fn place_order(
+        owner: &u64,
+        pair: TradingPair,
+        side: Side,
+        amount: u64,
+        limit: Option<FixedU128>,
+        _time_in_force: TimeInForce<u64>,
+    ) -> Result<OrderId, DispatchError> {
+        let limit = limit.ok_or(DispatchError::Other("market orders are not supported"))?;
+        let (asset, reserved) = match side {
+            Side::Bid => (pair.quote, limit.saturating_mul_int(amount)),
+            Side::Ask => (pair.base, amount),
+        };
+        Tokens::reserve(asset, owner, reserved)?;
+        Ok(ORDERS.with(|orders| {
+            let mut orders = orders.borrow_mut();
+            let id = orders.iter().map(|order| order.id + 1).max().unwrap_or_default();
+            orders.push(MockOrder { id, owner: *owner, asset, reserved });
+            id
+        }))
+    }
+
+    fn cancel_order(owner: &u64, id: OrderId) -> DispatchResult {
+        let order = ORDERS
+            .with(|orders| orders.borrow().iter().find(|order| order.id == id && order.owner == *owner).cloned())
+            .ok_or(DispatchError::Other("unknown order"))?;
+        Tokens::unreserve(order.asset, owner, order.reserved);
+        ORDERS.with(|orders| orders.borrow_mut().retain(|order| order.id != id));
+        Ok(())
+    }
+
+    fn is_open(id: OrderId) -> bool {
+        ORDERS.with(|orders| orders.borrow().iter().any(|order| order.id == id))
+    }
+
+    fn place_order_weight() -> Weight {
+        0
+    }
+
+    fn cancel_order_weight() -> Weight {
+        0
+    }
+
+    #[cfg(feature = "runtime-benchmarks")]
+    fn open_pair(_pair: TradingPair) {}
+}
+
+parameter_types! {
+    pub const MaxPositionOrders: u32 = 2;
+    pub const MarginPalletId: PalletId = PalletId(*b"mg/margn");
+    pub const InsuranceFundId: PalletId = PalletId(*b"if/insur");
+    // 1% per block.
+    pub BorrowRate: FixedU128 = FixedU128::saturating_from_rational(1, 100);
+    pub InitialMarginRatio: FixedU128 = FixedU128::saturating_from_rational(125, 100);
+    pub MaintenanceMarginRatio: FixedU128 = FixedU128::saturating_from_rational(110, 100);
+    pub const LiquidationReward: Permill = Permill::from_percent(5);
+}
+
+impl pallet_margin::Config for Test {
+    type Event = Event;
+    type Balance = u64;
+    type Currency = Tokens;
+    type Orderbook = MockOrderbook;
+    type PriceProvider = MockPrices;
+    type PalletId = MarginPalletId;
+    type InsuranceFundId = InsuranceFundId;
+    type BorrowRate = BorrowRate;
+    type InitialMarginRatio = InitialMarginRatio;
+    type MaintenanceMarginRatio = MaintenanceMarginRatio;
+    type LiquidationReward = LiquidationReward;
+    type MaxPositionOrders = MaxPositionOrders;
+    type WeightInfo = ();
+}
+
+pub const ALICE: u64 = 1;
+pub const LENDER: u64 = 2;
+pub const LIQUIDATOR: u64 = 3;
+
+pub fn new_test_ext() -> sp_io::TestExternalities {
+    let mut t = frame_system::GenesisConfig::default().build_storage::<Test>().unwrap();
+    GenesisBuild::<Test>::assimilate_storage(
+        &orml_tokens::GenesisConfig::<Test> {
+            endowed_accounts: vec![
+                (ALICE, AssetId::DOT, 1_000),
+                (ALICE, AssetId::USD, 1_000),
+                (LENDER, AssetId::DOT, 10_000),
+                (LENDER, AssetId::USD, 10_000),
+                (LENDER, AssetId::POLKADEX, 10_000),
+                (LIQUIDATOR, AssetId::DOT, 10_000),
+                (LIQUIDATOR, AssetId::USD, 10_000),
+            ],
+        },
+        &mut t,
+    )
+    .unwrap();
+    let mut ext = sp_io::TestExternalities::new(t);
+    ext.execute_with(|| {
+        System::set_block_number(1);
+        set_price(AssetId::DOT, 10);
+        set_price(AssetId::USD, 1);
+    });
+    ext
+}
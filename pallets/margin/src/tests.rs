@@ -0,0 +1,301 @@
+// This file is part of Polkadex.
+
+// Copyright (C) 2020-2021 Polkadex oü.
+// SPDX-License-Identifier: GPL-3.0-or-later WITH Classpath-exception-2.0
+
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with this program. If not, see <https://www.gnu.org/licenses/>.
+use crate::{mock::*, Error, Position, RawEvent};
+use frame_support::{assert_noop, assert_ok, dispatch::DispatchResult, StorageDoubleMap};
+use orml_traits::{MultiCurrency, MultiReservableCurrency};
+use pallet_orderbook::{OrderManager, Side, TimeInForce};
+use polkadex_primitives::assets::AssetId;
+use sp_runtime::{FixedPointNumber, FixedU128};
+
+const BASE: AssetId = AssetId::DOT;
+const QUOTE: AssetId = AssetId::USD;
+
+fn balance(asset: AssetId, who: u64) -> u64 {
+    Tokens::free_balance(asset, &who)
+}
+
+/// Free balance of `asset` in Alice's position.
+fn held(asset: AssetId) -> u64 {
+    balance(asset, Margin::position_account(&ALICE, BASE, QUOTE))
+}
+
+fn supply(asset: AssetId, amount: u64) -> DispatchResult {
+    Margin::supply(Origin::signed(LENDER), asset, amount)
+}
+
+fn deposit(asset: AssetId, amount: u64) -> DispatchResult {
+    Margin::deposit(Origin::signed(ALICE), BASE, QUOTE, asset, amount)
+}
+
+fn borrow(asset: AssetId, amount: u64) -> DispatchResult {
+    Margin::borrow(Origin::signed(ALICE), BASE, QUOTE, asset, amount)
+}
+
+fn place_order(side: Side, amount: u64, limit: u64) -> DispatchResult {
+    Margin::place_order(
+        Origin::signed(ALICE),
+        BASE,
+        QUOTE,
+        side,
+        amount,
+        Some(FixedU128::saturating_from_integer(limit)),
+        TimeInForce::GoodTillCancelled,
+    )
+}
+
+/// Alice's position with 100 USD of collateral and 40 DOT borrowed at a DOT price of 10.
+fn leveraged_position() {
+    assert_ok!(supply(BASE, 1_000));
+    assert_ok!(deposit(QUOTE, 100));
+    assert_ok!(borrow(BASE, 40));
+}
+
+#[test]
+fn lenders_redeem_shares_of_the_pool() {
+    new_test_ext().execute_with(|| {
+        assert_ok!(supply(BASE, 1_000));
+        assert_eq!(Margin::pool_shares(BASE, LENDER), 1_000_000_000);
+        assert_eq!(balance(BASE, Margin::account_id()), 1_000);
+
+        assert_ok!(Margin::redeem(Origin::signed(LENDER), BASE, 400_000_000));
+        assert_eq!(Margin::pool_shares(BASE, LENDER), 600_000_000);
+        assert_eq!(balance(BASE, LENDER), 9_400);
+        assert_noop!(
+            Margin::redeem(Origin::signed(LENDER), BASE, 600_000_001),
+            Error::<Test>::InsufficientShares
+        );
+    });
+}
+
+#[test]
+fn the_first_lender_cannot_inflate_the_shares() {
+    new_test_ext().execute_with(|| {
+        assert_ok!(supply(BASE, 1));
+        assert_eq!(Margin::pool_shares(BASE, LENDER), 1_000_000);
+        assert_ok!(deposit(QUOTE, 100));
+        assert_ok!(borrow(BASE, 1));
+        System::set_block_number(1_001);
+        assert_ok!(Margin::repay(Origin::signed(ALICE), BASE, QUOTE, BASE, 1));
+
+        // Without the virtual shares, the interest making the first supply of 1 worth 11 would
+        // round the shares of the next supply of 1 down to nothing.
+        assert_ok!(Margin::supply(Origin::signed(ALICE), BASE, 1));
+        assert_eq!(Margin::pool_shares(BASE, ALICE), 166_666);
+    });
+}
+
+#[test]
+fn borrowing_keeps_the_initial_margin() {
+    new_test_ext().execute_with(|| {
+        assert_ok!(supply(BASE, 1_000));
+        assert_ok!(deposit(QUOTE, 100));
+
+        // 100 USD and 41 DOT against 41 DOT of debt is below 125%.
+        assert_noop!(borrow(BASE, 41), Error::<Test>::InsufficientMargin);
+        assert_ok!(borrow(BASE, 40));
+        assert_eq!(
+            Margin::position(ALICE, (BASE, QUOTE)),
+            Position {
+                base_debt: 40,
+                quote_debt: 0,
+                orders: vec![],
+            }
+        );
+        assert_eq!(held(BASE), 40);
+        assert_eq!(held(QUOTE), 100);
+        assert_eq!(
+            Margin::health(&ALICE, BASE, QUOTE),
+            Ok(Some(FixedU128::saturating_from_rational(125, 100)))
+        );
+        assert_noop!(
+            Margin::withdraw(Origin::signed(ALICE), BASE, QUOTE, QUOTE, 1),
+            Error::<Test>::InsufficientMargin
+        );
+        assert_noop!(
+            Margin::borrow(Origin::signed(ALICE), BASE, QUOTE, QUOTE, 2_000),
+            Error::<Test>::InsufficientLiquidity
+        );
+    });
+}
+
+#[test]
+fn debts_accrue_interest_for_the_lenders() {
+    new_test_ext().execute_with(|| {
+        leveraged_position();
+        System::set_block_number(11);
+        assert_ok!(deposit(BASE, 10));
+
+        // 1% per block for 10 blocks.
+        assert_ok!(Margin::repay(Origin::signed(ALICE), BASE, QUOTE, BASE, 1_000));
+        assert!(System::events()
+            .iter()
+            .any(|record| record.event == RawEvent::Repaid(ALICE, BASE, QUOTE, BASE, 44).into()));
+        assert_eq!(held(BASE), 6);
+        assert_eq!(Margin::health(&ALICE, BASE, QUOTE), Ok(None));
+
+        // The virtual shares of the pool keep their part of the interest.
+        assert_ok!(Margin::redeem(Origin::signed(LENDER), BASE, 1_000_000_000));
+        assert_eq!(balance(BASE, LENDER), 10_003);
+    });
+}
+
+#[test]
+fn withdrawing_everything_closes_the_position() {
+    new_test_ext().execute_with(|| {
+        assert_ok!(deposit(QUOTE, 100));
+        assert_noop!(
+            Margin::withdraw(Origin::signed(ALICE), BASE, QUOTE, QUOTE, 101),
+            Error::<Test>::InsufficientBalance
+        );
+        assert_ok!(Margin::withdraw(Origin::signed(ALICE), BASE, QUOTE, QUOTE, 100));
+        assert!(!crate::Positions::<Test>::contains_key(ALICE, (BASE, QUOTE)));
+        assert_eq!(balance(QUOTE, ALICE), 1_000);
+    });
+}
+
+#[test]
+fn assets_must_be_in_a_valid_pair() {
+    new_test_ext().execute_with(|| {
+        assert_noop!(
+            Margin::deposit(Origin::signed(ALICE), BASE, BASE, BASE, 1),
+            Error::<Test>::InvalidPair
+        );
+        assert_noop!(
+            Margin::deposit(Origin::signed(ALICE), BASE, QUOTE, AssetId::BTC, 1),
+            Error::<Test>::AssetNotInPair
+        );
+    });
+}
+
+#[test]
+fn borrowing_needs_prices() {
+    new_test_ext().execute_with(|| {
+        assert_ok!(supply(AssetId::POLKADEX, 1_000));
+        assert_ok!(Margin::deposit(Origin::signed(ALICE), AssetId::POLKADEX, QUOTE, QUOTE, 100));
+        assert_noop!(
+            Margin::borrow(Origin::signed(ALICE), AssetId::POLKADEX, QUOTE, AssetId::POLKADEX, 1),
+            Error::<Test>::PriceUnavailable
+        );
+    });
+}
+
+#[test]
+fn healthy_positions_cannot_be_liquidated() {
+    new_test_ext().execute_with(|| {
+        leveraged_position();
+        set_price(BASE, 20);
+        assert_noop!(
+            Margin::liquidate(Origin::signed(LIQUIDATOR), ALICE, BASE, QUOTE),
+            Error::<Test>::PositionHealthy
+        );
+    });
+}
+
+#[test]
+fn liquidators_repay_the_debts_for_the_assets_and_a_reward() {
+    new_test_ext().execute_with(|| {
+        leveraged_position();
+        // 100 USD and 40 DOT worth 1_300 against 1_200 of debt.
+        set_price(BASE, 30);
+
+        assert_ok!(Margin::liquidate(Origin::signed(LIQUIDATOR), ALICE, BASE, QUOTE));
+
+        // The debts plus 5% are 1_260, or 96.9% of the assets; the rest goes back to Alice.
+        assert_eq!(balance(BASE, LIQUIDATOR), 10_000 - 40 + 38);
+        assert_eq!(balance(QUOTE, LIQUIDATOR), 10_000 + 96);
+        assert_eq!(balance(BASE, ALICE), 1_000 + 2);
+        assert_eq!(balance(QUOTE, ALICE), 900 + 4);
+        assert_eq!(held(BASE), 0);
+        assert_eq!(held(QUOTE), 0);
+        assert_eq!(balance(BASE, Margin::insurance_fund_id()), 0);
+        assert_eq!(Margin::pool(BASE).unwrap().cash, 1_000);
+        assert_eq!(Margin::pool(BASE).unwrap().borrowed, 0);
+        assert!(!crate::Positions::<Test>::contains_key(ALICE, (BASE, QUOTE)));
+        assert!(System::events()
+            .iter()
+            .any(|record| record.event == RawEvent::Liquidated(ALICE, BASE, QUOTE, LIQUIDATOR).into()));
+    });
+}
+
+#[test]
+fn the_insurance_fund_covers_underwater_positions() {
+    new_test_ext().execute_with(|| {
+        leveraged_position();
+        assert_ok!(Tokens::deposit(QUOTE, &Margin::insurance_fund_id(), 1_000));
+        // 100 USD and 40 DOT worth 4_100 against 4_000 of debt, 100 short of the reward.
+        set_price(BASE, 100);
+
+        assert_ok!(Margin::liquidate(Origin::signed(LIQUIDATOR), ALICE, BASE, QUOTE));
+
+        assert_eq!(balance(BASE, LIQUIDATOR), 10_000);
+        assert_eq!(balance(QUOTE, LIQUIDATOR), 10_000 + 100 + 100);
+        assert_eq!(balance(QUOTE, Margin::insurance_fund_id()), 900);
+    });
+}
+
+#[test]
+fn positions_trade_in_the_order_book() {
+    new_test_ext().execute_with(|| {
+        leveraged_position();
+        let account = Margin::position_account(&ALICE, BASE, QUOTE);
+
+        assert_ok!(place_order(Side::Ask, 40, 12));
+        assert_ok!(place_order(Side::Bid, 5, 10));
+        assert_eq!(Margin::position(ALICE, (BASE, QUOTE)).orders, vec![0, 1]);
+        assert_eq!(Tokens::reserved_balance(BASE, &account), 40);
+        assert_eq!(Tokens::reserved_balance(QUOTE, &account), 50);
+        assert!(System::events()
+            .iter()
+            .any(|record| record.event == RawEvent::OrderPlaced(ALICE, BASE, QUOTE, 1).into()));
+        // What the orders reserve still counts as assets, but cannot be withdrawn.
+        assert_eq!(
+            Margin::health(&ALICE, BASE, QUOTE),
+            Ok(Some(FixedU128::saturating_from_rational(125, 100)))
+        );
+        assert_noop!(
+            Margin::withdraw(Origin::signed(ALICE), BASE, QUOTE, BASE, 1),
+            Error::<Test>::InsufficientBalance
+        );
+        assert_noop!(place_order(Side::Bid, 1, 10), Error::<Test>::TooManyOrders);
+
+        assert_noop!(
+            Margin::cancel_order(Origin::signed(ALICE), BASE, QUOTE, 2),
+            Error::<Test>::UnknownOrder
+        );
+        assert_ok!(Margin::cancel_order(Origin::signed(ALICE), BASE, QUOTE, 0));
+        assert!(!MockOrderbook::is_open(0));
+        assert_eq!(Margin::position(ALICE, (BASE, QUOTE)).orders, vec![1]);
+        assert_eq!(held(BASE), 40);
+    });
+}
+
+#[test]
+fn liquidation_cancels_the_orders_of_the_position() {
+    new_test_ext().execute_with(|| {
+        leveraged_position();
+        assert_ok!(place_order(Side::Ask, 40, 12));
+        set_price(BASE, 30);
+
+        assert_ok!(Margin::liquidate(Origin::signed(LIQUIDATOR), ALICE, BASE, QUOTE));
+
+        assert!(!MockOrderbook::is_open(0));
+        assert_eq!(balance(BASE, LIQUIDATOR), 10_000 - 40 + 38);
+        assert_eq!(balance(BASE, ALICE), 1_000 + 2);
+        assert_eq!(Tokens::reserved_balance(BASE, &Margin::position_account(&ALICE, BASE, QUOTE)), 0);
+    });
+}
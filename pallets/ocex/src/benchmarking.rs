@@ -0,0 +1,169 @@
+// This file is part of Polkadex.
+
+// Copyright (C) 2020-2021 Polkadex oü.
+// SPDX-License-Identifier: GPL-3.0-or-later WITH Classpath-exception-2.0
+
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with this program. If not, see <https://www.gnu.org/licenses/>.
+//! Benchmarks for `pallet_ocex`.
+//!
+//! Snapshots are signed with sr25519 keys generated in the benchmark keystore, so the runtime's
+//! operator signatures and accounts must be convertible from sr25519 ones.
+
+#![cfg(feature = "runtime-benchmarks")]
+
+use super::*;
+use frame_benchmarking::{account, benchmarks, impl_benchmark_test_suite, whitelisted_caller};
+use frame_support::traits::OnInitialize;
+use frame_system::RawOrigin;
+use sp_core::{crypto::KeyTypeId, sr25519};
+
+const SEED: u32 = 0;
+const ASSET: AssetId = AssetId::DOT;
+const BENCH_KEY_TYPE: KeyTypeId = KeyTypeId(*b"ocex");
+
+fn operator_key<T: Config>() -> (sr25519::Public, T::AccountId)
+where
+    T::AccountId: From<sr25519::Public>,
+{
+    let public = sp_io::crypto::sr25519_generate(BENCH_KEY_TYPE, None);
+    let operator: T::AccountId = public.clone().into();
+    Operators::<T>::append(&operator);
+    (public, operator)
+}
+
+fn snapshot<T: Config>(id: u64, deltas: u32) -> SnapshotOf<T> {
+    Snapshot {
+        id,
+        state_root: H256::repeat_byte(1),
+        deltas: (0..deltas)
+            .map(|i| BalanceDelta { account: account("user", i, SEED), asset: ASSET, amount: 100u32.into() })
+            .collect(),
+    }
+}
+
+/// Queues `count` pending snapshots with `deltas` deltas each, the first one submitted at
+/// `submitted_at`.
+fn pending_snapshots<T: Config>(count: u32, deltas: u32, operator: &T::AccountId) {
+    let submitted_at = frame_system::Pallet::<T>::block_number();
+    for id in 1..=count as u64 {
+        PendingSnapshots::<T>::insert(id, PendingSnapshot {
+            snapshot: snapshot::<T>(id, deltas),
+            operator: operator.clone(),
+            submitted_at,
+        });
+    }
+    LastSnapshotId::put(count as u64);
+}
+
+benchmarks! {
+    where_clause {
+        where
+            T::AccountId: From<sr25519::Public>,
+            T::OperatorSignature: From<sr25519::Signature>,
+    }
+
+    register_operator {
+        for i in 0..T::MaxOperators::get().saturating_sub(1) {
+            Operators::<T>::append(&account::<T::AccountId>("operator", i, SEED));
+        }
+        let operator: T::AccountId = account("new", 0, SEED);
+        let origin = T::OperatorOrigin::successful_origin();
+    }: _<T::Origin>(origin, operator.clone())
+    verify {
+        assert!(Module::<T>::operators().contains(&operator));
+    }
+
+    remove_operator {
+        for i in 0..T::MaxOperators::get() {
+            Operators::<T>::append(&account::<T::AccountId>("operator", i, SEED));
+        }
+        let operator: T::AccountId = account("operator", 0, SEED);
+        let origin = T::OperatorOrigin::successful_origin();
+    }: _<T::Origin>(origin, operator.clone())
+    verify {
+        assert!(!Module::<T>::operators().contains(&operator));
+    }
+
+    deposit {
+        let caller: T::AccountId = whitelisted_caller();
+        T::Currency::deposit(ASSET, &caller, 1_000u32.into())?;
+    }: _(RawOrigin::Signed(caller.clone()), ASSET, 100u32.into())
+    verify {
+        assert_eq!(T::Currency::free_balance(ASSET, &Module::<T>::custody_account()), 100u32.into());
+    }
+
+    withdraw {
+        let caller: T::AccountId = whitelisted_caller();
+        T::Currency::deposit(ASSET, &Module::<T>::custody_account(), 1_000u32.into())?;
+        Withdrawable::<T>::insert(&caller, ASSET, BalanceOf::<T>::from(100u32));
+    }: _(RawOrigin::Signed(caller.clone()), ASSET, 100u32.into())
+    verify {
+        assert_eq!(T::Currency::free_balance(ASSET, &caller), 100u32.into());
+    }
+
+    submit_snapshot {
+        let d in 0 .. T::MaxDeltas::get();
+        let (public, operator) = operator_key::<T>();
+        let snapshot = snapshot::<T>(1, d);
+        let signature: T::OperatorSignature =
+            sp_io::crypto::sr25519_sign(BENCH_KEY_TYPE, &public, &Module::<T>::signing_payload(&snapshot))
+                .unwrap()
+                .into();
+        let caller: T::AccountId = whitelisted_caller();
+    }: _(RawOrigin::Signed(caller), snapshot, operator, signature)
+    verify {
+        assert_eq!(Module::<T>::last_snapshot_id(), 1);
+    }
+
+    challenge {
+        let (_, operator) = operator_key::<T>();
+        pending_snapshots::<T>(1, 0, &operator);
+        let caller: T::AccountId = whitelisted_caller();
+        T::BondCurrency::make_free_balance_be(&caller, T::ChallengeBond::get().saturating_mul(10u32.into()));
+    }: _(RawOrigin::Signed(caller), 1)
+    verify {
+        assert!(Module::<T>::open_dispute().is_some());
+    }
+
+    resolve_dispute {
+        let p in 1 .. T::MaxPendingSnapshots::get();
+        let (_, operator) = operator_key::<T>();
+        pending_snapshots::<T>(p, 0, &operator);
+        let challenger: T::AccountId = whitelisted_caller();
+        T::BondCurrency::make_free_balance_be(&challenger, T::ChallengeBond::get().saturating_mul(10u32.into()));
+        Module::<T>::challenge(RawOrigin::Signed(challenger).into(), 1)?;
+        let origin = T::DisputeOrigin::successful_origin();
+    }: _<T::Origin>(origin, true)
+    verify {
+        assert_eq!(Module::<T>::last_snapshot_id(), 0);
+    }
+
+    finalize_snapshot {
+        let d in 0 .. T::MaxDeltas::get();
+        let (_, operator) = operator_key::<T>();
+        pending_snapshots::<T>(1, d, &operator);
+        let now = frame_system::Pallet::<T>::block_number().saturating_add(T::ChallengePeriod::get());
+    }: {
+        Module::<T>::on_initialize(now);
+    }
+    verify {
+        assert_eq!(Module::<T>::last_finalized_id(), 1);
+    }
+}
+
+impl_benchmark_test_suite!(
+    Module,
+    crate::mock::new_test_ext(),
+    crate::mock::Test,
+);
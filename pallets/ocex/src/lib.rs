@@ -0,0 +1,447 @@
+// This file is part of Polkadex.
+
+// Copyright (C) 2020-2021 Polkadex oü.
+// SPDX-License-Identifier: GPL-3.0-or-later WITH Classpath-exception-2.0
+
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with this program. If not, see <https://www.gnu.org/licenses/>.
+//! # OCEX pallet
+//!
+//! On-chain settlement of the Polkadex off-chain exchange (OCEX).
+//!
+//! - Users [`Call::deposit`] assets into the OCEX custody account, derived from
+//!   [`Config::PalletId`]. The off-chain engine credits them when it sees the
+//!   [`RawEvent::Deposited`] event and trades off chain from then on.
+//! - Periodically, a registered operator (enclave) key signs a [`Snapshot`] of the engine: the
+//!   root of its balance tree and the balance deltas released from the exchange for withdrawal.
+//!   Anyone can relay it with [`Call::submit_snapshot`]; snapshots are strictly sequential.
+//!   The signed payload, [`Module::signing_payload`], binds the snapshot to the chain's genesis
+//!   hash and to the number of reverts so far, so reverted snapshots cannot be relayed again.
+//! - A snapshot can be challenged for [`Config::ChallengePeriod`] blocks after its submission by
+//!   reserving [`Config::ChallengeBond`]. [`Config::DisputeOrigin`] then resolves the dispute:
+//!   an upheld dispute reverts the snapshot with every later one and deregisters the operator
+//!   that signed it, a rejected one slashes the challenger's bond. One dispute is open at a time,
+//!   so the challenge periods of the later snapshots are extended by the blocks they spent
+//!   blocked behind a rejected dispute.
+//! - Snapshots that made it through the challenge period undisputed are finalized in order at
+//!   the start of a block, crediting their deltas to the withdrawable balances users
+//!   [`Call::withdraw`] from the custody account.
+
+#![cfg_attr(not(feature = "std"), no_std)]
+
+use codec::{Decode, Encode};
+use frame_support::{
+    decl_error, decl_event, decl_module, decl_storage,
+    dispatch::DispatchResult,
+    ensure,
+    traits::{Currency, EnsureOrigin, Get, OnUnbalanced, ReservableCurrency},
+    weights::Weight,
+    PalletId, Parameter, StorageDoubleMap, StorageMap, StorageValue,
+};
+use frame_system::ensure_signed;
+use orml_traits::MultiCurrency;
+use polkadex_primitives::assets::AssetId;
+use sp_core::H256;
+use sp_runtime::{
+    traits::{AccountIdConversion, IdentifyAccount, Saturating, Verify, Zero},
+    RuntimeDebug,
+};
+use sp_std::prelude::*;
+
+pub use weights::WeightInfo;
+
+#[cfg(feature = "runtime-benchmarks")]
+mod benchmarking;
+#[cfg(test)]
+mod mock;
+#[cfg(test)]
+mod tests;
+pub mod weights;
+
+/// Prefix of the payload operators sign, separating it from other signed payloads.
+pub const SNAPSHOT_SIGNING_TAG: [u8; 13] = *b"ocex/snapshot";
+
+/// Balance of the assets held in custody.
+pub type BalanceOf<T> = <<T as Config>::Currency as MultiCurrency<<T as frame_system::Config>::AccountId>>::Balance;
+/// Balance of the challenge bonds.
+pub type BondBalanceOf<T> = <<T as Config>::BondCurrency as Currency<<T as frame_system::Config>::AccountId>>::Balance;
+type NegativeImbalanceOf<T> = <<T as Config>::BondCurrency as Currency<<T as frame_system::Config>::AccountId>>::NegativeImbalance;
+
+/// Funds the engine released from the exchange for withdrawal by `account`.
+#[derive(Encode, Decode, Clone, PartialEq, Eq, RuntimeDebug)]
+pub struct BalanceDelta<AccountId, Balance> {
+    pub account: AccountId,
+    pub asset: AssetId,
+    pub amount: Balance,
+}
+
+/// State of the off-chain engine signed by an operator.
+#[derive(Encode, Decode, Clone, PartialEq, Eq, RuntimeDebug)]
+pub struct Snapshot<AccountId, Balance> {
+    /// Sequence number, one more than the id of the previous snapshot. The first one is `1`.
+    pub id: u64,
+    /// Root of the engine's balance tree after this snapshot.
+    pub state_root: H256,
+    /// Balances released for withdrawal since the previous snapshot.
+    pub deltas: Vec<BalanceDelta<AccountId, Balance>>,
+}
+
+/// A snapshot waiting for its challenge period to end.
+#[derive(Encode, Decode, Clone, PartialEq, Eq, RuntimeDebug)]
+pub struct PendingSnapshot<AccountId, Balance, BlockNumber> {
+    pub snapshot: Snapshot<AccountId, Balance>,
+    /// Operator that signed the snapshot.
+    pub operator: AccountId,
+    /// Start of the challenge period. The submission block, moved by rejected disputes.
+    pub submitted_at: BlockNumber,
+}
+
+/// A challenge of a pending snapshot.
+#[derive(Encode, Decode, Clone, PartialEq, Eq, RuntimeDebug)]
+pub struct Dispute<AccountId, Balance, BlockNumber> {
+    pub snapshot_id: u64,
+    pub challenger: AccountId,
+    /// Reserved from the challenger until the dispute is resolved.
+    pub bond: Balance,
+    pub opened_at: BlockNumber,
+}
+
+pub type SnapshotOf<T> = Snapshot<<T as frame_system::Config>::AccountId, BalanceOf<T>>;
+type PendingSnapshotOf<T> = PendingSnapshot<
+    <T as frame_system::Config>::AccountId,
+    BalanceOf<T>,
+    <T as frame_system::Config>::BlockNumber,
+>;
+type DisputeOf<T> = Dispute<
+    <T as frame_system::Config>::AccountId,
+    BondBalanceOf<T>,
+    <T as frame_system::Config>::BlockNumber,
+>;
+
+/// Configure the pallet by specifying the parameters and types on which it depends.
+pub trait Config: frame_system::Config {
+    /// Because this pallet emits events, it depends on the runtime's definition of an event.
+    type Event: From<Event<Self>> + Into<<Self as frame_system::Config>::Event>;
+    /// Assets users deposit into and withdraw from the exchange.
+    type Currency: MultiCurrency<Self::AccountId, CurrencyId = AssetId>;
+    /// Currency challenge bonds are reserved in.
+    type BondCurrency: ReservableCurrency<Self::AccountId>;
+    /// Id the custody account is derived from.
+    type PalletId: Get<PalletId>;
+    /// Public key of operators, identifying their account.
+    type OperatorPublic: IdentifyAccount<AccountId = Self::AccountId>;
+    /// Signature of snapshots by operators.
+    type OperatorSignature: Parameter + Verify<Signer = Self::OperatorPublic>;
+    /// Origin allowed to register and deregister operators.
+    type OperatorOrigin: EnsureOrigin<Self::Origin>;
+    /// Origin allowed to resolve disputes.
+    type DisputeOrigin: EnsureOrigin<Self::Origin>;
+    /// Number of blocks during which a snapshot can be challenged.
+    type ChallengePeriod: Get<Self::BlockNumber>;
+    /// Amount reserved from a challenger while the dispute is open.
+    type ChallengeBond: Get<BondBalanceOf<Self>>;
+    /// Handler for the bonds of rejected challenges.
+    type Slash: OnUnbalanced<NegativeImbalanceOf<Self>>;
+    /// Maximum number of operators.
+    type MaxOperators: Get<u32>;
+    /// Maximum number of deltas in a snapshot.
+    type MaxDeltas: Get<u32>;
+    /// Maximum number of snapshots waiting to be finalized.
+    type MaxPendingSnapshots: Get<u32>;
+    /// Weight information for extrinsics in this pallet.
+    type WeightInfo: WeightInfo;
+}
+
+decl_storage! {
+    trait Store for Module<T: Config> as Ocex {
+        /// Accounts whose keys sign snapshots.
+        pub Operators get(fn operators) config(): Vec<T::AccountId>;
+        /// Id of the last submitted snapshot.
+        pub LastSnapshotId get(fn last_snapshot_id): u64;
+        /// Id of the last finalized snapshot.
+        pub LastFinalizedId get(fn last_finalized_id): u64;
+        /// Number of upheld disputes, part of the payload operators sign.
+        pub RevertEpoch get(fn revert_epoch): u64;
+        /// Snapshots submitted but not finalized yet.
+        pub PendingSnapshots get(fn pending_snapshot):
+            map hasher(twox_64_concat) u64 => Option<PendingSnapshotOf<T>>;
+        /// State root of each finalized snapshot.
+        pub StateRoots get(fn state_root): map hasher(twox_64_concat) u64 => Option<H256>;
+        /// The dispute being resolved, if any.
+        pub OpenDispute get(fn open_dispute): Option<DisputeOf<T>>;
+        /// Balances users can withdraw from the custody account.
+        pub Withdrawable get(fn withdrawable):
+            double_map hasher(blake2_128_concat) T::AccountId, hasher(blake2_128_concat) AssetId => BalanceOf<T>;
+    }
+}
+
+decl_event!(
+    pub enum Event<T>
+    where
+        AccountId = <T as frame_system::Config>::AccountId,
+        Balance = BalanceOf<T>,
+    {
+        /// An operator was registered. \[operator\]
+        OperatorRegistered(AccountId),
+        /// An operator was deregistered. \[operator\]
+        OperatorRemoved(AccountId),
+        /// Assets were deposited into the exchange. \[who, asset, amount\]
+        Deposited(AccountId, AssetId, Balance),
+        /// Assets were withdrawn from the exchange. \[who, asset, amount\]
+        Withdrawn(AccountId, AssetId, Balance),
+        /// A snapshot was submitted. \[id, operator, state_root\]
+        SnapshotSubmitted(u64, AccountId, H256),
+        /// A snapshot was finalized. \[id\]
+        SnapshotFinalized(u64),
+        /// A snapshot was challenged. \[id, challenger\]
+        SnapshotChallenged(u64, AccountId),
+        /// A dispute was upheld and snapshots were reverted. \[first_id, last_id\]
+        SnapshotsReverted(u64, u64),
+        /// A dispute was rejected and the challenger's bond slashed. \[id, challenger\]
+        DisputeRejected(u64, AccountId),
+    }
+);
+
+decl_error! {
+    pub enum Error for Module<T: Config> {
+        /// The account is already an operator.
+        AlreadyOperator,
+        /// The account is not an operator.
+        UnknownOperator,
+        /// The maximum number of operators is reached.
+        TooManyOperators,
+        /// The snapshot signature is invalid.
+        BadSignature,
+        /// The snapshot does not follow the last submitted one.
+        UnexpectedSnapshotId,
+        /// The snapshot has more deltas than allowed.
+        TooManyDeltas,
+        /// Too many snapshots are waiting to be finalized.
+        TooManyPendingSnapshots,
+        /// The snapshot is not pending.
+        UnknownSnapshot,
+        /// The challenge period of the snapshot is over.
+        ChallengePeriodOver,
+        /// Another dispute is being resolved.
+        DisputeOpen,
+        /// There is no dispute to resolve.
+        NoDispute,
+        /// The amount is zero.
+        ZeroAmount,
+        /// The withdrawable balance is too low.
+        InsufficientWithdrawable,
+    }
+}
+
+decl_module! {
+    pub struct Module<T: Config> for enum Call where origin: T::Origin {
+        type Error = Error<T>;
+
+        fn deposit_event() = default;
+
+        /// Finalizes the oldest pending snapshot once it made it through its challenge period.
+        fn on_initialize(now: T::BlockNumber) -> Weight {
+            let id = Self::last_finalized_id() + 1;
+            let disputed = Self::open_dispute().map_or(false, |dispute| dispute.snapshot_id <= id);
+            match PendingSnapshots::<T>::get(id) {
+                Some(pending) if !disputed && now >= pending.submitted_at.saturating_add(T::ChallengePeriod::get()) => {
+                    let deltas = pending.snapshot.deltas.len() as u32;
+                    Self::finalize(pending);
+                    <T as Config>::WeightInfo::finalize_snapshot(deltas)
+                }
+                _ => T::DbWeight::get().reads(3),
+            }
+        }
+
+        /// Allow `operator` to sign snapshots.
+        #[weight = <T as Config>::WeightInfo::register_operator()]
+        pub fn register_operator(origin, operator: T::AccountId) -> DispatchResult {
+            T::OperatorOrigin::ensure_origin(origin)?;
+            Operators::<T>::try_mutate(|operators| -> DispatchResult {
+                ensure!(!operators.contains(&operator), Error::<T>::AlreadyOperator);
+                ensure!((operators.len() as u32) < T::MaxOperators::get(), Error::<T>::TooManyOperators);
+                operators.push(operator.clone());
+                Ok(())
+            })?;
+            Self::deposit_event(RawEvent::OperatorRegistered(operator));
+            Ok(())
+        }
+
+        /// Stop accepting snapshots signed by `operator`.
+        ///
+        /// Snapshots it already signed are still finalized unless disputed.
+        #[weight = <T as Config>::WeightInfo::remove_operator()]
+        pub fn remove_operator(origin, operator: T::AccountId) -> DispatchResult {
+            T::OperatorOrigin::ensure_origin(origin)?;
+            ensure!(Self::do_remove_operator(&operator), Error::<T>::UnknownOperator);
+            Ok(())
+        }
+
+        /// Move `amount` of `asset` into the exchange.
+        #[weight = <T as Config>::WeightInfo::deposit()]
+        pub fn deposit(origin, asset: AssetId, amount: BalanceOf<T>) -> DispatchResult {
+            let who = ensure_signed(origin)?;
+            ensure!(!amount.is_zero(), Error::<T>::ZeroAmount);
+            T::Currency::transfer(asset, &who, &Self::custody_account(), amount)?;
+            Self::deposit_event(RawEvent::Deposited(who, asset, amount));
+            Ok(())
+        }
+
+        /// Withdraw `amount` of `asset` released for the caller by finalized snapshots.
+        #[weight = <T as Config>::WeightInfo::withdraw()]
+        pub fn withdraw(origin, asset: AssetId, amount: BalanceOf<T>) -> DispatchResult {
+            let who = ensure_signed(origin)?;
+            ensure!(!amount.is_zero(), Error::<T>::ZeroAmount);
+            let withdrawable = Self::withdrawable(&who, asset);
+            ensure!(withdrawable >= amount, Error::<T>::InsufficientWithdrawable);
+
+            T::Currency::transfer(asset, &Self::custody_account(), &who, amount)?;
+            Withdrawable::<T>::insert(&who, asset, withdrawable - amount);
+            Self::deposit_event(RawEvent::Withdrawn(who, asset, amount));
+            Ok(())
+        }
+
+        /// Submit a snapshot signed by `operator`.
+        ///
+        /// `signature` is the operator's signature of [`Module::signing_payload`] of `snapshot`.
+        #[weight = <T as Config>::WeightInfo::submit_snapshot(snapshot.deltas.len() as u32)]
+        pub fn submit_snapshot(
+            origin,
+            snapshot: SnapshotOf<T>,
+            operator: T::AccountId,
+            signature: T::OperatorSignature,
+        ) -> DispatchResult {
+            ensure_signed(origin)?;
+            ensure!(Self::operators().contains(&operator), Error::<T>::UnknownOperator);
+            ensure!(signature.verify(&Self::signing_payload(&snapshot)[..], &operator), Error::<T>::BadSignature);
+            let last = Self::last_snapshot_id();
+            ensure!(snapshot.id == last + 1, Error::<T>::UnexpectedSnapshotId);
+            ensure!(snapshot.deltas.len() as u32 <= T::MaxDeltas::get(), Error::<T>::TooManyDeltas);
+            ensure!(
+                last - Self::last_finalized_id() < T::MaxPendingSnapshots::get() as u64,
+                Error::<T>::TooManyPendingSnapshots
+            );
+
+            let (id, state_root) = (snapshot.id, snapshot.state_root);
+            PendingSnapshots::<T>::insert(id, PendingSnapshot {
+                snapshot,
+                operator: operator.clone(),
+                submitted_at: frame_system::Pallet::<T>::block_number(),
+            });
+            LastSnapshotId::put(id);
+            Self::deposit_event(RawEvent::SnapshotSubmitted(id, operator, state_root));
+            Ok(())
+        }
+
+        /// Challenge the pending snapshot `id`, reserving the challenge bond until the dispute
+        /// is resolved.
+        #[weight = <T as Config>::WeightInfo::challenge()]
+        pub fn challenge(origin, id: u64) -> DispatchResult {
+            let challenger = ensure_signed(origin)?;
+            ensure!(Self::open_dispute().is_none(), Error::<T>::DisputeOpen);
+            let pending = Self::pending_snapshot(id).ok_or(Error::<T>::UnknownSnapshot)?;
+            let now = frame_system::Pallet::<T>::block_number();
+            ensure!(
+                now < pending.submitted_at.saturating_add(T::ChallengePeriod::get()),
+                Error::<T>::ChallengePeriodOver
+            );
+
+            let bond = T::ChallengeBond::get();
+            T::BondCurrency::reserve(&challenger, bond)?;
+            OpenDispute::<T>::put(Dispute { snapshot_id: id, challenger: challenger.clone(), bond, opened_at: now });
+            Self::deposit_event(RawEvent::SnapshotChallenged(id, challenger));
+            Ok(())
+        }
+
+        /// Resolve the open dispute.
+        ///
+        /// If `upheld`, the disputed snapshot and all later ones are reverted, the operator that
+        /// signed it is deregistered and the challenger gets its bond back. Snapshots signed
+        /// before the revert are no longer accepted. Otherwise the bond is
+        /// slashed and the snapshot is finalized as usual, while the later snapshots get back the
+        /// part of their challenge period the dispute blocked challenges during.
+        #[weight = <T as Config>::WeightInfo::resolve_dispute(T::MaxPendingSnapshots::get())]
+        pub fn resolve_dispute(origin, upheld: bool) -> DispatchResult {
+            T::DisputeOrigin::ensure_origin(origin)?;
+            let dispute = OpenDispute::<T>::take().ok_or(Error::<T>::NoDispute)?;
+
+            if upheld {
+                T::BondCurrency::unreserve(&dispute.challenger, dispute.bond);
+                let last = Self::last_snapshot_id();
+                for id in dispute.snapshot_id..=last {
+                    if let Some(pending) = PendingSnapshots::<T>::take(id) {
+                        if id == dispute.snapshot_id {
+                            Self::do_remove_operator(&pending.operator);
+                        }
+                    }
+                }
+                LastSnapshotId::put(dispute.snapshot_id - 1);
+                RevertEpoch::mutate(|epoch| *epoch += 1);
+                Self::deposit_event(RawEvent::SnapshotsReverted(dispute.snapshot_id, last));
+            } else {
+                let (imbalance, _) = T::BondCurrency::slash_reserved(&dispute.challenger, dispute.bond);
+                T::Slash::on_unbalanced(imbalance);
+                let now = frame_system::Pallet::<T>::block_number();
+                for id in dispute.snapshot_id + 1..=Self::last_snapshot_id() {
+                    PendingSnapshots::<T>::mutate(id, |pending| {
+                        if let Some(pending) = pending {
+                            let blocked_since = pending.submitted_at.max(dispute.opened_at);
+                            pending.submitted_at = pending.submitted_at.saturating_add(now.saturating_sub(blocked_since));
+                        }
+                    });
+                }
+                Self::deposit_event(RawEvent::DisputeRejected(dispute.snapshot_id, dispute.challenger));
+            }
+            Ok(())
+        }
+    }
+}
+
+impl<T: Config> Module<T> {
+    /// Account holding the assets deposited into the exchange.
+    pub fn custody_account() -> T::AccountId {
+        T::PalletId::get().into_account()
+    }
+
+    /// Payload an operator signs for `snapshot`: [`SNAPSHOT_SIGNING_TAG`], the genesis hash, the
+    /// current [`RevertEpoch`] and the snapshot, SCALE encoded.
+    pub fn signing_payload(snapshot: &SnapshotOf<T>) -> Vec<u8> {
+        let genesis_hash = frame_system::Pallet::<T>::block_hash(T::BlockNumber::zero());
+        (SNAPSHOT_SIGNING_TAG, genesis_hash, Self::revert_epoch(), snapshot).encode()
+    }
+
+    fn do_remove_operator(operator: &T::AccountId) -> bool {
+        let removed = Operators::<T>::mutate(|operators| {
+            let before = operators.len();
+            operators.retain(|o| o != operator);
+            operators.len() != before
+        });
+        if removed {
+            Self::deposit_event(RawEvent::OperatorRemoved(operator.clone()));
+        }
+        removed
+    }
+
+    fn finalize(pending: PendingSnapshotOf<T>) {
+        let snapshot = pending.snapshot;
+        for delta in snapshot.deltas {
+            Withdrawable::<T>::mutate(&delta.account, delta.asset, |balance| {
+                *balance = balance.saturating_add(delta.amount)
+            });
+        }
+        PendingSnapshots::<T>::remove(snapshot.id);
+        StateRoots::insert(snapshot.id, snapshot.state_root);
+        LastFinalizedId::put(snapshot.id);
+        Self::deposit_event(RawEvent::SnapshotFinalized(snapshot.id));
+    }
+}
@@ -0,0 +1,179 @@
+// This file is part of Polkadex.
+
+// Copyright (C) 2020-2021 Polkadex oü.
+// SPDX-License-Identifier: GPL-3.0-or-later WITH Classpath-exception-2.0
+
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with this program. If not, see <https://www.gnu.org/licenses/>.
+//! Test environment for the OCEX pallet.
+
+use crate::{self as pallet_ocex};
+use frame_support::{parameter_types, traits::GenesisBuild, PalletId};
+use frame_system::EnsureRoot;
+use orml_traits::parameter_type_with_key;
+use polkadex_primitives::assets::AssetId;
+use sp_core::{sr25519, H256};
+use sp_keystore::{testing::KeyStore, KeystoreExt};
+use sp_runtime::{
+    testing::Header,
+    traits::{BlakeTwo256, IdentityLookup},
+};
+use std::sync::Arc;
+
+type UncheckedExtrinsic = frame_system::mocking::MockUncheckedExtrinsic<Test>;
+type Block = frame_system::mocking::MockBlock<Test>;
+
+pub type AccountId = sr25519::Public;
+
+frame_support::construct_runtime!(
+    pub enum Test where
+        Block = Block,
+        NodeBlock = Block,
+        UncheckedExtrinsic = UncheckedExtrinsic,
+    {
+        System: frame_system::{Pallet, Call, Config, Storage, Event<T>},
+        Balances: pallet_balances::{Pallet, Call, Storage, Config<T>, Event<T>},
+        Tokens: orml_tokens::{Pallet, Storage, Event<T>, Config<T>},
+        Ocex: pallet_ocex::{Pallet, Call, Storage, Config<T>, Event<T>},
+    }
+);
+
+parameter_types! {
+    pub const BlockHashCount: u64 = 250;
+}
+
+impl frame_system::Config for Test {
+    type BaseCallFilter = ();
+    type BlockWeights = ();
+    type BlockLength = ();
+    type DbWeight = ();
+    type Origin = Origin;
+    type Call = Call;
+    type Index = u64;
+    type BlockNumber = u64;
+    type Hash = H256;
+    type Hashing = BlakeTwo256;
+    type AccountId = AccountId;
+    type Lookup = IdentityLookup<Self::AccountId>;
+    type Header = Header;
+    type Event = Event;
+    type BlockHashCount = BlockHashCount;
+    type Version = ();
+    type PalletInfo = PalletInfo;
+    type AccountData = pallet_balances::AccountData<u64>;
+    type OnNewAccount = ();
+    type OnKilledAccount = ();
+    type SystemWeightInfo = ();
+    type SS58Prefix = ();
+    type OnSetCode = ();
+}
+
+parameter_types! {
+    pub const ExistentialDeposit: u64 = 1;
+}
+
+impl pallet_balances::Config for Test {
+    type MaxLocks = ();
+    type Balance = u64;
+    type DustRemoval = ();
+    type Event = Event;
+    type ExistentialDeposit = ExistentialDeposit;
+    type AccountStore = System;
+    type WeightInfo = ();
+}
+
+parameter_type_with_key! {
+    pub ExistentialDeposits: |_currency_id: AssetId| -> u64 {
+        0
+    };
+}
+
+impl orml_tokens::Config for Test {
+    type Event = Event;
+    type Balance = u64;
+    type Amount = i64;
+    type CurrencyId = AssetId;
+    type WeightInfo = ();
+    type ExistentialDeposits = ExistentialDeposits;
+    type OnDust = ();
+}
+
+parameter_types! {
+    pub const OcexPalletId: PalletId = PalletId(*b"polka/ga");
+    pub const ChallengePeriod: u64 = 10;
+    pub const ChallengeBond: u64 = 100;
+    pub const MaxOperators: u32 = 4;
+    pub const MaxDeltas: u32 = 16;
+    pub const MaxPendingSnapshots: u32 = 8;
+}
+
+impl pallet_ocex::Config for Test {
+    type Event = Event;
+    type Currency = Tokens;
+    type BondCurrency = Balances;
+    type PalletId = OcexPalletId;
+    type OperatorPublic = sr25519::Public;
+    type OperatorSignature = sr25519::Signature;
+    type OperatorOrigin = EnsureRoot<AccountId>;
+    type DisputeOrigin = EnsureRoot<AccountId>;
+    type ChallengePeriod = ChallengePeriod;
+    type ChallengeBond = ChallengeBond;
+    type Slash = ();
+    type MaxOperators = MaxOperators;
+    type MaxDeltas = MaxDeltas;
+    type MaxPendingSnapshots = MaxPendingSnapshots;
+    type WeightInfo = ();
+}
+
+/// Key pair of the operator registered at genesis.
+pub fn operator() -> sr25519::Pair {
+    use sp_core::Pair;
+    sr25519::Pair::from_seed(&[42u8; 32])
+}
+
+pub fn account(seed: u8) -> AccountId {
+    sr25519::Public::from_raw([seed; 32])
+}
+
+pub const ALICE: u8 = 1;
+pub const BOB: u8 = 2;
+
+pub fn new_test_ext() -> sp_io::TestExternalities {
+    use sp_core::Pair;
+    let mut t = frame_system::GenesisConfig::default().build_storage::<Test>().unwrap();
+    pallet_balances::GenesisConfig::<Test> {
+        balances: vec![(account(ALICE), 1_000), (account(BOB), 1_000)],
+    }
+    .assimilate_storage(&mut t)
+    .unwrap();
+    GenesisBuild::<Test>::assimilate_storage(
+        &orml_tokens::GenesisConfig::<Test> {
+            endowed_accounts: vec![
+                (account(ALICE), AssetId::DOT, 1_000),
+                (account(BOB), AssetId::DOT, 1_000),
+            ],
+        },
+        &mut t,
+    )
+    .unwrap();
+    pallet_ocex::GenesisConfig::<Test> {
+        operators: vec![operator().public()],
+    }
+    .assimilate_storage(&mut t)
+    .unwrap();
+    let mut ext = sp_io::TestExternalities::new(t);
+    // Benchmarks sign snapshots with keys generated in the keystore.
+    ext.register_extension(KeystoreExt(Arc::new(KeyStore::new())));
+    ext.execute_with(|| System::set_block_number(1));
+    ext
+}
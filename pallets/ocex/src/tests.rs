@@ -0,0 +1,280 @@
+// This file is part of Polkadex.
+
+// Copyright (C) 2020-2021 Polkadex oü.
+// SPDX-License-Identifier: GPL-3.0-or-later WITH Classpath-exception-2.0
+
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with this program. If not, see <https://www.gnu.org/licenses/>.
+use crate::{mock::*, BalanceDelta, Error, RawEvent, Snapshot, SnapshotOf};
+use frame_support::{assert_noop, assert_ok, traits::OnInitialize};
+use orml_traits::MultiCurrency;
+use polkadex_primitives::assets::AssetId;
+use sp_core::{sr25519, Pair, H256};
+
+fn snapshot(id: u64, deltas: Vec<(u8, u64)>) -> SnapshotOf<Test> {
+    Snapshot {
+        id,
+        state_root: H256::repeat_byte(id as u8),
+        deltas: deltas
+            .into_iter()
+            .map(|(who, amount)| BalanceDelta { account: account(who), asset: AssetId::DOT, amount })
+            .collect(),
+    }
+}
+
+fn submit_signed_by(signer: &sr25519::Pair, snapshot: SnapshotOf<Test>) -> frame_support::dispatch::DispatchResult {
+    let signature = signer.sign(&Ocex::signing_payload(&snapshot));
+    Ocex::submit_snapshot(Origin::signed(account(BOB)), snapshot, operator().public(), signature)
+}
+
+fn submit(snapshot: SnapshotOf<Test>) {
+    assert_ok!(submit_signed_by(&operator(), snapshot));
+}
+
+fn run_to_block(n: u64) {
+    while System::block_number() < n {
+        System::set_block_number(System::block_number() + 1);
+        Ocex::on_initialize(System::block_number());
+    }
+}
+
+fn dot(who: &AccountId) -> u64 {
+    Tokens::free_balance(AssetId::DOT, who)
+}
+
+#[test]
+fn deposits_go_into_custody() {
+    new_test_ext().execute_with(|| {
+        assert_ok!(Ocex::deposit(Origin::signed(account(ALICE)), AssetId::DOT, 300));
+        assert_eq!(dot(&account(ALICE)), 700);
+        assert_eq!(dot(&Ocex::custody_account()), 300);
+        let deposited: Event = RawEvent::Deposited(account(ALICE), AssetId::DOT, 300).into();
+        assert!(System::events().iter().any(|record| record.event == deposited));
+
+        assert_noop!(Ocex::deposit(Origin::signed(account(ALICE)), AssetId::DOT, 0), Error::<Test>::ZeroAmount);
+    });
+}
+
+#[test]
+fn operators_are_managed_by_operator_origin() {
+    new_test_ext().execute_with(|| {
+        assert_noop!(
+            Ocex::register_operator(Origin::signed(account(ALICE)), account(ALICE)),
+            sp_runtime::DispatchError::BadOrigin
+        );
+        assert_noop!(
+            Ocex::register_operator(Origin::root(), operator().public()),
+            Error::<Test>::AlreadyOperator
+        );
+        assert_ok!(Ocex::register_operator(Origin::root(), account(ALICE)));
+        assert_ok!(Ocex::remove_operator(Origin::root(), operator().public()));
+        assert_eq!(Ocex::operators(), vec![account(ALICE)]);
+        assert_noop!(submit_signed_by(&operator(), snapshot(1, vec![])), Error::<Test>::UnknownOperator);
+    });
+}
+
+#[test]
+fn snapshots_must_be_signed_and_sequential() {
+    new_test_ext().execute_with(|| {
+        let impostor = sr25519::Pair::from_seed(&[7u8; 32]);
+        assert_noop!(submit_signed_by(&impostor, snapshot(1, vec![])), Error::<Test>::BadSignature);
+        assert_noop!(submit_signed_by(&operator(), snapshot(2, vec![])), Error::<Test>::UnexpectedSnapshotId);
+
+        submit(snapshot(1, vec![]));
+        assert_noop!(submit_signed_by(&operator(), snapshot(1, vec![])), Error::<Test>::UnexpectedSnapshotId);
+        submit(snapshot(2, vec![]));
+        assert_eq!(Ocex::last_snapshot_id(), 2);
+
+        let too_many = (0..=MaxDeltas::get() as u8).map(|who| (who, 1)).collect();
+        assert_noop!(submit_signed_by(&operator(), snapshot(3, too_many)), Error::<Test>::TooManyDeltas);
+    });
+}
+
+#[test]
+fn finalized_snapshots_release_withdrawals() {
+    new_test_ext().execute_with(|| {
+        assert_ok!(Ocex::deposit(Origin::signed(account(ALICE)), AssetId::DOT, 500));
+        submit(snapshot(1, vec![(BOB, 200)]));
+
+        // Nothing can be withdrawn during the challenge period.
+        run_to_block(1 + ChallengePeriod::get() - 1);
+        assert_eq!(Ocex::last_finalized_id(), 0);
+        assert_noop!(
+            Ocex::withdraw(Origin::signed(account(BOB)), AssetId::DOT, 200),
+            Error::<Test>::InsufficientWithdrawable
+        );
+
+        run_to_block(1 + ChallengePeriod::get());
+        assert_eq!(Ocex::last_finalized_id(), 1);
+        assert_eq!(Ocex::state_root(1), Some(H256::repeat_byte(1)));
+        assert!(Ocex::pending_snapshot(1).is_none());
+        assert_eq!(Ocex::withdrawable(account(BOB), AssetId::DOT), 200);
+
+        assert_noop!(
+            Ocex::withdraw(Origin::signed(account(BOB)), AssetId::DOT, 201),
+            Error::<Test>::InsufficientWithdrawable
+        );
+        assert_ok!(Ocex::withdraw(Origin::signed(account(BOB)), AssetId::DOT, 150));
+        assert_eq!(dot(&account(BOB)), 1_150);
+        assert_eq!(dot(&Ocex::custody_account()), 350);
+        assert_eq!(Ocex::withdrawable(account(BOB), AssetId::DOT), 50);
+    });
+}
+
+#[test]
+fn snapshots_finalize_in_order() {
+    new_test_ext().execute_with(|| {
+        submit(snapshot(1, vec![]));
+        run_to_block(5);
+        submit(snapshot(2, vec![]));
+
+        run_to_block(11);
+        assert_eq!(Ocex::last_finalized_id(), 1);
+        run_to_block(15);
+        assert_eq!(Ocex::last_finalized_id(), 2);
+    });
+}
+
+#[test]
+fn challenges_need_a_pending_snapshot_and_a_bond() {
+    new_test_ext().execute_with(|| {
+        assert_noop!(Ocex::challenge(Origin::signed(account(ALICE)), 1), Error::<Test>::UnknownSnapshot);
+        submit(snapshot(1, vec![]));
+        assert_noop!(
+            Ocex::challenge(Origin::signed(account(9)), 1),
+            pallet_balances::Error::<Test>::InsufficientBalance
+        );
+
+        assert_ok!(Ocex::challenge(Origin::signed(account(ALICE)), 1));
+        assert_eq!(Balances::reserved_balance(account(ALICE)), ChallengeBond::get());
+        assert_noop!(Ocex::challenge(Origin::signed(account(BOB)), 1), Error::<Test>::DisputeOpen);
+
+        // A disputed snapshot is not finalized.
+        run_to_block(20);
+        assert_eq!(Ocex::last_finalized_id(), 0);
+    });
+}
+
+#[test]
+fn challenge_period_ends() {
+    new_test_ext().execute_with(|| {
+        submit(snapshot(1, vec![]));
+        System::set_block_number(1 + ChallengePeriod::get());
+        assert_noop!(Ocex::challenge(Origin::signed(account(ALICE)), 1), Error::<Test>::ChallengePeriodOver);
+    });
+}
+
+#[test]
+fn upheld_dispute_reverts_snapshots() {
+    new_test_ext().execute_with(|| {
+        submit(snapshot(1, vec![(ALICE, 10)]));
+        submit(snapshot(2, vec![(BOB, 10)]));
+        submit(snapshot(3, vec![(BOB, 10)]));
+        assert_ok!(Ocex::challenge(Origin::signed(account(ALICE)), 2));
+
+        assert_noop!(Ocex::resolve_dispute(Origin::signed(account(ALICE)), true), sp_runtime::DispatchError::BadOrigin);
+        assert_ok!(Ocex::resolve_dispute(Origin::root(), true));
+
+        assert_eq!(Balances::reserved_balance(account(ALICE)), 0);
+        assert_eq!(Balances::free_balance(account(ALICE)), 1_000);
+        assert_eq!(Ocex::last_snapshot_id(), 1);
+        assert!(Ocex::pending_snapshot(2).is_none());
+        assert!(Ocex::pending_snapshot(3).is_none());
+        assert!(Ocex::operators().is_empty());
+        assert!(Ocex::open_dispute().is_none());
+
+        run_to_block(20);
+        assert_eq!(Ocex::last_finalized_id(), 1);
+        assert_eq!(Ocex::withdrawable(account(ALICE), AssetId::DOT), 10);
+        assert_eq!(Ocex::withdrawable(account(BOB), AssetId::DOT), 0);
+    });
+}
+
+#[test]
+fn reverted_snapshots_cannot_be_replayed() {
+    new_test_ext().execute_with(|| {
+        let reverted = snapshot(2, vec![(BOB, 10)]);
+        let signature = operator().sign(&Ocex::signing_payload(&reverted));
+        submit(snapshot(1, vec![]));
+        assert_ok!(Ocex::submit_snapshot(
+            Origin::signed(account(BOB)),
+            reverted.clone(),
+            operator().public(),
+            signature.clone()
+        ));
+        assert_ok!(Ocex::challenge(Origin::signed(account(ALICE)), 2));
+        assert_ok!(Ocex::resolve_dispute(Origin::root(), true));
+        assert_eq!(Ocex::revert_epoch(), 1);
+
+        assert_ok!(Ocex::register_operator(Origin::root(), operator().public()));
+        assert_noop!(
+            Ocex::submit_snapshot(Origin::signed(account(BOB)), reverted.clone(), operator().public(), signature),
+            Error::<Test>::BadSignature
+        );
+        submit(reverted);
+    });
+}
+
+#[test]
+fn rejected_dispute_slashes_bond() {
+    new_test_ext().execute_with(|| {
+        submit(snapshot(1, vec![(BOB, 10)]));
+        assert_ok!(Ocex::challenge(Origin::signed(account(ALICE)), 1));
+        run_to_block(20);
+        assert_eq!(Ocex::last_finalized_id(), 0);
+
+        assert_ok!(Ocex::resolve_dispute(Origin::root(), false));
+        assert_eq!(Balances::reserved_balance(account(ALICE)), 0);
+        assert_eq!(Balances::free_balance(account(ALICE)), 1_000 - ChallengeBond::get());
+        assert_noop!(Ocex::resolve_dispute(Origin::root(), false), Error::<Test>::NoDispute);
+
+        run_to_block(21);
+        assert_eq!(Ocex::last_finalized_id(), 1);
+        assert_eq!(Ocex::withdrawable(account(BOB), AssetId::DOT), 10);
+    });
+}
+
+#[test]
+fn rejected_dispute_extends_later_challenge_periods() {
+    new_test_ext().execute_with(|| {
+        submit(snapshot(1, vec![]));
+        run_to_block(3);
+        submit(snapshot(2, vec![]));
+        run_to_block(5);
+        assert_ok!(Ocex::challenge(Origin::signed(account(ALICE)), 1));
+        assert_noop!(Ocex::challenge(Origin::signed(account(BOB)), 2), Error::<Test>::DisputeOpen);
+        run_to_block(8);
+        submit(snapshot(3, vec![]));
+
+        // Snapshot 2 was blocked from block 5 and snapshot 3 since its submission.
+        run_to_block(20);
+        assert_ok!(Ocex::resolve_dispute(Origin::root(), false));
+        assert_eq!(Ocex::pending_snapshot(2).unwrap().submitted_at, 18);
+        assert_eq!(Ocex::pending_snapshot(3).unwrap().submitted_at, 20);
+        assert_ok!(Ocex::challenge(Origin::signed(account(BOB)), 2));
+    });
+}
+
+#[test]
+fn pending_snapshots_are_bounded() {
+    new_test_ext().execute_with(|| {
+        for id in 1..=MaxPendingSnapshots::get() as u64 {
+            submit(snapshot(id, vec![]));
+        }
+        let next = MaxPendingSnapshots::get() as u64 + 1;
+        assert_noop!(
+            submit_signed_by(&operator(), snapshot(next, vec![])),
+            Error::<Test>::TooManyPendingSnapshots
+        );
+    });
+}
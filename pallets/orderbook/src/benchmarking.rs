@@ -0,0 +1,217 @@
+// This file is part of Polkadex.
+
+// Copyright (C) 2020-2021 Polkadex oü.
+// SPDX-License-Identifier: GPL-3.0-or-later WITH Classpath-exception-2.0
+
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with this program. If not, see <https://www.gnu.org/licenses/>.
+//! Benchmarks for `pallet_orderbook`.
+
+#![cfg(feature = "runtime-benchmarks")]
+
+use super::*;
+use frame_benchmarking::{account, benchmarks, impl_benchmark_test_suite, whitelisted_caller};
+use frame_support::traits::{OnFinalize, OnInitialize};
+use frame_system::RawOrigin;
+
+const SEED: u32 = 0;
+const PAIR: TradingPair = TradingPair { base: AssetId::DOT, quote: AssetId::USD };
+
+fn funded<T: Config>(who: &T::AccountId) -> Result<(), &'static str> {
+    T::Currency::deposit(PAIR.base, who, 1_000_000u32.into())?;
+    T::Currency::deposit(PAIR.quote, who, 1_000_000u32.into())?;
+    Ok(())
+}
+
+fn price(value: u32) -> FixedU128 {
+    FixedU128::saturating_from_integer(value)
+}
+
+/// Opens the pair and rests `count` asks of 10 units at a price of 2, from different accounts.
+fn resting_asks<T: Config>(count: u32) -> Result<(), &'static str> {
+    TradingPairs::insert(&PAIR, true);
+    for i in 0..count {
+        let maker: T::AccountId = account("maker", i, SEED);
+        funded::<T>(&maker)?;
+        Module::<T>::place_order(
+            RawOrigin::Signed(maker).into(),
+            PAIR,
+            Side::Ask,
+            10u32.into(),
+            Some(price(2)),
+            None,
+            TimeInForce::GoodTillCancelled,
+        )?;
+    }
+    Ok(())
+}
+
+/// Places a bid of the caller for 10 units at a price of 1 that is good till `until`.
+fn good_till_block_bid<T: Config>(until: T::BlockNumber) -> Result<(T::AccountId, OrderId), &'static str> {
+    TradingPairs::insert(&PAIR, true);
+    let caller: T::AccountId = whitelisted_caller();
+    funded::<T>(&caller)?;
+    Module::<T>::place_order(
+        RawOrigin::Signed(caller.clone()).into(),
+        PAIR,
+        Side::Bid,
+        10u32.into(),
+        Some(price(1)),
+        None,
+        TimeInForce::GoodTillBlock(until),
+    )?;
+    Ok((caller, Module::<T>::next_order_id() - 1))
+}
+
+benchmarks! {
+    register_pair {
+        let pair = TradingPair { base: AssetId::BTC, quote: AssetId::USD };
+        let origin = T::GovernanceOrigin::successful_origin();
+    }: _<T::Origin>(origin, pair)
+    verify {
+        assert!(Module::<T>::is_trading_pair(&pair));
+    }
+
+    // Fills `f` asks and rests the rest of the bid.
+    place_order {
+        let f in 0 .. T::MaxFills::get();
+        resting_asks::<T>(f)?;
+        let caller: T::AccountId = whitelisted_caller();
+        funded::<T>(&caller)?;
+        let amount = (f + 1) * 10;
+    }: _(
+        RawOrigin::Signed(caller),
+        PAIR,
+        Side::Bid,
+        amount.into(),
+        Some(price(2)),
+        None,
+        TimeInForce::GoodTillBlock(frame_system::Pallet::<T>::block_number() + 10u32.into())
+    )
+    verify {
+        assert_eq!(Module::<T>::book(&PAIR, Side::Bid).len(), 1);
+        assert!(Module::<T>::book(&PAIR, Side::Ask).is_empty());
+    }
+
+    cancel_order {
+        let (caller, id) = good_till_block_bid::<T>(frame_system::Pallet::<T>::block_number() + 10u32.into())?;
+    }: _(RawOrigin::Signed(caller), id)
+    verify {
+        assert!(Module::<T>::order(id).is_none());
+    }
+
+    expire_order {
+        let (_, id) = good_till_block_bid::<T>(frame_system::Pallet::<T>::block_number() + 10u32.into())?;
+    }: _(RawOrigin::Root, id)
+    verify {
+        assert!(Module::<T>::order(id).is_none());
+    }
+
+    // Evaluates `c` triggers of a pair, none of which fires.
+    check_triggers {
+        let c in 0 .. T::MaxConditionalOrders::get();
+        TradingPairs::insert(&PAIR, true);
+        for i in 0..c {
+            let trader: T::AccountId = account("trader", i, SEED);
+            funded::<T>(&trader)?;
+            Module::<T>::place_order(
+                RawOrigin::Signed(trader).into(),
+                PAIR,
+                Side::Bid,
+                10u32.into(),
+                Some(price(1)),
+                Some(Trigger { kind: TriggerKind::Stop, price: price(3) }),
+                TimeInForce::GoodTillCancelled,
+            )?;
+        }
+        LastPrice::insert(&PAIR, price(2));
+        MovedPairs::put(vec![PAIR]);
+        let now = frame_system::Pallet::<T>::block_number() + 1u32.into();
+    }: {
+        Module::<T>::on_initialize(now);
+    }
+    verify {
+        assert_eq!(Module::<T>::conditional_orders(&PAIR).len() as u32, c);
+        assert!(Module::<T>::moved_pairs().is_empty());
+    }
+
+    // Fires a stop-limit bid that fills `f` asks and rests the rest.
+    trigger_order {
+        let f in 0 .. T::MaxFills::get();
+        resting_asks::<T>(f)?;
+        let caller: T::AccountId = whitelisted_caller();
+        funded::<T>(&caller)?;
+        Module::<T>::place_order(
+            RawOrigin::Signed(caller).into(),
+            PAIR,
+            Side::Bid,
+            ((f + 1) * 10).into(),
+            Some(price(2)),
+            Some(Trigger { kind: TriggerKind::Stop, price: price(2) }),
+            TimeInForce::GoodTillCancelled,
+        )?;
+        LastPrice::insert(&PAIR, price(2));
+        MovedPairs::put(vec![PAIR]);
+        let now = frame_system::Pallet::<T>::block_number() + 1u32.into();
+    }: {
+        Module::<T>::on_initialize(now);
+    }
+    verify {
+        assert!(Module::<T>::conditional_orders(&PAIR).is_empty());
+        assert_eq!(Module::<T>::book(&PAIR, Side::Bid).len(), 1);
+    }
+
+    set_market_mode {
+        TradingPairs::insert(&PAIR, true);
+        let origin = T::GovernanceOrigin::successful_origin();
+    }: _<T::Origin>(origin, PAIR, MarketMode::BatchAuction)
+    verify {
+        assert_eq!(Module::<T>::market_mode(&PAIR), MarketMode::BatchAuction);
+    }
+
+    // Clears an auction of `o` bids sharing the most resting asks it takes, each bid resting with
+    // what it did not get.
+    clear_auction {
+        let o in 1 .. T::MaxBatchOrders::get();
+        resting_asks::<T>(T::MaxFills::get())?;
+        AuctionPairs::put(vec![PAIR]);
+        let amount = T::MaxFills::get() * 20 / o + 1;
+        for i in 0..o {
+            let trader: T::AccountId = account("trader", i, SEED);
+            funded::<T>(&trader)?;
+            Module::<T>::place_order(
+                RawOrigin::Signed(trader).into(),
+                PAIR,
+                Side::Bid,
+                amount.into(),
+                Some(price(2)),
+                None,
+                TimeInForce::GoodTillCancelled,
+            )?;
+        }
+        let now = frame_system::Pallet::<T>::block_number();
+    }: {
+        Module::<T>::on_finalize(now);
+    }
+    verify {
+        assert!(Module::<T>::batch_orders(&PAIR).is_empty());
+        assert!(Module::<T>::book(&PAIR, Side::Ask).is_empty());
+        assert_eq!(Module::<T>::book(&PAIR, Side::Bid).len() as u32, o);
+    }
+}
+
+impl_benchmark_test_suite!(
+    Module,
+    crate::mock::new_test_ext(),
+    crate::mock::Test,
+);
@@ -0,0 +1,1090 @@
+// This file is part of Polkadex.
+
+// Copyright (C) 2020-2021 Polkadex oü.
+// SPDX-License-Identifier: GPL-3.0-or-later WITH Classpath-exception-2.0
+
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with this program. If not, see <https://www.gnu.org/licenses/>.
+//! # Orderbook pallet
+//!
+//! On-chain order books matching orders by price and time priority.
+//!
+//! - [`Config::GovernanceOrigin`] opens trading pairs with [`Call::register_pair`].
+//! - Traders place orders with [`Call::place_order`]. An order has a limit price, or none for a
+//!   market order, and a [`TimeInForce`]: good till cancelled, immediate or cancel, fill or
+//!   kill, post only, or good till a block. Market orders must be immediate or cancel or fill or
+//!   kill. What is left of a limit order that is good till cancelled, post only or good till a
+//!   block rests in the book until it is filled, cancelled with [`Call::cancel_order`] or, for
+//!   good till block orders, expired by [`Config::Scheduler`] after its last block.
+//! - An order can wait for a [`Trigger`] on the last trade price of its pair before trading:
+//!   stop orders fire when the price moves against the position they open, take-profit orders
+//!   when it moves in its favour. Stop-market, stop-limit, take-profit market and take-profit
+//!   limit orders are orders with a trigger and without or with a limit price. Triggers are
+//!   evaluated at the start of the block following a trade, spending at most
+//!   [`Config::MaxTriggerWeight`]; pairs that do not fit are evaluated in the next blocks.
+//! - The assets an order may trade are reserved while it is open: the base amount of asks and
+//!   the quote amount bids pay at their limit price. Market bids waiting for a trigger reserve
+//!   the amount at the trigger price plus [`Config::MarketBidBuffer`] and trade as much as that
+//!   pays for; market bids without a trigger may spend the caller's whole free quote balance.
+//!   Whatever an order did not trade is unreserved when it is filled, cancelled or expired.
+//! - [`Config::GovernanceOrigin`] can switch a pair to [`MarketMode::BatchAuction`] with
+//!   [`Call::set_market_mode`]. Orders of such a pair do not trade when placed or triggered but
+//!   are collected, and cleared with the best [`Config::MaxFills`] resting orders of each side at
+//!   the end of the block, all at a single price. The price is the limit price trading the
+//!   largest volume, then leaving the smallest imbalance, then the lowest. Better limits fill
+//!   first, and orders with the same limit share what is left pro rata, larger orders first for
+//!   what rounding leaves. As nothing depends on the order of the extrinsics in the block, block
+//!   authors gain nothing by reordering them. What is left of the orders rests in the book or is
+//!   cancelled according to their time in force. Fill or kill orders are not accepted and post
+//!   only orders rest in the book directly. Switching a pair back to continuous trading clears
+//!   the orders collected so far at once.
+
+#![cfg_attr(not(feature = "std"), no_std)]
+
+use codec::{Decode, Encode};
+use frame_support::{
+    decl_error, decl_event, decl_module, decl_storage,
+    dispatch::DispatchResult,
+    ensure,
+    traits::{
+        schedule::{DispatchTime, Named as ScheduleNamed, LOWEST_PRIORITY},
+        BalanceStatus, EnsureOrigin, Get,
+    },
+    transactional,
+    weights::Weight,
+    IterableStorageMap, Parameter, StorageDoubleMap, StorageMap, StorageValue,
+};
+use frame_system::{ensure_root, ensure_signed};
+use orml_traits::{MultiCurrency, MultiReservableCurrency};
+use polkadex_primitives::assets::AssetId;
+use sp_runtime::{
+    helpers_128bit::multiply_by_rational,
+    traits::{
+        AtLeast32BitUnsigned, MaybeSerializeDeserialize, Member, One, Saturating, UniqueSaturatedFrom,
+        UniqueSaturatedInto, Zero,
+    },
+    DispatchError, FixedPointNumber, FixedPointOperand, FixedU128, Permill, RuntimeDebug,
+};
+use sp_std::{cmp::Ordering, prelude::*};
+
+pub use weights::WeightInfo;
+
+#[cfg(feature = "runtime-benchmarks")]
+mod benchmarking;
+#[cfg(test)]
+mod mock;
+#[cfg(test)]
+mod tests;
+pub mod weights;
+
+/// Prefix of the names the expiries of orders are scheduled under.
+pub const EXPIRY_TASK_PREFIX: [u8; 16] = *b"orderbook/expiry";
+
+pub type OrderId = u64;
+
+/// A market trading a base asset against a quote asset.
+#[derive(Encode, Decode, Clone, Copy, PartialEq, Eq, RuntimeDebug)]
+pub struct TradingPair {
+    /// Asset being bought or sold.
+    pub base: AssetId,
+    /// Asset the base asset is priced in.
+    pub quote: AssetId,
+}
+
+/// How the orders of a pair trade.
+#[derive(Encode, Decode, Clone, Copy, PartialEq, Eq, RuntimeDebug)]
+pub enum MarketMode {
+    /// Orders trade against the book as soon as they are placed or triggered.
+    Continuous,
+    /// Orders are collected and cleared at a single price at the end of the block.
+    BatchAuction,
+}
+
+/// Side of an order.
+#[derive(Encode, Decode, Clone, Copy, PartialEq, Eq, RuntimeDebug)]
+pub enum Side {
+    /// Buys the base asset with the quote asset.
+    Bid,
+    /// Sells the base asset for the quote asset.
+    Ask,
+}
+
+impl Side {
+    fn opposite(self) -> Side {
+        match self {
+            Side::Bid => Side::Ask,
+            Side::Ask => Side::Bid,
+        }
+    }
+
+    /// Whether an order on this side with a `limit` price trades at `price`.
+    fn accepts(self, limit: FixedU128, price: FixedU128) -> bool {
+        match self {
+            Side::Bid => price <= limit,
+            Side::Ask => price >= limit,
+        }
+    }
+}
+
+#[derive(Encode, Decode, Clone, Copy, PartialEq, Eq, RuntimeDebug)]
+pub enum TriggerKind {
+    /// Fires when the price rises to the trigger price for bids, or falls to it for asks.
+    Stop,
+    /// Fires when the price falls to the trigger price for bids, or rises to it for asks.
+    TakeProfit,
+}
+
+/// Condition on the last trade price an order waits for before trading.
+#[derive(Encode, Decode, Clone, Copy, PartialEq, Eq, RuntimeDebug)]
+pub struct Trigger {
+    pub kind: TriggerKind,
+    /// Price in units of the quote asset per unit of the base asset.
+    pub price: FixedU128,
+}
+
+impl Trigger {
+    /// Whether a trade at `last` fires the trigger of an order on `side`.
+    pub fn fires(&self, side: Side, last: FixedU128) -> bool {
+        match (self.kind, side) {
+            (TriggerKind::Stop, Side::Bid) | (TriggerKind::TakeProfit, Side::Ask) => last >= self.price,
+            (TriggerKind::Stop, Side::Ask) | (TriggerKind::TakeProfit, Side::Bid) => last <= self.price,
+        }
+    }
+}
+
+/// How long an order stays open.
+#[derive(Encode, Decode, Clone, Copy, PartialEq, Eq, RuntimeDebug)]
+pub enum TimeInForce<BlockNumber> {
+    /// Rests in the book until filled or cancelled.
+    GoodTillCancelled,
+    /// Trades what it can at once; the rest is cancelled.
+    ImmediateOrCancel,
+    /// Trades its whole amount at once or not at all.
+    FillOrKill,
+    /// Rests in the book without trading; rejected if it would trade at once.
+    PostOnly,
+    /// Rests in the book until filled, cancelled or past the given block.
+    GoodTillBlock(BlockNumber),
+}
+
+impl<BlockNumber> TimeInForce<BlockNumber> {
+    /// Whether what is left of the order after trading rests in the book.
+    fn rests(&self) -> bool {
+        !matches!(self, TimeInForce::ImmediateOrCancel | TimeInForce::FillOrKill)
+    }
+}
+
+/// An open order.
+#[derive(Encode, Decode, Clone, PartialEq, Eq, RuntimeDebug)]
+pub struct Order<AccountId, Balance, BlockNumber> {
+    pub owner: AccountId,
+    pub pair: TradingPair,
+    pub side: Side,
+    /// Limit price in units of the quote asset per unit of the base asset; market orders have
+    /// none.
+    pub limit: Option<FixedU128>,
+    /// Condition the order waits for before trading; cleared once it fires.
+    pub trigger: Option<Trigger>,
+    pub time_in_force: TimeInForce<BlockNumber>,
+    /// Amount of the base asset left to buy or sell.
+    pub amount: Balance,
+    /// Amount reserved for the order: of the quote asset for bids, of the base asset for asks.
+    pub reserved: Balance,
+}
+
+impl<AccountId, Balance, BlockNumber> Order<AccountId, Balance, BlockNumber> {
+    /// The asset the order pays with.
+    fn reserved_asset(&self) -> AssetId {
+        match self.side {
+            Side::Bid => self.pair.quote,
+            Side::Ask => self.pair.base,
+        }
+    }
+}
+
+/// A trade between a resting order and an incoming one.
+struct Fill<Balance> {
+    maker: OrderId,
+    price: FixedU128,
+    base: Balance,
+    quote: Balance,
+}
+
+/// An order taking part in an auction.
+struct Bidder<Order, Balance> {
+    id: OrderId,
+    order: Order,
+    /// Whether the order rested in the book before the auction.
+    resting: bool,
+    /// Amount of the base asset the order can trade at the clearing price.
+    eligible: Balance,
+    /// Amount of the base asset the auction gives the order.
+    allocated: Balance,
+}
+
+pub type TimeInForceOf<T> = TimeInForce<<T as frame_system::Config>::BlockNumber>;
+pub type OrderOf<T> = Order<
+    <T as frame_system::Config>::AccountId,
+    <T as Config>::Balance,
+    <T as frame_system::Config>::BlockNumber,
+>;
+
+/// Gives other pallets access to the order books, for the accounts they hold assets with.
+pub trait OrderManager<AccountId, Balance, BlockNumber> {
+    /// Places an order of `owner` for `amount` of the base asset of `pair`, a market order
+    /// without a `limit` price, and returns its id.
+    fn place_order(
+        owner: &AccountId,
+        pair: TradingPair,
+        side: Side,
+        amount: Balance,
+        limit: Option<FixedU128>,
+        time_in_force: TimeInForce<BlockNumber>,
+    ) -> Result<OrderId, DispatchError>;
+
+    /// Cancels the open order `id` of `owner`.
+    fn cancel_order(owner: &AccountId, id: OrderId) -> DispatchResult;
+
+    /// Whether order `id` is open.
+    fn is_open(id: OrderId) -> bool;
+
+    /// Weight of [`OrderManager::place_order`].
+    fn place_order_weight() -> Weight;
+
+    /// Weight of [`OrderManager::cancel_order`].
+    fn cancel_order_weight() -> Weight;
+
+    /// Opens `pair` for trading, for the benchmarks of pallets placing orders.
+    #[cfg(feature = "runtime-benchmarks")]
+    fn open_pair(pair: TradingPair);
+}
+
+/// Configure the pallet by specifying the parameters and types on which it depends.
+pub trait Config: frame_system::Config {
+    /// Because this pallet emits events, it depends on the runtime's definition of an event.
+    type Event: From<Event<Self>> + Into<<Self as frame_system::Config>::Event>;
+    /// Balance of the traded assets.
+    type Balance: Parameter
+        + Member
+        + AtLeast32BitUnsigned
+        + FixedPointOperand
+        + Default
+        + Copy
+        + MaybeSerializeDeserialize;
+    /// Assets the orders trade.
+    type Currency: MultiReservableCurrency<Self::AccountId, CurrencyId = AssetId, Balance = Self::Balance>;
+    /// The runtime call expiring orders is dispatched as.
+    type Call: From<Call<Self>>;
+    /// The caller origin the expiries are scheduled with.
+    type PalletsOrigin: From<frame_system::RawOrigin<Self::AccountId>>;
+    /// Schedules the expiry of good till block orders.
+    type Scheduler: ScheduleNamed<Self::BlockNumber, <Self as Config>::Call, Self::PalletsOrigin>;
+    /// Origin allowed to open trading pairs.
+    type GovernanceOrigin: EnsureOrigin<Self::Origin>;
+    /// Maximum number of orders resting on each side of a book.
+    type MaxOrdersPerSide: Get<u32>;
+    /// Maximum number of orders of a pair waiting for their trigger.
+    type MaxConditionalOrders: Get<u32>;
+    /// Maximum number of resting orders an order trades against.
+    type MaxFills: Get<u32>;
+    /// Share over the trigger price market bids reserve while waiting for their trigger.
+    type MarketBidBuffer: Get<Permill>;
+    /// Maximum weight spent evaluating triggers at the start of a block.
+    type MaxTriggerWeight: Get<Weight>;
+    /// Maximum number of pairs cleared in batch auctions.
+    type MaxAuctionPairs: Get<u32>;
+    /// Maximum number of orders of a pair collected for the auction of a block.
+    type MaxBatchOrders: Get<u32>;
+    /// Weight information for extrinsics in this pallet.
+    type WeightInfo: WeightInfo;
+}
+
+decl_storage! {
+    trait Store for Module<T: Config> as Orderbook {
+        /// Pairs open for trading.
+        pub TradingPairs get(fn is_trading_pair): map hasher(blake2_128_concat) TradingPair => bool;
+        /// Id of the next order placed.
+        pub NextOrderId get(fn next_order_id): OrderId;
+        /// Open orders, resting in a book or waiting for their trigger.
+        pub Orders get(fn order): map hasher(twox_64_concat) OrderId => Option<OrderOf<T>>;
+        /// Limit prices and ids of the orders resting on each side of a book, best price first
+        /// and oldest first among equal prices.
+        pub OrderBook get(fn book):
+            double_map hasher(blake2_128_concat) TradingPair, hasher(twox_64_concat) Side
+            => Vec<(FixedU128, OrderId)>;
+        /// Orders of each pair waiting for their trigger.
+        pub ConditionalOrders get(fn conditional_orders):
+            map hasher(blake2_128_concat) TradingPair => Vec<OrderId>;
+        /// Price of the last trade of each pair.
+        pub LastPrice get(fn last_price): map hasher(blake2_128_concat) TradingPair => Option<FixedU128>;
+        /// Pairs that traded since their triggers were last evaluated.
+        pub MovedPairs get(fn moved_pairs): Vec<TradingPair>;
+        /// Pairs cleared in batch auctions.
+        pub AuctionPairs get(fn auction_pairs): Vec<TradingPair>;
+        /// Orders of each pair collected for the auction at the end of the block.
+        pub BatchOrders get(fn batch_orders): map hasher(blake2_128_concat) TradingPair => Vec<OrderId>;
+    }
+}
+
+decl_event!(
+    pub enum Event<T>
+    where
+        AccountId = <T as frame_system::Config>::AccountId,
+        Balance = <T as Config>::Balance,
+    {
+        /// A trading pair was opened. \[base, quote\]
+        PairRegistered(AssetId, AssetId),
+        /// An order was placed. \[id, owner, base, quote, side\]
+        OrderPlaced(OrderId, AccountId, AssetId, AssetId, Side),
+        /// The trigger of an order fired. \[id\]
+        OrderTriggered(OrderId),
+        /// Two orders traded. \[maker, taker, price, base_amount, quote_amount\]
+        Trade(OrderId, OrderId, FixedU128, Balance, Balance),
+        /// An order was filled. \[id\]
+        OrderFilled(OrderId),
+        /// An order, or what was left of it, was cancelled. \[id\]
+        OrderCancelled(OrderId),
+        /// A good till block order expired. \[id\]
+        OrderExpired(OrderId),
+        /// The market mode of a pair changed. \[base, quote, mode\]
+        MarketModeSet(AssetId, AssetId, MarketMode),
+        /// Two orders traded in an auction. \[bid, ask, price, base_amount, quote_amount\]
+        AuctionTrade(OrderId, OrderId, FixedU128, Balance, Balance),
+        /// An auction cleared. \[base, quote, price, base_amount\]
+        AuctionCleared(AssetId, AssetId, FixedU128, Balance),
+    }
+);
+
+decl_error! {
+    pub enum Error for Module<T: Config> {
+        /// The base and quote asset are the same.
+        InvalidPair,
+        /// The pair is already open for trading.
+        PairExists,
+        /// The pair is not open for trading.
+        UnknownPair,
+        /// The amount is zero.
+        ZeroAmount,
+        /// The limit price is zero.
+        ZeroPrice,
+        /// The amount of the quote asset is zero or does not fit in a balance.
+        InvalidQuoteAmount,
+        /// Market orders must be immediate or cancel or fill or kill.
+        InvalidTimeInForce,
+        /// The last block of a good till block order is in the past.
+        OrderExpired,
+        /// The trigger would fire at the last trade price.
+        WouldTrigger,
+        /// A post only order would trade at once.
+        WouldTakeLiquidity,
+        /// A fill or kill order cannot be filled at once.
+        CannotFill,
+        /// The side of the book is full.
+        OrderBookFull,
+        /// The pair has too many orders waiting for their trigger.
+        TooManyConditionalOrders,
+        /// The balance reserved for an order does not cover its trade.
+        InsufficientReserve,
+        /// The expiry of the order could not be scheduled.
+        ExpiryNotScheduled,
+        /// There is no open order with this id.
+        UnknownOrder,
+        /// The pair already trades in this mode.
+        ModeUnchanged,
+        /// The maximum number of pairs cleared in batch auctions is reached.
+        TooManyAuctionPairs,
+        /// Fill or kill orders cannot take part in batch auctions.
+        FillOrKillInAuction,
+        /// The auction of the pair has too many orders.
+        TooManyBatchOrders,
+        /// The order belongs to another account.
+        NotOrderOwner,
+    }
+}
+
+decl_module! {
+    pub struct Module<T: Config> for enum Call where origin: T::Origin {
+        type Error = Error<T>;
+
+        fn deposit_event() = default;
+
+        /// Fires the triggers met by the last trades, within [`Config::MaxTriggerWeight`], and
+        /// accounts for the auctions cleared at the end of the block.
+        fn on_initialize(_now: T::BlockNumber) -> Weight {
+            let auctions = AuctionPairs::decode_len().unwrap_or(0) as Weight;
+            let auction = <T as Config>::WeightInfo::clear_auction(T::MaxBatchOrders::get());
+            Self::fire_triggers()
+                .saturating_add(T::DbWeight::get().reads(1))
+                .saturating_add(auction.saturating_mul(auctions))
+        }
+
+        /// Clears the auctions of the orders collected in the block.
+        fn on_finalize(_now: T::BlockNumber) {
+            for (pair, batch) in BatchOrders::drain() {
+                Self::settle_batch(pair, batch);
+            }
+        }
+
+        /// Open `pair` for trading.
+        #[weight = <T as Config>::WeightInfo::register_pair()]
+        pub fn register_pair(origin, pair: TradingPair) -> DispatchResult {
+            T::GovernanceOrigin::ensure_origin(origin)?;
+            ensure!(pair.base != pair.quote, Error::<T>::InvalidPair);
+            ensure!(!Self::is_trading_pair(&pair), Error::<T>::PairExists);
+            TradingPairs::insert(&pair, true);
+            Self::deposit_event(RawEvent::PairRegistered(pair.base, pair.quote));
+            Ok(())
+        }
+
+        /// Switch `pair` to trading in `mode`.
+        ///
+        /// Switching to continuous trading clears the auction of the orders already collected
+        /// in the block right away.
+        #[weight = <T as Config>::WeightInfo::set_market_mode()
+            .saturating_add(<T as Config>::WeightInfo::clear_auction(T::MaxBatchOrders::get()))]
+        pub fn set_market_mode(origin, pair: TradingPair, mode: MarketMode) -> DispatchResult {
+            T::GovernanceOrigin::ensure_origin(origin)?;
+            ensure!(Self::is_trading_pair(&pair), Error::<T>::UnknownPair);
+            AuctionPairs::try_mutate(|pairs| -> DispatchResult {
+                let position = pairs.iter().position(|other| *other == pair);
+                match (mode, position) {
+                    (MarketMode::BatchAuction, None) => {
+                        ensure!((pairs.len() as u32) < T::MaxAuctionPairs::get(), Error::<T>::TooManyAuctionPairs);
+                        pairs.push(pair);
+                    }
+                    (MarketMode::Continuous, Some(position)) => {
+                        pairs.remove(position);
+                    }
+                    _ => return Err(Error::<T>::ModeUnchanged.into()),
+                }
+                Ok(())
+            })?;
+            if mode == MarketMode::Continuous {
+                Self::settle_batch(pair, BatchOrders::take(&pair));
+            }
+            Self::deposit_event(RawEvent::MarketModeSet(pair.base, pair.quote, mode));
+            Ok(())
+        }
+
+        /// Place an order for `amount` of the base asset of `pair`.
+        ///
+        /// Without a `limit` price the order is a market order. With a `trigger` the order waits
+        /// for the trigger to fire before trading.
+        #[weight = <T as Config>::WeightInfo::place_order(T::MaxFills::get())]
+        #[transactional]
+        pub fn place_order(
+            origin,
+            pair: TradingPair,
+            side: Side,
+            amount: T::Balance,
+            limit: Option<FixedU128>,
+            trigger: Option<Trigger>,
+            time_in_force: TimeInForceOf<T>,
+        ) -> DispatchResult {
+            let who = ensure_signed(origin)?;
+            ensure!(Self::is_trading_pair(&pair), Error::<T>::UnknownPair);
+            ensure!(!amount.is_zero(), Error::<T>::ZeroAmount);
+            ensure!(limit.map_or(true, |limit| !limit.is_zero()), Error::<T>::ZeroPrice);
+            ensure!(limit.is_some() || !time_in_force.rests(), Error::<T>::InvalidTimeInForce);
+            if let Some(trigger) = trigger {
+                ensure!(!trigger.price.is_zero(), Error::<T>::ZeroPrice);
+                ensure!(
+                    !Self::last_price(&pair).map_or(false, |last| trigger.fires(side, last)),
+                    Error::<T>::WouldTrigger
+                );
+            }
+
+            let mut order = Order {
+                owner: who.clone(),
+                pair,
+                side,
+                limit,
+                trigger,
+                time_in_force,
+                amount,
+                reserved: Zero::zero(),
+            };
+            order.reserved = Self::reserve_amount(&order)?;
+            T::Currency::reserve(order.reserved_asset(), &who, order.reserved)?;
+            let id = Self::next_order_id();
+            NextOrderId::put(id + 1);
+            Self::deposit_event(RawEvent::OrderPlaced(id, who, pair.base, pair.quote, side));
+            if let TimeInForce::GoodTillBlock(until) = time_in_force {
+                Self::schedule_expiry(id, until)?;
+            }
+
+            if trigger.is_some() {
+                ConditionalOrders::try_mutate(&pair, |ids| -> DispatchResult {
+                    ensure!((ids.len() as u32) < T::MaxConditionalOrders::get(), Error::<T>::TooManyConditionalOrders);
+                    ids.push(id);
+                    Ok(())
+                })?;
+                Orders::<T>::insert(id, order);
+                Ok(())
+            } else {
+                Self::submit(id, order)
+            }
+        }
+
+        /// Cancel the caller's open order `id`, unreserving what it did not trade.
+        #[weight = <T as Config>::WeightInfo::cancel_order()]
+        pub fn cancel_order(origin, id: OrderId) -> DispatchResult {
+            let who = ensure_signed(origin)?;
+            let order = Self::order(id).ok_or(Error::<T>::UnknownOrder)?;
+            ensure!(order.owner == who, Error::<T>::NotOrderOwner);
+            Self::close(id, order);
+            Self::deposit_event(RawEvent::OrderCancelled(id));
+            Ok(())
+        }
+
+        /// Expire the good till block order `id`.
+        ///
+        /// Dispatched by [`Config::Scheduler`] after the last block of the order.
+        #[weight = <T as Config>::WeightInfo::expire_order()]
+        pub fn expire_order(origin, id: OrderId) -> DispatchResult {
+            ensure_root(origin)?;
+            let order = Self::order(id).ok_or(Error::<T>::UnknownOrder)?;
+            Self::release(id, order);
+            Self::deposit_event(RawEvent::OrderExpired(id));
+            Ok(())
+        }
+    }
+}
+
+impl<T: Config> Module<T> {
+    /// How the orders of `pair` trade.
+    pub fn market_mode(pair: &TradingPair) -> MarketMode {
+        if Self::auction_pairs().contains(pair) {
+            MarketMode::BatchAuction
+        } else {
+            MarketMode::Continuous
+        }
+    }
+
+    /// The name the expiry of order `id` is scheduled under.
+    pub fn expiry_task(id: OrderId) -> Vec<u8> {
+        (EXPIRY_TASK_PREFIX, id).encode()
+    }
+
+    fn schedule_expiry(id: OrderId, until: T::BlockNumber) -> DispatchResult {
+        ensure!(until >= frame_system::Pallet::<T>::block_number(), Error::<T>::OrderExpired);
+        T::Scheduler::schedule_named(
+            Self::expiry_task(id),
+            DispatchTime::At(until.saturating_add(One::one())),
+            None,
+            LOWEST_PRIORITY,
+            frame_system::RawOrigin::<T::AccountId>::Root.into(),
+            Call::<T>::expire_order(id).into(),
+        )
+        .map_err(|_| Error::<T>::ExpiryNotScheduled)?;
+        Ok(())
+    }
+
+    /// The amount `order` reserves when placed.
+    fn reserve_amount(order: &OrderOf<T>) -> Result<T::Balance, DispatchError> {
+        if order.side == Side::Ask {
+            return Ok(order.amount);
+        }
+        let price = match (order.limit, order.trigger) {
+            (Some(limit), _) => limit,
+            (None, Some(trigger)) => {
+                trigger.price.saturating_mul(FixedU128::one().saturating_add(T::MarketBidBuffer::get().into()))
+            }
+            (None, None) => return Ok(T::Currency::free_balance(order.pair.quote, &order.owner)),
+        };
+        price
+            .checked_mul_int(order.amount)
+            .filter(|quote| !quote.is_zero())
+            .ok_or_else(|| Error::<T>::InvalidQuoteAmount.into())
+    }
+
+    /// Trades `order`, at once or in the auction of the block depending on the market mode of
+    /// its pair.
+    fn submit(id: OrderId, order: OrderOf<T>) -> DispatchResult {
+        if order.time_in_force == TimeInForce::PostOnly || Self::market_mode(&order.pair) == MarketMode::Continuous {
+            return Self::execute(id, order);
+        }
+        ensure!(order.time_in_force != TimeInForce::FillOrKill, Error::<T>::FillOrKillInAuction);
+        BatchOrders::try_mutate(&order.pair, |ids| -> DispatchResult {
+            ensure!((ids.len() as u32) < T::MaxBatchOrders::get(), Error::<T>::TooManyBatchOrders);
+            ids.push(id);
+            Ok(())
+        })?;
+        Orders::<T>::insert(id, order);
+        Ok(())
+    }
+
+    /// Trades `order` against the book, then rests or cancels what is left of it according to
+    /// its time in force.
+    #[transactional]
+    fn execute(id: OrderId, mut order: OrderOf<T>) -> DispatchResult {
+        let fills = Self::plan_fills(&order);
+        match order.time_in_force {
+            TimeInForce::PostOnly => ensure!(fills.is_empty(), Error::<T>::WouldTakeLiquidity),
+            TimeInForce::FillOrKill => {
+                let filled = fills.iter().fold(T::Balance::zero(), |filled, fill| filled + fill.base);
+                ensure!(filled == order.amount, Error::<T>::CannotFill);
+            }
+            _ => {}
+        }
+        Self::settle(id, &mut order, fills)?;
+
+        if order.amount.is_zero() {
+            Self::close(id, order);
+            Self::deposit_event(RawEvent::OrderFilled(id));
+        } else if order.time_in_force.rests() {
+            Self::rest(id, order)?;
+        } else {
+            Self::close(id, order);
+            Self::deposit_event(RawEvent::OrderCancelled(id));
+        }
+        Ok(())
+    }
+
+    /// The trades `order` would make against the book, best price first.
+    fn plan_fills(order: &OrderOf<T>) -> Vec<Fill<T::Balance>> {
+        let mut fills = Vec::new();
+        let mut left = order.amount;
+        let mut budget = order.reserved;
+        let book = Self::book(&order.pair, order.side.opposite());
+        for (price, maker) in book.into_iter().take(T::MaxFills::get() as usize) {
+            if left.is_zero() || order.limit.map_or(false, |limit| !order.side.accepts(limit, price)) {
+                break;
+            }
+            let maker_amount = match Self::order(maker) {
+                Some(resting) => resting.amount,
+                None => continue,
+            };
+            let mut base = left.min(maker_amount);
+            let mut quote = match price.checked_mul_int(base) {
+                Some(quote) => quote,
+                None => break,
+            };
+            if order.side == Side::Bid && quote > budget {
+                // Only market bids, whose budget is not bound to a limit price, get here.
+                base = Self::affordable(budget, price);
+                quote = price.checked_mul_int(base).unwrap_or(budget);
+            }
+            if base.is_zero() || quote.is_zero() {
+                break;
+            }
+            left -= base;
+            budget = budget.saturating_sub(quote);
+            fills.push(Fill { maker, price, base, quote });
+        }
+        fills
+    }
+
+    /// Amount of the base asset `budget` of the quote asset pays for at `price`.
+    fn affordable(budget: T::Balance, price: FixedU128) -> T::Balance {
+        price.reciprocal().and_then(|unit| unit.checked_mul_int(budget)).unwrap_or_else(Zero::zero)
+    }
+
+    /// Moves the assets of `fills` between `order` and the resting orders, closing the resting
+    /// orders they fill.
+    fn settle(id: OrderId, order: &mut OrderOf<T>, fills: Vec<Fill<T::Balance>>) -> DispatchResult {
+        let pair = order.pair;
+        let mut last = None;
+        for Fill { maker, price, base, quote } in fills {
+            let mut resting = Self::order(maker).ok_or(Error::<T>::UnknownOrder)?;
+            let (bid, ask) = match order.side {
+                Side::Bid => (&mut *order, &mut resting),
+                Side::Ask => (&mut resting, &mut *order),
+            };
+            Self::repatriate(pair.base, &ask.owner, &bid.owner, base)?;
+            Self::repatriate(pair.quote, &bid.owner, &ask.owner, quote)?;
+            bid.amount -= base;
+            bid.reserved = bid.reserved.saturating_sub(quote);
+            ask.amount -= base;
+            ask.reserved = ask.reserved.saturating_sub(base);
+            Self::deposit_event(RawEvent::Trade(maker, id, price, base, quote));
+            last = Some(price);
+
+            if resting.amount.is_zero() {
+                Self::close(maker, resting);
+                Self::deposit_event(RawEvent::OrderFilled(maker));
+            } else {
+                Orders::<T>::insert(maker, resting);
+            }
+        }
+        if let Some(price) = last {
+            Self::note_price(pair, price);
+        }
+        Ok(())
+    }
+
+    /// Records a trade of `pair` at `price`, so triggers are evaluated against it.
+    fn note_price(pair: TradingPair, price: FixedU128) {
+        LastPrice::insert(&pair, price);
+        MovedPairs::mutate(|moved| {
+            if !moved.contains(&pair) {
+                moved.push(pair);
+            }
+        });
+    }
+
+    /// Moves `amount` of `asset` reserved by `from` to the free balance of `to`.
+    fn repatriate(asset: AssetId, from: &T::AccountId, to: &T::AccountId, amount: T::Balance) -> DispatchResult {
+        let missing = T::Currency::repatriate_reserved(asset, from, to, amount, BalanceStatus::Free)?;
+        ensure!(missing.is_zero(), Error::<T>::InsufficientReserve);
+        Ok(())
+    }
+
+    /// Rests the limit order `order` in its book, unreserving what it no longer needs.
+    fn rest(id: OrderId, mut order: OrderOf<T>) -> DispatchResult {
+        let limit = order.limit.ok_or(Error::<T>::InvalidTimeInForce)?;
+        OrderBook::try_mutate(&order.pair, order.side, |book| -> DispatchResult {
+            ensure!((book.len() as u32) < T::MaxOrdersPerSide::get(), Error::<T>::OrderBookFull);
+            let at = book
+                .iter()
+                .position(|(price, _)| match order.side {
+                    Side::Bid => *price < limit,
+                    Side::Ask => *price > limit,
+                })
+                .unwrap_or_else(|| book.len());
+            book.insert(at, (limit, id));
+            Ok(())
+        })?;
+        Self::release_excess(&mut order);
+        Orders::<T>::insert(id, order);
+        Ok(())
+    }
+
+    /// Unreserves what the resting bid `order` no longer needs to pay for its amount at its
+    /// limit price, after trading at better prices.
+    fn release_excess(order: &mut OrderOf<T>) {
+        if let (Side::Bid, Some(limit)) = (order.side, order.limit) {
+            let needed = limit.saturating_mul_int(order.amount).min(order.reserved);
+            T::Currency::unreserve(order.pair.quote, &order.owner, order.reserved - needed);
+            order.reserved = needed;
+        }
+    }
+
+    /// Closes `order`, cancelling its scheduled expiry.
+    fn close(id: OrderId, order: OrderOf<T>) {
+        if let TimeInForce::GoodTillBlock(_) = order.time_in_force {
+            let _ = T::Scheduler::cancel_named(Self::expiry_task(id));
+        }
+        Self::release(id, order);
+    }
+
+    /// Removes `order` from its book or from the orders waiting for their trigger, and
+    /// unreserves what it did not trade.
+    fn release(id: OrderId, order: OrderOf<T>) {
+        if order.trigger.is_some() {
+            ConditionalOrders::mutate(&order.pair, |ids| ids.retain(|other| *other != id));
+        } else if order.limit.is_some() {
+            OrderBook::mutate(&order.pair, order.side, |book| book.retain(|(_, other)| *other != id));
+        }
+        T::Currency::unreserve(order.reserved_asset(), &order.owner, order.reserved);
+        Orders::<T>::remove(id);
+    }
+
+    /// Fires the triggers met by the last trade price of the pairs that traded, pair by pair,
+    /// as long as [`Config::MaxTriggerWeight`] allows. Pairs left over are evaluated first in
+    /// the next block.
+    fn fire_triggers() -> Weight {
+        let max_weight = T::MaxTriggerWeight::get();
+        let check_weight = <T as Config>::WeightInfo::check_triggers(T::MaxConditionalOrders::get());
+        let trigger_weight = <T as Config>::WeightInfo::trigger_order(T::MaxFills::get());
+        let mut weight = T::DbWeight::get().reads_writes(1, 1);
+        let mut pairs = MovedPairs::take();
+        if pairs.is_empty() {
+            return weight;
+        }
+
+        while let Some(pair) = pairs.first().copied() {
+            if weight.saturating_add(check_weight).saturating_add(trigger_weight) > max_weight {
+                break;
+            }
+            weight = weight.saturating_add(check_weight);
+            let last = match Self::last_price(&pair) {
+                Some(last) => last,
+                None => {
+                    pairs.remove(0);
+                    continue;
+                }
+            };
+            let mut checked_all = true;
+            for id in Self::conditional_orders(&pair) {
+                let order = match Self::order(id) {
+                    Some(order) => order,
+                    None => continue,
+                };
+                if !order.trigger.map_or(false, |trigger| trigger.fires(order.side, last)) {
+                    continue;
+                }
+                if weight.saturating_add(trigger_weight) > max_weight {
+                    checked_all = false;
+                    break;
+                }
+                weight = weight.saturating_add(trigger_weight);
+                Self::trigger(id, order);
+            }
+            if !checked_all {
+                break;
+            }
+            pairs.remove(0);
+        }
+
+        // Trades made by the fired orders moved their pairs again.
+        MovedPairs::mutate(|moved| {
+            for pair in sp_std::mem::take(moved) {
+                if !pairs.contains(&pair) {
+                    pairs.push(pair);
+                }
+            }
+            *moved = pairs;
+        });
+        weight
+    }
+
+    /// Lets the conditional `order` trade, cancelling it when its time in force cannot be met.
+    fn trigger(id: OrderId, mut order: OrderOf<T>) {
+        ConditionalOrders::mutate(&order.pair, |ids| ids.retain(|other| *other != id));
+        order.trigger = None;
+        Self::deposit_event(RawEvent::OrderTriggered(id));
+        if Self::submit(id, order.clone()).is_err() {
+            Self::close(id, order);
+            Self::deposit_event(RawEvent::OrderCancelled(id));
+        }
+    }
+
+    /// Clears the auction of `pair` for the orders in `batch`, cancelling them if it fails.
+    fn settle_batch(pair: TradingPair, batch: Vec<OrderId>) {
+        if Self::clear_auction(pair, &batch).is_err() {
+            for id in batch {
+                if let Some(order) = Self::order(id) {
+                    Self::close(id, order);
+                    Self::deposit_event(RawEvent::OrderCancelled(id));
+                }
+            }
+        }
+    }
+
+    /// Clears the auction of `pair` between the orders in `batch` and the best resting orders.
+    #[transactional]
+    fn clear_auction(pair: TradingPair, batch: &[OrderId]) -> DispatchResult {
+        let (mut bids, mut asks) = (Vec::new(), Vec::new());
+        let best = [Side::Bid, Side::Ask].iter().flat_map(|side| {
+            Self::book(&pair, *side).into_iter().take(T::MaxFills::get() as usize).map(|(_, id)| (id, true))
+        });
+        for (id, resting) in best.collect::<Vec<_>>().into_iter().chain(batch.iter().map(|id| (*id, false))) {
+            if let Some(order) = Self::order(id) {
+                let bidder = Bidder { id, order, resting, eligible: Zero::zero(), allocated: Zero::zero() };
+                match bidder.order.side {
+                    Side::Bid => bids.push(bidder),
+                    Side::Ask => asks.push(bidder),
+                }
+            }
+        }
+
+        if let Some((price, volume)) = Self::clearing_price(&bids, &asks) {
+            for bidder in bids.iter_mut().chain(asks.iter_mut()) {
+                bidder.eligible = Self::eligible(&bidder.order, price);
+            }
+            Self::allocate(&mut bids, volume);
+            Self::allocate(&mut asks, volume);
+            let traded = Self::settle_auction(&mut bids, &mut asks, price)?;
+            if !traded.is_zero() {
+                Self::note_price(pair, price);
+                Self::deposit_event(RawEvent::AuctionCleared(pair.base, pair.quote, price, traded));
+            }
+        }
+
+        for Bidder { id, mut order, resting, .. } in bids.into_iter().chain(asks) {
+            if order.amount.is_zero() {
+                Self::close(id, order);
+                Self::deposit_event(RawEvent::OrderFilled(id));
+            } else if resting {
+                Self::release_excess(&mut order);
+                Orders::<T>::insert(id, order);
+            } else if !order.time_in_force.rests() || Self::rest(id, order.clone()).is_err() {
+                Self::close(id, order);
+                Self::deposit_event(RawEvent::OrderCancelled(id));
+            }
+        }
+        Ok(())
+    }
+
+    /// Amount of the base asset `order` can trade at `price`.
+    fn eligible(order: &OrderOf<T>, price: FixedU128) -> T::Balance {
+        match (order.side, order.limit) {
+            (side, Some(limit)) if !side.accepts(limit, price) => Zero::zero(),
+            (Side::Bid, None) => order.amount.min(Self::affordable(order.reserved, price)),
+            _ => order.amount,
+        }
+    }
+
+    /// The price trading the largest volume between `bids` and `asks`, with the volume.
+    ///
+    /// Among limit prices trading the same volume, the one leaving the smallest difference
+    /// between the amounts bid and asked wins, then the lowest.
+    fn clearing_price(
+        bids: &[Bidder<OrderOf<T>, T::Balance>],
+        asks: &[Bidder<OrderOf<T>, T::Balance>],
+    ) -> Option<(FixedU128, T::Balance)> {
+        let mut prices: Vec<FixedU128> = bids.iter().chain(asks).filter_map(|bidder| bidder.order.limit).collect();
+        prices.sort();
+        prices.dedup();
+        let total = |bidders: &[Bidder<OrderOf<T>, T::Balance>], price| {
+            bidders
+                .iter()
+                .fold(T::Balance::zero(), |total, bidder| total.saturating_add(Self::eligible(&bidder.order, price)))
+        };
+
+        let mut best: Option<(FixedU128, T::Balance, T::Balance)> = None;
+        for price in prices {
+            let (demand, supply) = (total(bids, price), total(asks, price));
+            let volume = demand.min(supply);
+            let imbalance = demand.max(supply) - volume;
+            let better = best.map_or(true, |(_, best_volume, best_imbalance)| {
+                volume > best_volume || (volume == best_volume && imbalance < best_imbalance)
+            });
+            if !volume.is_zero() && better {
+                best = Some((price, volume, imbalance));
+            }
+        }
+        best.map(|(price, volume, _)| (price, volume))
+    }
+
+    /// Shares `volume` among `bidders` by their eligible amounts: better limits first, then pro
+    /// rata among equal limits, larger orders first for what rounding leaves.
+    ///
+    /// Sorts `bidders` in that order, which only depends on the orders and not on when they were
+    /// placed.
+    fn allocate(bidders: &mut [Bidder<OrderOf<T>, T::Balance>], volume: T::Balance) {
+        bidders.sort_by(|a, b| {
+            let by_limit = match (a.order.limit, b.order.limit) {
+                (None, None) => Ordering::Equal,
+                (None, Some(_)) => Ordering::Less,
+                (Some(_), None) => Ordering::Greater,
+                (Some(a_limit), Some(b_limit)) => match a.order.side {
+                    Side::Bid => b_limit.cmp(&a_limit),
+                    Side::Ask => a_limit.cmp(&b_limit),
+                },
+            };
+            by_limit
+                .then_with(|| b.eligible.cmp(&a.eligible))
+                .then_with(|| a.order.owner.cmp(&b.order.owner))
+        });
+
+        let mut left = volume;
+        let mut start = 0;
+        while start < bidders.len() && !left.is_zero() {
+            let limit = bidders[start].order.limit;
+            let end = bidders[start..]
+                .iter()
+                .position(|bidder| bidder.order.limit != limit)
+                .map_or(bidders.len(), |len| start + len);
+            let level = &mut bidders[start..end];
+            let eligible = level.iter().fold(T::Balance::zero(), |total, bidder| total.saturating_add(bidder.eligible));
+            if eligible <= left {
+                for bidder in level.iter_mut() {
+                    bidder.allocated = bidder.eligible;
+                }
+                left -= eligible;
+            } else {
+                let (share, total) = (left.unique_saturated_into(), eligible.unique_saturated_into());
+                for bidder in level.iter_mut() {
+                    let allocated = multiply_by_rational(share, bidder.eligible.unique_saturated_into(), total)
+                        .unwrap_or_default();
+                    bidder.allocated = T::Balance::unique_saturated_from(allocated);
+                    left -= bidder.allocated;
+                }
+                for bidder in level.iter_mut().filter(|bidder| bidder.allocated < bidder.eligible) {
+                    if left.is_zero() {
+                        break;
+                    }
+                    bidder.allocated += One::one();
+                    left -= One::one();
+                }
+                left = Zero::zero();
+            }
+            start = end;
+        }
+    }
+
+    /// Trades the allocated amounts of `bids` and `asks` at `price`, in the order they are
+    /// sorted in, returning the amount of the base asset traded.
+    fn settle_auction(
+        bids: &mut [Bidder<OrderOf<T>, T::Balance>],
+        asks: &mut [Bidder<OrderOf<T>, T::Balance>],
+        price: FixedU128,
+    ) -> Result<T::Balance, DispatchError> {
+        let mut traded = T::Balance::zero();
+        let (mut b, mut a) = (0, 0);
+        while b < bids.len() && a < asks.len() {
+            let (bid, ask) = (&mut bids[b], &mut asks[a]);
+            let base = bid.allocated.min(ask.allocated);
+            let quote = price.saturating_mul_int(base);
+            if !quote.is_zero() {
+                let pair = bid.order.pair;
+                Self::repatriate(pair.base, &ask.order.owner, &bid.order.owner, base)?;
+                Self::repatriate(pair.quote, &bid.order.owner, &ask.order.owner, quote)?;
+                bid.order.amount -= base;
+                bid.order.reserved = bid.order.reserved.saturating_sub(quote);
+                ask.order.amount -= base;
+                ask.order.reserved = ask.order.reserved.saturating_sub(base);
+                traded += base;
+                Self::deposit_event(RawEvent::AuctionTrade(bid.id, ask.id, price, base, quote));
+            }
+            bid.allocated -= base;
+            ask.allocated -= base;
+            if bid.allocated.is_zero() {
+                b += 1;
+            }
+            if ask.allocated.is_zero() {
+                a += 1;
+            }
+        }
+        Ok(traded)
+    }
+}
+
+impl<T: Config> OrderManager<T::AccountId, T::Balance, T::BlockNumber> for Module<T> {
+    fn place_order(
+        owner: &T::AccountId,
+        pair: TradingPair,
+        side: Side,
+        amount: T::Balance,
+        limit: Option<FixedU128>,
+        time_in_force: TimeInForceOf<T>,
+    ) -> Result<OrderId, DispatchError> {
+        let id = Self::next_order_id();
+        let origin = frame_system::RawOrigin::Signed(owner.clone()).into();
+        Module::<T>::place_order(origin, pair, side, amount, limit, None, time_in_force)?;
+        Ok(id)
+    }
+
+    fn cancel_order(owner: &T::AccountId, id: OrderId) -> DispatchResult {
+        Module::<T>::cancel_order(frame_system::RawOrigin::Signed(owner.clone()).into(), id)
+    }
+
+    fn is_open(id: OrderId) -> bool {
+        Orders::<T>::contains_key(id)
+    }
+
+    fn place_order_weight() -> Weight {
+        <T as Config>::WeightInfo::place_order(T::MaxFills::get())
+    }
+
+    fn cancel_order_weight() -> Weight {
+        <T as Config>::WeightInfo::cancel_order()
+    }
+
+    #[cfg(feature = "runtime-benchmarks")]
+    fn open_pair(pair: TradingPair) {
+        TradingPairs::insert(&pair, true);
+    }
+}
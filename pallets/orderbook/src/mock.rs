@@ -0,0 +1,171 @@
+// This file is part of Polkadex.
+
+// Copyright (C) 2020-2021 Polkadex oü.
+// SPDX-License-Identifier: GPL-3.0-or-later WITH Classpath-exception-2.0
+
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with this program. If not, see <https://www.gnu.org/licenses/>.
+//! Test environment for the orderbook pallet.
+
+use crate::{self as pallet_orderbook, TradingPair, WeightInfo};
+use frame_support::{parameter_types, traits::GenesisBuild, weights::Weight};
+use frame_system::EnsureRoot;
+use orml_traits::parameter_type_with_key;
+use polkadex_primitives::assets::AssetId;
+use sp_core::H256;
+use sp_runtime::{
+    testing::Header,
+    traits::{BlakeTwo256, IdentityLookup},
+    Permill,
+};
+
+type UncheckedExtrinsic = frame_system::mocking::MockUncheckedExtrinsic<Test>;
+type Block = frame_system::mocking::MockBlock<Test>;
+
+frame_support::construct_runtime!(
+    pub enum Test where
+        Block = Block,
+        NodeBlock = Block,
+        UncheckedExtrinsic = UncheckedExtrinsic,
+    {
+        System: frame_system::{Pallet, Call, Config, Storage, Event<T>},
+        Tokens: orml_tokens::{Pallet, Storage, Event<T>, Config<T>},
+        Scheduler: pallet_scheduler::{Pallet, Call, Storage, Event<T>},
+        Orderbook: pallet_orderbook::{Pallet, Call, Storage, Event<T>},
+    }
+);
+
+parameter_types! {
+    pub const BlockHashCount: u64 = 250;
+}
+
+impl frame_system::Config for Test {
+    type BaseCallFilter = ();
+    type BlockWeights = ();
+    type BlockLength = ();
+    type DbWeight = ();
+    type Origin = Origin;
+    type Call = Call;
+    type Index = u64;
+    type BlockNumber = u64;
+    type Hash = H256;
+    type Hashing = BlakeTwo256;
+    type AccountId = u64;
+    type Lookup = IdentityLookup<Self::AccountId>;
+    type Header = Header;
+    type Event = Event;
+    type BlockHashCount = BlockHashCount;
+    type Version = ();
+    type PalletInfo = PalletInfo;
+    type AccountData = ();
+    type OnNewAccount = ();
+    type OnKilledAccount = ();
+    type SystemWeightInfo = ();
+    type SS58Prefix = ();
+    type OnSetCode = ();
+}
+
+parameter_type_with_key! {
+    pub ExistentialDeposits: |_currency_id: AssetId| -> u64 {
+        0
+    };
+}
+
+impl orml_tokens::Config for Test {
+    type Event = Event;
+    type Balance = u64;
+    type Amount = i64;
+    type CurrencyId = AssetId;
+    type WeightInfo = ();
+    type ExistentialDeposits = ExistentialDeposits;
+    type OnDust = ();
+}
+
+parameter_types! {
+    pub const MaximumSchedulerWeight: Weight = 1_000_000_000_000;
+    pub const MaxScheduledPerBlock: u32 = 50;
+}
+
+impl pallet_scheduler::Config for Test {
+    type Event = Event;
+    type Origin = Origin;
+    type PalletsOrigin = OriginCaller;
+    type Call = Call;
+    type MaximumWeight = MaximumSchedulerWeight;
+    type ScheduleOrigin = EnsureRoot<u64>;
+    type MaxScheduledPerBlock = MaxScheduledPerBlock;
+    type WeightInfo = ();
+}
+
+parameter_types! {
+    pub const MaxOrdersPerSide: u32 = 10;
+    pub const MaxConditionalOrders: u32 = 10;
+    pub const MaxFills: u32 = 5;
+    pub const MarketBidBuffer: Permill = Permill::from_percent(10);
+    // Room to fire two orders a block.
+    pub MaxTriggerWeight: Weight = <() as WeightInfo>::check_triggers(MaxConditionalOrders::get())
+        + 2 * <() as WeightInfo>::trigger_order(MaxFills::get());
+    pub const MaxAuctionPairs: u32 = 2;
+    pub const MaxBatchOrders: u32 = 10;
+}
+
+impl pallet_orderbook::Config for Test {
+    type Event = Event;
+    type Balance = u64;
+    type Currency = Tokens;
+    type Call = Call;
+    type PalletsOrigin = OriginCaller;
+    type Scheduler = Scheduler;
+    type GovernanceOrigin = EnsureRoot<u64>;
+    type MaxOrdersPerSide = MaxOrdersPerSide;
+    type MaxConditionalOrders = MaxConditionalOrders;
+    type MaxFills = MaxFills;
+    type MarketBidBuffer = MarketBidBuffer;
+    type MaxTriggerWeight = MaxTriggerWeight;
+    type MaxAuctionPairs = MaxAuctionPairs;
+    type MaxBatchOrders = MaxBatchOrders;
+    type WeightInfo = ();
+}
+
+pub const PAIR: TradingPair = TradingPair { base: AssetId::DOT, quote: AssetId::USD };
+
+pub const ALICE: u64 = 1;
+pub const BOB: u64 = 2;
+pub const CHARLIE: u64 = 3;
+pub const DAVE: u64 = 4;
+
+pub fn new_test_ext() -> sp_io::TestExternalities {
+    let mut t = frame_system::GenesisConfig::default().build_storage::<Test>().unwrap();
+    GenesisBuild::<Test>::assimilate_storage(
+        &orml_tokens::GenesisConfig::<Test> {
+            endowed_accounts: vec![
+                (ALICE, AssetId::DOT, 1_000),
+                (ALICE, AssetId::USD, 1_000),
+                (BOB, AssetId::DOT, 1_000),
+                (BOB, AssetId::USD, 1_000),
+                (CHARLIE, AssetId::DOT, 1_000),
+                (CHARLIE, AssetId::USD, 1_000),
+                (DAVE, AssetId::DOT, 1_000),
+                (DAVE, AssetId::USD, 1_000),
+            ],
+        },
+        &mut t,
+    )
+    .unwrap();
+    let mut ext = sp_io::TestExternalities::new(t);
+    ext.execute_with(|| {
+        System::set_block_number(1);
+        Orderbook::register_pair(Origin::root(), PAIR).unwrap();
+    });
+    ext
+}
@@ -0,0 +1,574 @@
+// This file is part of Polkadex.
+
+// Copyright (C) 2020-2021 Polkadex oü.
+// SPDX-License-Identifier: GPL-3.0-or-later WITH Classpath-exception-2.0
+
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with this program. If not, see <https://www.gnu.org/licenses/>.
+use crate::{
+    mock::*, Error, MarketMode, OrderId, OrderManager, RawEvent, Side, TimeInForce, TimeInForceOf, TradingPair, Trigger, TriggerKind,
+};
+use frame_support::{
+    assert_noop, assert_ok,
+    dispatch::DispatchResult,
+    traits::{OnFinalize, OnInitialize},
+};
+use orml_traits::{MultiCurrency, MultiReservableCurrency};
+use polkadex_primitives::assets::AssetId;
+use sp_runtime::{FixedPointNumber, FixedU128};
+
+const GTC: TimeInForceOf<Test> = TimeInForce::GoodTillCancelled;
+const IOC: TimeInForceOf<Test> = TimeInForce::ImmediateOrCancel;
+const FOK: TimeInForceOf<Test> = TimeInForce::FillOrKill;
+
+fn price(value: u64) -> FixedU128 {
+    FixedU128::saturating_from_integer(value)
+}
+
+fn place(
+    who: u64,
+    side: Side,
+    amount: u64,
+    limit: Option<u64>,
+    trigger: Option<(TriggerKind, u64)>,
+    time_in_force: TimeInForceOf<Test>,
+) -> DispatchResult {
+    Orderbook::place_order(
+        Origin::signed(who),
+        PAIR,
+        side,
+        amount,
+        limit.map(price),
+        trigger.map(|(kind, at)| Trigger { kind, price: price(at) }),
+        time_in_force,
+    )
+}
+
+fn limit(who: u64, side: Side, amount: u64, at: u64) -> DispatchResult {
+    place(who, side, amount, Some(at), None, GTC)
+}
+
+/// Trades `amount` between Alice and Bob at `at`, setting the last price.
+fn trade_at(at: u64, amount: u64) {
+    assert_ok!(limit(ALICE, Side::Ask, amount, at));
+    assert_ok!(place(BOB, Side::Bid, amount, Some(at), None, IOC));
+}
+
+fn last_order() -> OrderId {
+    Orderbook::next_order_id() - 1
+}
+
+fn free(asset: AssetId, who: u64) -> u64 {
+    Tokens::free_balance(asset, &who)
+}
+
+fn reserved(asset: AssetId, who: u64) -> u64 {
+    Tokens::reserved_balance(asset, &who)
+}
+
+fn book(side: Side) -> Vec<OrderId> {
+    Orderbook::book(&PAIR, side).into_iter().map(|(_, id)| id).collect()
+}
+
+fn batch_auction() {
+    assert_ok!(Orderbook::set_market_mode(Origin::root(), PAIR, MarketMode::BatchAuction));
+}
+
+/// Places `orders` of `(who, side, amount, limit)` in a new auction and clears it, returning
+/// the balances of every account and the resting orders.
+fn clear_auction_of(orders: &[(u64, Side, u64, u64)]) -> (Vec<(u64, u64, u64, u64)>, usize, usize) {
+    new_test_ext().execute_with(|| {
+        batch_auction();
+        for &(who, side, amount, at) in orders {
+            assert_ok!(limit(who, side, amount, at));
+        }
+        Orderbook::on_finalize(1);
+        let balances = [ALICE, BOB, CHARLIE, DAVE]
+            .iter()
+            .map(|&who| {
+                (
+                    free(AssetId::DOT, who),
+                    reserved(AssetId::DOT, who),
+                    free(AssetId::USD, who),
+                    reserved(AssetId::USD, who),
+                )
+            })
+            .collect();
+        (balances, book(Side::Bid).len(), book(Side::Ask).len())
+    })
+}
+
+fn has_event(event: RawEvent<u64, u64>) -> bool {
+    let event: Event = event.into();
+    System::events().iter().any(|record| record.event == event)
+}
+
+fn run_to_block(n: u64) {
+    while System::block_number() < n {
+        Orderbook::on_finalize(System::block_number());
+        System::set_block_number(System::block_number() + 1);
+        Scheduler::on_initialize(System::block_number());
+        Orderbook::on_initialize(System::block_number());
+    }
+}
+
+#[test]
+fn limit_orders_trade_at_the_resting_price() {
+    new_test_ext().execute_with(|| {
+        assert_ok!(limit(ALICE, Side::Ask, 100, 2));
+        assert_ok!(limit(ALICE, Side::Ask, 50, 2));
+        assert_eq!(reserved(AssetId::DOT, ALICE), 150);
+
+        assert_ok!(limit(BOB, Side::Bid, 120, 3));
+
+        assert!(has_event(RawEvent::Trade(0, 2, price(2), 100, 200)));
+        assert!(has_event(RawEvent::Trade(1, 2, price(2), 20, 40)));
+        assert!(has_event(RawEvent::OrderFilled(2)));
+        assert_eq!(free(AssetId::DOT, BOB), 1_120);
+        assert_eq!(free(AssetId::USD, BOB), 760);
+        assert_eq!(reserved(AssetId::USD, BOB), 0);
+        assert_eq!(free(AssetId::DOT, ALICE), 850);
+        assert_eq!(reserved(AssetId::DOT, ALICE), 30);
+        assert_eq!(free(AssetId::USD, ALICE), 1_240);
+        assert_eq!(Orderbook::order(0), None);
+        assert_eq!(Orderbook::order(1).map(|order| order.amount), Some(30));
+        assert_eq!(book(Side::Ask), vec![1]);
+        assert_eq!(Orderbook::last_price(&PAIR), Some(price(2)));
+    });
+}
+
+#[test]
+fn books_keep_price_then_time_priority() {
+    new_test_ext().execute_with(|| {
+        assert_ok!(limit(ALICE, Side::Ask, 10, 3));
+        assert_ok!(limit(BOB, Side::Ask, 10, 2));
+        assert_ok!(limit(ALICE, Side::Ask, 10, 2));
+        assert_ok!(limit(ALICE, Side::Bid, 10, 1));
+        assert_ok!(limit(BOB, Side::Bid, 10, 1));
+
+        assert_eq!(book(Side::Ask), vec![1, 2, 0]);
+        assert_eq!(book(Side::Bid), vec![3, 4]);
+    });
+}
+
+#[test]
+fn resting_bids_release_what_they_saved_on_better_prices() {
+    new_test_ext().execute_with(|| {
+        assert_ok!(limit(ALICE, Side::Ask, 10, 1));
+
+        assert_ok!(limit(BOB, Side::Bid, 30, 3));
+
+        assert_eq!(free(AssetId::USD, BOB), 930);
+        assert_eq!(reserved(AssetId::USD, BOB), 60);
+        assert_eq!(book(Side::Bid), vec![1]);
+    });
+}
+
+#[test]
+fn market_orders_trade_at_once_and_cancel_the_rest() {
+    new_test_ext().execute_with(|| {
+        assert_ok!(limit(ALICE, Side::Ask, 50, 2));
+        assert_noop!(place(BOB, Side::Bid, 80, None, None, GTC), Error::<Test>::InvalidTimeInForce);
+
+        assert_ok!(place(BOB, Side::Bid, 80, None, None, IOC));
+
+        assert!(has_event(RawEvent::OrderCancelled(1)));
+        assert_eq!(free(AssetId::DOT, BOB), 1_050);
+        assert_eq!(free(AssetId::USD, BOB), 900);
+        assert_eq!(reserved(AssetId::USD, BOB), 0);
+        assert!(book(Side::Bid).is_empty());
+    });
+}
+
+#[test]
+fn fill_or_kill_orders_trade_their_whole_amount_or_nothing() {
+    new_test_ext().execute_with(|| {
+        assert_ok!(limit(ALICE, Side::Ask, 50, 2));
+
+        assert_noop!(place(BOB, Side::Bid, 80, Some(2), None, FOK), Error::<Test>::CannotFill);
+        assert_ok!(place(BOB, Side::Bid, 50, Some(2), None, FOK));
+
+        assert_eq!(free(AssetId::DOT, BOB), 1_050);
+        assert!(book(Side::Ask).is_empty());
+    });
+}
+
+#[test]
+fn post_only_orders_never_take_liquidity() {
+    new_test_ext().execute_with(|| {
+        assert_ok!(limit(ALICE, Side::Ask, 50, 2));
+
+        assert_noop!(
+            place(BOB, Side::Bid, 10, Some(2), None, TimeInForce::PostOnly),
+            Error::<Test>::WouldTakeLiquidity
+        );
+        assert_ok!(place(BOB, Side::Bid, 10, Some(1), None, TimeInForce::PostOnly));
+
+        assert_eq!(book(Side::Bid), vec![1]);
+        assert_eq!(reserved(AssetId::USD, BOB), 10);
+    });
+}
+
+#[test]
+fn good_till_block_orders_expire_after_their_last_block() {
+    new_test_ext().execute_with(|| {
+        assert_noop!(
+            place(BOB, Side::Bid, 10, Some(2), None, TimeInForce::GoodTillBlock(0)),
+            Error::<Test>::OrderExpired
+        );
+        assert_ok!(place(BOB, Side::Bid, 10, Some(2), None, TimeInForce::GoodTillBlock(3)));
+        let id = last_order();
+        assert_eq!(reserved(AssetId::USD, BOB), 20);
+
+        run_to_block(3);
+        assert_eq!(book(Side::Bid), vec![id]);
+
+        run_to_block(4);
+        assert!(has_event(RawEvent::OrderExpired(id)));
+        assert_eq!(Orderbook::order(id), None);
+        assert!(book(Side::Bid).is_empty());
+        assert_eq!(reserved(AssetId::USD, BOB), 0);
+        assert_eq!(free(AssetId::USD, BOB), 1_000);
+    });
+}
+
+#[test]
+fn orders_are_cancelled_by_their_owner_only() {
+    new_test_ext().execute_with(|| {
+        assert_ok!(place(BOB, Side::Ask, 10, Some(2), None, TimeInForce::GoodTillBlock(3)));
+        let id = last_order();
+
+        assert_noop!(Orderbook::cancel_order(Origin::signed(ALICE), id), Error::<Test>::NotOrderOwner);
+        assert_ok!(Orderbook::cancel_order(Origin::signed(BOB), id));
+        assert_noop!(Orderbook::cancel_order(Origin::signed(BOB), id), Error::<Test>::UnknownOrder);
+
+        assert!(book(Side::Ask).is_empty());
+        assert_eq!(free(AssetId::DOT, BOB), 1_000);
+        run_to_block(5);
+        assert!(!has_event(RawEvent::OrderExpired(id)));
+    });
+}
+
+#[test]
+fn stop_orders_trade_once_the_price_moves_against_them() {
+    new_test_ext().execute_with(|| {
+        trade_at(2, 10);
+        assert_noop!(
+            place(CHARLIE, Side::Ask, 20, None, Some((TriggerKind::Stop, 2)), IOC),
+            Error::<Test>::WouldTrigger
+        );
+        assert_ok!(place(CHARLIE, Side::Ask, 20, None, Some((TriggerKind::Stop, 1)), IOC));
+        let stop = last_order();
+        assert_eq!(reserved(AssetId::DOT, CHARLIE), 20);
+        assert_ok!(limit(BOB, Side::Bid, 30, 1));
+
+        run_to_block(2);
+        assert!(!has_event(RawEvent::OrderTriggered(stop)));
+
+        assert_ok!(place(ALICE, Side::Ask, 5, Some(1), None, IOC));
+        run_to_block(3);
+
+        assert!(has_event(RawEvent::OrderTriggered(stop)));
+        assert!(has_event(RawEvent::OrderFilled(stop)));
+        assert_eq!(free(AssetId::DOT, CHARLIE), 980);
+        assert_eq!(free(AssetId::USD, CHARLIE), 1_020);
+        assert_eq!(reserved(AssetId::DOT, CHARLIE), 0);
+        assert!(Orderbook::conditional_orders(&PAIR).is_empty());
+    });
+}
+
+#[test]
+fn take_profit_orders_trade_once_the_price_moves_in_their_favour() {
+    new_test_ext().execute_with(|| {
+        trade_at(2, 10);
+        assert_ok!(place(CHARLIE, Side::Bid, 10, Some(1), Some((TriggerKind::TakeProfit, 1)), GTC));
+        let take_profit = last_order();
+        assert_eq!(reserved(AssetId::USD, CHARLIE), 10);
+
+        trade_at(1, 10);
+        run_to_block(2);
+
+        assert!(has_event(RawEvent::OrderTriggered(take_profit)));
+        assert_eq!(book(Side::Bid), vec![take_profit]);
+        assert_eq!(reserved(AssetId::USD, CHARLIE), 10);
+    });
+}
+
+#[test]
+fn market_bids_waiting_for_a_trigger_reserve_a_buffer() {
+    new_test_ext().execute_with(|| {
+        assert_ok!(place(CHARLIE, Side::Bid, 100, None, Some((TriggerKind::Stop, 2)), IOC));
+
+        assert_eq!(reserved(AssetId::USD, CHARLIE), 220);
+    });
+}
+
+#[test]
+fn triggered_orders_failing_their_time_in_force_are_cancelled() {
+    new_test_ext().execute_with(|| {
+        trade_at(2, 10);
+        assert_ok!(place(CHARLIE, Side::Ask, 20, None, Some((TriggerKind::Stop, 1)), FOK));
+        let stop = last_order();
+
+        trade_at(1, 10);
+        run_to_block(2);
+
+        assert!(has_event(RawEvent::OrderCancelled(stop)));
+        assert_eq!(Orderbook::order(stop), None);
+        assert_eq!(reserved(AssetId::DOT, CHARLIE), 0);
+        assert_eq!(free(AssetId::DOT, CHARLIE), 1_000);
+    });
+}
+
+#[test]
+fn triggers_fire_within_the_weight_limit() {
+    new_test_ext().execute_with(|| {
+        trade_at(2, 10);
+        for _ in 0..3 {
+            assert_ok!(place(CHARLIE, Side::Bid, 10, Some(1), Some((TriggerKind::Stop, 3)), GTC));
+        }
+
+        trade_at(3, 1);
+        run_to_block(2);
+        assert_eq!(book(Side::Bid).len(), 2);
+        assert_eq!(Orderbook::conditional_orders(&PAIR).len(), 1);
+        assert_eq!(Orderbook::moved_pairs(), vec![PAIR]);
+
+        run_to_block(3);
+        assert_eq!(book(Side::Bid).len(), 3);
+        assert!(Orderbook::conditional_orders(&PAIR).is_empty());
+        assert!(Orderbook::moved_pairs().is_empty());
+    });
+}
+
+#[test]
+fn pairs_are_opened_by_governance() {
+    new_test_ext().execute_with(|| {
+        let pair = TradingPair { base: AssetId::BTC, quote: AssetId::BTC };
+        assert_noop!(Orderbook::register_pair(Origin::root(), pair), Error::<Test>::InvalidPair);
+        assert_noop!(Orderbook::register_pair(Origin::root(), PAIR), Error::<Test>::PairExists);
+        assert_noop!(
+            Orderbook::register_pair(Origin::signed(ALICE), TradingPair { quote: AssetId::USD, ..pair }),
+            sp_runtime::DispatchError::BadOrigin
+        );
+        assert_noop!(
+            Orderbook::place_order(
+                Origin::signed(ALICE),
+                TradingPair { quote: AssetId::USD, ..pair },
+                Side::Bid,
+                10,
+                Some(price(1)),
+                None,
+                GTC,
+            ),
+            Error::<Test>::UnknownPair
+        );
+    });
+}
+
+#[test]
+fn auctions_clear_at_a_single_price_at_the_end_of_the_block() {
+    new_test_ext().execute_with(|| {
+        batch_auction();
+        assert_ok!(limit(ALICE, Side::Ask, 30, 1));
+        assert_ok!(limit(BOB, Side::Ask, 30, 2));
+        assert_ok!(limit(CHARLIE, Side::Bid, 40, 3));
+        assert_ok!(limit(DAVE, Side::Bid, 40, 2));
+        let dave = last_order();
+        assert!(book(Side::Bid).is_empty() && book(Side::Ask).is_empty());
+        assert_eq!(Orderbook::batch_orders(&PAIR).len(), 4);
+        assert_eq!(reserved(AssetId::USD, CHARLIE), 120);
+
+        // At 2, 80 are bid and 60 asked: more than at 1 or 3.
+        run_to_block(2);
+        assert!(has_event(RawEvent::AuctionCleared(AssetId::DOT, AssetId::USD, price(2), 60)));
+        assert_eq!(Orderbook::last_price(&PAIR), Some(price(2)));
+        assert!(Orderbook::batch_orders(&PAIR).is_empty());
+        for seller in [ALICE, BOB].iter() {
+            assert_eq!(free(AssetId::DOT, *seller), 970);
+            assert_eq!(free(AssetId::USD, *seller), 1_060);
+        }
+        assert_eq!(free(AssetId::DOT, CHARLIE), 1_040);
+        assert_eq!((free(AssetId::USD, CHARLIE), reserved(AssetId::USD, CHARLIE)), (920, 0));
+        // The bid of Dave, behind the better limit of Charlie, rests with the 20 it did not get.
+        assert_eq!(free(AssetId::DOT, DAVE), 1_020);
+        assert_eq!((free(AssetId::USD, DAVE), reserved(AssetId::USD, DAVE)), (920, 40));
+        assert_eq!(book(Side::Bid), vec![dave]);
+        assert_eq!(Orderbook::order(dave).unwrap().amount, 20);
+    });
+}
+
+#[test]
+fn auctions_share_a_price_level_pro_rata() {
+    new_test_ext().execute_with(|| {
+        batch_auction();
+        assert_ok!(limit(ALICE, Side::Ask, 30, 2));
+        assert_ok!(place(CHARLIE, Side::Bid, 35, Some(2), None, IOC));
+        assert_ok!(place(DAVE, Side::Bid, 20, Some(2), None, IOC));
+
+        // 30 * 35 / 55 rounds down to 19 and 30 * 20 / 55 to 10; the larger order gets the last unit.
+        run_to_block(2);
+        assert_eq!(free(AssetId::DOT, CHARLIE), 1_020);
+        assert_eq!(free(AssetId::DOT, DAVE), 1_010);
+        assert_eq!(free(AssetId::USD, ALICE), 1_060);
+        assert_eq!(reserved(AssetId::USD, CHARLIE) + reserved(AssetId::USD, DAVE), 0);
+        assert!(book(Side::Bid).is_empty() && book(Side::Ask).is_empty());
+    });
+}
+
+#[test]
+fn auctions_do_not_depend_on_the_order_of_the_orders() {
+    let orders = vec![
+        (ALICE, Side::Ask, 30, 1),
+        (BOB, Side::Ask, 25, 2),
+        (ALICE, Side::Ask, 20, 3),
+        (CHARLIE, Side::Bid, 35, 2),
+        (DAVE, Side::Bid, 20, 2),
+        (BOB, Side::Bid, 15, 3),
+        (DAVE, Side::Bid, 10, 1),
+    ];
+    let expected = clear_auction_of(&orders);
+    // Something traded and something rests.
+    assert_ne!(expected.0[CHARLIE as usize - 1].0, 1_000);
+    assert_ne!(expected.1 + expected.2, 0);
+
+    let mut reversed = orders.clone();
+    reversed.reverse();
+    assert_eq!(clear_auction_of(&reversed), expected);
+    for shift in 1..orders.len() {
+        let mut rotated = orders.clone();
+        rotated.rotate_left(shift);
+        assert_eq!(clear_auction_of(&rotated), expected);
+    }
+    let mut interleaved: Vec<_> = orders.iter().step_by(2).cloned().collect();
+    interleaved.extend(orders.iter().skip(1).step_by(2).cloned());
+    assert_eq!(clear_auction_of(&interleaved), expected);
+}
+
+#[test]
+fn resting_and_market_orders_join_the_auction() {
+    new_test_ext().execute_with(|| {
+        assert_ok!(limit(ALICE, Side::Ask, 10, 2));
+        let resting = last_order();
+        batch_auction();
+        assert_ok!(place(BOB, Side::Bid, 30, None, None, IOC));
+        let market = last_order();
+        assert_ok!(limit(CHARLIE, Side::Ask, 10, 3));
+
+        // At 3, the market bid takes both asks, the resting one included.
+        run_to_block(2);
+        assert!(has_event(RawEvent::AuctionTrade(market, resting, price(3), 10, 30)));
+        assert!(has_event(RawEvent::OrderCancelled(market)));
+        assert_eq!(free(AssetId::USD, ALICE), 1_030);
+        assert_eq!(free(AssetId::USD, CHARLIE), 1_030);
+        assert_eq!(free(AssetId::DOT, BOB), 1_020);
+        assert_eq!((free(AssetId::USD, BOB), reserved(AssetId::USD, BOB)), (940, 0));
+        assert!(book(Side::Bid).is_empty() && book(Side::Ask).is_empty());
+    });
+}
+
+#[test]
+fn auctions_refuse_fill_or_kill_and_rest_post_only_orders_at_once() {
+    new_test_ext().execute_with(|| {
+        batch_auction();
+        assert_noop!(place(ALICE, Side::Ask, 10, Some(2), None, FOK), Error::<Test>::FillOrKillInAuction);
+        assert_ok!(place(ALICE, Side::Ask, 10, Some(2), None, TimeInForce::PostOnly));
+        assert_eq!(book(Side::Ask), vec![last_order()]);
+        assert_noop!(
+            place(BOB, Side::Bid, 10, Some(2), None, TimeInForce::PostOnly),
+            Error::<Test>::WouldTakeLiquidity
+        );
+
+        for _ in 0..MaxBatchOrders::get() {
+            assert_ok!(limit(BOB, Side::Bid, 1, 1));
+        }
+        assert_noop!(limit(BOB, Side::Bid, 1, 1), Error::<Test>::TooManyBatchOrders);
+    });
+}
+
+#[test]
+fn switching_to_continuous_trading_clears_the_pending_auction() {
+    new_test_ext().execute_with(|| {
+        batch_auction();
+        assert_ok!(limit(ALICE, Side::Ask, 30, 2));
+        assert_ok!(limit(BOB, Side::Bid, 40, 2));
+        let bob = last_order();
+
+        assert_ok!(Orderbook::set_market_mode(Origin::root(), PAIR, MarketMode::Continuous));
+        assert!(Orderbook::batch_orders(&PAIR).is_empty());
+        assert!(has_event(RawEvent::AuctionCleared(AssetId::DOT, AssetId::USD, price(2), 30)));
+        assert_eq!(free(AssetId::DOT, BOB), 1_030);
+        assert_eq!(book(Side::Bid), vec![bob]);
+        assert_eq!(Orderbook::order(bob).unwrap().amount, 10);
+
+        // Nothing is left for the end of the block.
+        run_to_block(2);
+        assert_eq!(book(Side::Bid), vec![bob]);
+    });
+}
+
+#[test]
+fn market_modes_are_set_by_governance() {
+    new_test_ext().execute_with(|| {
+        assert_noop!(
+            Orderbook::set_market_mode(Origin::signed(ALICE), PAIR, MarketMode::BatchAuction),
+            sp_runtime::DispatchError::BadOrigin
+        );
+        let unknown = TradingPair { base: AssetId::BTC, quote: AssetId::USD };
+        assert_noop!(
+            Orderbook::set_market_mode(Origin::root(), unknown, MarketMode::BatchAuction),
+            Error::<Test>::UnknownPair
+        );
+        assert_noop!(
+            Orderbook::set_market_mode(Origin::root(), PAIR, MarketMode::Continuous),
+            Error::<Test>::ModeUnchanged
+        );
+
+        batch_auction();
+        assert_eq!(Orderbook::market_mode(&PAIR), MarketMode::BatchAuction);
+        assert!(has_event(RawEvent::MarketModeSet(AssetId::DOT, AssetId::USD, MarketMode::BatchAuction)));
+        assert_ok!(Orderbook::register_pair(Origin::root(), unknown));
+        assert_ok!(Orderbook::set_market_mode(Origin::root(), unknown, MarketMode::BatchAuction));
+        let third = TradingPair { base: AssetId::BTC, quote: AssetId::DOT };
+        assert_ok!(Orderbook::register_pair(Origin::root(), third));
+        assert_noop!(
+            Orderbook::set_market_mode(Origin::root(), third, MarketMode::BatchAuction),
+            Error::<Test>::TooManyAuctionPairs
+        );
+
+        assert_ok!(Orderbook::set_market_mode(Origin::root(), PAIR, MarketMode::Continuous));
+        assert_eq!(Orderbook::market_mode(&PAIR), MarketMode::Continuous);
+        trade_at(2, 10);
+        assert!(book(Side::Ask).is_empty());
+    });
+}
+
+#[test]
+fn other_pallets_place_and_cancel_orders() {
+    new_test_ext().execute_with(|| {
+        let id =
+            <Orderbook as OrderManager<_, _, _>>::place_order(&ALICE, PAIR, Side::Ask, 10, Some(price(2)), GTC)
+                .unwrap();
+        assert!(<Orderbook as OrderManager<_, _, _>>::is_open(id));
+        assert_eq!(Orderbook::order(id).unwrap().owner, ALICE);
+        assert_eq!(reserved(PAIR.base, ALICE), 10);
+
+        assert_noop!(
+            <Orderbook as OrderManager<_, _, _>>::cancel_order(&BOB, id),
+            Error::<Test>::NotOrderOwner
+        );
+        assert_ok!(<Orderbook as OrderManager<_, _, _>>::cancel_order(&ALICE, id));
+        assert!(!<Orderbook as OrderManager<_, _, _>>::is_open(id));
+        assert_eq!(reserved(PAIR.base, ALICE), 0);
+    });
+}
@@ -0,0 +1,91 @@
+// This file is part of Polkadex.
+
+// Copyright (C) 2020-2021 Polkadex oü.
+// SPDX-License-Identifier: GPL-3.0-or-later WITH Classpath-exception-2.0
+
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with this program. If not, see <https://www.gnu.org/licenses/>.
+//! Estimated weights for `pallet_orderbook`.
+//!
+//! The runtime uses the weights in `runtime/src/weights`; the `()` implementation below is meant
+//! for tests only.
+
+#![allow(unused_parens)]
+#![allow(unused_imports)]
+
+use frame_support::{traits::Get, weights::{constants::RocksDbWeight, Weight}};
+
+/// Weight functions needed for `pallet_orderbook`.
+pub trait WeightInfo {
+    fn register_pair() -> Weight;
+    fn place_order(f: u32) -> Weight;
+    fn cancel_order() -> Weight;
+    fn expire_order() -> Weight;
+    fn check_triggers(c: u32) -> Weight;
+    fn trigger_order(f: u32) -> Weight;
+    fn set_market_mode() -> Weight;
+    fn clear_auction(o: u32) -> Weight;
+}
+
+impl WeightInfo for () {
+    fn register_pair() -> Weight {
+        (20_000_000 as Weight)
+            .saturating_add(RocksDbWeight::get().reads(1 as Weight))
+            .saturating_add(RocksDbWeight::get().writes(1 as Weight))
+    }
+    fn place_order(f: u32) -> Weight {
+        (120_000_000 as Weight)
+            .saturating_add((70_000_000 as Weight).saturating_mul(f as Weight))
+            .saturating_add(RocksDbWeight::get().reads(7 as Weight))
+            .saturating_add(RocksDbWeight::get().reads((5 as Weight).saturating_mul(f as Weight)))
+            .saturating_add(RocksDbWeight::get().writes(7 as Weight))
+            .saturating_add(RocksDbWeight::get().writes((5 as Weight).saturating_mul(f as Weight)))
+    }
+    fn cancel_order() -> Weight {
+        (60_000_000 as Weight)
+            .saturating_add(RocksDbWeight::get().reads(4 as Weight))
+            .saturating_add(RocksDbWeight::get().writes(4 as Weight))
+    }
+    fn expire_order() -> Weight {
+        (55_000_000 as Weight)
+            .saturating_add(RocksDbWeight::get().reads(3 as Weight))
+            .saturating_add(RocksDbWeight::get().writes(3 as Weight))
+    }
+    fn check_triggers(c: u32) -> Weight {
+        (10_000_000 as Weight)
+            .saturating_add((4_000_000 as Weight).saturating_mul(c as Weight))
+            .saturating_add(RocksDbWeight::get().reads(2 as Weight))
+            .saturating_add(RocksDbWeight::get().reads((1 as Weight).saturating_mul(c as Weight)))
+    }
+    fn trigger_order(f: u32) -> Weight {
+        (110_000_000 as Weight)
+            .saturating_add((70_000_000 as Weight).saturating_mul(f as Weight))
+            .saturating_add(RocksDbWeight::get().reads(6 as Weight))
+            .saturating_add(RocksDbWeight::get().reads((5 as Weight).saturating_mul(f as Weight)))
+            .saturating_add(RocksDbWeight::get().writes(6 as Weight))
+            .saturating_add(RocksDbWeight::get().writes((5 as Weight).saturating_mul(f as Weight)))
+    }
+    fn set_market_mode() -> Weight {
+        (20_000_000 as Weight)
+            .saturating_add(RocksDbWeight::get().reads(2 as Weight))
+            .saturating_add(RocksDbWeight::get().writes(1 as Weight))
+    }
+    fn clear_auction(o: u32) -> Weight {
+        (250_000_000 as Weight)
+            .saturating_add((80_000_000 as Weight).saturating_mul(o as Weight))
+            .saturating_add(RocksDbWeight::get().reads(15 as Weight))
+            .saturating_add(RocksDbWeight::get().reads((5 as Weight).saturating_mul(o as Weight)))
+            .saturating_add(RocksDbWeight::get().writes(15 as Weight))
+            .saturating_add(RocksDbWeight::get().writes((5 as Weight).saturating_mul(o as Weight)))
+    }
+}
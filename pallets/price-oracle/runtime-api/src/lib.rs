@@ -0,0 +1,34 @@
+// This file is part of Polkadex.
+
+// Copyright (C) 2020-2021 Polkadex oü.
+// SPDX-License-Identifier: GPL-3.0-or-later WITH Classpath-exception-2.0
+
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with this program. If not, see <https://www.gnu.org/licenses/>.
+//! Runtime API definition for the price oracle pallet.
+
+#![cfg_attr(not(feature = "std"), no_std)]
+
+use polkadex_primitives::assets::AssetId;
+use sp_runtime::FixedU128;
+use sp_std::prelude::*;
+
+sp_api::decl_runtime_apis! {
+    /// Prices aggregated by the oracle.
+    pub trait PriceOracleApi {
+        /// Median price of `asset`, or `None` if there is none or it is stale.
+        fn price(asset: AssetId) -> Option<FixedU128>;
+        /// Median prices of every asset that has a price which is not stale.
+        fn prices() -> Vec<(AssetId, FixedU128)>;
+    }
+}
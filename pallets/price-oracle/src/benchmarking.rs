@@ -0,0 +1,86 @@
+// This file is part of Polkadex.
+
+// Copyright (C) 2020-2021 Polkadex oü.
+// SPDX-License-Identifier: GPL-3.0-or-later WITH Classpath-exception-2.0
+
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with this program. If not, see <https://www.gnu.org/licenses/>.
+//! Benchmarks for `pallet_price_oracle`.
+//!
+//! Feeder removals are benchmarked with `T::MaxFeeders` feeders having priced every asset, the
+//! worst case for aggregation. Submissions are benchmarked with up to `T::MaxFeeders` of them.
+
+#![cfg(feature = "runtime-benchmarks")]
+
+use super::*;
+use frame_benchmarking::{account, benchmarks, impl_benchmark_test_suite, whitelisted_caller};
+use frame_system::RawOrigin;
+
+const SEED: u32 = 0;
+
+/// Fills the feeder set with `count` accounts that all priced every asset.
+fn feeders_with_prices<T: Config>(count: u32) -> Vec<T::AccountId> {
+    let now = frame_system::Pallet::<T>::block_number();
+    let feeders = (0..count)
+        .map(|i| account("feeder", i, SEED))
+        .collect::<Vec<T::AccountId>>();
+    for feeder in &feeders {
+        for asset in T::OracleAssets::get() {
+            RawPrices::<T>::insert(asset, feeder, (FixedU128::saturating_from_integer(1u32), now));
+        }
+    }
+    Feeders::<T>::put(&feeders);
+    feeders
+}
+
+benchmarks! {
+    add_feeder {
+        feeders_with_prices::<T>(T::MaxFeeders::get() - 1);
+        let feeder: T::AccountId = account("new", 0, SEED);
+        let origin = T::FeederOrigin::successful_origin();
+    }: _<T::Origin>(origin, feeder.clone())
+    verify {
+        assert!(Module::<T>::feeders().contains(&feeder));
+    }
+
+    remove_feeder {
+        let feeder = feeders_with_prices::<T>(T::MaxFeeders::get() - 1).pop().unwrap();
+        let origin = T::FeederOrigin::successful_origin();
+    }: _<T::Origin>(origin, feeder.clone())
+    verify {
+        assert!(!Module::<T>::feeders().contains(&feeder));
+    }
+
+    submit_prices {
+        let n in 1 .. T::OracleAssets::get().len() as u32;
+        let f in 1 .. T::MaxFeeders::get();
+        feeders_with_prices::<T>(f - 1);
+        let feeder: T::AccountId = whitelisted_caller();
+        Feeders::<T>::append(&feeder);
+        let prices = T::OracleAssets::get()
+            .into_iter()
+            .take(n as usize)
+            .map(|asset| (asset, FixedU128::saturating_from_integer(2u32)))
+            .collect::<Vec<_>>();
+    }: _(RawOrigin::Signed(feeder.clone()), prices)
+    verify {
+        let asset = T::OracleAssets::get()[0];
+        assert!(Module::<T>::raw_price(asset, &feeder).is_some());
+    }
+}
+
+impl_benchmark_test_suite!(
+    Module,
+    crate::mock::new_test_ext(),
+    crate::mock::Test,
+);
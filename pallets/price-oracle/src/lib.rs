@@ -0,0 +1,454 @@
+// This file is part of Polkadex.
+
+// Copyright (C) 2020-2021 Polkadex oü.
+// SPDX-License-Identifier: GPL-3.0-or-later WITH Classpath-exception-2.0
+
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with this program. If not, see <https://www.gnu.org/licenses/>.
+//! # Price oracle pallet
+//!
+//! Aggregates the prices of assets submitted by a set of authorized feeders.
+//!
+//! Feeders submit prices with [`Call::submit_prices`], either by hand or from the offchain
+//! worker of a node holding a feeder key of type [`KEY_TYPE`] in its keystore. Every
+//! [`Config::SubmissionInterval`] blocks, the offchain worker asks [`Config::PriceFetcher`] for
+//! the price of each of [`Config::OracleAssets`] and submits what it got as a signed
+//! transaction. [`HttpFetcher`] fetches prices from an HTTP service; tests and other setups can
+//! plug in their own [`PriceFetcher`].
+//!
+//! The price of an asset is the median of the submissions of all feeders that are not older
+//! than [`Config::StalePeriod`], once at least [`Config::MinFeeders`] feeders submitted one.
+//! Below that quorum the aggregated price is left alone until it goes stale. An aggregated price
+//! that has not been updated within the stale period is considered stale and is no longer
+//! returned by [`PriceProvider::price`] or the runtime API.
+
+#![cfg_attr(not(feature = "std"), no_std)]
+
+use frame_support::{
+    decl_error, decl_event, decl_module, decl_storage,
+    dispatch::{DispatchResult, DispatchResultWithPostInfo},
+    ensure,
+    traits::{EnsureOrigin, Get},
+    weights::Pays,
+    StorageDoubleMap, StorageMap, StorageValue,
+};
+use frame_system::{
+    ensure_signed,
+    offchain::{AppCrypto, CreateSignedTransaction, SendSignedTransaction, Signer},
+};
+use lite_json::json::JsonValue;
+use polkadex_primitives::assets::AssetId;
+use sp_core::crypto::KeyTypeId;
+use sp_runtime::{
+    offchain::{http, Duration, StorageKind},
+    traits::{IdentifyAccount, Saturating, Zero},
+    FixedPointNumber, FixedU128, RuntimeDebug,
+};
+use sp_std::{convert::TryFrom, marker::PhantomData, prelude::*};
+
+pub use weights::WeightInfo;
+
+#[cfg(feature = "runtime-benchmarks")]
+mod benchmarking;
+#[cfg(test)]
+mod mock;
+#[cfg(test)]
+mod tests;
+pub mod weights;
+
+/// Key type of the keys the offchain worker signs price submissions with.
+pub const KEY_TYPE: KeyTypeId = KeyTypeId(*b"orcl");
+
+/// Offchain (persistent) local storage key holding the base URL used by [`HttpFetcher`].
+///
+/// Node operators set it with the `offchain_localStorageSet` RPC; the value is the raw URL.
+pub const ENDPOINT_KEY: &[u8] = b"price-oracle::endpoint";
+
+/// How long [`HttpFetcher`] waits for a price service to answer, in milliseconds.
+const HTTP_TIMEOUT_MS: u64 = 2_000;
+
+pub mod crypto {
+    use super::KEY_TYPE;
+    use sp_core::sr25519::Signature as Sr25519Signature;
+    use sp_runtime::{
+        app_crypto::{app_crypto, sr25519},
+        traits::Verify,
+        MultiSignature, MultiSigner,
+    };
+
+    app_crypto!(sr25519, KEY_TYPE);
+
+    /// Identifies the keys of price feeders, see [`crate::Config::AuthorityId`].
+    pub struct OracleAuthId;
+
+    impl frame_system::offchain::AppCrypto<MultiSigner, MultiSignature> for OracleAuthId {
+        type RuntimeAppPublic = Public;
+        type GenericSignature = sp_core::sr25519::Signature;
+        type GenericPublic = sp_core::sr25519::Public;
+    }
+
+    impl frame_system::offchain::AppCrypto<<Sr25519Signature as Verify>::Signer, Sr25519Signature> for OracleAuthId {
+        type RuntimeAppPublic = Public;
+        type GenericSignature = sp_core::sr25519::Signature;
+        type GenericPublic = sp_core::sr25519::Public;
+    }
+}
+
+/// Reasons a price could not be fetched.
+#[derive(Clone, Copy, PartialEq, Eq, RuntimeDebug)]
+pub enum FetchError {
+    /// The fetcher does not know how to price the asset.
+    UnknownAsset,
+    /// No price service is configured.
+    NoEndpoint,
+    /// The request failed, timed out or did not succeed.
+    Http,
+    /// The response could not be parsed into a price.
+    InvalidResponse,
+}
+
+/// Source of the prices submitted by the offchain worker.
+pub trait PriceFetcher {
+    /// Fetches the current price of `asset`. Only called from offchain workers.
+    fn fetch_price(asset: AssetId) -> Result<FixedU128, FetchError>;
+}
+
+/// Names assets for [`HttpFetcher`].
+pub trait PriceSource {
+    /// Symbol `asset` is quoted under, or `None` if the price service does not quote it.
+    fn symbol(asset: AssetId) -> Option<&'static str>;
+}
+
+/// Fetches prices with `GET <endpoint>/price/<symbol>`, expecting a response body like
+/// `{"price": 12.345}`.
+///
+/// `<endpoint>` is read from [`ENDPOINT_KEY`]; nothing is fetched until it is set.
+pub struct HttpFetcher<S>(PhantomData<S>);
+
+impl<S: PriceSource> PriceFetcher for HttpFetcher<S> {
+    fn fetch_price(asset: AssetId) -> Result<FixedU128, FetchError> {
+        let symbol = S::symbol(asset).ok_or(FetchError::UnknownAsset)?;
+        let mut url = sp_io::offchain::local_storage_get(StorageKind::PERSISTENT, ENDPOINT_KEY)
+            .ok_or(FetchError::NoEndpoint)?;
+        url.extend_from_slice(b"/price/");
+        url.extend_from_slice(symbol.as_bytes());
+        let url = sp_std::str::from_utf8(&url).map_err(|_| FetchError::Http)?;
+
+        let deadline = sp_io::offchain::timestamp().add(Duration::from_millis(HTTP_TIMEOUT_MS));
+        let pending = http::Request::get(url)
+            .deadline(deadline)
+            .send()
+            .map_err(|_| FetchError::Http)?;
+        let response = pending
+            .try_wait(deadline)
+            .map_err(|_| FetchError::Http)?
+            .map_err(|_| FetchError::Http)?;
+        if response.code != 200 {
+            return Err(FetchError::Http);
+        }
+
+        let body = response.body().collect::<Vec<u8>>();
+        parse_price(&body).ok_or(FetchError::InvalidResponse)
+    }
+}
+
+/// Parses a `{"price": <non-negative decimal>}` JSON body.
+pub fn parse_price(body: &[u8]) -> Option<FixedU128> {
+    let body = sp_std::str::from_utf8(body).ok()?;
+    let number = match lite_json::parse_json(body).ok()? {
+        JsonValue::Object(object) => {
+            match object.into_iter().find(|(key, _)| key.iter().copied().eq("price".chars()))? {
+                (_, JsonValue::Number(number)) => number,
+                _ => return None,
+            }
+        }
+        _ => return None,
+    };
+    if number.exponent != 0 {
+        return None;
+    }
+
+    let integer = u128::from(u64::try_from(number.integer).ok()?);
+    let mut fraction = u128::from(number.fraction);
+    let mut fraction_length = number.fraction_length;
+    // `FixedU128` has 18 decimals, drop any further digits.
+    while fraction_length > 18 {
+        fraction /= 10;
+        fraction_length -= 1;
+    }
+    let fraction = fraction.checked_mul(10u128.pow(18 - fraction_length))?;
+    let inner = integer.checked_mul(FixedU128::accuracy())?.checked_add(fraction)?;
+    Some(FixedU128::from_inner(inner))
+}
+
+/// Gives other pallets access to the aggregated prices.
+pub trait PriceProvider {
+    /// Median price of `asset`, or `None` if there is none or it is stale.
+    fn price(asset: AssetId) -> Option<FixedU128>;
+
+    /// Sets the price of `asset`, for the benchmarks of pallets using prices.
+    #[cfg(feature = "runtime-benchmarks")]
+    fn set_price(asset: AssetId, price: FixedU128);
+}
+
+/// Configure the pallet by specifying the parameters and types on which it depends.
+pub trait Config: CreateSignedTransaction<Call<Self>> {
+    /// Keys the offchain worker signs price submissions with.
+    type AuthorityId: AppCrypto<Self::Public, Self::Signature>;
+    /// Because this pallet emits events, it depends on the runtime's definition of an event.
+    type Event: From<Event<Self>> + Into<<Self as frame_system::Config>::Event>;
+    /// The overarching dispatch call type.
+    type Call: From<Call<Self>>;
+    /// Origin allowed to add and remove feeders.
+    type FeederOrigin: EnsureOrigin<Self::Origin>;
+    /// Where the offchain worker gets prices from.
+    type PriceFetcher: PriceFetcher;
+    /// Assets the oracle prices.
+    type OracleAssets: Get<Vec<AssetId>>;
+    /// Number of blocks between two submissions of the offchain worker.
+    type SubmissionInterval: Get<Self::BlockNumber>;
+    /// Number of blocks after which a price is stale.
+    type StalePeriod: Get<Self::BlockNumber>;
+    /// Minimum number of fresh submissions an aggregated price is computed from.
+    type MinFeeders: Get<u32>;
+    /// Maximum number of feeders.
+    type MaxFeeders: Get<u32>;
+    /// Weight information for extrinsics in this pallet.
+    type WeightInfo: WeightInfo;
+}
+
+decl_storage! {
+    trait Store for Module<T: Config> as PriceOracle {
+        /// Accounts allowed to submit prices.
+        pub Feeders get(fn feeders) config(): Vec<T::AccountId>;
+        /// Last price submitted by each feeder for each asset, with the block it was submitted at.
+        pub RawPrices get(fn raw_price):
+            double_map hasher(blake2_128_concat) AssetId, hasher(blake2_128_concat) T::AccountId
+            => Option<(FixedU128, T::BlockNumber)>;
+        /// Median price of each asset, with the block it was aggregated at.
+        pub AggregatedPrices get(fn aggregated_price):
+            map hasher(blake2_128_concat) AssetId => Option<(FixedU128, T::BlockNumber)>;
+    }
+}
+
+decl_event!(
+    pub enum Event<T>
+    where
+        AccountId = <T as frame_system::Config>::AccountId,
+    {
+        /// A feeder was added. \[feeder\]
+        FeederAdded(AccountId),
+        /// A feeder was removed. \[feeder\]
+        FeederRemoved(AccountId),
+        /// A feeder submitted a price. \[feeder, asset, price\]
+        PriceSubmitted(AccountId, AssetId, FixedU128),
+        /// The median price of an asset changed. \[asset, price\]
+        PriceAggregated(AssetId, FixedU128),
+    }
+);
+
+decl_error! {
+    pub enum Error for Module<T: Config> {
+        /// The account is not a feeder.
+        NotFeeder,
+        /// The account is already a feeder.
+        AlreadyFeeder,
+        /// The maximum number of feeders is reached.
+        TooManyFeeders,
+        /// The asset is not priced by the oracle.
+        UnsupportedAsset,
+        /// More prices than priced assets were submitted.
+        TooManyPrices,
+    }
+}
+
+decl_module! {
+    pub struct Module<T: Config> for enum Call where origin: T::Origin {
+        type Error = Error<T>;
+
+        fn deposit_event() = default;
+
+        /// Allow `feeder` to submit prices.
+        #[weight = <T as Config>::WeightInfo::add_feeder()]
+        pub fn add_feeder(origin, feeder: T::AccountId) -> DispatchResult {
+            T::FeederOrigin::ensure_origin(origin)?;
+            Feeders::<T>::try_mutate(|feeders| -> DispatchResult {
+                ensure!(!feeders.contains(&feeder), Error::<T>::AlreadyFeeder);
+                ensure!((feeders.len() as u32) < T::MaxFeeders::get(), Error::<T>::TooManyFeeders);
+                feeders.push(feeder.clone());
+                Ok(())
+            })?;
+            Self::deposit_event(RawEvent::FeederAdded(feeder));
+            Ok(())
+        }
+
+        /// Revoke `feeder`, discarding the prices it submitted.
+        #[weight = <T as Config>::WeightInfo::remove_feeder()]
+        pub fn remove_feeder(origin, feeder: T::AccountId) -> DispatchResult {
+            T::FeederOrigin::ensure_origin(origin)?;
+            Feeders::<T>::try_mutate(|feeders| -> DispatchResult {
+                let index = feeders.iter().position(|f| f == &feeder).ok_or(Error::<T>::NotFeeder)?;
+                feeders.swap_remove(index);
+                Ok(())
+            })?;
+
+            let now = frame_system::Pallet::<T>::block_number();
+            for asset in T::OracleAssets::get() {
+                RawPrices::<T>::remove(asset, &feeder);
+                Self::aggregate(asset, now);
+            }
+            Self::deposit_event(RawEvent::FeederRemoved(feeder));
+            Ok(())
+        }
+
+        /// Submit the current price of some assets.
+        ///
+        /// Submissions of feeders are free. Every submitted price is aggregated with the
+        /// submissions of up to [`Config::MaxFeeders`] feeders.
+        #[weight = <T as Config>::WeightInfo::submit_prices(prices.len() as u32, T::MaxFeeders::get())]
+        pub fn submit_prices(origin, prices: Vec<(AssetId, FixedU128)>) -> DispatchResultWithPostInfo {
+            let feeder = ensure_signed(origin)?;
+            ensure!(Self::feeders().contains(&feeder), Error::<T>::NotFeeder);
+            let assets = T::OracleAssets::get();
+            ensure!(prices.len() <= assets.len(), Error::<T>::TooManyPrices);
+            ensure!(prices.iter().all(|(asset, _)| assets.contains(asset)), Error::<T>::UnsupportedAsset);
+
+            let now = frame_system::Pallet::<T>::block_number();
+            for (asset, price) in prices {
+                RawPrices::<T>::insert(asset, &feeder, (price, now));
+                Self::deposit_event(RawEvent::PriceSubmitted(feeder.clone(), asset, price));
+                Self::aggregate(asset, now);
+            }
+            Ok(Pays::No.into())
+        }
+
+        fn offchain_worker(now: T::BlockNumber) {
+            let interval = T::SubmissionInterval::get();
+            if !interval.is_zero() && !(now % interval).is_zero() {
+                return;
+            }
+            if let Err(e) = Self::fetch_and_submit() {
+                log::warn!(target: "price-oracle", "Price submission at block {:?} failed: {}", now, e);
+            }
+        }
+    }
+}
+
+impl<T: Config> Module<T> {
+    /// Updates the median price of `asset` from the submissions that are not stale yet, removing
+    /// it if there are none left. Fewer than [`Config::MinFeeders`] submissions leave the price
+    /// unchanged.
+    fn aggregate(asset: AssetId, now: T::BlockNumber) {
+        let stale_period = T::StalePeriod::get();
+        let mut fresh = RawPrices::<T>::iter_prefix_values(asset)
+            .filter(|(_, submitted_at)| now.saturating_sub(*submitted_at) < stale_period)
+            .map(|(price, _)| price)
+            .collect::<Vec<_>>();
+        if !fresh.is_empty() && (fresh.len() as u32) < T::MinFeeders::get() {
+            return;
+        }
+        if let Some(price) = median(&mut fresh) {
+            AggregatedPrices::<T>::insert(asset, (price, now));
+            Self::deposit_event(RawEvent::PriceAggregated(asset, price));
+        } else {
+            AggregatedPrices::<T>::remove(asset);
+        }
+    }
+
+    /// Median price of `asset`, or `None` if there is none or it is stale.
+    pub fn price(asset: AssetId) -> Option<FixedU128> {
+        let (price, aggregated_at) = Self::aggregated_price(asset)?;
+        let now = frame_system::Pallet::<T>::block_number();
+        if now.saturating_sub(aggregated_at) >= T::StalePeriod::get() {
+            return None;
+        }
+        Some(price)
+    }
+
+    /// Median prices of every asset that has a price which is not stale.
+    pub fn prices() -> Vec<(AssetId, FixedU128)> {
+        T::OracleAssets::get()
+            .into_iter()
+            .filter_map(|asset| Self::price(asset).map(|price| (asset, price)))
+            .collect()
+    }
+
+    /// Fetches the price of every oracle asset and submits them, signed with a local feeder key.
+    ///
+    /// Nodes without a feeder key do nothing.
+    fn fetch_and_submit() -> Result<(), &'static str> {
+        let feeders = Self::feeders();
+        let keys = <T::AuthorityId as AppCrypto<T::Public, T::Signature>>::RuntimeAppPublic::all()
+            .into_iter()
+            .map(|key| {
+                let generic = <T::AuthorityId as AppCrypto<T::Public, T::Signature>>::GenericPublic::from(key);
+                let public: T::Public = generic.into();
+                public
+            })
+            .filter(|public| feeders.contains(&public.clone().into_account()))
+            .collect::<Vec<_>>();
+        if keys.is_empty() {
+            log::debug!(target: "price-oracle", "No feeder key in the keystore, not submitting prices");
+            return Ok(());
+        }
+
+        let prices = T::OracleAssets::get()
+            .into_iter()
+            .filter_map(|asset| match T::PriceFetcher::fetch_price(asset) {
+                Ok(price) => Some((asset, price)),
+                Err(e) => {
+                    log::warn!(target: "price-oracle", "Failed to fetch price of {:?}: {:?}", asset, e);
+                    None
+                }
+            })
+            .collect::<Vec<_>>();
+        if prices.is_empty() {
+            return Ok(());
+        }
+
+        let result = Signer::<T, T::AuthorityId>::any_account()
+            .with_filter(keys)
+            .send_signed_transaction(|_| Call::submit_prices(prices.clone()));
+        match result {
+            Some((_, Ok(()))) => Ok(()),
+            Some((_, Err(()))) => Err("failed to submit prices"),
+            None => Err("no feeder key in the keystore"),
+        }
+    }
+}
+
+impl<T: Config> PriceProvider for Module<T> {
+    fn price(asset: AssetId) -> Option<FixedU128> {
+        Module::<T>::price(asset)
+    }
+
+    #[cfg(feature = "runtime-benchmarks")]
+    fn set_price(asset: AssetId, price: FixedU128) {
+        AggregatedPrices::<T>::insert(asset, (price, frame_system::Pallet::<T>::block_number()));
+    }
+}
+
+/// Median of `values`; the mean of the two middle values for an even number of values.
+fn median(values: &mut [FixedU128]) -> Option<FixedU128> {
+    if values.is_empty() {
+        return None;
+    }
+    values.sort();
+    let mid = values.len() / 2;
+    if values.len() % 2 == 1 {
+        Some(values[mid])
+    } else {
+        let (low, high) = (values[mid - 1].into_inner(), values[mid].into_inner());
+        Some(FixedU128::from_inner(low / 2 + high / 2 + (low % 2 + high % 2) / 2))
+    }
+}
@@ -0,0 +1,152 @@
+// This file is part of Polkadex.
+
+// Copyright (C) 2020-2021 Polkadex oü.
+// SPDX-License-Identifier: GPL-3.0-or-later WITH Classpath-exception-2.0
+
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with this program. If not, see <https://www.gnu.org/licenses/>.
+//! Test environment for the price oracle pallet.
+
+use crate::{self as pallet_price_oracle, crypto::OracleAuthId, HttpFetcher, PriceSource};
+use frame_support::parameter_types;
+use frame_system::EnsureRoot;
+use polkadex_primitives::assets::AssetId;
+use sp_core::{sr25519::Signature, H256};
+use sp_runtime::{
+    testing::{Header, TestXt},
+    traits::{BlakeTwo256, Extrinsic as ExtrinsicT, IdentifyAccount, IdentityLookup, Verify},
+};
+
+type UncheckedExtrinsic = frame_system::mocking::MockUncheckedExtrinsic<Test>;
+type Block = frame_system::mocking::MockBlock<Test>;
+
+pub type Extrinsic = TestXt<Call, ()>;
+pub type AccountId = <<Signature as Verify>::Signer as IdentifyAccount>::AccountId;
+
+frame_support::construct_runtime!(
+    pub enum Test where
+        Block = Block,
+        NodeBlock = Block,
+        UncheckedExtrinsic = UncheckedExtrinsic,
+    {
+        System: frame_system::{Pallet, Call, Config, Storage, Event<T>},
+        PriceOracle: pallet_price_oracle::{Pallet, Call, Storage, Config<T>, Event<T>},
+    }
+);
+
+parameter_types! {
+    pub const BlockHashCount: u64 = 250;
+}
+
+impl frame_system::Config for Test {
+    type BaseCallFilter = ();
+    type BlockWeights = ();
+    type BlockLength = ();
+    type DbWeight = ();
+    type Origin = Origin;
+    type Call = Call;
+    type Index = u64;
+    type BlockNumber = u64;
+    type Hash = H256;
+    type Hashing = BlakeTwo256;
+    type AccountId = AccountId;
+    type Lookup = IdentityLookup<Self::AccountId>;
+    type Header = Header;
+    type Event = Event;
+    type BlockHashCount = BlockHashCount;
+    type Version = ();
+    type PalletInfo = PalletInfo;
+    type AccountData = ();
+    type OnNewAccount = ();
+    type OnKilledAccount = ();
+    type SystemWeightInfo = ();
+    type SS58Prefix = ();
+    type OnSetCode = ();
+}
+
+impl frame_system::offchain::SigningTypes for Test {
+    type Public = <Signature as Verify>::Signer;
+    type Signature = Signature;
+}
+
+impl<LocalCall> frame_system::offchain::SendTransactionTypes<LocalCall> for Test
+where
+    Call: From<LocalCall>,
+{
+    type OverarchingCall = Call;
+    type Extrinsic = Extrinsic;
+}
+
+impl<LocalCall> frame_system::offchain::CreateSignedTransaction<LocalCall> for Test
+where
+    Call: From<LocalCall>,
+{
+    fn create_transaction<C: frame_system::offchain::AppCrypto<Self::Public, Self::Signature>>(
+        call: Call,
+        _public: <Signature as Verify>::Signer,
+        _account: AccountId,
+        nonce: u64,
+    ) -> Option<(Call, <Extrinsic as ExtrinsicT>::SignaturePayload)> {
+        Some((call, (nonce, ())))
+    }
+}
+
+/// Quotes DOT and BTC only.
+pub struct TestPriceSource;
+
+impl PriceSource for TestPriceSource {
+    fn symbol(asset: AssetId) -> Option<&'static str> {
+        [(AssetId::DOT, "DOT"), (AssetId::BTC, "BTC")]
+            .iter()
+            .find(|(a, _)| *a == asset)
+            .map(|(_, symbol)| *symbol)
+    }
+}
+
+parameter_types! {
+    pub OracleAssets: Vec<AssetId> = vec![AssetId::DOT, AssetId::BTC];
+    pub const SubmissionInterval: u64 = 5;
+    pub const StalePeriod: u64 = 10;
+    pub const MaxFeeders: u32 = 10;
+    pub static MinFeeders: u32 = 1;
+}
+
+impl pallet_price_oracle::Config for Test {
+    type AuthorityId = OracleAuthId;
+    type Event = Event;
+    type Call = Call;
+    type FeederOrigin = EnsureRoot<AccountId>;
+    type PriceFetcher = HttpFetcher<TestPriceSource>;
+    type OracleAssets = OracleAssets;
+    type SubmissionInterval = SubmissionInterval;
+    type StalePeriod = StalePeriod;
+    type MinFeeders = MinFeeders;
+    type MaxFeeders = MaxFeeders;
+    type WeightInfo = ();
+}
+
+pub fn account(seed: u8) -> AccountId {
+    sp_core::sr25519::Public::from_raw([seed; 32])
+}
+
+pub fn new_test_ext() -> sp_io::TestExternalities {
+    let mut t = frame_system::GenesisConfig::default().build_storage::<Test>().unwrap();
+    pallet_price_oracle::GenesisConfig::<Test> {
+        feeders: vec![account(1), account(2), account(3)],
+    }
+    .assimilate_storage(&mut t)
+    .unwrap();
+    let mut ext = sp_io::TestExternalities::new(t);
+    ext.execute_with(|| System::set_block_number(1));
+    ext
+}
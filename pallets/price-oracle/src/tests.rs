@@ -0,0 +1,221 @@
+// This file is part of Polkadex.
+
+// Copyright (C) 2020-2021 Polkadex oü.
+// SPDX-License-Identifier: GPL-3.0-or-later WITH Classpath-exception-2.0
+
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with this program. If not, see <https://www.gnu.org/licenses/>.
+use crate::{mock::*, parse_price, Error, FetchError, ENDPOINT_KEY, KEY_TYPE};
+use codec::Decode;
+use frame_support::{assert_noop, assert_ok, traits::OffchainWorker, weights::Pays};
+use polkadex_primitives::assets::AssetId;
+use sp_core::offchain::{
+    testing::{self, PendingRequest},
+    OffchainDbExt, OffchainWorkerExt, StorageKind, TransactionPoolExt,
+};
+use sp_keystore::{testing::KeyStore, KeystoreExt, SyncCryptoStore};
+use sp_runtime::{FixedPointNumber, FixedU128};
+use std::sync::Arc;
+
+fn price(value: u32) -> FixedU128 {
+    FixedU128::saturating_from_integer(value)
+}
+
+fn submit(feeder: u8, asset: AssetId, value: u32) {
+    assert_ok!(PriceOracle::submit_prices(Origin::signed(account(feeder)), vec![(asset, price(value))]));
+}
+
+#[test]
+fn only_feeders_submit_prices() {
+    new_test_ext().execute_with(|| {
+        assert_noop!(
+            PriceOracle::submit_prices(Origin::signed(account(9)), vec![(AssetId::DOT, price(1))]),
+            Error::<Test>::NotFeeder
+        );
+        assert_noop!(
+            PriceOracle::submit_prices(Origin::signed(account(1)), vec![(AssetId::USD, price(1))]),
+            Error::<Test>::UnsupportedAsset
+        );
+
+        let result = PriceOracle::submit_prices(Origin::signed(account(1)), vec![(AssetId::DOT, price(1))]);
+        assert_eq!(result.unwrap().pays_fee, Pays::No);
+        assert_eq!(PriceOracle::price(AssetId::DOT), Some(price(1)));
+    });
+}
+
+#[test]
+fn feeders_are_managed_by_feeder_origin() {
+    new_test_ext().execute_with(|| {
+        assert_noop!(
+            PriceOracle::add_feeder(Origin::signed(account(1)), account(4)),
+            sp_runtime::DispatchError::BadOrigin
+        );
+        assert_noop!(PriceOracle::add_feeder(Origin::root(), account(1)), Error::<Test>::AlreadyFeeder);
+        assert_ok!(PriceOracle::add_feeder(Origin::root(), account(4)));
+        submit(4, AssetId::DOT, 10);
+
+        submit(1, AssetId::DOT, 20);
+        assert_eq!(PriceOracle::price(AssetId::DOT), Some(price(15)));
+
+        assert_ok!(PriceOracle::remove_feeder(Origin::root(), account(4)));
+        assert!(PriceOracle::raw_price(AssetId::DOT, account(4)).is_none());
+        assert_eq!(PriceOracle::price(AssetId::DOT), Some(price(20)));
+        assert_noop!(PriceOracle::remove_feeder(Origin::root(), account(4)), Error::<Test>::NotFeeder);
+    });
+}
+
+#[test]
+fn removing_the_last_feeder_removes_the_price() {
+    new_test_ext().execute_with(|| {
+        submit(1, AssetId::DOT, 20);
+        assert_eq!(PriceOracle::price(AssetId::DOT), Some(price(20)));
+
+        assert_ok!(PriceOracle::remove_feeder(Origin::root(), account(1)));
+        assert!(PriceOracle::aggregated_price(AssetId::DOT).is_none());
+        assert_eq!(PriceOracle::price(AssetId::DOT), None);
+    });
+}
+
+#[test]
+fn price_is_the_median_of_submissions() {
+    new_test_ext().execute_with(|| {
+        submit(1, AssetId::DOT, 30);
+        submit(2, AssetId::DOT, 10);
+        submit(3, AssetId::DOT, 1_000);
+        assert_eq!(PriceOracle::price(AssetId::DOT), Some(price(30)));
+
+        // A feeder resubmitting replaces its previous price.
+        submit(3, AssetId::DOT, 20);
+        assert_eq!(PriceOracle::price(AssetId::DOT), Some(price(20)));
+        assert_eq!(PriceOracle::prices(), vec![(AssetId::DOT, price(20))]);
+    });
+}
+
+#[test]
+fn price_needs_a_quorum_of_feeders() {
+    new_test_ext().execute_with(|| {
+        MinFeeders::set(2);
+        submit(1, AssetId::DOT, 1_000);
+        assert_eq!(PriceOracle::price(AssetId::DOT), None);
+
+        submit(2, AssetId::DOT, 10);
+        assert_eq!(PriceOracle::price(AssetId::DOT), Some(price(505)));
+
+        // Below the quorum the last aggregated price is kept until it goes stale.
+        System::set_block_number(1 + StalePeriod::get());
+        submit(1, AssetId::DOT, 20);
+        assert_eq!(PriceOracle::aggregated_price(AssetId::DOT), Some((price(505), 1)));
+        assert_eq!(PriceOracle::price(AssetId::DOT), None);
+    });
+}
+
+#[test]
+fn stale_prices_are_ignored() {
+    new_test_ext().execute_with(|| {
+        submit(1, AssetId::DOT, 100);
+        System::set_block_number(1 + StalePeriod::get() - 1);
+        assert_eq!(PriceOracle::price(AssetId::DOT), Some(price(100)));
+
+        System::set_block_number(1 + StalePeriod::get());
+        assert_eq!(PriceOracle::price(AssetId::DOT), None);
+        assert!(PriceOracle::prices().is_empty());
+
+        // The stale submission does not count towards the new median.
+        submit(2, AssetId::DOT, 10);
+        assert_eq!(PriceOracle::price(AssetId::DOT), Some(price(10)));
+    });
+}
+
+#[test]
+fn parse_price_works() {
+    assert_eq!(parse_price(br#"{"price": 7.25}"#), Some(FixedU128::saturating_from_rational(29, 4)));
+    assert_eq!(parse_price(br#"{"price": 42}"#), Some(price(42)));
+    assert_eq!(parse_price(br#"{"symbol": "DOT", "price": 0.000001}"#), Some(FixedU128::from_inner(1_000_000_000_000)));
+    assert_eq!(parse_price(br#"{"price": "7.25"}"#), None);
+    assert_eq!(parse_price(br#"[7.25]"#), None);
+    assert_eq!(parse_price(b"not json"), None);
+}
+
+#[test]
+fn offchain_worker_submits_fetched_prices() {
+    let (offchain, offchain_state) = testing::TestOffchainExt::new();
+    let (pool, pool_state) = testing::TestTransactionPoolExt::new();
+    let keystore = KeyStore::new();
+    let feeder = SyncCryptoStore::sr25519_generate_new(&keystore, KEY_TYPE, None).unwrap();
+
+    let mut t = new_test_ext();
+    t.register_extension(OffchainDbExt::new(offchain.clone()));
+    t.register_extension(OffchainWorkerExt::new(offchain));
+    t.register_extension(TransactionPoolExt::new(pool));
+    t.register_extension(KeystoreExt(Arc::new(keystore)));
+
+    {
+        let mut state = offchain_state.write();
+        state.expect_request(PendingRequest {
+            method: "GET".into(),
+            uri: "http://prices.local/price/DOT".into(),
+            response: Some(br#"{"price": 7.25}"#.to_vec()),
+            sent: true,
+            ..Default::default()
+        });
+        state.expect_request(PendingRequest {
+            method: "GET".into(),
+            uri: "http://prices.local/price/BTC".into(),
+            response: Some(br#"{"price": "unavailable"}"#.to_vec()),
+            sent: true,
+            ..Default::default()
+        });
+    }
+
+    t.execute_with(|| {
+        sp_io::offchain::local_storage_set(StorageKind::PERSISTENT, ENDPOINT_KEY, b"http://prices.local");
+
+        // Not a feeder yet, nothing is fetched or submitted.
+        PriceOracle::offchain_worker(5);
+        assert!(pool_state.read().transactions.is_empty());
+
+        assert_ok!(PriceOracle::add_feeder(Origin::root(), feeder));
+        // Not a submission block.
+        PriceOracle::offchain_worker(6);
+        assert!(pool_state.read().transactions.is_empty());
+
+        PriceOracle::offchain_worker(10);
+        let tx = pool_state.write().transactions.pop().unwrap();
+        assert!(pool_state.read().transactions.is_empty());
+        let tx = Extrinsic::decode(&mut &*tx).unwrap();
+        assert_eq!(tx.signature.unwrap().0, 0);
+        assert_eq!(
+            tx.call,
+            Call::PriceOracle(crate::Call::submit_prices(vec![(
+                AssetId::DOT,
+                FixedU128::saturating_from_rational(29, 4),
+            )]))
+        );
+    });
+}
+
+#[test]
+fn nothing_is_fetched_without_an_endpoint() {
+    let (offchain, _) = testing::TestOffchainExt::new();
+    let mut t = new_test_ext();
+    t.register_extension(OffchainDbExt::new(offchain.clone()));
+    t.register_extension(OffchainWorkerExt::new(offchain));
+
+    t.execute_with(|| {
+        assert!(sp_io::offchain::local_storage_get(StorageKind::PERSISTENT, ENDPOINT_KEY).is_none());
+        assert_eq!(
+            <crate::HttpFetcher<TestPriceSource> as crate::PriceFetcher>::fetch_price(AssetId::DOT),
+            Err(FetchError::NoEndpoint)
+        );
+    });
+}
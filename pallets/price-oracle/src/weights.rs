@@ -0,0 +1,54 @@
+// This file is part of Polkadex.
+
+// Copyright (C) 2020-2021 Polkadex oü.
+// SPDX-License-Identifier: GPL-3.0-or-later WITH Classpath-exception-2.0
+
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with this program. If not, see <https://www.gnu.org/licenses/>.
+//! Estimated weights for `pallet_price_oracle`.
+//!
+//! The runtime uses the weights in `runtime/src/weights`; the `()` implementation below is meant
+//! for tests only.
+
+#![allow(unused_parens)]
+#![allow(unused_imports)]
+
+use frame_support::{traits::Get, weights::{constants::RocksDbWeight, Weight}};
+
+/// Weight functions needed for `pallet_price_oracle`.
+pub trait WeightInfo {
+    fn add_feeder() -> Weight;
+    fn remove_feeder() -> Weight;
+    fn submit_prices(n: u32, f: u32) -> Weight;
+}
+
+impl WeightInfo for () {
+    fn add_feeder() -> Weight {
+        (25_000_000 as Weight)
+            .saturating_add(RocksDbWeight::get().reads(1 as Weight))
+            .saturating_add(RocksDbWeight::get().writes(1 as Weight))
+    }
+    fn remove_feeder() -> Weight {
+        (64_000_000 as Weight)
+            .saturating_add(RocksDbWeight::get().reads(5 as Weight))
+            .saturating_add(RocksDbWeight::get().writes(9 as Weight))
+    }
+    fn submit_prices(n: u32, f: u32) -> Weight {
+        (20_000_000 as Weight)
+            .saturating_add((10_000_000 as Weight).saturating_mul(n as Weight))
+            .saturating_add((4_000_000 as Weight).saturating_mul(n as Weight).saturating_mul(f as Weight))
+            .saturating_add(RocksDbWeight::get().reads(1 as Weight))
+            .saturating_add(RocksDbWeight::get().reads((1 as Weight).saturating_mul(n as Weight).saturating_mul(f as Weight)))
+            .saturating_add(RocksDbWeight::get().writes((2 as Weight).saturating_mul(n as Weight)))
+    }
+}
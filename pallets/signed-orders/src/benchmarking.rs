@@ -0,0 +1,96 @@
+// This file is part of Polkadex.
+
+// Copyright (C) 2020-2021 Polkadex oü.
+// SPDX-License-Identifier: GPL-3.0-or-later WITH Classpath-exception-2.0
+
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with this program. If not, see <https://www.gnu.org/licenses/>.
+//! Benchmarks for `pallet_signed_orders`.
+//!
+//! Orders are signed with sr25519 keys generated in the benchmark keystore, so the runtime's
+//! signatures and accounts must be convertible from sr25519 ones.
+
+#![cfg(feature = "runtime-benchmarks")]
+
+use super::*;
+use frame_benchmarking::{benchmarks, impl_benchmark_test_suite, whitelisted_caller};
+use frame_system::RawOrigin;
+use sp_core::{crypto::KeyTypeId, sr25519};
+
+const BASE: AssetId = AssetId::DOT;
+const QUOTE: AssetId = AssetId::USD;
+const BENCH_KEY_TYPE: KeyTypeId = KeyTypeId(*b"sord");
+
+/// A funded trader's order on `side` for 100 units of the base asset at a price of 2.
+fn signed_order<T: Config>(side: Side) -> Result<SignedOrderOf<T>, &'static str>
+where
+    T::AccountId: From<sr25519::Public>,
+    T::Signature: From<sr25519::Signature>,
+{
+    let public = sp_io::crypto::sr25519_generate(BENCH_KEY_TYPE, None);
+    let account: T::AccountId = public.clone().into();
+    T::Currency::deposit(BASE, &account, 1_000u32.into())?;
+    T::Currency::deposit(QUOTE, &account, 1_000u32.into())?;
+    let order = Order {
+        account,
+        nonce: 0,
+        base: BASE,
+        quote: QUOTE,
+        side,
+        price: FixedU128::saturating_from_integer(2),
+        amount: 100u32.into(),
+        expiry: frame_system::Pallet::<T>::block_number() + 10u32.into(),
+    };
+    let signature = sp_io::crypto::sr25519_sign(BENCH_KEY_TYPE, &public, &Module::<T>::signing_payload(&order))
+        .ok_or("the benchmark key is in the keystore")?
+        .into();
+    Ok(SignedOrder { order, signature })
+}
+
+benchmarks! {
+    where_clause {
+        where
+            T::AccountId: From<sr25519::Public>,
+            T::Signature: From<sr25519::Signature>,
+    }
+
+    settle_matched_orders {
+        let bid = signed_order::<T>(Side::Bid)?;
+        let ask = signed_order::<T>(Side::Ask)?;
+        let buyer = bid.order.account.clone();
+        let caller: T::AccountId = whitelisted_caller();
+    }: _(RawOrigin::Signed(caller), bid, ask, 100u32.into(), FixedU128::saturating_from_integer(2))
+    verify {
+        assert_eq!(Module::<T>::filled_amount(&buyer, 0), 100u32.into());
+    }
+
+    cancel_order {
+        let caller: T::AccountId = whitelisted_caller();
+    }: _(RawOrigin::Signed(caller.clone()), 0)
+    verify {
+        assert!(Module::<T>::is_cancelled(&caller, 0));
+    }
+
+    cancel_orders {
+        let caller: T::AccountId = whitelisted_caller();
+    }: _(RawOrigin::Signed(caller.clone()), 10)
+    verify {
+        assert_eq!(Module::<T>::min_nonce(&caller), 10);
+    }
+}
+
+impl_benchmark_test_suite!(
+    Module,
+    crate::mock::new_test_ext(),
+    crate::mock::Test,
+);
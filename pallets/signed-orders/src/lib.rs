@@ -0,0 +1,299 @@
+// This file is part of Polkadex.
+
+// Copyright (C) 2020-2021 Polkadex oü.
+// SPDX-License-Identifier: GPL-3.0-or-later WITH Classpath-exception-2.0
+
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with this program. If not, see <https://www.gnu.org/licenses/>.
+//! # Signed orders pallet
+//!
+//! Settles orders that traders sign off chain and relayers match.
+//!
+//! - A trader signs an [`Order`]: side, trading pair, limit price, amount of the base asset, a
+//!   nonce and the last block it can be settled in. The signed payload is the SCALE encoding of
+//!   `(SIGNING_DOMAIN, genesis_hash, order)`, so a signature is valid for orders of one chain
+//!   only and cannot be mistaken for anything else signed with the same key. Signatures are
+//!   checked against [`Config::Signature`], `MultiSignature` in the runtime, so sr25519, ed25519
+//!   and ecdsa keys all work.
+//! - Anyone can relay a matched bid and ask with [`Call::settle_matched_orders`], choosing the
+//!   amount and a price between both limits. The relayer pays the transaction fee; the traders
+//!   only pay the assets they trade, which move through [`Config::Currency`].
+//! - An order is identified by its signer and nonce. The amount settled for each order is kept
+//!   in [`FilledAmount`], so an order can be filled in several settlements but never for more
+//!   than its amount, and a settled order cannot be replayed.
+//! - Traders cancel an order with [`Call::cancel_order`], or all their orders below a nonce
+//!   with [`Call::cancel_orders`]. Nothing of a cancelled order can be settled anymore, whatever
+//!   was filled before.
+
+#![cfg_attr(not(feature = "std"), no_std)]
+
+use codec::{Decode, Encode};
+use frame_support::{
+    decl_error, decl_event, decl_module, decl_storage,
+    dispatch::DispatchResult,
+    ensure, transactional, Parameter, StorageDoubleMap, StorageMap,
+};
+use frame_system::ensure_signed;
+use orml_traits::MultiCurrency;
+use polkadex_primitives::assets::AssetId;
+use sp_runtime::{
+    traits::{AtLeast32BitUnsigned, CheckedAdd, IdentifyAccount, MaybeSerializeDeserialize, Member, Verify, Zero},
+    DispatchError, FixedPointNumber, FixedPointOperand, FixedU128, RuntimeDebug,
+};
+use sp_std::prelude::*;
+
+pub use weights::WeightInfo;
+
+#[cfg(feature = "runtime-benchmarks")]
+mod benchmarking;
+#[cfg(test)]
+mod mock;
+#[cfg(test)]
+mod tests;
+pub mod weights;
+
+/// Prefix of the payload traders sign for an order.
+pub const SIGNING_DOMAIN: &[u8] = b"polkadex/signed-order";
+
+/// Side of an order.
+#[derive(Encode, Decode, Clone, Copy, PartialEq, Eq, RuntimeDebug)]
+pub enum Side {
+    /// Buys the base asset with the quote asset.
+    Bid,
+    /// Sells the base asset for the quote asset.
+    Ask,
+}
+
+/// An order as signed by a trader.
+#[derive(Encode, Decode, Clone, PartialEq, Eq, RuntimeDebug)]
+pub struct Order<AccountId, Balance, BlockNumber> {
+    /// Account signing the order and trading its assets.
+    pub account: AccountId,
+    /// Identifies the order among the orders of `account`.
+    pub nonce: u64,
+    /// Asset being bought or sold.
+    pub base: AssetId,
+    /// Asset the base asset is priced in.
+    pub quote: AssetId,
+    pub side: Side,
+    /// Limit price, in units of the quote asset per unit of the base asset.
+    pub price: FixedU128,
+    /// Amount of the base asset to buy or sell.
+    pub amount: Balance,
+    /// Last block the order can be settled in.
+    pub expiry: BlockNumber,
+}
+
+/// An order with the signature of its account.
+#[derive(Encode, Decode, Clone, PartialEq, Eq, RuntimeDebug)]
+pub struct SignedOrder<AccountId, Balance, BlockNumber, Signature> {
+    pub order: Order<AccountId, Balance, BlockNumber>,
+    /// Signature of the payload of `order`, see [`Module::signing_payload`], by `order.account`.
+    pub signature: Signature,
+}
+
+pub type OrderOf<T> = Order<
+    <T as frame_system::Config>::AccountId,
+    <T as Config>::Balance,
+    <T as frame_system::Config>::BlockNumber,
+>;
+pub type SignedOrderOf<T> = SignedOrder<
+    <T as frame_system::Config>::AccountId,
+    <T as Config>::Balance,
+    <T as frame_system::Config>::BlockNumber,
+    <T as Config>::Signature,
+>;
+
+/// Configure the pallet by specifying the parameters and types on which it depends.
+pub trait Config: frame_system::Config {
+    /// Because this pallet emits events, it depends on the runtime's definition of an event.
+    type Event: From<Event<Self>> + Into<<Self as frame_system::Config>::Event>;
+    /// Balance of the traded assets.
+    type Balance: Parameter
+        + Member
+        + AtLeast32BitUnsigned
+        + FixedPointOperand
+        + Default
+        + Copy
+        + MaybeSerializeDeserialize;
+    /// Assets the orders trade.
+    type Currency: MultiCurrency<Self::AccountId, CurrencyId = AssetId, Balance = Self::Balance>;
+    /// Public key of traders, identifying their account.
+    type Public: IdentifyAccount<AccountId = Self::AccountId>;
+    /// Signature of orders by traders.
+    type Signature: Parameter + Verify<Signer = Self::Public>;
+    /// Weight information for extrinsics in this pallet.
+    type WeightInfo: WeightInfo;
+}
+
+decl_storage! {
+    trait Store for Module<T: Config> as SignedOrders {
+        /// Amount of the base asset settled for each order, by account and nonce.
+        pub FilledAmount get(fn filled_amount):
+            double_map hasher(blake2_128_concat) T::AccountId, hasher(twox_64_concat) u64 => T::Balance;
+        /// Orders of an account with a lower nonce are cancelled.
+        pub MinNonce get(fn min_nonce): map hasher(blake2_128_concat) T::AccountId => u64;
+        /// Orders cancelled one by one, by account and nonce.
+        pub CancelledOrders get(fn is_cancelled):
+            double_map hasher(blake2_128_concat) T::AccountId, hasher(twox_64_concat) u64 => bool;
+    }
+}
+
+decl_event!(
+    pub enum Event<T>
+    where
+        AccountId = <T as frame_system::Config>::AccountId,
+        Balance = <T as Config>::Balance,
+    {
+        /// A bid and an ask were settled.
+        /// \[buyer, bid_nonce, seller, ask_nonce, base, quote, base_amount, quote_amount\]
+        OrdersSettled(AccountId, u64, AccountId, u64, AssetId, AssetId, Balance, Balance),
+        /// An order was cancelled. \[who, nonce\]
+        OrderCancelled(AccountId, u64),
+        /// All orders below a nonce were cancelled. \[who, min_nonce\]
+        OrdersCancelled(AccountId, u64),
+    }
+);
+
+decl_error! {
+    pub enum Error for Module<T: Config> {
+        /// The signature does not match the order.
+        BadSignature,
+        /// The bid is not a bid or the ask is not an ask.
+        WrongSide,
+        /// The orders trade different pairs.
+        PairMismatch,
+        /// The base and quote asset are the same.
+        InvalidPair,
+        /// Both orders belong to the same account.
+        SelfTrade,
+        /// The order expired.
+        OrderExpired,
+        /// The order was cancelled.
+        OrderCancelled,
+        /// The settlement price is above the bid or below the ask.
+        PriceMismatch,
+        /// The amount exceeds what is left of an order.
+        Overfilled,
+        /// The amount is zero.
+        ZeroAmount,
+        /// The amount of the quote asset is zero or does not fit in a balance.
+        InvalidQuoteAmount,
+        /// The nonce does not cancel any further orders.
+        NonceTooLow,
+    }
+}
+
+decl_module! {
+    pub struct Module<T: Config> for enum Call where origin: T::Origin {
+        type Error = Error<T>;
+
+        fn deposit_event() = default;
+
+        /// Settle `amount` of the base asset between `bid` and `ask` at `price`.
+        ///
+        /// The buyer receives `amount` of the base asset from the seller and pays `amount * price`
+        /// of the quote asset. `price` must satisfy the limits of both orders.
+        #[weight = <T as Config>::WeightInfo::settle_matched_orders()]
+        #[transactional]
+        pub fn settle_matched_orders(
+            origin,
+            bid: SignedOrderOf<T>,
+            ask: SignedOrderOf<T>,
+            amount: T::Balance,
+            price: FixedU128,
+        ) -> DispatchResult {
+            ensure_signed(origin)?;
+            ensure!(!amount.is_zero(), Error::<T>::ZeroAmount);
+            let bid_filled = Self::check_order(&bid, Side::Bid, amount)?;
+            let ask_filled = Self::check_order(&ask, Side::Ask, amount)?;
+            let (bid, ask) = (bid.order, ask.order);
+            ensure!(bid.base == ask.base && bid.quote == ask.quote, Error::<T>::PairMismatch);
+            ensure!(bid.account != ask.account, Error::<T>::SelfTrade);
+            ensure!(ask.price <= price && price <= bid.price, Error::<T>::PriceMismatch);
+            let quote_amount = price
+                .checked_mul_int(amount)
+                .filter(|quote_amount| !quote_amount.is_zero())
+                .ok_or(Error::<T>::InvalidQuoteAmount)?;
+
+            T::Currency::transfer(bid.base, &ask.account, &bid.account, amount)?;
+            T::Currency::transfer(bid.quote, &bid.account, &ask.account, quote_amount)?;
+            FilledAmount::<T>::insert(&bid.account, bid.nonce, bid_filled);
+            FilledAmount::<T>::insert(&ask.account, ask.nonce, ask_filled);
+            Self::deposit_event(RawEvent::OrdersSettled(
+                bid.account,
+                bid.nonce,
+                ask.account,
+                ask.nonce,
+                bid.base,
+                bid.quote,
+                amount,
+                quote_amount,
+            ));
+            Ok(())
+        }
+
+        /// Cancel the caller's order with `nonce`, leaving nothing of it to settle.
+        #[weight = <T as Config>::WeightInfo::cancel_order()]
+        pub fn cancel_order(origin, nonce: u64) -> DispatchResult {
+            let who = ensure_signed(origin)?;
+            CancelledOrders::<T>::insert(&who, nonce, true);
+            Self::deposit_event(RawEvent::OrderCancelled(who, nonce));
+            Ok(())
+        }
+
+        /// Cancel all orders of the caller with a nonce below `min_nonce`.
+        #[weight = <T as Config>::WeightInfo::cancel_orders()]
+        pub fn cancel_orders(origin, min_nonce: u64) -> DispatchResult {
+            let who = ensure_signed(origin)?;
+            ensure!(min_nonce > Self::min_nonce(&who), Error::<T>::NonceTooLow);
+            MinNonce::<T>::insert(&who, min_nonce);
+            Self::deposit_event(RawEvent::OrdersCancelled(who, min_nonce));
+            Ok(())
+        }
+    }
+}
+
+impl<T: Config> Module<T> {
+    /// The payload `order.account` signs for `order`: the SCALE encoding of
+    /// `(SIGNING_DOMAIN, genesis_hash, order)`.
+    pub fn signing_payload(order: &OrderOf<T>) -> Vec<u8> {
+        let genesis_hash = frame_system::Pallet::<T>::block_hash(T::BlockNumber::zero());
+        (SIGNING_DOMAIN, genesis_hash, order).encode()
+    }
+
+    /// Checks that `signed` is a valid, live order on `side` with at least `amount` left,
+    /// returning its filled amount once `amount` is settled.
+    fn check_order(signed: &SignedOrderOf<T>, side: Side, amount: T::Balance) -> Result<T::Balance, DispatchError> {
+        let order = &signed.order;
+        ensure!(order.side == side, Error::<T>::WrongSide);
+        ensure!(order.base != order.quote, Error::<T>::InvalidPair);
+        ensure!(
+            signed.signature.verify(&Self::signing_payload(order)[..], &order.account),
+            Error::<T>::BadSignature
+        );
+        ensure!(
+            order.nonce >= Self::min_nonce(&order.account) && !Self::is_cancelled(&order.account, order.nonce),
+            Error::<T>::OrderCancelled
+        );
+        ensure!(
+            frame_system::Pallet::<T>::block_number() <= order.expiry,
+            Error::<T>::OrderExpired
+        );
+        let filled = Self::filled_amount(&order.account, order.nonce)
+            .checked_add(&amount)
+            .filter(|filled| *filled <= order.amount)
+            .ok_or(Error::<T>::Overfilled)?;
+        Ok(filled)
+    }
+}
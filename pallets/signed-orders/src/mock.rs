@@ -0,0 +1,138 @@
+// This file is part of Polkadex.
+
+// Copyright (C) 2020-2021 Polkadex oü.
+// SPDX-License-Identifier: GPL-3.0-or-later WITH Classpath-exception-2.0
+
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with this program. If not, see <https://www.gnu.org/licenses/>.
+//! Test environment for the signed orders pallet.
+
+use crate::{self as pallet_signed_orders};
+use frame_support::{parameter_types, traits::GenesisBuild};
+use orml_traits::parameter_type_with_key;
+use polkadex_primitives::assets::AssetId;
+use sp_core::{sr25519, H256};
+use sp_keystore::{testing::KeyStore, KeystoreExt};
+use sp_runtime::{
+    testing::Header,
+    traits::{BlakeTwo256, IdentityLookup},
+};
+use std::sync::Arc;
+
+type UncheckedExtrinsic = frame_system::mocking::MockUncheckedExtrinsic<Test>;
+type Block = frame_system::mocking::MockBlock<Test>;
+
+pub type AccountId = sr25519::Public;
+
+frame_support::construct_runtime!(
+    pub enum Test where
+        Block = Block,
+        NodeBlock = Block,
+        UncheckedExtrinsic = UncheckedExtrinsic,
+    {
+        System: frame_system::{Pallet, Call, Config, Storage, Event<T>},
+        Tokens: orml_tokens::{Pallet, Storage, Event<T>, Config<T>},
+        SignedOrders: pallet_signed_orders::{Pallet, Call, Storage, Event<T>},
+    }
+);
+
+parameter_types! {
+    pub const BlockHashCount: u64 = 250;
+}
+
+impl frame_system::Config for Test {
+    type BaseCallFilter = ();
+    type BlockWeights = ();
+    type BlockLength = ();
+    type DbWeight = ();
+    type Origin = Origin;
+    type Call = Call;
+    type Index = u64;
+    type BlockNumber = u64;
+    type Hash = H256;
+    type Hashing = BlakeTwo256;
+    type AccountId = AccountId;
+    type Lookup = IdentityLookup<Self::AccountId>;
+    type Header = Header;
+    type Event = Event;
+    type BlockHashCount = BlockHashCount;
+    type Version = ();
+    type PalletInfo = PalletInfo;
+    type AccountData = ();
+    type OnNewAccount = ();
+    type OnKilledAccount = ();
+    type SystemWeightInfo = ();
+    type SS58Prefix = ();
+    type OnSetCode = ();
+}
+
+parameter_type_with_key! {
+    pub ExistentialDeposits: |_currency_id: AssetId| -> u64 {
+        0
+    };
+}
+
+impl orml_tokens::Config for Test {
+    type Event = Event;
+    type Balance = u64;
+    type Amount = i64;
+    type CurrencyId = AssetId;
+    type WeightInfo = ();
+    type ExistentialDeposits = ExistentialDeposits;
+    type OnDust = ();
+}
+
+impl pallet_signed_orders::Config for Test {
+    type Event = Event;
+    type Balance = u64;
+    type Currency = Tokens;
+    type Public = sr25519::Public;
+    type Signature = sr25519::Signature;
+    type WeightInfo = ();
+}
+
+/// Key pair of a trader.
+pub fn trader(seed: u8) -> sr25519::Pair {
+    use sp_core::Pair;
+    sr25519::Pair::from_seed(&[seed; 32])
+}
+
+pub fn account(seed: u8) -> AccountId {
+    use sp_core::Pair;
+    trader(seed).public()
+}
+
+pub const ALICE: u8 = 1;
+pub const BOB: u8 = 2;
+pub const RELAYER: u8 = 3;
+
+pub fn new_test_ext() -> sp_io::TestExternalities {
+    let mut t = frame_system::GenesisConfig::default().build_storage::<Test>().unwrap();
+    GenesisBuild::<Test>::assimilate_storage(
+        &orml_tokens::GenesisConfig::<Test> {
+            endowed_accounts: vec![
+                (account(ALICE), AssetId::DOT, 1_000),
+                (account(ALICE), AssetId::USD, 1_000),
+                (account(BOB), AssetId::DOT, 1_000),
+                (account(BOB), AssetId::USD, 1_000),
+            ],
+        },
+        &mut t,
+    )
+    .unwrap();
+    let mut ext = sp_io::TestExternalities::new(t);
+    // Benchmarks sign orders with keys generated in the keystore.
+    ext.register_extension(KeystoreExt(Arc::new(KeyStore::new())));
+    ext.execute_with(|| System::set_block_number(1));
+    ext
+}
@@ -0,0 +1,202 @@
+// This file is part of Polkadex.
+
+// Copyright (C) 2020-2021 Polkadex oü.
+// SPDX-License-Identifier: GPL-3.0-or-later WITH Classpath-exception-2.0
+
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with this program. If not, see <https://www.gnu.org/licenses/>.
+use crate::{mock::*, Error, Order, OrderOf, RawEvent, Side, SignedOrder, SignedOrderOf};
+use codec::Encode;
+use frame_support::{assert_noop, assert_ok, dispatch::DispatchResult};
+use orml_traits::MultiCurrency;
+use polkadex_primitives::assets::AssetId;
+use sp_core::Pair;
+use sp_runtime::{FixedPointNumber, FixedU128};
+
+fn price(value: u64) -> FixedU128 {
+    FixedU128::saturating_from_integer(value)
+}
+
+fn order(who: u8, nonce: u64, side: Side, limit: u64, amount: u64) -> OrderOf<Test> {
+    Order {
+        account: account(who),
+        nonce,
+        base: AssetId::DOT,
+        quote: AssetId::USD,
+        side,
+        price: price(limit),
+        amount,
+        expiry: 10,
+    }
+}
+
+fn sign(order: OrderOf<Test>, by: u8) -> SignedOrderOf<Test> {
+    let signature = trader(by).sign(&SignedOrders::signing_payload(&order));
+    SignedOrder { order, signature }
+}
+
+fn settle(bid: &SignedOrderOf<Test>, ask: &SignedOrderOf<Test>, amount: u64, at: u64) -> DispatchResult {
+    SignedOrders::settle_matched_orders(Origin::signed(account(RELAYER)), bid.clone(), ask.clone(), amount, price(at))
+}
+
+fn balance(asset: AssetId, who: u8) -> u64 {
+    Tokens::free_balance(asset, &account(who))
+}
+
+#[test]
+fn matched_orders_are_settled_between_the_traders() {
+    new_test_ext().execute_with(|| {
+        let bid = sign(order(ALICE, 0, Side::Bid, 3, 100), ALICE);
+        let ask = sign(order(BOB, 0, Side::Ask, 2, 100), BOB);
+
+        assert_ok!(settle(&bid, &ask, 40, 2));
+
+        assert_eq!(balance(AssetId::DOT, ALICE), 1_040);
+        assert_eq!(balance(AssetId::USD, ALICE), 920);
+        assert_eq!(balance(AssetId::DOT, BOB), 960);
+        assert_eq!(balance(AssetId::USD, BOB), 1_080);
+        assert_eq!(balance(AssetId::DOT, RELAYER), 0);
+        assert_eq!(SignedOrders::filled_amount(&account(ALICE), 0), 40);
+        assert_eq!(SignedOrders::filled_amount(&account(BOB), 0), 40);
+        let settled: Event =
+            RawEvent::OrdersSettled(account(ALICE), 0, account(BOB), 0, AssetId::DOT, AssetId::USD, 40, 80).into();
+        assert!(System::events().iter().any(|record| record.event == settled));
+    });
+}
+
+#[test]
+fn orders_cannot_be_filled_beyond_their_amount() {
+    new_test_ext().execute_with(|| {
+        let bid = sign(order(ALICE, 0, Side::Bid, 2, 100), ALICE);
+        let ask = sign(order(BOB, 0, Side::Ask, 2, 100), BOB);
+
+        assert_ok!(settle(&bid, &ask, 60, 2));
+        assert_noop!(settle(&bid, &ask, 60, 2), Error::<Test>::Overfilled);
+        assert_ok!(settle(&bid, &ask, 40, 2));
+        assert_noop!(settle(&bid, &ask, 1, 2), Error::<Test>::Overfilled);
+    });
+}
+
+#[test]
+fn signatures_must_match_the_orders() {
+    new_test_ext().execute_with(|| {
+        let bid = sign(order(ALICE, 0, Side::Bid, 2, 100), ALICE);
+        let mut ask = sign(order(BOB, 0, Side::Ask, 2, 100), BOB);
+        ask.order.amount = 1_000;
+
+        assert_noop!(settle(&bid, &ask, 10, 2), Error::<Test>::BadSignature);
+
+        let forged = sign(order(BOB, 0, Side::Ask, 2, 100), ALICE);
+        assert_noop!(settle(&bid, &forged, 10, 2), Error::<Test>::BadSignature);
+
+        // Signatures of the bare order, without the domain and genesis hash, are refused.
+        let ask = order(BOB, 0, Side::Ask, 2, 100);
+        let bare = SignedOrder { signature: trader(BOB).sign(&ask.encode()), order: ask };
+        assert_noop!(settle(&bid, &bare, 10, 2), Error::<Test>::BadSignature);
+    });
+}
+
+#[test]
+fn orders_must_cross_at_the_settlement_price() {
+    new_test_ext().execute_with(|| {
+        let bid = sign(order(ALICE, 0, Side::Bid, 2, 100), ALICE);
+        let ask = sign(order(BOB, 0, Side::Ask, 3, 100), BOB);
+
+        assert_noop!(settle(&bid, &ask, 10, 2), Error::<Test>::PriceMismatch);
+        assert_noop!(settle(&bid, &ask, 10, 3), Error::<Test>::PriceMismatch);
+        assert_noop!(settle(&ask, &bid, 10, 2), Error::<Test>::WrongSide);
+    });
+}
+
+#[test]
+fn orders_must_trade_the_same_pair() {
+    new_test_ext().execute_with(|| {
+        let bid = sign(order(ALICE, 0, Side::Bid, 2, 100), ALICE);
+        let ask = sign(Order { quote: AssetId::BTC, ..order(BOB, 0, Side::Ask, 2, 100) }, BOB);
+        assert_noop!(settle(&bid, &ask, 10, 2), Error::<Test>::PairMismatch);
+
+        let own_ask = sign(order(ALICE, 1, Side::Ask, 2, 100), ALICE);
+        assert_noop!(settle(&bid, &own_ask, 10, 2), Error::<Test>::SelfTrade);
+    });
+}
+
+#[test]
+fn expired_orders_are_rejected() {
+    new_test_ext().execute_with(|| {
+        let bid = sign(order(ALICE, 0, Side::Bid, 2, 100), ALICE);
+        let ask = sign(order(BOB, 0, Side::Ask, 2, 100), BOB);
+
+        System::set_block_number(11);
+
+        assert_noop!(settle(&bid, &ask, 10, 2), Error::<Test>::OrderExpired);
+    });
+}
+
+#[test]
+fn cancelled_orders_are_rejected() {
+    new_test_ext().execute_with(|| {
+        let bid = sign(order(ALICE, 0, Side::Bid, 2, 100), ALICE);
+        let ask = sign(order(BOB, 0, Side::Ask, 2, 100), BOB);
+
+        // Cancelling only applies to the caller's own orders.
+        assert_ok!(SignedOrders::cancel_order(Origin::signed(account(RELAYER)), 0));
+        assert_ok!(settle(&bid, &ask, 10, 2));
+        assert_ok!(SignedOrders::cancel_order(Origin::signed(account(ALICE)), 0));
+        assert_noop!(settle(&bid, &ask, 10, 2), Error::<Test>::OrderCancelled);
+
+        let next_bid = sign(order(ALICE, 1, Side::Bid, 2, 100), ALICE);
+        assert_ok!(SignedOrders::cancel_orders(Origin::signed(account(BOB)), 1));
+        assert_noop!(settle(&next_bid, &ask, 10, 2), Error::<Test>::OrderCancelled);
+        assert_noop!(
+            SignedOrders::cancel_orders(Origin::signed(account(BOB)), 1),
+            Error::<Test>::NonceTooLow
+        );
+
+        let next_ask = sign(order(BOB, 1, Side::Ask, 2, 100), BOB);
+        assert_ok!(settle(&next_bid, &next_ask, 10, 2));
+    });
+}
+
+#[test]
+fn filled_orders_stay_cancelled() {
+    new_test_ext().execute_with(|| {
+        let bid = sign(order(ALICE, 0, Side::Bid, 2, 100), ALICE);
+        let ask = sign(order(BOB, 0, Side::Ask, 2, 200), BOB);
+        assert_ok!(settle(&bid, &ask, 60, 2));
+
+        // Cancelling an order after part of it was filled does not lower what was filled.
+        assert_ok!(SignedOrders::cancel_order(Origin::signed(account(ALICE)), 0));
+        assert_eq!(SignedOrders::filled_amount(&account(ALICE), 0), 60);
+        let cancelled: Event = RawEvent::OrderCancelled(account(ALICE), 0).into();
+        assert!(System::events().iter().any(|record| record.event == cancelled));
+        assert_noop!(settle(&bid, &ask, 40, 2), Error::<Test>::OrderCancelled);
+        assert_noop!(settle(&bid, &ask, 10, 2), Error::<Test>::OrderCancelled);
+
+        let next_bid = sign(order(ALICE, 1, Side::Bid, 2, 100), ALICE);
+        assert_ok!(settle(&next_bid, &ask, 40, 2));
+    });
+}
+
+#[test]
+fn failed_transfers_leave_no_trace() {
+    new_test_ext().execute_with(|| {
+        let bid = sign(order(ALICE, 0, Side::Bid, 20, 100), ALICE);
+        let ask = sign(order(BOB, 0, Side::Ask, 20, 100), BOB);
+
+        assert_noop!(
+            settle(&bid, &ask, 100, 20),
+            orml_tokens::Error::<Test>::BalanceTooLow
+        );
+        assert_eq!(SignedOrders::filled_amount(&account(ALICE), 0), 0);
+    });
+}
@@ -0,0 +1,80 @@
+// This file is part of Polkadex.
+
+// Copyright (C) 2020-2021 Polkadex oü.
+// SPDX-License-Identifier: GPL-3.0-or-later WITH Classpath-exception-2.0
+
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with this program. If not, see <https://www.gnu.org/licenses/>.
+//! Runtime API for estimating fees and dry running call data that has not been signed yet.
+//!
+//! `TransactionPaymentApi` needs a fully built extrinsic. The functions here take the bare
+//! call and the account meant to sign it; the runtime fills in the signed extensions and a
+//! placeholder signature of the right size, so the reported length and fees match the ones
+//! of the real submission.
+
+#![cfg_attr(not(feature = "std"), no_std)]
+
+use codec::{Codec, Decode, Encode};
+use frame_support::weights::Weight;
+pub use pallet_transaction_payment_rpc_runtime_api::{FeeDetails, InclusionFee};
+use sp_runtime::{traits::Block as BlockT, ApplyExtrinsicResult, RuntimeDebug};
+use sp_std::prelude::*;
+
+/// Weight, length and fees of a call submitted by a given signer.
+#[derive(Clone, Eq, PartialEq, Encode, Decode, RuntimeDebug)]
+pub struct FeeEstimate<Balance> {
+    /// Weight declared by the call, before dispatch.
+    pub weight: Weight,
+    /// Length of the signed extrinsic in bytes.
+    pub len: u32,
+    /// Fees charged before any refund of unused weight.
+    pub fee: FeeDetails<Balance>,
+}
+
+/// Outcome of applying a call on top of a block without keeping any of its changes.
+#[derive(Clone, Eq, PartialEq, Encode, Decode, RuntimeDebug)]
+pub struct DryRunOutcome<Balance, Event> {
+    /// Validity of the extrinsic and, if valid, the result of the dispatch.
+    pub result: ApplyExtrinsicResult,
+    /// Events deposited while the extrinsic was applied.
+    pub events: Vec<Event>,
+    /// Weight actually consumed, after refunds reported by the dispatch.
+    pub actual_weight: Weight,
+    /// Fees the signer would pay once unused weight is refunded.
+    pub actual_fee: Balance,
+    /// Estimate computed from the declared weight.
+    pub estimate: FeeEstimate<Balance>,
+}
+
+sp_api::decl_runtime_apis! {
+    /// Fee estimation and dry runs of call data for a given signer.
+    pub trait DryRunApi<AccountId, Call, Balance, Event> where
+        AccountId: Codec,
+        Call: Codec,
+        Balance: Codec,
+        Event: Codec,
+    {
+        /// Weight, length and fees of `call` signed by `signer` with `tip`.
+        fn estimate_fee(signer: AccountId, call: Call, tip: Balance) -> FeeEstimate<Balance>;
+        /// Apply `call` signed by `signer` in a new block on top of `parent`.
+        ///
+        /// The signature is never checked; everything else, including the nonce and the
+        /// fee payment, goes through the same signed extensions as a real extrinsic.
+        fn dry_run(
+            signer: AccountId,
+            call: Call,
+            tip: Balance,
+            parent: <Block as BlockT>::Hash,
+        ) -> DryRunOutcome<Balance, Event>;
+    }
+}
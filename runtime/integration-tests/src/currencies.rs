@@ -0,0 +1,91 @@
+// This file is part of Polkadex.
+
+// Copyright (C) 2020-2021 Polkadex oü.
+// SPDX-License-Identifier: GPL-3.0-or-later WITH Classpath-exception-2.0
+
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with this program. If not, see <https://www.gnu.org/licenses/>.
+
+use node_polkadex_runtime::constants::currency::DOLLARS;
+use node_polkadex_runtime::{Balances, Call, Currencies, Runtime};
+use orml_traits::MultiCurrency;
+use polkadex_primitives::assets::AssetId;
+use sp_keyring::Sr25519Keyring::{Alice, Bob};
+use sp_runtime::MultiAddress;
+
+use crate::mock::*;
+
+fn transfer(asset: AssetId, amount: u128) -> Call {
+    Call::Currencies(orml_currencies::Call::transfer(
+        MultiAddress::Id(account(Bob)),
+        asset,
+        amount,
+    ))
+}
+
+#[test]
+fn transfers_tokens_between_accounts() {
+    new_test_ext().execute_with(|| {
+        let alice_before = Currencies::free_balance(AssetId::DOT, &account(Alice));
+        let bob_before = Currencies::free_balance(AssetId::DOT, &account(Bob));
+
+        execute_ok(Alice, transfer(AssetId::DOT, 10 * DOLLARS));
+
+        assert_eq!(
+            Currencies::free_balance(AssetId::DOT, &account(Alice)),
+            alice_before - 10 * DOLLARS
+        );
+        assert_eq!(
+            Currencies::free_balance(AssetId::DOT, &account(Bob)),
+            bob_before + 10 * DOLLARS
+        );
+        assert!(has_event(orml_currencies::Event::<Runtime>::Transferred(
+            AssetId::DOT,
+            account(Alice),
+            account(Bob),
+            10 * DOLLARS,
+        )));
+    });
+}
+
+#[test]
+fn transfers_native_currency_through_balances() {
+    new_test_ext().execute_with(|| {
+        let bob_before = Balances::free_balance(&account(Bob));
+
+        execute_ok(Alice, transfer(AssetId::POLKADEX, 10 * DOLLARS));
+
+        assert_eq!(Balances::free_balance(&account(Bob)), bob_before + 10 * DOLLARS);
+        assert_eq!(
+            Currencies::free_balance(AssetId::POLKADEX, &account(Bob)),
+            bob_before + 10 * DOLLARS
+        );
+        assert!(has_event(pallet_balances::Event::<Runtime>::Transfer(
+            account(Alice),
+            account(Bob),
+            10 * DOLLARS,
+        )));
+    });
+}
+
+#[test]
+fn rejects_transfers_above_the_free_balance() {
+    new_test_ext().execute_with(|| {
+        let balance = Currencies::free_balance(AssetId::BTC, &account(Alice));
+
+        let result = execute(Alice, transfer(AssetId::BTC, balance + 1));
+
+        assert!(matches!(result, Ok(Err(_))));
+        assert_eq!(Currencies::free_balance(AssetId::BTC, &account(Alice)), balance);
+    });
+}
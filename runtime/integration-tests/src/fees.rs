@@ -0,0 +1,53 @@
+// This file is part of Polkadex.
+
+// Copyright (C) 2020-2021 Polkadex oü.
+// SPDX-License-Identifier: GPL-3.0-or-later WITH Classpath-exception-2.0
+
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with this program. If not, see <https://www.gnu.org/licenses/>.
+
+use frame_support::traits::Currency;
+use node_polkadex_runtime::constants::currency::DOLLARS;
+use node_polkadex_runtime::{Authorship, Balances, BalancesCall, Call, Treasury};
+use sp_keyring::Sr25519Keyring::{Bob, Charlie};
+use sp_runtime::MultiAddress;
+
+use crate::mock::*;
+
+#[test]
+fn fees_are_split_between_treasury_and_block_author() {
+    new_test_ext().execute_with(|| {
+        let treasury = Treasury::account_id();
+        let author = Authorship::author();
+        // Fees are far below the existential deposit, so the treasury has to exist beforehand.
+        Balances::make_free_balance_be(&treasury, DOLLARS);
+        let bob_before = Balances::free_balance(&account(Bob));
+        let author_before = Balances::free_balance(&author);
+
+        execute_ok(
+            Bob,
+            Call::Balances(BalancesCall::transfer(
+                MultiAddress::Id(account(Charlie)),
+                DOLLARS,
+            )),
+        );
+
+        let fee = bob_before - DOLLARS - Balances::free_balance(&account(Bob));
+        assert!(fee > 0);
+        assert_eq!(Balances::free_balance(&treasury), DOLLARS + fee * 80 / 100);
+        assert_eq!(
+            Balances::free_balance(&author),
+            author_before + fee - fee * 80 / 100
+        );
+    });
+}
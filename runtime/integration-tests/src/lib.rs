@@ -0,0 +1,32 @@
+// This file is part of Polkadex.
+
+// Copyright (C) 2020-2021 Polkadex oü.
+// SPDX-License-Identifier: GPL-3.0-or-later WITH Classpath-exception-2.0
+
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with this program. If not, see <https://www.gnu.org/licenses/>.
+
+//! Integration tests executing signed extrinsics against the Polkadex runtime.
+//!
+//! Genesis is built with the node's `testnet_genesis`, blocks are initialised with a BABE
+//! pre-digest and every extrinsic is signed and applied through `Executive`, so signed
+//! extensions, call filters and fee handling take part exactly as they do on chain.
+
+#![cfg(test)]
+
+mod currencies;
+mod fees;
+mod mock;
+mod proxy;
+mod staking;
+mod vesting;
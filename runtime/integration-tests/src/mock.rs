@@ -0,0 +1,108 @@
+// This file is part of Polkadex.
+
+// Copyright (C) 2020-2021 Polkadex oü.
+// SPDX-License-Identifier: GPL-3.0-or-later WITH Classpath-exception-2.0
+
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with this program. If not, see <https://www.gnu.org/licenses/>.
+
+//! Genesis, block initialisation and extrinsic signing shared by the integration tests.
+
+use codec::Encode;
+use node_polkadex_runtime::{
+    signed_extra, Address, Call, Event, Executive, Header, SignedPayload, System,
+    UncheckedExtrinsic,
+};
+use polkadex_node::chain_spec::{authority_keys_from_seed, testnet_genesis, token_endowments};
+use polkadex_primitives::{AccountId, BlockNumber};
+use sp_consensus_babe::digests::{PreDigest, SecondaryPlainPreDigest};
+use sp_consensus_babe::BABE_ENGINE_ID;
+use sp_keyring::Sr25519Keyring;
+use sp_runtime::generic::{Digest, DigestItem, Era};
+use sp_runtime::traits::Header as HeaderT;
+use sp_runtime::{ApplyExtrinsicResult, BuildStorage};
+
+/// Test externalities holding the genesis state of a two validator (Alice and Bob) testnet.
+///
+/// Balances are endowed for the default development accounts and tokens for Alice and Bob.
+/// Block 1 is already initialised.
+pub fn new_test_ext() -> sp_io::TestExternalities {
+    let storage = testnet_genesis(
+        vec![authority_keys_from_seed("Alice"), authority_keys_from_seed("Bob")],
+        vec![],
+        Sr25519Keyring::Alice.to_account_id(),
+        None,
+        token_endowments(&[
+            Sr25519Keyring::Alice.to_account_id(),
+            Sr25519Keyring::Bob.to_account_id(),
+        ]),
+        vec![],
+        false,
+    )
+    .build_storage()
+    .unwrap();
+    let mut ext = sp_io::TestExternalities::new(storage);
+    ext.execute_with(|| initialize_block(1));
+    ext
+}
+
+/// Initialises block `number`, authored by the first session validator in slot `number`.
+///
+/// Blocks are never finalised: that would require the timestamp and other inherents.
+pub fn initialize_block(number: BlockNumber) {
+    let pre_digest = PreDigest::SecondaryPlain(SecondaryPlainPreDigest {
+        authority_index: 0,
+        slot: (number as u64).into(),
+    });
+    let digest = Digest {
+        logs: vec![DigestItem::PreRuntime(BABE_ENGINE_ID, pre_digest.encode())],
+    };
+    Executive::initialize_block(&Header::new(
+        number,
+        Default::default(),
+        Default::default(),
+        System::block_hash(number - 1),
+        digest,
+    ));
+}
+
+/// Signs `call` by `signer` with its current nonce, an immortal era and no tip.
+pub fn sign(signer: Sr25519Keyring, call: Call) -> UncheckedExtrinsic {
+    let account = signer.to_account_id();
+    let extra = signed_extra(System::account_nonce(&account), Era::Immortal, 0);
+    let payload = SignedPayload::new(call, extra).unwrap();
+    let signature = payload.using_encoded(|payload| signer.sign(payload));
+    let (call, extra, _) = payload.deconstruct();
+    UncheckedExtrinsic::new_signed(call, Address::Id(account), signature.into(), extra)
+}
+
+/// Signs `call` by `signer` and applies it through `Executive`.
+pub fn execute(signer: Sr25519Keyring, call: Call) -> ApplyExtrinsicResult {
+    Executive::apply_extrinsic(sign(signer, call))
+}
+
+/// Applies `call` signed by `signer`, asserting that it was valid and dispatched successfully.
+pub fn execute_ok(signer: Sr25519Keyring, call: Call) {
+    assert_eq!(execute(signer, call), Ok(Ok(())));
+}
+
+/// Whether `event` was deposited in the current block.
+pub fn has_event(event: impl Into<Event>) -> bool {
+    let event = event.into();
+    System::events().iter().any(|record| record.event == event)
+}
+
+/// Account id of a development keyring account.
+pub fn account(keyring: Sr25519Keyring) -> AccountId {
+    keyring.to_account_id()
+}
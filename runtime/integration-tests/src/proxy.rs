@@ -0,0 +1,120 @@
+// This file is part of Polkadex.
+
+// Copyright (C) 2020-2021 Polkadex oü.
+// SPDX-License-Identifier: GPL-3.0-or-later WITH Classpath-exception-2.0
+
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with this program. If not, see <https://www.gnu.org/licenses/>.
+
+use node_polkadex_runtime::constants::currency::DOLLARS;
+use node_polkadex_runtime::{BalancesCall, Call, Event, ProxyType, Runtime, System};
+use polkadex_primitives::assets::AssetId;
+use sp_keyring::Sr25519Keyring::{Alice, Bob, Charlie};
+use sp_runtime::MultiAddress;
+
+use crate::mock::*;
+
+/// Lets Bob act for Alice with `proxy_type`.
+fn add_bob_as_proxy(proxy_type: ProxyType) {
+    execute_ok(
+        Alice,
+        Call::Proxy(pallet_proxy::Call::add_proxy(account(Bob), proxy_type, 0)),
+    );
+}
+
+/// Dispatches `call` as Alice through Bob, returning whether the proxied call succeeded.
+fn proxied_by_bob(call: Call) -> bool {
+    execute_ok(
+        Bob,
+        Call::Proxy(pallet_proxy::Call::proxy(account(Alice), None, Box::new(call))),
+    );
+    System::events()
+        .into_iter()
+        .rev()
+        .find_map(|record| match record.event {
+            Event::pallet_proxy(pallet_proxy::Event::ProxyExecuted(result)) => Some(result),
+            _ => None,
+        })
+        .expect("the proxy call was dispatched")
+        .is_ok()
+}
+
+fn transfer() -> Call {
+    Call::Balances(BalancesCall::transfer(MultiAddress::Id(account(Charlie)), DOLLARS))
+}
+
+fn token_transfer() -> Call {
+    Call::Currencies(orml_currencies::Call::transfer(
+        MultiAddress::Id(account(Charlie)),
+        AssetId::DOT,
+        DOLLARS,
+    ))
+}
+
+fn remark() -> Call {
+    Call::System(frame_system::Call::<Runtime>::remark(b"proxied".to_vec()))
+}
+
+fn propose_spend() -> Call {
+    Call::Treasury(pallet_treasury::Call::propose_spend(
+        DOLLARS,
+        MultiAddress::Id(account(Charlie)),
+    ))
+}
+
+fn chill() -> Call {
+    Call::Staking(pallet_staking::Call::chill())
+}
+
+#[test]
+fn any_proxy_can_dispatch_every_call() {
+    new_test_ext().execute_with(|| {
+        add_bob_as_proxy(ProxyType::Any);
+
+        assert!(proxied_by_bob(transfer()));
+        assert!(proxied_by_bob(remark()));
+    });
+}
+
+#[test]
+fn non_transfer_proxy_cannot_move_funds() {
+    new_test_ext().execute_with(|| {
+        add_bob_as_proxy(ProxyType::NonTransfer);
+
+        assert!(!proxied_by_bob(transfer()));
+        assert!(!proxied_by_bob(token_transfer()));
+        assert!(proxied_by_bob(remark()));
+    });
+}
+
+#[test]
+fn governance_proxy_is_limited_to_governance_pallets() {
+    new_test_ext().execute_with(|| {
+        add_bob_as_proxy(ProxyType::Governance);
+
+        assert!(proxied_by_bob(propose_spend()));
+        assert!(!proxied_by_bob(transfer()));
+        assert!(!proxied_by_bob(remark()));
+    });
+}
+
+#[test]
+fn staking_proxy_is_limited_to_staking() {
+    new_test_ext().execute_with(|| {
+        add_bob_as_proxy(ProxyType::Staking);
+
+        assert!(proxied_by_bob(chill()));
+        assert!(!proxied_by_bob(transfer()));
+        assert!(!proxied_by_bob(propose_spend()));
+    });
+}
@@ -0,0 +1,69 @@
+// This file is part of Polkadex.
+
+// Copyright (C) 2020-2021 Polkadex oü.
+// SPDX-License-Identifier: GPL-3.0-or-later WITH Classpath-exception-2.0
+
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with this program. If not, see <https://www.gnu.org/licenses/>.
+
+use node_polkadex_runtime::constants::currency::DOLLARS;
+use node_polkadex_runtime::{Balances, Call, Runtime, Staking};
+use pallet_staking::RewardDestination;
+use sp_keyring::Sr25519Keyring::Charlie;
+use sp_runtime::MultiAddress;
+
+use crate::mock::*;
+
+#[test]
+fn bonding_reserves_stake_and_creates_a_ledger() {
+    new_test_ext().execute_with(|| {
+        let charlie = account(Charlie);
+
+        execute_ok(
+            Charlie,
+            Call::Staking(pallet_staking::Call::bond(
+                MultiAddress::Id(charlie.clone()),
+                1_000 * DOLLARS,
+                RewardDestination::Staked,
+            )),
+        );
+
+        assert_eq!(Staking::bonded(&charlie), Some(charlie.clone()));
+        let ledger = Staking::ledger(&charlie).expect("Charlie controls the stash");
+        assert_eq!(ledger.total, 1_000 * DOLLARS);
+        assert_eq!(ledger.active, 1_000 * DOLLARS);
+        assert_eq!(Staking::payee(&charlie), RewardDestination::Staked);
+        assert!(Balances::locks(&charlie)
+            .iter()
+            .any(|lock| lock.amount == 1_000 * DOLLARS));
+        assert!(has_event(pallet_staking::Event::<Runtime>::Bonded(
+            charlie,
+            1_000 * DOLLARS,
+        )));
+    });
+}
+
+#[test]
+fn bonding_twice_is_rejected() {
+    new_test_ext().execute_with(|| {
+        let bond = Call::Staking(pallet_staking::Call::bond(
+            MultiAddress::Id(account(Charlie)),
+            1_000 * DOLLARS,
+            RewardDestination::Staked,
+        ));
+
+        execute_ok(Charlie, bond.clone());
+
+        assert!(matches!(execute(Charlie, bond), Ok(Err(_))));
+    });
+}
@@ -0,0 +1,78 @@
+// This file is part of Polkadex.
+
+// Copyright (C) 2020-2021 Polkadex oü.
+// SPDX-License-Identifier: GPL-3.0-or-later WITH Classpath-exception-2.0
+
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with this program. If not, see <https://www.gnu.org/licenses/>.
+
+use node_polkadex_runtime::constants::currency::DOLLARS;
+use node_polkadex_runtime::{Call, Runtime, Vesting};
+use pallet_vesting::VestingInfo;
+use sp_keyring::Sr25519Keyring::{Alice, Dave};
+use sp_runtime::MultiAddress;
+
+use crate::mock::*;
+
+fn vested_transfer_to_dave() {
+    execute_ok(
+        Alice,
+        Call::Vesting(pallet_vesting::Call::vested_transfer(
+            MultiAddress::Id(account(Dave)),
+            VestingInfo {
+                locked: 1_000 * DOLLARS,
+                per_block: 100 * DOLLARS,
+                starting_block: 1,
+            },
+        )),
+    );
+}
+
+#[test]
+fn vested_transfer_locks_funds_until_they_vest() {
+    new_test_ext().execute_with(|| {
+        vested_transfer_to_dave();
+
+        assert_eq!(Vesting::vesting_balance(&account(Dave)), Some(1_000 * DOLLARS));
+        assert!(has_event(pallet_vesting::Event::<Runtime>::VestingUpdated(
+            account(Dave),
+            1_000 * DOLLARS,
+        )));
+
+        initialize_block(2);
+        initialize_block(3);
+        initialize_block(4);
+        execute_ok(Dave, Call::Vesting(pallet_vesting::Call::vest()));
+
+        assert_eq!(Vesting::vesting_balance(&account(Dave)), Some(700 * DOLLARS));
+        assert!(has_event(pallet_vesting::Event::<Runtime>::VestingUpdated(
+            account(Dave),
+            700 * DOLLARS,
+        )));
+    });
+}
+
+#[test]
+fn fully_vested_schedules_are_removed() {
+    new_test_ext().execute_with(|| {
+        vested_transfer_to_dave();
+
+        initialize_block(11);
+        execute_ok(Dave, Call::Vesting(pallet_vesting::Call::vest()));
+
+        assert_eq!(Vesting::vesting_balance(&account(Dave)), None);
+        assert!(has_event(pallet_vesting::Event::<Runtime>::VestingCompleted(
+            account(Dave)
+        )));
+    });
+}
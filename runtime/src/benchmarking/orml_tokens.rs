@@ -0,0 +1,70 @@
+// This file is part of Polkadex.
+
+// Copyright (C) 2020-2021 Polkadex oü.
+// SPDX-License-Identifier: GPL-3.0-or-later WITH Classpath-exception-2.0
+
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with this program. If not, see <https://www.gnu.org/licenses/>.
+
+use frame_benchmarking::{account, whitelisted_caller};
+use frame_system::RawOrigin;
+use orml_benchmarking::runtime_benchmarks;
+use orml_traits::MultiCurrency;
+use polkadex_primitives::assets::AssetId;
+
+use super::utils::{lookup_of_account, set_balance};
+use crate::{constants::currency::DOLLARS, AccountId, Balance, Runtime, Tokens};
+
+const SEED: u32 = 0;
+const ASSET: AssetId = AssetId::DOT;
+
+runtime_benchmarks! {
+	{ Runtime, orml_tokens }
+
+	transfer {
+		let amount: Balance = 100 * DOLLARS;
+
+		let from: AccountId = whitelisted_caller();
+		set_balance(ASSET, &from, amount);
+
+		let to: AccountId = account("to", 0, SEED);
+		let to_lookup = lookup_of_account(to.clone());
+	}: _(RawOrigin::Signed(from), to_lookup, ASSET, amount)
+	verify {
+		assert_eq!(<Tokens as MultiCurrency<_>>::total_balance(ASSET, &to), amount);
+	}
+
+	transfer_all {
+		let amount: Balance = 100 * DOLLARS;
+
+		let from: AccountId = whitelisted_caller();
+		set_balance(ASSET, &from, amount);
+
+		let to: AccountId = account("to", 0, SEED);
+		let to_lookup = lookup_of_account(to.clone());
+	}: _(RawOrigin::Signed(from.clone()), to_lookup, ASSET)
+	verify {
+		assert_eq!(<Tokens as MultiCurrency<_>>::total_balance(ASSET, &from), 0);
+		assert_eq!(<Tokens as MultiCurrency<_>>::total_balance(ASSET, &to), amount);
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use orml_benchmarking::impl_benchmark_test_suite;
+
+	use super::*;
+	use crate::benchmarking::utils::tests::new_test_ext;
+
+	impl_benchmark_test_suite!(new_test_ext(),);
+}
@@ -0,0 +1,129 @@
+// This file is part of Polkadex.
+
+// Copyright (C) 2020-2021 Polkadex oü.
+// SPDX-License-Identifier: GPL-3.0-or-later WITH Classpath-exception-2.0
+
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with this program. If not, see <https://www.gnu.org/licenses/>.
+
+use frame_benchmarking::{account, whitelisted_caller};
+use frame_support::traits::Get;
+use frame_system::RawOrigin;
+use orml_benchmarking::runtime_benchmarks;
+use orml_traits::MultiCurrency;
+use orml_vesting::VestingSchedule;
+use sp_runtime::traits::AccountIdConversion;
+use sp_std::prelude::*;
+
+use super::utils::{lookup_of_account, set_balance};
+use crate::{
+	AccountId, Balance, BlockNumber, Currencies, GetNativeCurrencyId, MinVestedTransfer,
+	OrmlVesting, PolkadexTreasuryModuleId, Runtime, System,
+};
+
+pub type Schedule = VestingSchedule<BlockNumber, Balance>;
+
+const SEED: u32 = 0;
+const MAX_VESTING_SCHEDULES: u32 = 100;
+
+runtime_benchmarks! {
+	{ Runtime, orml_vesting }
+
+	vested_transfer {
+		let schedule = Schedule {
+			start: 0,
+			period: 2,
+			period_count: 3,
+			per_period: MinVestedTransfer::get(),
+		};
+
+		// vested transfers are restricted to the Polkadex treasury.
+		let from: AccountId = PolkadexTreasuryModuleId::get().into_account();
+		set_balance(GetNativeCurrencyId::get(), &from, schedule.total_amount().unwrap() * 2);
+
+		let to: AccountId = account("to", 0, SEED);
+		let to_lookup = lookup_of_account(to.clone());
+	}: _(RawOrigin::Signed(from), to_lookup, schedule.clone())
+	verify {
+		assert_eq!(
+			<Currencies as MultiCurrency<_>>::total_balance(GetNativeCurrencyId::get(), &to),
+			schedule.total_amount().unwrap()
+		);
+	}
+
+	claim {
+		let i in 1 .. MAX_VESTING_SCHEDULES;
+
+		let mut schedule = Schedule {
+			start: 0,
+			period: 2,
+			period_count: 3,
+			per_period: MinVestedTransfer::get(),
+		};
+
+		let from: AccountId = PolkadexTreasuryModuleId::get().into_account();
+		set_balance(GetNativeCurrencyId::get(), &from, schedule.total_amount().unwrap() * i as Balance + 1);
+
+		let to: AccountId = whitelisted_caller();
+		let to_lookup = lookup_of_account(to.clone());
+
+		for _ in 0..i {
+			schedule.start = i;
+			OrmlVesting::vested_transfer(RawOrigin::Signed(from.clone()).into(), to_lookup.clone(), schedule.clone())?;
+		}
+		System::set_block_number(schedule.end().unwrap() + 1u32);
+	}: _(RawOrigin::Signed(to.clone()))
+	verify {
+		assert_eq!(
+			<Currencies as MultiCurrency<_>>::free_balance(GetNativeCurrencyId::get(), &to),
+			schedule.total_amount().unwrap() * i as Balance,
+		);
+	}
+
+	update_vesting_schedules {
+		let i in 1 .. MAX_VESTING_SCHEDULES;
+
+		let mut schedule = Schedule {
+			start: 0,
+			period: 2,
+			period_count: 3,
+			per_period: MinVestedTransfer::get(),
+		};
+
+		let to: AccountId = account("to", 0, SEED);
+		set_balance(GetNativeCurrencyId::get(), &to, schedule.total_amount().unwrap() * i as Balance);
+		let to_lookup = lookup_of_account(to.clone());
+
+		let mut schedules = vec![];
+		for _ in 0..i {
+			schedule.start = i;
+			schedules.push(schedule.clone());
+		}
+	}: _(RawOrigin::Root, to_lookup, schedules)
+	verify {
+		assert_eq!(
+			<Currencies as MultiCurrency<_>>::free_balance(GetNativeCurrencyId::get(), &to),
+			schedule.total_amount().unwrap() * i as Balance
+		);
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use orml_benchmarking::impl_benchmark_test_suite;
+
+	use super::*;
+	use crate::benchmarking::utils::tests::new_test_ext;
+
+	impl_benchmark_test_suite!(new_test_ext(),);
+}
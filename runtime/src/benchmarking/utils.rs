@@ -0,0 +1,54 @@
+// This file is part of Polkadex.
+
+// Copyright (C) 2020-2021 Polkadex oü.
+// SPDX-License-Identifier: GPL-3.0-or-later WITH Classpath-exception-2.0
+
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with this program. If not, see <https://www.gnu.org/licenses/>.
+
+//! Helpers shared by the runtime benchmarks.
+
+use orml_traits::MultiCurrencyExtended;
+use polkadex_primitives::assets::AssetId;
+use sp_runtime::traits::{SaturatedConversion, StaticLookup};
+
+use crate::{AccountId, Balance, Currencies, Indices};
+
+/// Sets the free balance of `who` in `currency_id`, native or not.
+pub fn set_balance(currency_id: AssetId, who: &AccountId, balance: Balance) {
+	assert!(<Currencies as MultiCurrencyExtended<_>>::update_balance(
+		currency_id,
+		who,
+		balance.saturated_into()
+	)
+	.is_ok());
+}
+
+/// Converts an account into the runtime's lookup source.
+pub fn lookup_of_account(who: AccountId) -> <Indices as StaticLookup>::Source {
+	<Indices as StaticLookup>::unlookup(who)
+}
+
+#[cfg(test)]
+pub mod tests {
+	use frame_support::traits::GenesisBuild;
+
+	use crate::Runtime;
+
+	pub fn new_test_ext() -> sp_io::TestExternalities {
+		frame_system::GenesisConfig::default()
+			.build_storage::<Runtime>()
+			.unwrap()
+			.into()
+	}
+}
@@ -0,0 +1,530 @@
+// This file is part of Polkadex.
+
+// Copyright (C) 2020-2021 Polkadex oü.
+// SPDX-License-Identifier: GPL-3.0-or-later WITH Classpath-exception-2.0
+
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with this program. If not, see <https://www.gnu.org/licenses/>.
+
+//! Chain extension giving smart contracts access to the multi-asset balances of `orml_tokens`
+//! and to the orderbook.
+//!
+//! A contract selects a function by id and passes its SCALE encoded arguments as input:
+//!
+//! | id     | arguments                                                                       | output    |
+//! |--------|---------------------------------------------------------------------------------|-----------|
+//! | `1001` | `(AssetId, AccountId)`                                                          | `Balance` |
+//! | `1002` | `(AssetId, AccountId)`                                                          | `Balance` |
+//! | `1003` | `(AssetId, AccountId, Balance)`                                                 | -         |
+//! | `1004` | `(TradingPair, Side, Balance, Option<FixedU128>, Option<Trigger>, TimeInForce)` | `OrderId` |
+//! | `1005` | `OrderId`                                                                       | -         |
+//!
+//! `1001` and `1002` return the free and total balance of an account. `1003` transfers funds
+//! out of the calling contract's own account and reports failure through [`RetCode`] instead
+//! of trapping, so the contract can recover. Native transfers go through the contract's own
+//! transfer, which keeps the contract above its subsistence threshold.
+//!
+//! `1004` and `1005` place and cancel orders of the calling contract with
+//! `pallet_orderbook::Call::place_order` and `pallet_orderbook::Call::cancel_order`, reporting
+//! refused orders through [`RetCode`] as well. Like native transfers, orders that would take the
+//! free native balance of the contract below its subsistence threshold are refused.
+
+use codec::Encode;
+use frame_support::{
+	storage::{with_transaction, TransactionOutcome},
+	traits::Get,
+	weights::Weight,
+};
+use orml_currencies::WeightInfo;
+use orml_traits::MultiCurrency;
+use pallet_contracts::chain_extension::{
+	ChainExtension, Environment, Ext, InitState, RetVal, SysConfig, UncheckedFrom,
+};
+use pallet_orderbook::{OrderId, Side, TimeInForceOf, TradingPair, Trigger, WeightInfo as OrderbookWeightInfo};
+use polkadex_primitives::assets::AssetId;
+use sp_runtime::{DispatchError, FixedU128};
+use sp_std::convert::TryFrom;
+
+use crate::{AccountId, Balance, Contracts, Currencies, Orderbook, Origin, Runtime};
+
+/// Functions exposed by [`PolkadexExtension`].
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum FuncId {
+	/// Free balance of an account in an asset.
+	FreeBalance,
+	/// Total (free and reserved) balance of an account in an asset.
+	TotalBalance,
+	/// Transfer an asset from the calling contract to another account.
+	Transfer,
+	/// Place an order of the calling contract in the orderbook.
+	PlaceOrder,
+	/// Cancel an order of the calling contract.
+	CancelOrder,
+}
+
+impl From<FuncId> for u32 {
+	fn from(func_id: FuncId) -> u32 {
+		match func_id {
+			FuncId::FreeBalance => 1001,
+			FuncId::TotalBalance => 1002,
+			FuncId::Transfer => 1003,
+			FuncId::PlaceOrder => 1004,
+			FuncId::CancelOrder => 1005,
+		}
+	}
+}
+
+impl TryFrom<u32> for FuncId {
+	type Error = DispatchError;
+
+	fn try_from(func_id: u32) -> Result<Self, Self::Error> {
+		match func_id {
+			1001 => Ok(FuncId::FreeBalance),
+			1002 => Ok(FuncId::TotalBalance),
+			1003 => Ok(FuncId::Transfer),
+			1004 => Ok(FuncId::PlaceOrder),
+			1005 => Ok(FuncId::CancelOrder),
+			_ => Err(DispatchError::Other("PolkadexExtension: unknown func_id")),
+		}
+	}
+}
+
+/// Status codes handed back to the contract.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum RetCode {
+	Success = 0,
+	TransferFailed = 1,
+	OrderFailed = 2,
+}
+
+/// Weight of [`FuncId::Transfer`], which goes through `pallet_balances` for the native asset.
+fn transfer_weight(asset_id: AssetId) -> Weight {
+	if asset_id == <Runtime as orml_currencies::Config>::GetNativeCurrencyId::get() {
+		<Runtime as orml_currencies::Config>::WeightInfo::transfer_native_currency()
+	} else {
+		<Runtime as orml_currencies::Config>::WeightInfo::transfer_non_native_currency()
+	}
+}
+
+/// The chain extension plugged into `pallet_contracts`.
+pub struct PolkadexExtension;
+
+impl ChainExtension<Runtime> for PolkadexExtension {
+	fn call<E>(func_id: u32, env: Environment<E, InitState>) -> Result<RetVal, DispatchError>
+		where
+			E: Ext<T=Runtime>,
+			<E::T as SysConfig>::AccountId: UncheckedFrom<<E::T as SysConfig>::Hash> + AsRef<[u8]>,
+	{
+		let func_id = FuncId::try_from(func_id)?;
+		let mut env = env.buf_in_buf_out();
+		match func_id {
+			FuncId::FreeBalance | FuncId::TotalBalance => {
+				env.charge_weight(<Runtime as frame_system::Config>::DbWeight::get().reads(1))?;
+				let (asset_id, who): (AssetId, AccountId) = env.read_as()?;
+				let balance: Balance = if func_id == FuncId::FreeBalance {
+					Currencies::free_balance(asset_id, &who)
+				} else {
+					Currencies::total_balance(asset_id, &who)
+				};
+				env.write(&balance.encode(), false, None)
+					.map_err(|_| DispatchError::Other("PolkadexExtension: failed to write balance"))?;
+				Ok(RetVal::Converging(RetCode::Success as u32))
+			}
+			FuncId::Transfer => {
+				let (asset_id, to, amount): (AssetId, AccountId, Balance) = env.read_as()?;
+				env.charge_weight(transfer_weight(asset_id))?;
+				let result = if asset_id == <Runtime as orml_currencies::Config>::GetNativeCurrencyId::get() {
+					env.ext().transfer(&to, amount)
+				} else {
+					let from = env.ext().address().clone();
+					Currencies::transfer(asset_id, &from, &to, amount)
+				};
+				match result {
+					Ok(()) => Ok(RetVal::Converging(RetCode::Success as u32)),
+					Err(_) => Ok(RetVal::Converging(RetCode::TransferFailed as u32)),
+				}
+			}
+			FuncId::PlaceOrder => {
+				let max_fills = <Runtime as pallet_orderbook::Config>::MaxFills::get();
+				env.charge_weight(<Runtime as pallet_orderbook::Config>::WeightInfo::place_order(max_fills))?;
+				let (pair, side, amount, limit, trigger, time_in_force): (
+					TradingPair,
+					Side,
+					Balance,
+					Option<FixedU128>,
+					Option<Trigger>,
+					TimeInForceOf<Runtime>,
+				) = env.read_as()?;
+				let contract = env.ext().address().clone();
+				let native = <Runtime as orml_currencies::Config>::GetNativeCurrencyId::get();
+				let id: OrderId = Orderbook::next_order_id();
+				// The reserves of the order must leave the contract above its subsistence threshold.
+				let placed = with_transaction(|| {
+					let native_before = Currencies::free_balance(native, &contract);
+					let result = Orderbook::place_order(
+						Origin::signed(contract.clone()),
+						pair,
+						side,
+						amount,
+						limit,
+						trigger,
+						time_in_force,
+					);
+					let native_after = Currencies::free_balance(native, &contract);
+					if result.is_ok()
+						&& (native_after >= native_before || native_after >= Contracts::subsistence_threshold())
+					{
+						TransactionOutcome::Commit(true)
+					} else {
+						TransactionOutcome::Rollback(false)
+					}
+				});
+				if !placed {
+					return Ok(RetVal::Converging(RetCode::OrderFailed as u32));
+				}
+				env.write(&id.encode(), false, None)
+					.map_err(|_| DispatchError::Other("PolkadexExtension: failed to write order id"))?;
+				Ok(RetVal::Converging(RetCode::Success as u32))
+			}
+			FuncId::CancelOrder => {
+				env.charge_weight(<Runtime as pallet_orderbook::Config>::WeightInfo::cancel_order())?;
+				let id: OrderId = env.read_as()?;
+				let origin = Origin::signed(env.ext().address().clone());
+				match Orderbook::cancel_order(origin, id) {
+					Ok(()) => Ok(RetVal::Converging(RetCode::Success as u32)),
+					Err(_) => Ok(RetVal::Converging(RetCode::OrderFailed as u32)),
+				}
+			}
+		}
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use codec::{Decode, Encode};
+	use frame_support::traits::{Get, GenesisBuild};
+	use orml_currencies::WeightInfo;
+	use orml_traits::{MultiCurrency, MultiReservableCurrency};
+	use pallet_contracts_primitives::Code;
+	use pallet_orderbook::{OrderId, Side, TimeInForceOf, TradingPair, Trigger, WeightInfo as OrderbookWeightInfo};
+	use polkadex_primitives::assets::AssetId;
+	use sp_core::Bytes;
+	use sp_runtime::{AccountId32, FixedPointNumber, FixedU128};
+
+	use super::{transfer_weight, FuncId, RetCode};
+	use crate::{
+		constants::currency::DOLLARS, AccountId, Balance, Contracts, Currencies, Orderbook, Origin, Runtime, System,
+	};
+
+	const ALICE: AccountId32 = AccountId32::new([1u8; 32]);
+	const BOB: AccountId32 = AccountId32::new([2u8; 32]);
+	const GAS_LIMIT: u64 = 100_000_000_000;
+
+	/// The `contracts/asset-vault` build written by `scripts/build_contracts.sh`.
+	const ASSET_VAULT: &str =
+		concat!(env!("CARGO_MANIFEST_DIR"), "/../contracts/asset-vault/target/ink/asset_vault.wasm");
+	/// Selectors of the asset vault's constructor and messages.
+	const VAULT_NEW: [u8; 4] = [0, 0, 0, 1];
+	const VAULT_BALANCE_OF: [u8; 4] = [0, 0, 0, 2];
+	const VAULT_WITHDRAW: [u8; 4] = [0, 0, 0, 3];
+	const VAULT_PLACE_ORDER: [u8; 4] = [0, 0, 0, 4];
+	const VAULT_CANCEL_ORDER: [u8; 4] = [0, 0, 0, 5];
+
+	fn new_test_ext() -> sp_io::TestExternalities {
+		let mut t = frame_system::GenesisConfig::default()
+			.build_storage::<Runtime>()
+			.unwrap();
+		pallet_balances::GenesisConfig::<Runtime> {
+			balances: vec![(ALICE, 10_000 * DOLLARS)],
+		}
+			.assimilate_storage(&mut t)
+			.unwrap();
+		pallet_contracts::GenesisConfig::<Runtime> {
+			current_schedule: Default::default(),
+		}
+			.assimilate_storage(&mut t)
+			.unwrap();
+		let mut ext = sp_io::TestExternalities::new(t);
+		ext.execute_with(|| System::set_block_number(1));
+		ext
+	}
+
+	/// Instantiates the pass-through fixture and returns its account.
+	fn deploy() -> AccountId {
+		let wasm = wat::parse_str(include_str!("../fixtures/chain_extension.wat")).unwrap();
+		Contracts::bare_instantiate(
+			ALICE,
+			1_000 * DOLLARS,
+			GAS_LIMIT,
+			Code::Upload(Bytes(wasm)),
+			vec![],
+			vec![],
+			false,
+		)
+			.result
+			.unwrap()
+			.account_id
+	}
+
+	/// Calls the extension through the fixture, returning the status code, the extension
+	/// output and the gas consumed by the call.
+	fn call_extension(contract: &AccountId, func_id: FuncId, input: impl Encode) -> (u32, Vec<u8>, u64) {
+		let mut data = u32::from(func_id).encode();
+		data.extend(input.encode());
+		let result = Contracts::bare_call(ALICE, contract.clone(), 0, GAS_LIMIT, data);
+		let output = result.exec_result.unwrap().data.to_vec();
+		let ret_code = u32::decode(&mut &output[..4]).unwrap();
+		(ret_code, output[4..].to_vec(), result.gas_consumed)
+	}
+
+	#[test]
+	fn balance_queries_work() {
+		new_test_ext().execute_with(|| {
+			let contract = deploy();
+			assert!(Currencies::deposit(AssetId::DOT, &BOB, 500 * DOLLARS).is_ok());
+
+			let (ret_code, output, _) = call_extension(&contract, FuncId::FreeBalance, (AssetId::DOT, BOB));
+			assert_eq!(ret_code, RetCode::Success as u32);
+			assert_eq!(Balance::decode(&mut &output[..]).unwrap(), 500 * DOLLARS);
+
+			let (ret_code, output, _) = call_extension(&contract, FuncId::TotalBalance, (AssetId::DOT, BOB));
+			assert_eq!(ret_code, RetCode::Success as u32);
+			assert_eq!(Balance::decode(&mut &output[..]).unwrap(), 500 * DOLLARS);
+		});
+	}
+
+	#[test]
+	fn transfer_moves_funds_out_of_the_contract() {
+		new_test_ext().execute_with(|| {
+			let contract = deploy();
+			assert!(Currencies::deposit(AssetId::DOT, &contract, 500 * DOLLARS).is_ok());
+
+			let (ret_code, _, gas_consumed) =
+				call_extension(&contract, FuncId::Transfer, (AssetId::DOT, BOB, 200 * DOLLARS));
+			assert_eq!(ret_code, RetCode::Success as u32);
+			assert_eq!(Currencies::free_balance(AssetId::DOT, &contract), 300 * DOLLARS);
+			assert_eq!(Currencies::free_balance(AssetId::DOT, &BOB), 200 * DOLLARS);
+			assert!(
+				gas_consumed >= <Runtime as orml_currencies::Config>::WeightInfo::transfer_non_native_currency()
+			);
+		});
+	}
+
+	#[test]
+	fn native_transfer_is_charged_the_native_weight() {
+		new_test_ext().execute_with(|| {
+			let contract = deploy();
+			let before = Currencies::free_balance(AssetId::POLKADEX, &contract);
+
+			let (ret_code, _, gas_consumed) =
+				call_extension(&contract, FuncId::Transfer, (AssetId::POLKADEX, BOB, 200 * DOLLARS));
+			assert_eq!(ret_code, RetCode::Success as u32);
+			assert_eq!(Currencies::free_balance(AssetId::POLKADEX, &contract), before - 200 * DOLLARS);
+			assert_eq!(Currencies::free_balance(AssetId::POLKADEX, &BOB), 200 * DOLLARS);
+			assert_eq!(
+				transfer_weight(AssetId::POLKADEX),
+				<Runtime as orml_currencies::Config>::WeightInfo::transfer_native_currency()
+			);
+			assert!(gas_consumed >= transfer_weight(AssetId::POLKADEX));
+		});
+	}
+
+	#[test]
+	fn native_transfer_keeps_the_contract_alive() {
+		new_test_ext().execute_with(|| {
+			let contract = deploy();
+			let before = Currencies::free_balance(AssetId::POLKADEX, &contract);
+
+			let (ret_code, _, _) =
+				call_extension(&contract, FuncId::Transfer, (AssetId::POLKADEX, BOB, before));
+			assert_eq!(ret_code, RetCode::TransferFailed as u32);
+			assert_eq!(Currencies::free_balance(AssetId::POLKADEX, &contract), before);
+			assert_eq!(Currencies::free_balance(AssetId::POLKADEX, &BOB), 0);
+		});
+	}
+
+	#[test]
+	fn failed_transfer_is_reported_to_the_contract() {
+		new_test_ext().execute_with(|| {
+			let contract = deploy();
+			assert!(Currencies::deposit(AssetId::DOT, &contract, 100 * DOLLARS).is_ok());
+
+			let (ret_code, _, _) =
+				call_extension(&contract, FuncId::Transfer, (AssetId::DOT, BOB, 200 * DOLLARS));
+			assert_eq!(ret_code, RetCode::TransferFailed as u32);
+			assert_eq!(Currencies::free_balance(AssetId::DOT, &contract), 100 * DOLLARS);
+			assert_eq!(Currencies::free_balance(AssetId::DOT, &BOB), 0);
+		});
+	}
+
+	#[test]
+	fn orders_are_placed_and_cancelled_for_the_contract() {
+		new_test_ext().execute_with(|| {
+			let contract = deploy();
+			let pair = TradingPair { base: AssetId::DOT, quote: AssetId::USD };
+			assert!(Orderbook::register_pair(Origin::root(), pair).is_ok());
+			assert!(Currencies::deposit(AssetId::DOT, &contract, 500 * DOLLARS).is_ok());
+
+			let order = (
+				pair,
+				Side::Ask,
+				200 * DOLLARS,
+				Some(FixedU128::saturating_from_integer(2)),
+				None::<Trigger>,
+				TimeInForceOf::<Runtime>::GoodTillCancelled,
+			);
+			let (ret_code, output, gas_consumed) = call_extension(&contract, FuncId::PlaceOrder, order);
+			assert_eq!(ret_code, RetCode::Success as u32);
+			let id = OrderId::decode(&mut &output[..]).unwrap();
+			assert_eq!(Orderbook::order(id).unwrap().owner, contract);
+			assert_eq!(Currencies::reserved_balance(AssetId::DOT, &contract), 200 * DOLLARS);
+			let max_fills = <Runtime as pallet_orderbook::Config>::MaxFills::get();
+			assert!(
+				gas_consumed >= <Runtime as pallet_orderbook::Config>::WeightInfo::place_order(max_fills)
+			);
+
+			let (ret_code, _, _) = call_extension(&contract, FuncId::CancelOrder, id);
+			assert_eq!(ret_code, RetCode::Success as u32);
+			assert!(Orderbook::order(id).is_none());
+			assert_eq!(Currencies::free_balance(AssetId::DOT, &contract), 500 * DOLLARS);
+		});
+	}
+
+	#[test]
+	fn refused_orders_are_reported_to_the_contract() {
+		new_test_ext().execute_with(|| {
+			let contract = deploy();
+			let pair = TradingPair { base: AssetId::DOT, quote: AssetId::USD };
+			let order = (
+				pair,
+				Side::Ask,
+				200 * DOLLARS,
+				Some(FixedU128::saturating_from_integer(2)),
+				None::<Trigger>,
+				TimeInForceOf::<Runtime>::GoodTillCancelled,
+			);
+
+			// The pair is not open for trading yet.
+			let (ret_code, _, _) = call_extension(&contract, FuncId::PlaceOrder, order);
+			assert_eq!(ret_code, RetCode::OrderFailed as u32);
+
+			// Nor can the contract cancel the orders of other accounts.
+			assert!(Orderbook::register_pair(Origin::root(), pair).is_ok());
+			assert!(Currencies::deposit(AssetId::DOT, &BOB, 500 * DOLLARS).is_ok());
+			assert!(Orderbook::place_order(
+				Origin::signed(BOB),
+				pair,
+				Side::Ask,
+				200 * DOLLARS,
+				Some(FixedU128::saturating_from_integer(2)),
+				None,
+				TimeInForceOf::<Runtime>::GoodTillCancelled,
+			)
+			.is_ok());
+			let id = Orderbook::next_order_id() - 1;
+			let (ret_code, _, _) = call_extension(&contract, FuncId::CancelOrder, id);
+			assert_eq!(ret_code, RetCode::OrderFailed as u32);
+			assert!(Orderbook::order(id).is_some());
+		});
+	}
+
+	#[test]
+	fn orders_keep_the_contract_alive() {
+		new_test_ext().execute_with(|| {
+			let contract = deploy();
+			let pair = TradingPair { base: AssetId::POLKADEX, quote: AssetId::USD };
+			assert!(Orderbook::register_pair(Origin::root(), pair).is_ok());
+			let free = Currencies::free_balance(AssetId::POLKADEX, &contract);
+			let order = |amount: Balance| (
+				pair,
+				Side::Ask,
+				amount,
+				Some(FixedU128::saturating_from_integer(2)),
+				None::<Trigger>,
+				TimeInForceOf::<Runtime>::GoodTillCancelled,
+			);
+
+			let (ret_code, _, _) = call_extension(&contract, FuncId::PlaceOrder, order(free));
+			assert_eq!(ret_code, RetCode::OrderFailed as u32);
+			assert_eq!(Currencies::free_balance(AssetId::POLKADEX, &contract), free);
+			assert_eq!(Currencies::reserved_balance(AssetId::POLKADEX, &contract), 0);
+
+			let amount = free - Contracts::subsistence_threshold();
+			let (ret_code, _, _) = call_extension(&contract, FuncId::PlaceOrder, order(amount));
+			assert_eq!(ret_code, RetCode::Success as u32);
+			assert_eq!(Currencies::reserved_balance(AssetId::POLKADEX, &contract), amount);
+		});
+	}
+
+	#[test]
+	#[ignore = "needs the asset vault built by scripts/build_contracts.sh"]
+	fn asset_vault_moves_and_trades_assets_through_the_extension() {
+		new_test_ext().execute_with(|| {
+			let wasm = std::fs::read(ASSET_VAULT).expect("asset vault is built");
+			let vault = Contracts::bare_instantiate(
+				ALICE,
+				1_000 * DOLLARS,
+				GAS_LIMIT,
+				Code::Upload(Bytes(wasm)),
+				VAULT_NEW.to_vec(),
+				vec![],
+				false,
+			)
+				.result
+				.unwrap()
+				.account_id;
+			assert!(Currencies::deposit(AssetId::DOT, &vault, 500 * DOLLARS).is_ok());
+
+			let balance_of = (VAULT_BALANCE_OF, AssetId::DOT).encode();
+			let result = Contracts::bare_call(ALICE, vault.clone(), 0, GAS_LIMIT, balance_of);
+			let output = result.exec_result.unwrap().data.to_vec();
+			assert_eq!(Balance::decode(&mut &output[..]).unwrap(), 500 * DOLLARS);
+
+			let withdraw = (VAULT_WITHDRAW, AssetId::DOT, BOB, 200 * DOLLARS).encode();
+			let result = Contracts::bare_call(BOB, vault.clone(), 0, GAS_LIMIT, withdraw.clone());
+			assert!(result.exec_result.is_ok());
+			assert_eq!(Currencies::free_balance(AssetId::DOT, &vault), 500 * DOLLARS);
+
+			let result = Contracts::bare_call(ALICE, vault.clone(), 0, GAS_LIMIT, withdraw);
+			let output = result.exec_result.unwrap().data.to_vec();
+			assert_eq!(output, Ok::<(), u8>(()).encode());
+			assert_eq!(Currencies::free_balance(AssetId::DOT, &vault), 300 * DOLLARS);
+			assert_eq!(Currencies::free_balance(AssetId::DOT, &BOB), 200 * DOLLARS);
+
+			let pair = TradingPair { base: AssetId::DOT, quote: AssetId::POLKADEX };
+			assert!(Orderbook::register_pair(Origin::root(), pair).is_ok());
+			let limit = FixedU128::saturating_from_integer(2).into_inner();
+			let place_order = (VAULT_PLACE_ORDER, pair, Side::Ask, 100 * DOLLARS, limit).encode();
+			let result = Contracts::bare_call(ALICE, vault.clone(), 0, GAS_LIMIT, place_order);
+			let output = result.exec_result.unwrap().data.to_vec();
+			let id = Result::<OrderId, u8>::decode(&mut &output[..]).unwrap().unwrap();
+			assert_eq!(Orderbook::order(id).unwrap().owner, vault);
+			assert_eq!(Currencies::reserved_balance(AssetId::DOT, &vault), 100 * DOLLARS);
+
+			let cancel_order = (VAULT_CANCEL_ORDER, id).encode();
+			let result = Contracts::bare_call(ALICE, vault.clone(), 0, GAS_LIMIT, cancel_order);
+			assert!(result.exec_result.is_ok());
+			assert!(Orderbook::order(id).is_none());
+			assert_eq!(Currencies::free_balance(AssetId::DOT, &vault), 300 * DOLLARS);
+		});
+	}
+
+	#[test]
+	fn unknown_func_id_traps() {
+		new_test_ext().execute_with(|| {
+			let contract = deploy();
+			let result = Contracts::bare_call(ALICE, contract, 0, GAS_LIMIT, 9999u32.encode());
+			assert!(result.exec_result.is_err());
+		});
+	}
+}
@@ -0,0 +1,178 @@
+// This file is part of Polkadex.
+
+// Copyright (C) 2020-2021 Polkadex oü.
+// SPDX-License-Identifier: GPL-3.0-or-later WITH Classpath-exception-2.0
+
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with this program. If not, see <https://www.gnu.org/licenses/>.
+//! Fee estimation and dry runs of call data that has not been signed yet.
+
+use codec::Encode;
+use frame_support::weights::{GetDispatchInfo, PostDispatchInfo};
+use polkadex_dry_run_runtime_api::{DryRunOutcome, FeeEstimate};
+use sp_core::sr25519;
+use sp_runtime::generic::Era;
+use sp_runtime::traits::{Applyable, StaticLookup};
+use sp_std::prelude::*;
+
+use crate::{
+    signed_extra, AccountId, Balance, Call, CheckedExtrinsic, Event, Hash, Indices, Runtime,
+    SignedExtra, System, TransactionPayment, UncheckedExtrinsic,
+};
+
+/// Signed extensions `signer` would attach to its next transaction.
+fn next_signed_extra(signer: &AccountId, tip: Balance) -> SignedExtra {
+    signed_extra(System::account_nonce(signer), Era::Immortal, tip)
+}
+
+/// Encoded length of `call` once signed by `signer`.
+///
+/// The placeholder signature has the size of a real sr25519 one, which is what wallets use.
+fn signed_len(signer: &AccountId, call: &Call, extra: &SignedExtra) -> u32 {
+    UncheckedExtrinsic::new_signed(
+        call.clone(),
+        Indices::unlookup(signer.clone()),
+        sr25519::Signature::from_raw([0u8; 64]).into(),
+        extra.clone(),
+    )
+    .encoded_size() as u32
+}
+
+/// Weight, length and fees of `call` signed by `signer` with `tip`.
+pub fn estimate_fee(signer: AccountId, call: Call, tip: Balance) -> FeeEstimate<Balance> {
+    let extra = next_signed_extra(&signer, tip);
+    let len = signed_len(&signer, &call, &extra);
+    let info = call.get_dispatch_info();
+    FeeEstimate {
+        weight: info.weight,
+        len,
+        fee: TransactionPayment::compute_fee_details(len, &info, tip),
+    }
+}
+
+/// Apply `call` signed by `signer` in a fresh block on top of `parent`.
+///
+/// Only `frame_system` is initialised for the new block: the `on_initialize` hooks of the other
+/// pallets do not run, as they expect consensus digests that a dry run cannot provide. The
+/// outcome can therefore differ from the one of the transaction once included, e.g. if it
+/// depends on state those hooks change. The caller is expected to throw away the resulting
+/// state changes.
+pub fn dry_run(
+    signer: AccountId,
+    call: Call,
+    tip: Balance,
+    parent: Hash,
+) -> DryRunOutcome<Balance, Event> {
+    System::initialize(
+        &(System::block_number() + 1),
+        &parent,
+        &Default::default(),
+        frame_system::InitKind::Full,
+    );
+
+    let estimate = estimate_fee(signer.clone(), call.clone(), tip);
+    let (info, len) = (call.get_dispatch_info(), estimate.len);
+
+    let checked = CheckedExtrinsic {
+        signed: Some((signer.clone(), next_signed_extra(&signer, tip))),
+        function: call,
+    };
+    let applied = checked.apply::<Runtime>(&info, len as usize);
+    let post_info = match &applied {
+        Ok(Ok(post_info)) => *post_info,
+        Ok(Err(err)) => err.post_info,
+        // Nothing was dispatched, nor charged.
+        Err(_) => PostDispatchInfo {
+            actual_weight: Some(0),
+            pays_fee: info.pays_fee,
+        },
+    };
+    let actual_fee = match &applied {
+        Ok(_) => TransactionPayment::compute_actual_fee(len, &info, &post_info, tip),
+        Err(_) => 0,
+    };
+
+    DryRunOutcome {
+        result: applied.map(|outcome| outcome.map(|_| ()).map_err(|err| err.error)),
+        events: System::events()
+            .into_iter()
+            .map(|record| record.event)
+            .collect(),
+        actual_weight: post_info.calc_actual_weight(&info),
+        actual_fee,
+        estimate,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use frame_support::assert_ok;
+
+    use super::*;
+    use crate::constants::currency::DOLLARS;
+    use crate::BalancesCall;
+
+    fn account(seed: u8) -> AccountId {
+        AccountId::new([seed; 32])
+    }
+
+    fn new_test_ext() -> sp_io::TestExternalities {
+        let mut storage = frame_system::GenesisConfig::default()
+            .build_storage::<Runtime>()
+            .unwrap();
+        pallet_balances::GenesisConfig::<Runtime> {
+            balances: vec![(account(1), 1_000 * DOLLARS)],
+        }
+        .assimilate_storage(&mut storage)
+        .unwrap();
+        let mut ext = sp_io::TestExternalities::new(storage);
+        ext.execute_with(|| System::set_block_number(1));
+        ext
+    }
+
+    #[test]
+    fn dry_run_reports_events_and_fees() {
+        new_test_ext().execute_with(|| {
+            let call = Call::Balances(BalancesCall::transfer(
+                Indices::unlookup(account(2)),
+                DOLLARS,
+            ));
+            let estimate = estimate_fee(account(1), call.clone(), 0);
+            let outcome = dry_run(account(1), call, 0, Default::default());
+
+            assert_ok!(outcome.result.clone().unwrap());
+            assert_eq!(outcome.estimate, estimate);
+            assert!(outcome.actual_fee > 0);
+            assert!(outcome.actual_fee <= estimate.fee.final_fee());
+            assert!(outcome.events.iter().any(|event| matches!(
+                event,
+                Event::pallet_balances(pallet_balances::Event::Transfer(..))
+            )));
+        });
+    }
+
+    #[test]
+    fn dry_run_reports_invalid_transactions() {
+        new_test_ext().execute_with(|| {
+            let call = Call::Balances(BalancesCall::transfer(
+                Indices::unlookup(account(1)),
+                DOLLARS,
+            ));
+            let outcome = dry_run(account(3), call, 0, Default::default());
+
+            assert!(outcome.result.is_err());
+            assert_eq!(outcome.actual_fee, 0);
+            assert!(outcome.events.is_empty());
+        });
+    }
+}
@@ -18,8 +18,10 @@
 //! Some configurable implementations as associated type for the substrate runtime.
 
 use frame_support::traits::{Currency, OnUnbalanced};
+use polkadex_primitives::assets::AssetId;
+use sp_runtime::traits::{Hash, StaticLookup};
 
-use crate::{Authorship, Balances, NegativeImbalance};
+use crate::{AccountId, Authorship, Balances, Call, Contracts, Indices, NegativeImbalance, Runtime};
 
 pub struct Author;
 
@@ -29,6 +31,48 @@ impl OnUnbalanced<NegativeImbalance> for Author {
     }
 }
 
+/// Lets sponsors pay for `Contracts::call`, the only call that can be sponsored.
+pub struct ContractCallTarget;
+
+impl pallet_contract_sponsorship::SponsoredCallTarget<Call, AccountId> for ContractCallTarget {
+    fn target(call: &Call) -> Option<AccountId> {
+        match call {
+            Call::Contracts(pallet_contracts::Call::call(dest, ..)) => Indices::lookup(dest.clone()).ok(),
+            _ => None,
+        }
+    }
+
+    fn instantiated(call: &Call, deployer: &AccountId) -> Option<AccountId> {
+        match call {
+            Call::Contracts(pallet_contracts::Call::instantiate_with_code(_, _, code, _, salt)) => {
+                let code_hash = <Runtime as frame_system::Config>::Hashing::hash(code);
+                Some(Contracts::contract_address(deployer, &code_hash, salt))
+            }
+            Call::Contracts(pallet_contracts::Call::instantiate(_, _, code_hash, _, salt)) => {
+                Some(Contracts::contract_address(deployer, code_hash, salt))
+            }
+            _ => None,
+        }
+    }
+}
+
+/// Symbols the price oracle's offchain workers quote assets under.
+pub struct OraclePriceSource;
+
+impl pallet_price_oracle::PriceSource for OraclePriceSource {
+    fn symbol(asset: AssetId) -> Option<&'static str> {
+        [
+            (AssetId::POLKADEX, "PDEX"),
+            (AssetId::DOT, "DOT"),
+            (AssetId::BTC, "BTC"),
+            (AssetId::USD, "USD"),
+        ]
+        .iter()
+        .find(|(id, _)| *id == asset)
+        .map(|(_, symbol)| *symbol)
+    }
+}
+
 #[cfg(test)]
 mod multiplier_tests {
     use frame_support::weights::{DispatchClass, Weight, WeightToFeePolynomial};
@@ -68,7 +68,7 @@ use sp_core::{
 };
 use sp_inherents::{CheckInherentsResult, InherentData};
 use sp_runtime::{
-	ApplyExtrinsicResult, create_runtime_str, FixedPointNumber, generic, impl_opaque_keys, Perbill,
+	ApplyExtrinsicResult, create_runtime_str, FixedPointNumber, FixedU128, generic, impl_opaque_keys, Perbill,
 	Percent, Permill, Perquintill,
 };
 #[cfg(any(feature = "std", test))]
@@ -96,7 +96,15 @@ pub mod impls;
 
 /// Constant values used within the runtime.
 pub mod constants;
+
+/// Chain extension exposing runtime functionality to smart contracts.
+pub mod chain_extensions;
+
+/// Fee estimation and dry runs of unsigned call data.
+pub mod dry_run;
 mod weights;
+#[cfg(feature = "runtime-benchmarks")]
+mod benchmarking;
 
 // Make the WASM binary available.
 #[cfg(feature = "std")]
@@ -121,10 +129,10 @@ pub const VERSION: RuntimeVersion = RuntimeVersion {
 	// and set impl_version to 0. If only runtime
 	// implementation changes and behavior does not, then leave spec_version as
 	// is and increment impl_version.
-	spec_version: 266,
+	spec_version: 277,
 	impl_version: 0,
 	apis: RUNTIME_API_VERSIONS,
-	transaction_version: 2,
+	transaction_version: 3,
 };
 
 /// The BABE epoch configuration at genesis.
@@ -220,7 +228,7 @@ impl frame_system::Config for Runtime {
 	type AccountData = pallet_balances::AccountData<Balance>;
 	type OnNewAccount = ();
 	type OnKilledAccount = ();
-	type SystemWeightInfo = frame_system::weights::SubstrateWeight<Runtime>;
+	type SystemWeightInfo = weights::frame_system::WeightInfo<Runtime>;
 	type SS58Prefix = SS58Prefix;
 	type OnSetCode = ();
 }
@@ -228,7 +236,7 @@ impl frame_system::Config for Runtime {
 impl pallet_utility::Config for Runtime {
 	type Event = Event;
 	type Call = Call;
-	type WeightInfo = pallet_utility::weights::SubstrateWeight<Runtime>;
+	type WeightInfo = weights::pallet_utility::WeightInfo<Runtime>;
 }
 
 parameter_types! {
@@ -246,7 +254,7 @@ impl pallet_multisig::Config for Runtime {
 	type DepositBase = DepositBase;
 	type DepositFactor = DepositFactor;
 	type MaxSignatories = MaxSignatories;
-	type WeightInfo = pallet_multisig::weights::SubstrateWeight<Runtime>;
+	type WeightInfo = weights::pallet_multisig::WeightInfo<Runtime>;
 }
 
 parameter_types! {
@@ -284,6 +292,10 @@ impl InstanceFilter<Call> for ProxyType {
                 Call::Balances(..)
                     | Call::Vesting(pallet_vesting::Call::vested_transfer(..))
                     | Call::Indices(pallet_indices::Call::transfer(..))
+                    | Call::Currencies(..)
+                    | Call::Orderbook(..)
+                    | Call::Margin(..)
+                    | Call::Ocex(pallet_ocex::Call::withdraw(..))
             ),
 			ProxyType::Governance => matches!(
                 c,
@@ -314,7 +326,7 @@ impl pallet_proxy::Config for Runtime {
 	type ProxyDepositBase = ProxyDepositBase;
 	type ProxyDepositFactor = ProxyDepositFactor;
 	type MaxProxies = MaxProxies;
-	type WeightInfo = pallet_proxy::weights::SubstrateWeight<Runtime>;
+	type WeightInfo = weights::pallet_proxy::WeightInfo<Runtime>;
 	type MaxPending = MaxPending;
 	type CallHasher = BlakeTwo256;
 	type AnnouncementDepositBase = AnnouncementDepositBase;
@@ -335,7 +347,7 @@ impl pallet_scheduler::Config for Runtime {
 	type MaximumWeight = MaximumSchedulerWeight;
 	type ScheduleOrigin = EnsureRoot<AccountId>;
 	type MaxScheduledPerBlock = MaxScheduledPerBlock;
-	type WeightInfo = pallet_scheduler::weights::SubstrateWeight<Runtime>;
+	type WeightInfo = weights::pallet_scheduler::WeightInfo<Runtime>;
 }
 
 parameter_types! {
@@ -367,7 +379,7 @@ impl pallet_babe::Config for Runtime {
 	type HandleEquivocation =
 	pallet_babe::EquivocationHandler<Self::KeyOwnerIdentification, Offences, ReportLongevity>;
 
-	type WeightInfo = ();
+	type WeightInfo = weights::pallet_babe::WeightInfo<Runtime>;
 }
 
 parameter_types! {
@@ -379,7 +391,7 @@ impl pallet_indices::Config for Runtime {
 	type Currency = Balances;
 	type Deposit = IndexDeposit;
 	type Event = Event;
-	type WeightInfo = pallet_indices::weights::SubstrateWeight<Runtime>;
+	type WeightInfo = weights::pallet_indices::WeightInfo<Runtime>;
 }
 
 parameter_types! {
@@ -396,7 +408,7 @@ impl pallet_balances::Config for Runtime {
 	type Event = Event;
 	type ExistentialDeposit = ExistentialDeposit;
 	type AccountStore = frame_system::Pallet<Runtime>;
-	type WeightInfo = pallet_balances::weights::SubstrateWeight<Runtime>;
+	type WeightInfo = weights::pallet_balances::WeightInfo<Runtime>;
 }
 
 parameter_types! {
@@ -422,7 +434,7 @@ impl pallet_timestamp::Config for Runtime {
 	type Moment = Moment;
 	type OnTimestampSet = Babe;
 	type MinimumPeriod = MinimumPeriod;
-	type WeightInfo = pallet_timestamp::weights::SubstrateWeight<Runtime>;
+	type WeightInfo = weights::pallet_timestamp::WeightInfo<Runtime>;
 }
 
 parameter_types! {
@@ -459,7 +471,7 @@ impl pallet_session::Config for Runtime {
 	type SessionHandler = <SessionKeys as OpaqueKeys>::KeyTypeIdProviders;
 	type Keys = SessionKeys;
 	type DisabledValidatorsThreshold = DisabledValidatorsThreshold;
-	type WeightInfo = pallet_session::weights::SubstrateWeight<Runtime>;
+	type WeightInfo = weights::pallet_session::WeightInfo<Runtime>;
 }
 
 impl pallet_session::historical::Config for Runtime {
@@ -512,7 +524,7 @@ impl pallet_staking::Config for Runtime {
 	type NextNewSession = Session;
 	type MaxNominatorRewardedPerValidator = MaxNominatorRewardedPerValidator;
 	type ElectionProvider = ElectionProviderMultiPhase;
-	type WeightInfo = pallet_staking::weights::SubstrateWeight<Runtime>;
+	type WeightInfo = weights::pallet_staking::WeightInfo<Runtime>;
 }
 
 parameter_types! {
@@ -567,7 +579,7 @@ impl pallet_election_provider_multi_phase::Config for Runtime {
 	type OnChainAccuracy = Perbill;
 	type CompactSolution = NposCompactSolution16;
 	type Fallback = Fallback;
-	type WeightInfo = pallet_election_provider_multi_phase::weights::SubstrateWeight<Runtime>;
+	type WeightInfo = weights::pallet_election_provider_multi_phase::WeightInfo<Runtime>;
 	type BenchmarkingConfig = ();
 }
 
@@ -602,7 +614,7 @@ impl pallet_collective::Config<CouncilCollective> for Runtime {
 	type MaxProposals = CouncilMaxProposals;
 	type MaxMembers = CouncilMaxMembers;
 	type DefaultVote = pallet_collective::PrimeDefaultVote;
-	type WeightInfo = pallet_collective::weights::SubstrateWeight<Runtime>;
+	type WeightInfo = weights::pallet_collective::WeightInfo<Runtime>;
 }
 
 parameter_types! {
@@ -637,7 +649,7 @@ impl pallet_elections_phragmen::Config for Runtime {
 	type DesiredMembers = DesiredMembers;
 	type DesiredRunnersUp = DesiredRunnersUp;
 	type TermDuration = TermDuration;
-	type WeightInfo = pallet_elections_phragmen::weights::SubstrateWeight<Runtime>;
+	type WeightInfo = weights::pallet_elections_phragmen::WeightInfo<Runtime>;
 }
 
 parameter_types! {
@@ -656,7 +668,7 @@ impl pallet_collective::Config<TechnicalCollective> for Runtime {
 	type MaxProposals = TechnicalMaxProposals;
 	type MaxMembers = TechnicalMaxMembers;
 	type DefaultVote = pallet_collective::PrimeDefaultVote;
-	type WeightInfo = pallet_collective::weights::SubstrateWeight<Runtime>;
+	type WeightInfo = weights::pallet_collective::WeightInfo<Runtime>;
 }
 
 type EnsureRootOrHalfCouncil = EnsureOneOf<
@@ -675,7 +687,7 @@ impl pallet_membership::Config<pallet_membership::Instance1> for Runtime {
 	type MembershipInitialized = TechnicalCommittee;
 	type MembershipChanged = TechnicalCommittee;
 	type MaxMembers = TechnicalMaxMembers;
-	type WeightInfo = pallet_membership::weights::SubstrateWeight<Runtime>;
+	type WeightInfo = weights::pallet_membership::WeightInfo<Runtime>;
 }
 
 parameter_types! {
@@ -718,7 +730,7 @@ impl pallet_treasury::Config for Runtime {
 	type Burn = Burn;
 	type BurnDestination = ();
 	type SpendFunds = Bounties;
-	type WeightInfo = pallet_treasury::weights::SubstrateWeight<Runtime>;
+	type WeightInfo = weights::pallet_treasury::WeightInfo<Runtime>;
 	type MaxApprovals = MaxApprovals;
 }
 
@@ -731,7 +743,7 @@ impl pallet_bounties::Config for Runtime {
 	type BountyValueMinimum = BountyValueMinimum;
 	type DataDepositPerByte = DataDepositPerByte;
 	type MaximumReasonLength = MaximumReasonLength;
-	type WeightInfo = pallet_bounties::weights::SubstrateWeight<Runtime>;
+	type WeightInfo = weights::pallet_bounties::WeightInfo<Runtime>;
 }
 
 parameter_types! {
@@ -775,13 +787,140 @@ impl pallet_contracts::Config for Runtime {
 	type MaxDepth = MaxDepth;
 	type MaxValueSize = MaxValueSize;
 	type WeightPrice = pallet_transaction_payment::Module<Self>;
-	type WeightInfo = pallet_contracts::weights::SubstrateWeight<Self>;
-	type ChainExtension = ();
+	type WeightInfo = weights::pallet_contracts::WeightInfo<Self>;
+	type ChainExtension = chain_extensions::PolkadexExtension;
 	type DeletionQueueDepth = DeletionQueueDepth;
 	type DeletionWeightLimit = DeletionWeightLimit;
 	type MaxCodeSize = MaxCodeSize;
 }
 
+parameter_types! {
+    pub const SponsorshipCapPeriod: BlockNumber = 1 * DAYS;
+}
+
+impl pallet_contract_sponsorship::Config for Runtime {
+	type Event = Event;
+	type CallTarget = impls::ContractCallTarget;
+	type CapPeriod = SponsorshipCapPeriod;
+	type WeightInfo = weights::pallet_contract_sponsorship::WeightInfo<Runtime>;
+}
+
+parameter_types! {
+    pub OracleAssets: Vec<AssetId> = vec![AssetId::POLKADEX, AssetId::DOT, AssetId::BTC, AssetId::USD];
+    pub const OracleSubmissionInterval: BlockNumber = 10;
+    pub const OracleStalePeriod: BlockNumber = 1 * HOURS;
+    pub const MinOracleFeeders: u32 = 3;
+    pub const MaxOracleFeeders: u32 = 16;
+}
+
+impl pallet_price_oracle::Config for Runtime {
+	type AuthorityId = pallet_price_oracle::crypto::OracleAuthId;
+	type Event = Event;
+	type Call = Call;
+	type FeederOrigin = EnsureRootOrHalfCouncil;
+	type PriceFetcher = pallet_price_oracle::HttpFetcher<impls::OraclePriceSource>;
+	type OracleAssets = OracleAssets;
+	type SubmissionInterval = OracleSubmissionInterval;
+	type StalePeriod = OracleStalePeriod;
+	type MinFeeders = MinOracleFeeders;
+	type MaxFeeders = MaxOracleFeeders;
+	type WeightInfo = weights::pallet_price_oracle::WeightInfo<Runtime>;
+}
+
+parameter_types! {
+    pub const OcexPalletId: PalletId = PalletId(*b"polka/ga");
+    pub const OcexChallengePeriod: BlockNumber = 1 * DAYS;
+    pub const OcexChallengeBond: Balance = 100 * DOLLARS;
+    pub const MaxOcexOperators: u32 = 8;
+    pub const MaxSnapshotDeltas: u32 = 1_000;
+    // One snapshot every 15 minutes for a challenge period.
+    pub const MaxPendingSnapshots: u32 = 100;
+}
+
+impl pallet_ocex::Config for Runtime {
+	type Event = Event;
+	type Currency = Currencies;
+	type BondCurrency = Balances;
+	type PalletId = OcexPalletId;
+	type OperatorPublic = <Signature as traits::Verify>::Signer;
+	type OperatorSignature = Signature;
+	type OperatorOrigin = EnsureRootOrHalfCouncil;
+	type DisputeOrigin = EnsureRootOrHalfCouncil;
+	type ChallengePeriod = OcexChallengePeriod;
+	type ChallengeBond = OcexChallengeBond;
+	type Slash = Treasury;
+	type MaxOperators = MaxOcexOperators;
+	type MaxDeltas = MaxSnapshotDeltas;
+	type MaxPendingSnapshots = MaxPendingSnapshots;
+	type WeightInfo = weights::pallet_ocex::WeightInfo<Runtime>;
+}
+
+parameter_types! {
+    pub const MaxOrdersPerSide: u32 = 1_000;
+    pub const MaxConditionalOrders: u32 = 1_000;
+    pub const MaxOrderFills: u32 = 50;
+    pub const MarketBidBuffer: Permill = Permill::from_percent(10);
+    pub OrderbookMaxTriggerWeight: Weight = Perbill::from_percent(10) *
+        RuntimeBlockWeights::get().max_block;
+    pub const MaxAuctionPairs: u32 = 8;
+    pub const MaxBatchOrders: u32 = 200;
+}
+
+impl pallet_orderbook::Config for Runtime {
+	type Event = Event;
+	type Balance = Balance;
+	type Currency = Currencies;
+	type Call = Call;
+	type PalletsOrigin = OriginCaller;
+	type Scheduler = Scheduler;
+	type GovernanceOrigin = EnsureRootOrHalfCouncil;
+	type MaxOrdersPerSide = MaxOrdersPerSide;
+	type MaxConditionalOrders = MaxConditionalOrders;
+	type MaxFills = MaxOrderFills;
+	type MarketBidBuffer = MarketBidBuffer;
+	type MaxTriggerWeight = OrderbookMaxTriggerWeight;
+	type MaxAuctionPairs = MaxAuctionPairs;
+	type MaxBatchOrders = MaxBatchOrders;
+	type WeightInfo = weights::pallet_orderbook::WeightInfo<Runtime>;
+}
+
+impl pallet_signed_orders::Config for Runtime {
+	type Event = Event;
+	type Balance = Balance;
+	type Currency = Currencies;
+	type Public = <Signature as traits::Verify>::Signer;
+	type Signature = Signature;
+	type WeightInfo = weights::pallet_signed_orders::WeightInfo<Runtime>;
+}
+
+parameter_types! {
+    pub const MarginPalletId: PalletId = PalletId(*b"polka/mg");
+    pub const MarginInsuranceFundId: PalletId = PalletId(*b"polka/if");
+    // About 5% a year.
+    pub MarginBorrowRate: FixedU128 = FixedU128::saturating_from_rational(5, 100 * 365 * DAYS);
+    // Up to 5x leverage.
+    pub MarginInitialRatio: FixedU128 = FixedU128::saturating_from_rational(125, 100);
+    pub MarginMaintenanceRatio: FixedU128 = FixedU128::saturating_from_rational(110, 100);
+    pub const MarginLiquidationReward: Permill = Permill::from_percent(5);
+    pub const MarginMaxPositionOrders: u32 = 20;
+}
+
+impl pallet_margin::Config for Runtime {
+	type Event = Event;
+	type Balance = Balance;
+	type Currency = Currencies;
+	type Orderbook = Orderbook;
+	type PriceProvider = PriceOracle;
+	type PalletId = MarginPalletId;
+	type InsuranceFundId = MarginInsuranceFundId;
+	type BorrowRate = MarginBorrowRate;
+	type InitialMarginRatio = MarginInitialRatio;
+	type MaintenanceMarginRatio = MarginMaintenanceRatio;
+	type LiquidationReward = MarginLiquidationReward;
+	type MaxPositionOrders = MarginMaxPositionOrders;
+	type WeightInfo = weights::pallet_margin::WeightInfo<Runtime>;
+}
+
 impl pallet_sudo::Config for Runtime {
 	type Event = Event;
 	type Call = Call;
@@ -818,15 +957,7 @@ impl<LocalCall> frame_system::offchain::CreateSignedTransaction<LocalCall> for R
 			// so the actual block number is `n`.
 			.saturating_sub(1);
 		let era = Era::mortal(period, current_block);
-		let extra = (
-			frame_system::CheckSpecVersion::<Runtime>::new(),
-			frame_system::CheckTxVersion::<Runtime>::new(),
-			frame_system::CheckGenesis::<Runtime>::new(),
-			frame_system::CheckEra::<Runtime>::from(era),
-			frame_system::CheckNonce::<Runtime>::from(nonce),
-			frame_system::CheckWeight::<Runtime>::new(),
-			pallet_transaction_payment::ChargeTransactionPayment::<Runtime>::from(tip),
-		);
+		let extra = signed_extra(nonce, era, tip);
 		let raw_payload = SignedPayload::new(call, extra)
 			.map_err(|e| {
 				log::warn!("Unable to create signed payload: {:?}", e);
@@ -859,7 +990,7 @@ impl pallet_im_online::Config for Runtime {
 	type ValidatorSet = Historical;
 	type ReportUnresponsiveness = Offences;
 	type UnsignedPriority = ImOnlineUnsignedPriority;
-	type WeightInfo = pallet_im_online::weights::SubstrateWeight<Runtime>;
+	type WeightInfo = weights::pallet_im_online::WeightInfo<Runtime>;
 }
 
 impl pallet_offences::Config for Runtime {
@@ -890,7 +1021,7 @@ impl pallet_grandpa::Config for Runtime {
 		ReportLongevity,
 	>;
 
-	type WeightInfo = ();
+	type WeightInfo = weights::pallet_grandpa::WeightInfo<Runtime>;
 }
 
 parameter_types! {
@@ -914,7 +1045,7 @@ impl pallet_identity::Config for Runtime {
 	type Slashed = Treasury;
 	type ForceOrigin = EnsureRootOrHalfCouncil;
 	type RegistrarOrigin = EnsureRootOrHalfCouncil;
-	type WeightInfo = pallet_identity::weights::SubstrateWeight<Runtime>;
+	type WeightInfo = weights::pallet_identity::WeightInfo<Runtime>;
 }
 
 parameter_types! {
@@ -939,7 +1070,7 @@ impl pallet_vesting::Config for Runtime {
 	type Currency = Balances;
 	type BlockNumberToBalance = ConvertInto;
 	type MinVestedTransfer = MinVestedTransfer;
-	type WeightInfo = pallet_vesting::weights::SubstrateWeight<Runtime>;
+	type WeightInfo = weights::pallet_vesting::WeightInfo<Runtime>;
 }
 
 
@@ -1003,7 +1134,13 @@ construct_runtime!(
         // Pallets
         OrmlVesting: orml_vesting::{Pallet, Storage, Call, Event<T>, Config<T>} = 31,
         Currencies: orml_currencies::{Pallet, Call, Event<T>} = 32,
-        Tokens: orml_tokens::{Pallet, Storage, Event<T>, Config<T>} = 33
+        Tokens: orml_tokens::{Pallet, Storage, Event<T>, Config<T>} = 33,
+        ContractSponsorship: pallet_contract_sponsorship::{Pallet, Call, Storage, Event<T>} = 34,
+        PriceOracle: pallet_price_oracle::{Pallet, Call, Storage, Config<T>, Event<T>} = 35,
+        Ocex: pallet_ocex::{Pallet, Call, Storage, Config<T>, Event<T>} = 36,
+        Orderbook: pallet_orderbook::{Pallet, Call, Storage, Event<T>} = 37,
+        SignedOrders: pallet_signed_orders::{Pallet, Call, Storage, Event<T>} = 38,
+        Margin: pallet_margin::{Pallet, Call, Storage, Event<T>} = 39
     }
 );
 
@@ -1019,9 +1156,7 @@ pub type SignedBlock = generic::SignedBlock<Block>;
 pub type BlockId = generic::BlockId<Block>;
 /// The SignedExtension to the basic transaction logic.
 ///
-/// When you change this, you **MUST** modify [`sign`] in `bin/node/testing/src/keyring.rs`!
-///
-/// [`sign`]: <../../testing/src/keyring.rs.html>
+/// When you change this, you **MUST** modify [`signed_extra`] as well!
 pub type SignedExtra = (
 	frame_system::CheckSpecVersion<Runtime>,
 	frame_system::CheckTxVersion<Runtime>,
@@ -1029,7 +1164,7 @@ pub type SignedExtra = (
 	frame_system::CheckEra<Runtime>,
 	frame_system::CheckNonce<Runtime>,
 	frame_system::CheckWeight<Runtime>,
-	pallet_transaction_payment::ChargeTransactionPayment<Runtime>,
+	pallet_contract_sponsorship::ChargeSponsoredTransactionPayment<Runtime>,
 );
 /// Unchecked extrinsic type as expected by this runtime.
 pub type UncheckedExtrinsic = generic::UncheckedExtrinsic<Address, Call, Signature, SignedExtra>;
@@ -1037,6 +1172,19 @@ pub type UncheckedExtrinsic = generic::UncheckedExtrinsic<Address, Call, Signatu
 pub type SignedPayload = generic::SignedPayload<Call, SignedExtra>;
 /// Extrinsic type that has already been checked.
 pub type CheckedExtrinsic = generic::CheckedExtrinsic<AccountId, Call, SignedExtra>;
+
+/// The `SignedExtra` of a transaction with `nonce`, valid during `era` and paying `tip`.
+pub fn signed_extra(nonce: Index, era: Era, tip: Balance) -> SignedExtra {
+	(
+		frame_system::CheckSpecVersion::<Runtime>::new(),
+		frame_system::CheckTxVersion::<Runtime>::new(),
+		frame_system::CheckGenesis::<Runtime>::new(),
+		frame_system::CheckEra::<Runtime>::from(era),
+		frame_system::CheckNonce::<Runtime>::from(nonce),
+		frame_system::CheckWeight::<Runtime>::new(),
+		pallet_contract_sponsorship::ChargeSponsoredTransactionPayment::<Runtime>::from(tip),
+	)
+}
 /// Executive: handles dispatch to the various modules.
 pub type Executive = frame_executive::Executive<
 	Runtime,
@@ -1251,6 +1399,35 @@ impl_runtime_apis! {
         }
     }
 
+    impl pallet_price_oracle_runtime_api::PriceOracleApi<Block> for Runtime {
+        fn price(asset: AssetId) -> Option<FixedU128> {
+            PriceOracle::price(asset)
+        }
+
+        fn prices() -> Vec<(AssetId, FixedU128)> {
+            PriceOracle::prices()
+        }
+    }
+
+    impl polkadex_dry_run_runtime_api::DryRunApi<Block, AccountId, Call, Balance, Event> for Runtime {
+        fn estimate_fee(
+            signer: AccountId,
+            call: Call,
+            tip: Balance,
+        ) -> polkadex_dry_run_runtime_api::FeeEstimate<Balance> {
+            dry_run::estimate_fee(signer, call, tip)
+        }
+
+        fn dry_run(
+            signer: AccountId,
+            call: Call,
+            tip: Balance,
+            parent: <Block as BlockT>::Hash,
+        ) -> polkadex_dry_run_runtime_api::DryRunOutcome<Balance, Event> {
+            dry_run::dry_run(signer, call, tip, parent)
+        }
+    }
+
     impl sp_session::SessionKeys<Block> for Runtime {
         fn generate_session_keys(seed: Option<Vec<u8>>) -> Vec<u8> {
             SessionKeys::generate(seed)
@@ -1277,6 +1454,7 @@ impl_runtime_apis! {
             config: frame_benchmarking::BenchmarkConfig
         ) -> Result<Vec<frame_benchmarking::BenchmarkBatch>, sp_runtime::RuntimeString> {
             use frame_benchmarking::{Benchmarking, BenchmarkBatch, add_benchmark, TrackedStorageKey};
+            use orml_benchmarking::add_benchmark as orml_add_benchmark;
             // Trying to add benchmarks directly to the Session Pallet caused cyclic dependency
             // issues. To get around that, we separated the Session benchmarks into its own crate,
             // which is why we need these two lines below.
@@ -1306,11 +1484,11 @@ impl_runtime_apis! {
             let mut batches = Vec::<BenchmarkBatch>::new();
             let params = (&config, &whitelist);
 
-            add_benchmark!(params, batches, pallet_assets, Assets);
             add_benchmark!(params, batches, pallet_babe, Babe);
             add_benchmark!(params, batches, pallet_balances, Balances);
             add_benchmark!(params, batches, pallet_bounties, Bounties);
             add_benchmark!(params, batches, pallet_collective, Council);
+            add_benchmark!(params, batches, pallet_contract_sponsorship, ContractSponsorship);
             add_benchmark!(params, batches, pallet_contracts, Contracts);
             add_benchmark!(params, batches, pallet_election_provider_multi_phase, ElectionProviderMultiPhase);
             add_benchmark!(params, batches, pallet_elections_phragmen, Elections);
@@ -1318,19 +1496,26 @@ impl_runtime_apis! {
             add_benchmark!(params, batches, pallet_identity, Identity);
             add_benchmark!(params, batches, pallet_im_online, ImOnline);
             add_benchmark!(params, batches, pallet_indices, Indices);
+            add_benchmark!(params, batches, pallet_margin, Margin);
             add_benchmark!(params, batches, pallet_membership, TechnicalMembership);
             add_benchmark!(params, batches, pallet_multisig, Multisig);
+            add_benchmark!(params, batches, pallet_ocex, Ocex);
             add_benchmark!(params, batches, pallet_offences, OffencesBench::<Runtime>);
+            add_benchmark!(params, batches, pallet_orderbook, Orderbook);
+            add_benchmark!(params, batches, pallet_price_oracle, PriceOracle);
             add_benchmark!(params, batches, pallet_proxy, Proxy);
             add_benchmark!(params, batches, pallet_scheduler, Scheduler);
             add_benchmark!(params, batches, pallet_session, SessionBench::<Runtime>);
+            add_benchmark!(params, batches, pallet_signed_orders, SignedOrders);
             add_benchmark!(params, batches, pallet_staking, Staking);
             add_benchmark!(params, batches, frame_system, SystemBench::<Runtime>);
             add_benchmark!(params, batches, pallet_timestamp, Timestamp);
             add_benchmark!(params, batches, pallet_treasury, Treasury);
             add_benchmark!(params, batches, pallet_utility, Utility);
             add_benchmark!(params, batches, pallet_vesting, Vesting);
-            add_benchmark!(params, batches, pallet_verifier_lightclient, VerifierLightclient);
+            orml_add_benchmark!(params, batches, orml_currencies, benchmarking::orml_currencies);
+            orml_add_benchmark!(params, batches, orml_tokens, benchmarking::orml_tokens);
+            orml_add_benchmark!(params, batches, orml_vesting, benchmarking::orml_vesting);
 
             if batches.is_empty() { return Err("Benchmark not found for this pallet.".into()) }
             Ok(batches)
@@ -1403,7 +1588,7 @@ impl orml_vesting::Config for Runtime {
 	type Currency = pallet_balances::Module<Runtime>;
 	type MinVestedTransfer = MinVestedTransfer;
 	type VestedTransferOrigin = EnsureRootOrPolkadexTreasury;
-	type WeightInfo = ();
+	type WeightInfo = weights::orml_vesting::WeightInfo<Runtime>;
 }
 
 parameter_type_with_key! {
@@ -1420,7 +1605,7 @@ impl orml_tokens::Config for Runtime {
 	type Balance = Balance;
 	type Amount = Amount;
 	type CurrencyId = AssetId;
-	type WeightInfo = ();
+	type WeightInfo = weights::orml_tokens::WeightInfo<Runtime>;
 	type ExistentialDeposits = ExistentialDeposits;
 	type OnDust = orml_tokens::TransferDust<Runtime, TreasuryModuleAccount>;
 }
@@ -1434,7 +1619,7 @@ impl orml_currencies::Config for Runtime {
 	type MultiCurrency = Tokens;
 	type NativeCurrency = BasicCurrencyAdapter<Runtime, Balances, Amount, BlockNumber>;
 	type GetNativeCurrencyId = GetNativeCurrencyId;
-	type WeightInfo = ();
+	type WeightInfo = weights::orml_currencies::WeightInfo<Runtime>;
 }
 
 parameter_types! {
@@ -14,7 +14,41 @@
 // limitations under the License.
 
 //! A list of the different weight modules for our runtime.
+//!
+//! `scripts/run_benchmarks.sh` overwrites these modules with the output of the benchmark
+//! subcommand. Modules that still describe themselves as copied from a pallet's own weights or as
+//! estimated have not been regenerated on reference hardware yet.
 
+pub mod frame_system;
+pub mod orml_currencies;
+pub mod orml_tokens;
+pub mod orml_vesting;
+pub mod pallet_babe;
+pub mod pallet_babe_benchmarks;
+pub mod pallet_balances;
+pub mod pallet_bounties;
+pub mod pallet_collective;
+pub mod pallet_contract_sponsorship;
+pub mod pallet_contracts;
+pub mod pallet_election_provider_multi_phase;
+pub mod pallet_elections_phragmen;
+pub mod pallet_grandpa;
+pub mod pallet_grandpa_benchmarks;
+pub mod pallet_identity;
+pub mod pallet_im_online;
+pub mod pallet_indices;
+pub mod pallet_margin;
+pub mod pallet_membership;
+pub mod pallet_multisig;
+pub mod pallet_ocex;
+pub mod pallet_orderbook;
+pub mod pallet_price_oracle;
+pub mod pallet_proxy;
+pub mod pallet_scheduler;
 pub mod pallet_session;
+pub mod pallet_signed_orders;
 pub mod pallet_staking;
+pub mod pallet_timestamp;
+pub mod pallet_treasury;
 pub mod pallet_utility;
+pub mod pallet_vesting;
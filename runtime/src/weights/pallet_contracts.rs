@@ -0,0 +1,27 @@
+// This file is part of Polkadex.
+
+// Copyright (C) 2020-2021 Polkadex oü.
+// SPDX-License-Identifier: GPL-3.0-or-later WITH Classpath-exception-2.0
+
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with this program. If not, see <https://www.gnu.org/licenses/>.
+
+//! Weights for `pallet_contracts`, re-exported from the `SubstrateWeight` implementation shipped
+//! with the pallet.
+//!
+//! This is an alias, not benchmark output. The pallet's schedule and extrinsic weights are
+//! generated from the same benchmarks, so `scripts/run_benchmarks.sh` replaces this alias with a
+//! full `WeightInfo` implementation measured on reference hardware.
+
+/// Weight functions for `pallet_contracts`.
+pub type WeightInfo<T> = pallet_contracts::weights::SubstrateWeight<T>;
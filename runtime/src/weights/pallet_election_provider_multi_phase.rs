@@ -0,0 +1,72 @@
+// This file is part of Polkadex.
+
+// Copyright (C) 2020-2021 Polkadex oü.
+// SPDX-License-Identifier: GPL-3.0-or-later WITH Classpath-exception-2.0
+
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with this program. If not, see <https://www.gnu.org/licenses/>.
+
+//! Weights for `pallet_election_provider_multi_phase`, copied from the `SubstrateWeight` implementation
+//! shipped with the pallet.
+
+#![allow(unused_parens)]
+#![allow(unused_imports)]
+
+use frame_support::{traits::Get, weights::Weight};
+use sp_std::marker::PhantomData;
+
+/// Weight functions for `pallet_election_provider_multi_phase`.
+pub struct WeightInfo<T>(PhantomData<T>);
+impl<T: frame_system::Config> pallet_election_provider_multi_phase::WeightInfo for WeightInfo<T> {
+	fn on_initialize_nothing() -> Weight {
+		(23_401_000 as Weight)
+			.saturating_add(T::DbWeight::get().reads(8 as Weight))
+	}
+	fn on_initialize_open_signed() -> Weight {
+		(79_260_000 as Weight)
+			.saturating_add(T::DbWeight::get().reads(10 as Weight))
+			.saturating_add(T::DbWeight::get().writes(4 as Weight))
+	}
+	fn on_initialize_open_unsigned_with_snapshot() -> Weight {
+		(77_745_000 as Weight)
+			.saturating_add(T::DbWeight::get().reads(10 as Weight))
+			.saturating_add(T::DbWeight::get().writes(4 as Weight))
+	}
+	fn on_initialize_open_unsigned_without_snapshot() -> Weight {
+		(21_764_000 as Weight)
+			.saturating_add(T::DbWeight::get().reads(1 as Weight))
+			.saturating_add(T::DbWeight::get().writes(1 as Weight))
+	}
+	fn elect_queued() -> Weight {
+		(8_197_282_000 as Weight)
+			.saturating_add(T::DbWeight::get().reads(2 as Weight))
+			.saturating_add(T::DbWeight::get().writes(6 as Weight))
+	}
+	fn submit_unsigned(v: u32, t: u32, a: u32, d: u32, ) -> Weight {
+		(0 as Weight)
+			.saturating_add((4_171_000 as Weight).saturating_mul(v as Weight))
+			.saturating_add((26_138_000 as Weight).saturating_mul(t as Weight))
+			.saturating_add((13_839_000 as Weight).saturating_mul(a as Weight))
+			.saturating_add((5_025_000 as Weight).saturating_mul(d as Weight))
+			.saturating_add(T::DbWeight::get().reads(7 as Weight))
+			.saturating_add(T::DbWeight::get().writes(1 as Weight))
+	}
+	fn feasibility_check(v: u32, t: u32, a: u32, d: u32, ) -> Weight {
+		(0 as Weight)
+			.saturating_add((4_166_000 as Weight).saturating_mul(v as Weight))
+			.saturating_add((26_095_000 as Weight).saturating_mul(t as Weight))
+			.saturating_add((13_849_000 as Weight).saturating_mul(a as Weight))
+			.saturating_add((4_852_000 as Weight).saturating_mul(d as Weight))
+			.saturating_add(T::DbWeight::get().reads(4 as Weight))
+	}
+}
@@ -0,0 +1,46 @@
+// This file is part of Polkadex.
+
+// Copyright (C) 2020-2021 Polkadex oü.
+// SPDX-License-Identifier: GPL-3.0-or-later WITH Classpath-exception-2.0
+
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with this program. If not, see <https://www.gnu.org/licenses/>.
+
+//! Estimated benchmark figures for `pallet_grandpa`, copied from the constants behind the
+//! pallet's default weights.
+//!
+//! These are not measurements taken on reference hardware. Run `scripts/run_benchmarks.sh` to
+//! replace them with measured figures.
+//!
+//! The pallet's benchmarks do not match its `WeightInfo` functions, so
+//! `scripts/run_benchmarks.sh` writes its measurements here through
+//! `scripts/measurements-template.hbs` and [`super::pallet_grandpa`] builds the extrinsic weights
+//! from them.
+
+#![allow(unused_parens)]
+#![allow(unused_imports)]
+
+use frame_support::{traits::Get, weights::Weight};
+use sp_std::marker::PhantomData;
+
+/// Estimated benchmark figures for `pallet_grandpa`.
+pub struct Measurements<T>(PhantomData<T>);
+impl<T: frame_system::Config> Measurements<T> {
+	pub fn check_equivocation_proof(_x: u32, ) -> Weight {
+		(95_000_000 as Weight)
+	}
+	pub fn note_stalled(_delay: u32, _best_finalized_block_number: u32, ) -> Weight {
+		(3_270_000 as Weight)
+			.saturating_add(T::DbWeight::get().writes(1 as Weight))
+	}
+}
@@ -0,0 +1,140 @@
+// This file is part of Polkadex.
+
+// Copyright (C) 2020-2021 Polkadex oü.
+// SPDX-License-Identifier: GPL-3.0-or-later WITH Classpath-exception-2.0
+
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with this program. If not, see <https://www.gnu.org/licenses/>.
+
+//! Weights for `pallet_identity`, copied from the `SubstrateWeight` implementation shipped with
+//! the pallet.
+
+#![allow(unused_parens)]
+#![allow(unused_imports)]
+
+use frame_support::{traits::Get, weights::Weight};
+use sp_std::marker::PhantomData;
+
+/// Weight functions for `pallet_identity`.
+pub struct WeightInfo<T>(PhantomData<T>);
+impl<T: frame_system::Config> pallet_identity::WeightInfo for WeightInfo<T> {
+	fn add_registrar(r: u32, ) -> Weight {
+		(21_825_000 as Weight)
+			.saturating_add((288_000 as Weight).saturating_mul(r as Weight))
+			.saturating_add(T::DbWeight::get().reads(1 as Weight))
+			.saturating_add(T::DbWeight::get().writes(1 as Weight))
+	}
+	fn set_identity(r: u32, x: u32, ) -> Weight {
+		(53_354_000 as Weight)
+			.saturating_add((274_000 as Weight).saturating_mul(r as Weight))
+			.saturating_add((939_000 as Weight).saturating_mul(x as Weight))
+			.saturating_add(T::DbWeight::get().reads(1 as Weight))
+			.saturating_add(T::DbWeight::get().writes(1 as Weight))
+	}
+	fn set_subs_new(s: u32, ) -> Weight {
+		(42_017_000 as Weight)
+			.saturating_add((6_457_000 as Weight).saturating_mul(s as Weight))
+			.saturating_add(T::DbWeight::get().reads(1 as Weight))
+			.saturating_add(T::DbWeight::get().reads((1 as Weight).saturating_mul(s as Weight)))
+			.saturating_add(T::DbWeight::get().writes(1 as Weight))
+			.saturating_add(T::DbWeight::get().writes((1 as Weight).saturating_mul(s as Weight)))
+	}
+	fn set_subs_old(p: u32, ) -> Weight {
+		(41_605_000 as Weight)
+			.saturating_add((2_157_000 as Weight).saturating_mul(p as Weight))
+			.saturating_add(T::DbWeight::get().reads(1 as Weight))
+			.saturating_add(T::DbWeight::get().writes(1 as Weight))
+			.saturating_add(T::DbWeight::get().writes((1 as Weight).saturating_mul(p as Weight)))
+	}
+	fn clear_identity(r: u32, s: u32, x: u32, ) -> Weight {
+		(51_811_000 as Weight)
+			.saturating_add((202_000 as Weight).saturating_mul(r as Weight))
+			.saturating_add((2_157_000 as Weight).saturating_mul(s as Weight))
+			.saturating_add((618_000 as Weight).saturating_mul(x as Weight))
+			.saturating_add(T::DbWeight::get().reads(2 as Weight))
+			.saturating_add(T::DbWeight::get().writes(2 as Weight))
+			.saturating_add(T::DbWeight::get().writes((1 as Weight).saturating_mul(s as Weight)))
+	}
+	fn request_judgement(r: u32, x: u32, ) -> Weight {
+		(54_657_000 as Weight)
+			.saturating_add((381_000 as Weight).saturating_mul(r as Weight))
+			.saturating_add((1_153_000 as Weight).saturating_mul(x as Weight))
+			.saturating_add(T::DbWeight::get().reads(2 as Weight))
+			.saturating_add(T::DbWeight::get().writes(1 as Weight))
+	}
+	fn cancel_request(r: u32, x: u32, ) -> Weight {
+		(50_895_000 as Weight)
+			.saturating_add((266_000 as Weight).saturating_mul(r as Weight))
+			.saturating_add((1_131_000 as Weight).saturating_mul(x as Weight))
+			.saturating_add(T::DbWeight::get().reads(1 as Weight))
+			.saturating_add(T::DbWeight::get().writes(1 as Weight))
+	}
+	fn set_fee(r: u32, ) -> Weight {
+		(8_036_000 as Weight)
+			.saturating_add((281_000 as Weight).saturating_mul(r as Weight))
+			.saturating_add(T::DbWeight::get().reads(1 as Weight))
+			.saturating_add(T::DbWeight::get().writes(1 as Weight))
+	}
+	fn set_account_id(r: u32, ) -> Weight {
+		(9_001_000 as Weight)
+			.saturating_add((288_000 as Weight).saturating_mul(r as Weight))
+			.saturating_add(T::DbWeight::get().reads(1 as Weight))
+			.saturating_add(T::DbWeight::get().writes(1 as Weight))
+	}
+	fn set_fields(r: u32, ) -> Weight {
+		(8_039_000 as Weight)
+			.saturating_add((286_000 as Weight).saturating_mul(r as Weight))
+			.saturating_add(T::DbWeight::get().reads(1 as Weight))
+			.saturating_add(T::DbWeight::get().writes(1 as Weight))
+	}
+	fn provide_judgement(r: u32, x: u32, ) -> Weight {
+		(35_746_000 as Weight)
+			.saturating_add((346_000 as Weight).saturating_mul(r as Weight))
+			.saturating_add((1_164_000 as Weight).saturating_mul(x as Weight))
+			.saturating_add(T::DbWeight::get().reads(2 as Weight))
+			.saturating_add(T::DbWeight::get().writes(1 as Weight))
+	}
+	fn kill_identity(r: u32, s: u32, x: u32, ) -> Weight {
+		(65_304_000 as Weight)
+			.saturating_add((149_000 as Weight).saturating_mul(r as Weight))
+			.saturating_add((2_141_000 as Weight).saturating_mul(s as Weight))
+			.saturating_add((6_000 as Weight).saturating_mul(x as Weight))
+			.saturating_add(T::DbWeight::get().reads(3 as Weight))
+			.saturating_add(T::DbWeight::get().writes(3 as Weight))
+			.saturating_add(T::DbWeight::get().writes((1 as Weight).saturating_mul(s as Weight)))
+	}
+	fn add_sub(s: u32, ) -> Weight {
+		(55_491_000 as Weight)
+			.saturating_add((220_000 as Weight).saturating_mul(s as Weight))
+			.saturating_add(T::DbWeight::get().reads(3 as Weight))
+			.saturating_add(T::DbWeight::get().writes(2 as Weight))
+	}
+	fn rename_sub(s: u32, ) -> Weight {
+		(17_564_000 as Weight)
+			.saturating_add((84_000 as Weight).saturating_mul(s as Weight))
+			.saturating_add(T::DbWeight::get().reads(2 as Weight))
+			.saturating_add(T::DbWeight::get().writes(1 as Weight))
+	}
+	fn remove_sub(s: u32, ) -> Weight {
+		(56_535_000 as Weight)
+			.saturating_add((209_000 as Weight).saturating_mul(s as Weight))
+			.saturating_add(T::DbWeight::get().reads(3 as Weight))
+			.saturating_add(T::DbWeight::get().writes(2 as Weight))
+	}
+	fn quit_sub(s: u32, ) -> Weight {
+		(35_369_000 as Weight)
+			.saturating_add((203_000 as Weight).saturating_mul(s as Weight))
+			.saturating_add(T::DbWeight::get().reads(2 as Weight))
+			.saturating_add(T::DbWeight::get().writes(2 as Weight))
+	}
+}
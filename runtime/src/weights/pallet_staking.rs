@@ -1,173 +1,189 @@
-// This file is part of Substrate.
+// This file is part of Polkadex.
 
-// Copyright (C) 2017-2020 Parity Technologies (UK) Ltd.
-// SPDX-License-Identifier: Apache-2.0
+// Copyright (C) 2020-2021 Polkadex oü.
+// SPDX-License-Identifier: GPL-3.0-or-later WITH Classpath-exception-2.0
 
-// Licensed under the Apache License, Version 2.0 (the "License");
-// you may not use this file except in compliance with the License.
-// You may obtain a copy of the License at
-//
-// 	http://www.apache.org/licenses/LICENSE-2.0
-//
-// Unless required by applicable law or agreed to in writing, software
-// distributed under the License is distributed on an "AS IS" BASIS,
-// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
-// See the License for the specific language governing permissions and
-// limitations under the License.
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
 
-//! Default weights of pallet-staking.
-//! THIS FILE WAS AUTO-GENERATED USING THE SUBSTRATE BENCHMARK CLI VERSION 2.0.0-rc6
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
 
-#![allow(unused_parens)]
-#![allow(unused_imports)]
-
-use frame_support::weights::{constants::RocksDbWeight as DbWeight, Weight};
-
-pub struct WeightInfo;
+// You should have received a copy of the GNU General Public License
+// along with this program. If not, see <https://www.gnu.org/licenses/>.
 
-impl pallet_staking::WeightInfo for WeightInfo {
-    fn bond() -> Weight {
-        (144278000 as Weight)
-            .saturating_add(DbWeight::get().reads(5 as Weight))
-            .saturating_add(DbWeight::get().writes(4 as Weight))
-    }
-    fn bond_extra() -> Weight {
-        (110715000 as Weight)
-            .saturating_add(DbWeight::get().reads(4 as Weight))
-            .saturating_add(DbWeight::get().writes(2 as Weight))
-    }
-    fn unbond() -> Weight {
-        (99840000 as Weight)
-            .saturating_add(DbWeight::get().reads(5 as Weight))
-            .saturating_add(DbWeight::get().writes(3 as Weight))
-    }
-    fn withdraw_unbonded_update(s: u32) -> Weight {
-        (100728000 as Weight)
-            .saturating_add((63000 as Weight).saturating_mul(s as Weight))
-            .saturating_add(DbWeight::get().reads(5 as Weight))
-            .saturating_add(DbWeight::get().writes(3 as Weight))
-    }
-    fn withdraw_unbonded_kill(s: u32) -> Weight {
-        (168879000 as Weight)
-            .saturating_add((6666000 as Weight).saturating_mul(s as Weight))
-            .saturating_add(DbWeight::get().reads(7 as Weight))
-            .saturating_add(DbWeight::get().writes(8 as Weight))
-            .saturating_add(DbWeight::get().writes((1 as Weight).saturating_mul(s as Weight)))
-    }
-    fn validate() -> Weight {
-        (35539000 as Weight)
-            .saturating_add(DbWeight::get().reads(2 as Weight))
-            .saturating_add(DbWeight::get().writes(2 as Weight))
-    }
+//! Weights for `pallet_staking`, copied from the `SubstrateWeight` implementation shipped with
+//! the pallet.
 
-    fn kick(k: u32) -> u64 {
-        todo!()
-    }
-
-    fn nominate(n: u32) -> Weight {
-        (48596000 as Weight)
-            .saturating_add((308000 as Weight).saturating_mul(n as Weight))
-            .saturating_add(DbWeight::get().reads(3 as Weight))
-            .saturating_add(DbWeight::get().writes(2 as Weight))
-    }
-    fn chill() -> Weight {
-        (35144000 as Weight)
-            .saturating_add(DbWeight::get().reads(2 as Weight))
-            .saturating_add(DbWeight::get().writes(2 as Weight))
-    }
-    fn set_payee() -> Weight {
-        (24255000 as Weight)
-            .saturating_add(DbWeight::get().reads(1 as Weight))
-            .saturating_add(DbWeight::get().writes(1 as Weight))
-    }
-    fn set_controller() -> Weight {
-        (52294000 as Weight)
-            .saturating_add(DbWeight::get().reads(3 as Weight))
-            .saturating_add(DbWeight::get().writes(3 as Weight))
-    }
-    fn set_validator_count() -> Weight {
-        (5185000 as Weight)
-            .saturating_add(DbWeight::get().writes(1 as Weight))
-    }
-    fn force_no_eras() -> Weight {
-        (5907000 as Weight)
-            .saturating_add(DbWeight::get().writes(1 as Weight))
-    }
-    fn force_new_era() -> Weight {
-        (5917000 as Weight)
-            .saturating_add(DbWeight::get().writes(1 as Weight))
-    }
-    fn force_new_era_always() -> Weight {
-        (5952000 as Weight)
-            .saturating_add(DbWeight::get().writes(1 as Weight))
-    }
-    fn set_invulnerables(v: u32) -> Weight {
-        (6324000 as Weight)
-            .saturating_add((9000 as Weight).saturating_mul(v as Weight))
-            .saturating_add(DbWeight::get().writes(1 as Weight))
-    }
-    fn force_unstake(s: u32) -> Weight {
-        (119691000 as Weight)
-            .saturating_add((6681000 as Weight).saturating_mul(s as Weight))
-            .saturating_add(DbWeight::get().reads(4 as Weight))
-            .saturating_add(DbWeight::get().writes(8 as Weight))
-            .saturating_add(DbWeight::get().writes((1 as Weight).saturating_mul(s as Weight)))
-    }
-    fn cancel_deferred_slash(s: u32) -> Weight {
-        (5820201000 as Weight)
-            .saturating_add((34672000 as Weight).saturating_mul(s as Weight))
-            .saturating_add(DbWeight::get().reads(1 as Weight))
-            .saturating_add(DbWeight::get().writes(1 as Weight))
-    }
-    fn payout_stakers_dead_controller(n: u32) -> Weight {
-        (0 as Weight)
-            .saturating_add((92486000 as Weight).saturating_mul(n as Weight))
-            .saturating_add(DbWeight::get().reads(4 as Weight))
-            .saturating_add(DbWeight::get().reads((3 as Weight).saturating_mul(n as Weight)))
-            .saturating_add(DbWeight::get().writes((1 as Weight).saturating_mul(n as Weight)))
-    }
-    fn payout_stakers_alive_staked(n: u32) -> Weight {
-        (0 as Weight)
-            .saturating_add((117324000 as Weight).saturating_mul(n as Weight))
-            .saturating_add(DbWeight::get().reads((5 as Weight).saturating_mul(n as Weight)))
-            .saturating_add(DbWeight::get().writes((3 as Weight).saturating_mul(n as Weight)))
-    }
-    fn rebond(l: u32) -> Weight {
-        (71316000 as Weight)
-            .saturating_add((142000 as Weight).saturating_mul(l as Weight))
-            .saturating_add(DbWeight::get().reads(4 as Weight))
-            .saturating_add(DbWeight::get().writes(3 as Weight))
-    }
-    fn set_history_depth(e: u32) -> Weight {
-        (0 as Weight)
-            .saturating_add((51901000 as Weight).saturating_mul(e as Weight))
-            .saturating_add(DbWeight::get().reads(2 as Weight))
-            .saturating_add(DbWeight::get().writes(4 as Weight))
-            .saturating_add(DbWeight::get().writes((7 as Weight).saturating_mul(e as Weight)))
-    }
-    fn reap_stash(s: u32) -> Weight {
-        (147166000 as Weight)
-            .saturating_add((6661000 as Weight).saturating_mul(s as Weight))
-            .saturating_add(DbWeight::get().reads(4 as Weight))
-            .saturating_add(DbWeight::get().writes(8 as Weight))
-            .saturating_add(DbWeight::get().writes((1 as Weight).saturating_mul(s as Weight)))
-    }
-    fn new_era(v: u32, n: u32) -> Weight {
-        (0 as Weight)
-            .saturating_add((1440459000 as Weight).saturating_mul(v as Weight))
-            .saturating_add((182580000 as Weight).saturating_mul(n as Weight))
-            .saturating_add(DbWeight::get().reads(10 as Weight))
-            .saturating_add(DbWeight::get().reads((4 as Weight).saturating_mul(v as Weight)))
-            .saturating_add(DbWeight::get().reads((3 as Weight).saturating_mul(n as Weight)))
-            .saturating_add(DbWeight::get().writes(8 as Weight))
-            .saturating_add(DbWeight::get().writes((3 as Weight).saturating_mul(v as Weight)))
-    }
+#![allow(unused_parens)]
+#![allow(unused_imports)]
 
-    fn get_npos_voters(v: u32, n: u32, s: u32) -> u64 {
-        todo!()
-    }
+use frame_support::{traits::Get, weights::Weight};
+use sp_std::marker::PhantomData;
 
-    fn get_npos_targets(v: u32) -> u64 {
-        todo!()
-    }
+/// Weight functions for `pallet_staking`.
+pub struct WeightInfo<T>(PhantomData<T>);
+impl<T: frame_system::Config> pallet_staking::WeightInfo for WeightInfo<T> {
+	fn bond() -> Weight {
+		(77_492_000 as Weight)
+			.saturating_add(T::DbWeight::get().reads(5 as Weight))
+			.saturating_add(T::DbWeight::get().writes(4 as Weight))
+	}
+	fn bond_extra() -> Weight {
+		(59_476_000 as Weight)
+			.saturating_add(T::DbWeight::get().reads(3 as Weight))
+			.saturating_add(T::DbWeight::get().writes(2 as Weight))
+	}
+	fn unbond() -> Weight {
+		(54_989_000 as Weight)
+			.saturating_add(T::DbWeight::get().reads(4 as Weight))
+			.saturating_add(T::DbWeight::get().writes(3 as Weight))
+	}
+	fn withdraw_unbonded_update(s: u32, ) -> Weight {
+		(52_343_000 as Weight)
+			.saturating_add((73_000 as Weight).saturating_mul(s as Weight))
+			.saturating_add(T::DbWeight::get().reads(4 as Weight))
+			.saturating_add(T::DbWeight::get().writes(3 as Weight))
+	}
+	fn withdraw_unbonded_kill(s: u32, ) -> Weight {
+		(86_264_000 as Weight)
+			.saturating_add((2_999_000 as Weight).saturating_mul(s as Weight))
+			.saturating_add(T::DbWeight::get().reads(6 as Weight))
+			.saturating_add(T::DbWeight::get().writes(8 as Weight))
+			.saturating_add(T::DbWeight::get().writes((1 as Weight).saturating_mul(s as Weight)))
+	}
+	fn validate() -> Weight {
+		(32_657_000 as Weight)
+			.saturating_add(T::DbWeight::get().reads(1 as Weight))
+			.saturating_add(T::DbWeight::get().writes(2 as Weight))
+	}
+	fn kick(k: u32, ) -> Weight {
+		(15_113_000 as Weight)
+			.saturating_add((19_071_000 as Weight).saturating_mul(k as Weight))
+			.saturating_add(T::DbWeight::get().reads(1 as Weight))
+			.saturating_add(T::DbWeight::get().reads((1 as Weight).saturating_mul(k as Weight)))
+			.saturating_add(T::DbWeight::get().writes((1 as Weight).saturating_mul(k as Weight)))
+	}
+	fn nominate(n: u32, ) -> Weight {
+		(45_459_000 as Weight)
+			.saturating_add((5_881_000 as Weight).saturating_mul(n as Weight))
+			.saturating_add(T::DbWeight::get().reads(3 as Weight))
+			.saturating_add(T::DbWeight::get().reads((1 as Weight).saturating_mul(n as Weight)))
+			.saturating_add(T::DbWeight::get().writes(2 as Weight))
+	}
+	fn chill() -> Weight {
+		(15_851_000 as Weight)
+			.saturating_add(T::DbWeight::get().reads(1 as Weight))
+			.saturating_add(T::DbWeight::get().writes(2 as Weight))
+	}
+	fn set_payee() -> Weight {
+		(12_944_000 as Weight)
+			.saturating_add(T::DbWeight::get().reads(1 as Weight))
+			.saturating_add(T::DbWeight::get().writes(1 as Weight))
+	}
+	fn set_controller() -> Weight {
+		(28_367_000 as Weight)
+			.saturating_add(T::DbWeight::get().reads(2 as Weight))
+			.saturating_add(T::DbWeight::get().writes(3 as Weight))
+	}
+	fn set_validator_count() -> Weight {
+		(2_330_000 as Weight)
+			.saturating_add(T::DbWeight::get().writes(1 as Weight))
+	}
+	fn force_no_eras() -> Weight {
+		(2_652_000 as Weight)
+			.saturating_add(T::DbWeight::get().writes(1 as Weight))
+	}
+	fn force_new_era() -> Weight {
+		(2_659_000 as Weight)
+			.saturating_add(T::DbWeight::get().writes(1 as Weight))
+	}
+	fn force_new_era_always() -> Weight {
+		(2_688_000 as Weight)
+			.saturating_add(T::DbWeight::get().writes(1 as Weight))
+	}
+	fn set_invulnerables(v: u32, ) -> Weight {
+		(2_789_000 as Weight)
+			.saturating_add((35_000 as Weight).saturating_mul(v as Weight))
+			.saturating_add(T::DbWeight::get().writes(1 as Weight))
+	}
+	fn force_unstake(s: u32, ) -> Weight {
+		(61_424_000 as Weight)
+			.saturating_add((3_015_000 as Weight).saturating_mul(s as Weight))
+			.saturating_add(T::DbWeight::get().reads(4 as Weight))
+			.saturating_add(T::DbWeight::get().writes(8 as Weight))
+			.saturating_add(T::DbWeight::get().writes((1 as Weight).saturating_mul(s as Weight)))
+	}
+	fn cancel_deferred_slash(s: u32, ) -> Weight {
+		(3_367_473_000 as Weight)
+			.saturating_add((222_581_000 as Weight).saturating_mul(s as Weight))
+			.saturating_add(T::DbWeight::get().reads(1 as Weight))
+			.saturating_add(T::DbWeight::get().writes(1 as Weight))
+	}
+	fn payout_stakers_dead_controller(n: u32, ) -> Weight {
+		(119_955_000 as Weight)
+			.saturating_add((49_801_000 as Weight).saturating_mul(n as Weight))
+			.saturating_add(T::DbWeight::get().reads(10 as Weight))
+			.saturating_add(T::DbWeight::get().reads((3 as Weight).saturating_mul(n as Weight)))
+			.saturating_add(T::DbWeight::get().writes(2 as Weight))
+			.saturating_add(T::DbWeight::get().writes((1 as Weight).saturating_mul(n as Weight)))
+	}
+	fn payout_stakers_alive_staked(n: u32, ) -> Weight {
+		(149_019_000 as Weight)
+			.saturating_add((65_256_000 as Weight).saturating_mul(n as Weight))
+			.saturating_add(T::DbWeight::get().reads(11 as Weight))
+			.saturating_add(T::DbWeight::get().reads((5 as Weight).saturating_mul(n as Weight)))
+			.saturating_add(T::DbWeight::get().writes(3 as Weight))
+			.saturating_add(T::DbWeight::get().writes((3 as Weight).saturating_mul(n as Weight)))
+	}
+	fn rebond(l: u32, ) -> Weight {
+		(46_032_000 as Weight)
+			.saturating_add((63_000 as Weight).saturating_mul(l as Weight))
+			.saturating_add(T::DbWeight::get().reads(3 as Weight))
+			.saturating_add(T::DbWeight::get().writes(3 as Weight))
+	}
+	fn set_history_depth(e: u32, ) -> Weight {
+		(0 as Weight)
+			.saturating_add((32_005_000 as Weight).saturating_mul(e as Weight))
+			.saturating_add(T::DbWeight::get().reads(2 as Weight))
+			.saturating_add(T::DbWeight::get().writes(4 as Weight))
+			.saturating_add(T::DbWeight::get().writes((7 as Weight).saturating_mul(e as Weight)))
+	}
+	fn reap_stash(s: u32, ) -> Weight {
+		(72_669_000 as Weight)
+			.saturating_add((2_994_000 as Weight).saturating_mul(s as Weight))
+			.saturating_add(T::DbWeight::get().reads(4 as Weight))
+			.saturating_add(T::DbWeight::get().writes(8 as Weight))
+			.saturating_add(T::DbWeight::get().writes((1 as Weight).saturating_mul(s as Weight)))
+	}
+	fn new_era(v: u32, n: u32, ) -> Weight {
+		(0 as Weight)
+			.saturating_add((1_219_458_000 as Weight).saturating_mul(v as Weight))
+			.saturating_add((158_186_000 as Weight).saturating_mul(n as Weight))
+			.saturating_add(T::DbWeight::get().reads(10 as Weight))
+			.saturating_add(T::DbWeight::get().reads((4 as Weight).saturating_mul(v as Weight)))
+			.saturating_add(T::DbWeight::get().reads((3 as Weight).saturating_mul(n as Weight)))
+			.saturating_add(T::DbWeight::get().writes(4 as Weight))
+			.saturating_add(T::DbWeight::get().writes((3 as Weight).saturating_mul(v as Weight)))
+	}
+	fn get_npos_voters(v: u32, n: u32, s: u32, ) -> Weight {
+		(0 as Weight)
+			.saturating_add((101_379_000 as Weight).saturating_mul(v as Weight))
+			.saturating_add((103_387_000 as Weight).saturating_mul(n as Weight))
+			.saturating_add((34_875_000 as Weight).saturating_mul(s as Weight))
+			.saturating_add(T::DbWeight::get().reads(3 as Weight))
+			.saturating_add(T::DbWeight::get().reads((4 as Weight).saturating_mul(v as Weight)))
+			.saturating_add(T::DbWeight::get().reads((3 as Weight).saturating_mul(n as Weight)))
+			.saturating_add(T::DbWeight::get().reads((1 as Weight).saturating_mul(s as Weight)))
+	}
+	fn get_npos_targets(v: u32, ) -> Weight {
+		(0 as Weight)
+			.saturating_add((35_221_000 as Weight).saturating_mul(v as Weight))
+			.saturating_add(T::DbWeight::get().reads(1 as Weight))
+			.saturating_add(T::DbWeight::get().reads((1 as Weight).saturating_mul(v as Weight)))
+	}
 }
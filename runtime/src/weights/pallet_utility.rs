@@ -1,40 +1,42 @@
-// This file is part of Substrate.
+// This file is part of Polkadex.
 
-// Copyright (C) 2017-2020 Parity Technologies (UK) Ltd.
-// SPDX-License-Identifier: Apache-2.0
+// Copyright (C) 2020-2021 Polkadex oü.
+// SPDX-License-Identifier: GPL-3.0-or-later WITH Classpath-exception-2.0
 
-// Licensed under the Apache License, Version 2.0 (the "License");
-// you may not use this file except in compliance with the License.
-// You may obtain a copy of the License at
-//
-// 	http://www.apache.org/licenses/LICENSE-2.0
-//
-// Unless required by applicable law or agreed to in writing, software
-// distributed under the License is distributed on an "AS IS" BASIS,
-// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
-// See the License for the specific language governing permissions and
-// limitations under the License.
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
 
-//! THIS FILE WAS AUTO-GENERATED USING THE SUBSTRATE BENCHMARK CLI VERSION 2.0.0-rc5
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
 
-#![allow(unused_parens)]
-#![allow(unused_imports)]
-
-use frame_support::weights::{constants::RocksDbWeight as DbWeight, Weight};
+// You should have received a copy of the GNU General Public License
+// along with this program. If not, see <https://www.gnu.org/licenses/>.
 
-pub struct WeightInfo;
+//! Weights for `pallet_utility`, copied from the `SubstrateWeight` implementation shipped with
+//! the pallet.
 
-impl pallet_utility::WeightInfo for WeightInfo {
-    fn batch(c: u32) -> Weight {
-        (16461000 as Weight)
-            .saturating_add((1982000 as Weight).saturating_mul(c as Weight))
-    }
-    // WARNING! Some components were not used: ["u"]
-    fn as_derivative() -> Weight {
-        (4086000 as Weight)
-    }
+#![allow(unused_parens)]
+#![allow(unused_imports)]
 
-    fn batch_all(c: u32) -> u64 {
-        todo!()
-    }
+use frame_support::{traits::Get, weights::Weight};
+use sp_std::marker::PhantomData;
+
+/// Weight functions for `pallet_utility`.
+pub struct WeightInfo<T>(PhantomData<T>);
+impl<T: frame_system::Config> pallet_utility::WeightInfo for WeightInfo<T> {
+	fn batch(c: u32, ) -> Weight {
+		(19_099_000 as Weight)
+			.saturating_add((706_000 as Weight).saturating_mul(c as Weight))
+	}
+	fn as_derivative() -> Weight {
+		(3_701_000 as Weight)
+	}
+	fn batch_all(c: u32, ) -> Weight {
+		(19_199_000 as Weight)
+			.saturating_add((1_108_000 as Weight).saturating_mul(c as Weight))
+	}
 }
@@ -0,0 +1,11 @@
+#!/usr/bin/env bash
+# This script is meant to be run on Unix/Linux based systems
+set -e
+
+echo "*** Building example contracts ***"
+
+cd $(dirname ${BASH_SOURCE[0]})/../contracts/asset-vault
+
+cargo +nightly contract build
+
+echo "*** Run the runtime tests driving them with: cargo test -p node-polkadex-runtime asset_vault -- --ignored ***"
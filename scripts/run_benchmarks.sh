@@ -0,0 +1,102 @@
+#!/usr/bin/env bash
+# This script is meant to be run on Unix/Linux based systems
+set -e
+
+echo "*** Regenerating runtime weights ***"
+
+cd $(dirname ${BASH_SOURCE[0]})/..
+
+cargo build --release --features runtime-benchmarks
+
+PALLETS=(
+    "frame_system"
+    "orml_currencies"
+    "orml_tokens"
+    "orml_vesting"
+    "pallet_balances"
+    "pallet_bounties"
+    "pallet_collective"
+    "pallet_contract_sponsorship"
+    "pallet_contracts"
+    "pallet_election_provider_multi_phase"
+    "pallet_elections_phragmen"
+    "pallet_identity"
+    "pallet_im_online"
+    "pallet_indices"
+    "pallet_margin"
+    "pallet_membership"
+    "pallet_multisig"
+    "pallet_ocex"
+    "pallet_orderbook"
+    "pallet_price_oracle"
+    "pallet_proxy"
+    "pallet_scheduler"
+    "pallet_session"
+    "pallet_signed_orders"
+    "pallet_staking"
+    "pallet_timestamp"
+    "pallet_treasury"
+    "pallet_utility"
+    "pallet_vesting"
+)
+
+for PALLET in "${PALLETS[@]}"; do
+    ./target/release/polkadex-node benchmark \
+        --chain=dev \
+        --steps=50 \
+        --repeat=20 \
+        --pallet="$PALLET" \
+        --extrinsic="*" \
+        --execution=wasm \
+        --wasm-execution=compiled \
+        --heap-pages=4096 \
+        --header=./scripts/HEADER \
+        --output="./runtime/src/weights/$PALLET.rs"
+done
+
+# The BABE and GRANDPA benchmarks do not match their WeightInfo functions, so the template writes
+# the measurements into modules that the hand-composed weight modules build on.
+COMPOSED_PALLETS=(
+    "pallet_babe"
+    "pallet_grandpa"
+)
+
+for PALLET in "${COMPOSED_PALLETS[@]}"; do
+    ./target/release/polkadex-node benchmark \
+        --chain=dev \
+        --steps=50 \
+        --repeat=20 \
+        --pallet="$PALLET" \
+        --extrinsic="*" \
+        --execution=wasm \
+        --wasm-execution=compiled \
+        --heap-pages=4096 \
+        --header=./scripts/HEADER \
+        --template=./scripts/measurements-template.hbs \
+        --output="./runtime/src/weights/${PALLET}_benchmarks.rs"
+done
+
+# Polkadex pallets keep their WeightInfo trait, and the weights their tests use, in weights.rs.
+LOCAL_PALLETS=(
+    "pallet_contract_sponsorship:contract-sponsorship"
+    "pallet_margin:margin"
+    "pallet_ocex:ocex"
+    "pallet_orderbook:orderbook"
+    "pallet_price_oracle:price-oracle"
+    "pallet_signed_orders:signed-orders"
+)
+
+for ENTRY in "${LOCAL_PALLETS[@]}"; do
+    ./target/release/polkadex-node benchmark \
+        --chain=dev \
+        --steps=50 \
+        --repeat=20 \
+        --pallet="${ENTRY%%:*}" \
+        --extrinsic="*" \
+        --execution=wasm \
+        --wasm-execution=compiled \
+        --heap-pages=4096 \
+        --header=./scripts/HEADER \
+        --template=./scripts/pallet-weight-template.hbs \
+        --output="./pallets/${ENTRY#*:}/src/weights.rs"
+done